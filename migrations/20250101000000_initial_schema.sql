@@ -0,0 +1,244 @@
+-- Full schema, equivalent to the Liquibase changelog in db/changelog.
+--
+-- Every statement is idempotent, so this also applies cleanly to a database
+-- that Liquibase has already brought up to date.
+
+CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
+
+-- Enums
+
+DO $$ BEGIN
+    CREATE TYPE transaction_type AS ENUM ('credit', 'debit', 'transfer', 'verification', 'authorization');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+DO $$ BEGIN
+    CREATE TYPE transaction_status AS ENUM ('pending', 'completed', 'failed');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+DO $$ BEGIN
+    CREATE TYPE authorization_status AS ENUM ('pending', 'captured', 'voided');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+DO $$ BEGIN
+    CREATE TYPE account_status AS ENUM ('active', 'frozen', 'closed');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+DO $$ BEGIN
+    CREATE TYPE delivery_status AS ENUM ('pending', 'success', 'failed', 'retrying');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+-- Accounts
+
+CREATE TABLE IF NOT EXISTS accounts (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    business_name VARCHAR(255) NOT NULL,
+    balance DECIMAL(20, 2) NOT NULL DEFAULT 0.00,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    webhook_secret VARCHAR(255) NOT NULL DEFAULT 'placeholder_secret',
+    status account_status NOT NULL DEFAULT 'active',
+    -- NULL falls back to the configured default limit
+    per_transaction_limit DECIMAL(20, 2),
+    daily_debit_limit DECIMAL(20, 2),
+    -- Funds reserved by pending authorizations; spendable funds are balance - held_balance
+    held_balance DECIMAL(20, 2) NOT NULL DEFAULT 0.00,
+    CONSTRAINT balance_non_negative CHECK (balance >= 0),
+    CONSTRAINT transaction_limits_positive CHECK (
+        (per_transaction_limit IS NULL OR per_transaction_limit > 0) AND
+        (daily_debit_limit IS NULL OR daily_debit_limit > 0)
+    ),
+    CONSTRAINT held_balance_within_balance CHECK (held_balance >= 0 AND held_balance <= balance)
+);
+
+CREATE INDEX IF NOT EXISTS idx_accounts_business_name ON accounts (business_name);
+CREATE INDEX IF NOT EXISTS idx_accounts_deleted_at ON accounts (deleted_at);
+
+-- Transactions
+
+CREATE TABLE IF NOT EXISTS transactions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    transaction_type transaction_type NOT NULL,
+    from_account_id UUID CONSTRAINT fk_transactions_from_account REFERENCES accounts (id),
+    to_account_id UUID CONSTRAINT fk_transactions_to_account REFERENCES accounts (id),
+    amount DECIMAL(20, 2) NOT NULL,
+    idempotency_key VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    -- Only set on authorizations
+    authorization_status authorization_status,
+    status transaction_status NOT NULL DEFAULT 'completed',
+    description VARCHAR(500),
+    CONSTRAINT unique_idempotency_key UNIQUE (idempotency_key),
+    CONSTRAINT amount_positive CHECK (amount > 0 OR transaction_type = 'verification'),
+    CONSTRAINT credit_constraint CHECK (
+        (transaction_type = 'credit' AND from_account_id IS NULL AND to_account_id IS NOT NULL) OR
+        (transaction_type != 'credit')
+    ),
+    CONSTRAINT debit_constraint CHECK (
+        (transaction_type = 'debit' AND from_account_id IS NOT NULL AND to_account_id IS NULL) OR
+        (transaction_type != 'debit')
+    ),
+    CONSTRAINT transfer_constraint CHECK (
+        (transaction_type = 'transfer' AND from_account_id IS NOT NULL AND to_account_id IS NOT NULL AND from_account_id != to_account_id) OR
+        (transaction_type != 'transfer')
+    ),
+    CONSTRAINT verification_constraint CHECK (
+        (transaction_type = 'verification' AND from_account_id IS NULL AND to_account_id IS NOT NULL AND amount = 0) OR
+        (transaction_type != 'verification')
+    ),
+    CONSTRAINT authorization_constraint CHECK (
+        (transaction_type = 'authorization' AND from_account_id IS NOT NULL AND to_account_id IS NULL AND authorization_status IS NOT NULL) OR
+        (transaction_type != 'authorization' AND authorization_status IS NULL)
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_from_account ON transactions (from_account_id);
+CREATE INDEX IF NOT EXISTS idx_transactions_to_account ON transactions (to_account_id);
+CREATE INDEX IF NOT EXISTS idx_transactions_created_at ON transactions (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_transactions_idempotency_key ON transactions (idempotency_key) WHERE idempotency_key IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_transactions_status_created_at ON transactions (status, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS archived_transactions (
+    id UUID PRIMARY KEY,
+    transaction_type transaction_type NOT NULL,
+    from_account_id UUID,
+    to_account_id UUID,
+    amount DECIMAL(20, 2) NOT NULL,
+    idempotency_key VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    authorization_status authorization_status,
+    status transaction_status NOT NULL DEFAULT 'completed',
+    description VARCHAR(500)
+);
+
+CREATE INDEX IF NOT EXISTS idx_archived_transactions_from_account ON archived_transactions (from_account_id);
+CREATE INDEX IF NOT EXISTS idx_archived_transactions_to_account ON archived_transactions (to_account_id);
+CREATE INDEX IF NOT EXISTS idx_archived_transactions_created_at ON archived_transactions (created_at);
+
+-- Snapshots outlive the transaction row once it is archived, so transaction_id has no foreign key
+CREATE TABLE IF NOT EXISTS balance_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    account_id UUID NOT NULL CONSTRAINT fk_balance_snapshots_account REFERENCES accounts (id),
+    transaction_id UUID NOT NULL,
+    balance DECIMAL(20, 2) NOT NULL,
+    recorded_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_balance_snapshots_account_recorded_at ON balance_snapshots (account_id, recorded_at);
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    key VARCHAR(255) PRIMARY KEY,
+    request_fingerprint VARCHAR(64) NOT NULL,
+    response JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires_at ON idempotency_keys (expires_at);
+
+-- API keys
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    account_id UUID NOT NULL CONSTRAINT fk_api_keys_account REFERENCES accounts (id) ON DELETE CASCADE,
+    rate_limit_per_hour INTEGER NOT NULL DEFAULT 1000,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    superseded_by UUID CONSTRAINT fk_api_keys_superseded_by REFERENCES api_keys (id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_key_hash ON api_keys (key_hash);
+CREATE INDEX IF NOT EXISTS idx_api_keys_account ON api_keys (account_id);
+
+-- Webhooks
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL CONSTRAINT fk_webhooks_accounts REFERENCES accounts (id),
+    url VARCHAR(2048) NOT NULL,
+    event VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    secret VARCHAR(255) NOT NULL,
+    previous_secret VARCHAR(255),
+    previous_secret_expires_at TIMESTAMP WITH TIME ZONE,
+    -- NULL falls back to the global delivery timeout
+    timeout_ms INTEGER,
+    status VARCHAR(20) NOT NULL DEFAULT 'active',
+    verification_token VARCHAR(255),
+    accepts_gzip BOOLEAN NOT NULL DEFAULT false,
+    last_sequence BIGINT NOT NULL DEFAULT 0,
+    CONSTRAINT timeout_ms_positive CHECK (timeout_ms IS NULL OR timeout_ms > 0),
+    CONSTRAINT webhook_status_valid CHECK (status IN ('active', 'pending'))
+);
+
+-- Delivery logs outlive the transaction row once it is archived, so transaction_id has no foreign key
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    webhook_id UUID NOT NULL CONSTRAINT fk_webhook_deliveries_webhook REFERENCES webhooks (id) ON DELETE CASCADE,
+    transaction_id UUID NOT NULL,
+    event_type VARCHAR(100) NOT NULL,
+    status delivery_status NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_attempt_at TIMESTAMP WITH TIME ZONE,
+    next_retry_at TIMESTAMP WITH TIME ZONE,
+    response_status_code INTEGER,
+    response_body TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    completed_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries (webhook_id);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_transaction ON webhook_deliveries (transaction_id);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_status ON webhook_deliveries (status);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_next_retry ON webhook_deliveries (next_retry_at) WHERE status = 'retrying';
+
+CREATE TABLE IF NOT EXISTS webhook_dead_letters (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    webhook_id UUID NOT NULL CONSTRAINT fk_webhook_dead_letters_webhook REFERENCES webhooks (id) ON DELETE CASCADE,
+    event VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_dead_letters_webhook ON webhook_dead_letters (webhook_id);
+
+CREATE TABLE IF NOT EXISTS webhook_outbox (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    webhook_id UUID NOT NULL CONSTRAINT fk_webhook_outbox_webhook REFERENCES webhooks (id) ON DELETE CASCADE,
+    event VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL,
+    request_id VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_outbox_created_at ON webhook_outbox (created_at);
+
+-- updated_at triggers
+
+CREATE OR REPLACE FUNCTION update_updated_at_column()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.updated_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER update_accounts_updated_at
+    BEFORE UPDATE ON accounts
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE OR REPLACE TRIGGER update_webhooks_updated_at
+    BEFORE UPDATE ON webhooks
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
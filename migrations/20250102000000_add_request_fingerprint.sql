@@ -0,0 +1,4 @@
+-- Hash of the request that created a transaction, compared when its idempotency key is reused.
+-- NULL for transactions recorded before fingerprints; their keys replay without a comparison.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS request_fingerprint VARCHAR(64);
+ALTER TABLE archived_transactions ADD COLUMN IF NOT EXISTS request_fingerprint VARCHAR(64);
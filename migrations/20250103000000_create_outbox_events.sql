@@ -0,0 +1,13 @@
+-- Webhook events written in the same transaction as the deposit, withdrawal or transfer
+-- they announce, delivered by the outbox relay. No foreign keys: transactions get archived.
+CREATE TABLE IF NOT EXISTS outbox_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    account_id UUID NOT NULL,
+    event VARCHAR(100) NOT NULL,
+    transaction_id UUID NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    sent_at TIMESTAMP WITH TIME ZONE,
+    locked_until TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_events_unsent ON outbox_events (created_at) WHERE sent_at IS NULL;
@@ -0,0 +1,5 @@
+-- Non-secret leading characters of each raw API key, used to narrow verification
+-- and to identify keys in listings. NULL for keys created before it was recorded.
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS key_prefix VARCHAR(8);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_key_prefix ON api_keys (key_prefix);
@@ -0,0 +1,12 @@
+-- Set by the cleanup job once a transaction's idempotency key is older than the TTL.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS idempotency_key_expired BOOLEAN NOT NULL DEFAULT false;
+
+-- Only live keys are unique, so an expired key can be reused without touching old rows
+ALTER TABLE transactions DROP CONSTRAINT IF EXISTS unique_idempotency_key;
+CREATE UNIQUE INDEX IF NOT EXISTS uq_transactions_live_idempotency_key
+    ON transactions (idempotency_key)
+    WHERE idempotency_key IS NOT NULL AND NOT idempotency_key_expired;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_unexpired_idempotency_key
+    ON transactions (created_at)
+    WHERE idempotency_key IS NOT NULL AND NOT idempotency_key_expired;
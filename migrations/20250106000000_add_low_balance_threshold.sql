@@ -0,0 +1,11 @@
+-- Balance below which a debit fires a balance.low webhook. NULL disables the alert.
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS low_balance_threshold DECIMAL(20, 2);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'low_balance_threshold_positive') THEN
+        ALTER TABLE accounts ADD CONSTRAINT low_balance_threshold_positive
+            CHECK (low_balance_threshold IS NULL OR low_balance_threshold > 0);
+    END IF;
+END
+$$;
@@ -0,0 +1,16 @@
+-- One live webhook per (account, url, event); creating it again returns the existing one.
+-- Set when a webhook is disabled; the row stays so its delivery history survives.
+ALTER TABLE webhooks ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- Keep the oldest of any duplicate subscriptions live and disable the others rather than
+-- deleting them, which would cascade to their deliveries, dead letters and outbox rows
+UPDATE webhooks w SET deleted_at = NOW(), updated_at = NOW()
+FROM webhooks older
+WHERE w.deleted_at IS NULL AND older.deleted_at IS NULL
+  AND w.account_id = older.account_id AND w.url = older.url AND w.event = older.event
+  AND (older.created_at, older.id) < (w.created_at, w.id);
+
+-- Only live subscriptions are unique, so a disabled webhook can be created again
+CREATE UNIQUE INDEX IF NOT EXISTS uq_webhooks_live_account_url_event
+    ON webhooks (account_id, url, event)
+    WHERE deleted_at IS NULL;
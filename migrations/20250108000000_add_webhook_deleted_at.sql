@@ -0,0 +1,9 @@
+-- 20250107000000 now adds the column and index itself; this stays a no-op after it.
+-- Set when a webhook is disabled; the row stays so its delivery history survives.
+ALTER TABLE webhooks ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- Only live subscriptions are unique, so a disabled webhook can be created again
+ALTER TABLE webhooks DROP CONSTRAINT IF EXISTS uq_webhooks_account_url_event;
+CREATE UNIQUE INDEX IF NOT EXISTS uq_webhooks_live_account_url_event
+    ON webhooks (account_id, url, event)
+    WHERE deleted_at IS NULL;
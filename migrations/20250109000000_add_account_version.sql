@@ -0,0 +1,2 @@
+-- Bumped by every balance or name change, so writers without a row lock can detect lost updates.
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- Accounts a key may act for besides its own, so one tenant key can span several accounts.
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS additional_account_ids UUID[] NOT NULL DEFAULT '{}';
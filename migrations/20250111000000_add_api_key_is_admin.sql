@@ -0,0 +1,2 @@
+-- Admin keys may change any account's limits and lift freezes.
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS is_admin BOOLEAN NOT NULL DEFAULT FALSE;
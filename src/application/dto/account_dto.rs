@@ -3,19 +3,70 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::domain::entities::Account;
+use crate::application::dto::money_format;
+use crate::domain::entities::{Account, BalanceSnapshot};
+use crate::domain::value_objects::AccountStatus;
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct CreateAccountRequest {
     pub business_name: String,
+    /// Opening balance, recorded as a deposit in the same database transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_balance: Option<Decimal>,
 }
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateAccountRequest {
+    pub business_name: String,
+}
+
+/// Accounts to look up in one request
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BatchAccountLookupRequest {
+    /// Account IDs, at most 200
+    pub ids: Vec<Uuid>,
+}
+
+/// Result of a batch lookup; every requested id is in exactly one of the lists
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BatchAccountLookupResponse {
+    pub accounts: Vec<AccountResponse>,
+    /// Requested ids with no (non-deleted) account
+    pub not_found: Vec<Uuid>,
+}
+
+/// Replaces an account's own transaction limits. `null` falls back to the server default.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateTransactionLimitsRequest {
+    /// Largest single withdrawal or outgoing transfer
+    pub per_transaction_limit: Option<Decimal>,
+    /// Total withdrawals and outgoing transfers per UTC day
+    pub daily_debit_limit: Option<Decimal>,
+    /// A withdrawal or outgoing transfer leaving the balance below this fires `balance.low`; `null` disables it
+    #[serde(default)]
+    pub low_balance_threshold: Option<Decimal>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct TransactionLimitsResponse {
+    pub account_id: Uuid,
+    #[serde(serialize_with = "money_format::serialize_option")]
+    pub per_transaction_limit: Option<Decimal>,
+    #[serde(serialize_with = "money_format::serialize_option")]
+    pub daily_debit_limit: Option<Decimal>,
+    #[serde(serialize_with = "money_format::serialize_option")]
+    pub low_balance_threshold: Option<Decimal>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct AccountResponse {
     pub id: Uuid,
     pub business_name: String,
+    #[serde(serialize_with = "money_format::serialize")]
     pub balance: Decimal,
+    pub status: AccountStatus,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl From<Account> for AccountResponse {
@@ -24,7 +75,28 @@ impl From<Account> for AccountResponse {
             id: account.id,
             business_name: account.business_name,
             balance: account.balance.amount(), // Assuming Money has an amount() method returning Decimal
+            status: account.status,
             created_at: account.created_at,
+            updated_at: account.updated_at,
+        }
+    }
+}
+
+/// An account's balance immediately after a transaction
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BalanceSnapshotResponse {
+    pub transaction_id: Uuid,
+    #[serde(serialize_with = "money_format::serialize")]
+    pub balance: Decimal,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl From<BalanceSnapshot> for BalanceSnapshotResponse {
+    fn from(snapshot: BalanceSnapshot) -> Self {
+        Self {
+            transaction_id: snapshot.transaction_id,
+            balance: snapshot.balance.amount(),
+            recorded_at: snapshot.recorded_at,
         }
     }
 }
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use crate::domain::entities::ApiKey;
+
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct CreateApiKeyRequest {
+    /// Quota for the new key. Defaults to the calling key's quota and may not exceed it.
+    pub rate_limit_per_hour: Option<u32>,
+    /// Lifetime of the new key in seconds. Omit for a key that never expires.
+    pub ttl_seconds: Option<u64>,
+    /// Other accounts the new key may act for. Each must be one the calling key is authorized for.
+    #[serde(default)]
+    pub additional_account_ids: Vec<Uuid>,
+}
+
+/// An existing API key. Only its prefix is shown; the raw key is never stored.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    /// Other accounts the key may act for
+    pub additional_account_ids: Vec<Uuid>,
+    /// Leading characters of the raw key, to tell keys apart. Absent for keys
+    /// created before prefixes were recorded until they are next used.
+    pub key_prefix: Option<String>,
+    pub rate_limit_per_hour: u32,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Key that replaced this one in a rotation
+    pub superseded_by: Option<Uuid>,
+    /// Admin keys may change any account's limits and lift freezes
+    pub is_admin: bool,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.id,
+            account_id: api_key.account_id,
+            additional_account_ids: api_key.additional_account_ids,
+            key_prefix: api_key.key_prefix,
+            rate_limit_per_hour: api_key.rate_limit_per_hour,
+            created_at: api_key.created_at,
+            last_used_at: api_key.last_used_at,
+            expires_at: api_key.expires_at,
+            superseded_by: api_key.superseded_by,
+            is_admin: api_key.is_admin,
+        }
+    }
+}
+
+/// A freshly minted API key.
+///
+/// `raw_key` is only ever returned here; the service stores just its hash, so
+/// it cannot be retrieved again. Lose it and a new key has to be created.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CreatedApiKeyResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub raw_key: String,
+    pub additional_account_ids: Vec<Uuid>,
+    pub key_prefix: Option<String>,
+    pub rate_limit_per_hour: u32,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// The replacement minted by a key rotation.
+///
+/// The previous key keeps authenticating until `previous_key_expires_at`, giving
+/// clients time to switch over.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct RotatedApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: CreatedApiKeyResponse,
+    pub previous_key_id: Uuid,
+    pub previous_key_expires_at: DateTime<Utc>,
+}
+
+impl CreatedApiKeyResponse {
+    pub fn new(api_key: ApiKey, raw_key: String) -> Self {
+        Self {
+            id: api_key.id,
+            account_id: api_key.account_id,
+            raw_key,
+            additional_account_ids: api_key.additional_account_ids,
+            key_prefix: api_key.key_prefix,
+            rate_limit_per_hour: api_key.rate_limit_per_hour,
+            created_at: api_key.created_at,
+            expires_at: api_key.expires_at,
+        }
+    }
+}
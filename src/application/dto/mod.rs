@@ -1,7 +1,23 @@
 pub mod account_dto;
+pub mod api_key_dto;
+pub mod money_format;
+pub mod pagination_dto;
 pub mod transaction_dto;
+pub mod validation;
 pub mod webhook_dto;
 
-pub use account_dto::{AccountResponse, CreateAccountRequest};
-pub use transaction_dto::{DepositRequest, TransactionResponse, TransferRequest, WithdrawRequest};
-pub use webhook_dto::{CreateWebhookRequest, WebhookResponse};
\ No newline at end of file
+pub use account_dto::{
+    AccountResponse, BalanceSnapshotResponse, BatchAccountLookupRequest, BatchAccountLookupResponse, CreateAccountRequest, TransactionLimitsResponse, UpdateAccountRequest,
+    UpdateTransactionLimitsRequest,
+};
+pub use api_key_dto::{ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse, RotatedApiKeyResponse};
+pub use pagination_dto::Paginated;
+pub use transaction_dto::{
+    AccountStatsResponse, AuthorizeRequest, BatchOperation, BatchPreviewRequest, BatchPreviewResponse, DepositRequest,
+    ProjectedBalanceResponse, TransactionResponse, TransferDryRunResponse, TransferRequest, VerificationRequest,
+    WithdrawRequest,
+};
+pub use validation::ValidateFields;
+pub use webhook_dto::{
+    CreateWebhookRequest, UpdateWebhookRequest, WebhookResponse, WebhookSecretResponse, WebhookTestResponse,
+};
\ No newline at end of file
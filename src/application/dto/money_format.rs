@@ -0,0 +1,84 @@
+//! Per-request choice of how response DTOs write monetary amounts.
+//!
+//! Amount fields of response DTOs serialize through [`serialize`], which writes
+//! a bare decimal string unless the current request asked for
+//! [`MoneyFormat::Rich`]. The format is held in a task-local set by the money
+//! format middleware, so anything serialized outside a request (webhooks,
+//! the outbox relay) stays plain.
+
+use rust_decimal::Decimal;
+use serde::{Serialize, Serializer};
+use std::future::Future;
+
+use crate::domain::value_objects::{MoneyFormat, RichMoney};
+
+tokio::task_local! {
+    static MONEY_FORMAT: MoneyFormat;
+}
+
+/// Run `future` with amounts serialized in `format`
+pub async fn scope<F: Future>(format: MoneyFormat, future: F) -> F::Output {
+    MONEY_FORMAT.scope(format, future).await
+}
+
+/// Run `f` with amounts serialized plainly, whatever the request asked for.
+/// For JSON that is stored or sent on rather than returned to the caller.
+pub fn plain<R>(f: impl FnOnce() -> R) -> R {
+    MONEY_FORMAT.sync_scope(MoneyFormat::Plain, f)
+}
+
+fn current() -> MoneyFormat {
+    MONEY_FORMAT.try_with(|format| *format).unwrap_or_default()
+}
+
+/// `serialize_with` for amount fields
+pub fn serialize<S: Serializer>(amount: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+    match current() {
+        MoneyFormat::Plain => Serialize::serialize(amount, serializer),
+        MoneyFormat::Rich => RichMoney::from_decimal(*amount).serialize(serializer),
+    }
+}
+
+/// `serialize_with` for optional amount fields
+pub fn serialize_option<S: Serializer>(amount: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error> {
+    match amount {
+        Some(amount) => serialize(amount, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    #[derive(Serialize)]
+    struct Priced {
+        #[serde(serialize_with = "serialize")]
+        amount: Decimal,
+        #[serde(serialize_with = "serialize_option")]
+        limit: Option<Decimal>,
+    }
+
+    #[test]
+    fn test_same_amount_in_plain_and_rich_form() {
+        let priced = Priced { amount: dec!(100.50), limit: None };
+
+        let bare = serde_json::to_value(&priced).unwrap();
+        assert_eq!(bare, json!({ "amount": "100.50", "limit": null }));
+
+        let rich = MONEY_FORMAT.sync_scope(MoneyFormat::Rich, || serde_json::to_value(&priced).unwrap());
+        assert_eq!(
+            rich,
+            json!({
+                "amount": { "decimal": "100.50", "minor_units": 10050, "currency": "USD" },
+                "limit": null
+            })
+        );
+
+        // Stored and forwarded JSON stays plain inside a rich request
+        let stored = MONEY_FORMAT.sync_scope(MoneyFormat::Rich, || plain(|| serde_json::to_value(&priced).unwrap()));
+        assert_eq!(stored, bare);
+    }
+}
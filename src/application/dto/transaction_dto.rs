@@ -1,30 +1,280 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
+use serde::de::{self, Deserializer};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
+use crate::application::dto::{money_format, AccountResponse};
+use crate::application::dto::validation::{check_optional_string, check_uuid, ValidateFields};
 use crate::domain::entities::Transaction;
+use crate::domain::errors::FieldError;
+use crate::domain::repositories::{AccountStats, BatchPreview, ProjectedBalance};
+use crate::domain::value_objects::{AuthorizationStatus, Money, TransactionStatus};
+
+/// Deserialize a request amount, rejecting anything above `Money::MAX_AMOUNT`.
+///
+/// Oversized amounts are refused while the body is parsed, with a message naming
+/// the limit, instead of failing later inside `Money::new`. Numbers too large for
+/// `Decimal` itself get the same message rather than a generic parse error.
+fn deserialize_amount<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    parse_amount(value).map_err(|issue| de::Error::custom(format!("amount {}", issue)))
+}
+
+/// Parse an amount from a JSON number or string, returning the issue on failure
+fn parse_amount(value: serde_json::Value) -> Result<Decimal, String> {
+    let text = match value {
+        serde_json::Value::Number(number) => number.to_string(),
+        serde_json::Value::String(text) => text,
+        other => return Err(format!("must be a number, got {}", other)),
+    };
+    let too_large = || format!("exceeds the maximum of {}", Money::MAX_AMOUNT);
+
+    let amount = match Decimal::from_str_exact(&text).or_else(|_| Decimal::from_scientific(&text)) {
+        Ok(amount) => amount,
+        Err(_) => {
+            return Err(match text.parse::<f64>() {
+                Ok(value) if value.is_finite() => too_large(),
+                _ => format!("is not a valid number: {}", text),
+            });
+        }
+    };
+    if amount.abs() > Money::MAX_AMOUNT {
+        return Err(too_large());
+    }
+
+    Ok(amount)
+}
+
+/// Check the `amount` field of a request body
+fn check_amount(body: &serde_json::Value, errors: &mut Vec<FieldError>) {
+    match body.get("amount") {
+        None | Some(serde_json::Value::Null) => errors.push(FieldError::new("amount", "is required")),
+        Some(value) => match parse_amount(value.clone()) {
+            Ok(amount) if amount.is_sign_negative() => errors.push(FieldError::new("amount", "must not be negative")),
+            Ok(_) => {}
+            Err(issue) => errors.push(FieldError::new("amount", issue)),
+        },
+    }
+}
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct DepositRequest {
     pub account_id: Uuid,
+    #[serde(deserialize_with = "deserialize_amount")]
     pub amount: Decimal,
     pub idempotency_key: Option<String>,
+    /// Note shown on the ledger entry, up to 500 characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct WithdrawRequest {
     pub account_id: Uuid,
+    #[serde(deserialize_with = "deserialize_amount")]
     pub amount: Decimal,
     pub idempotency_key: Option<String>,
+    /// Note shown on the ledger entry, up to 500 characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct TransferRequest {
     pub from_account_id: Uuid,
     pub to_account_id: Uuid,
+    #[serde(deserialize_with = "deserialize_amount")]
     pub amount: Decimal,
     pub idempotency_key: Option<String>,
+    /// Note shown on the ledger entry, up to 500 characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl ValidateFields for DepositRequest {
+    fn field_errors(body: &serde_json::Value) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        check_uuid(body, "account_id", &mut errors);
+        check_amount(body, &mut errors);
+        check_optional_string(body, "idempotency_key", &mut errors);
+        check_optional_string(body, "description", &mut errors);
+        errors
+    }
+}
+
+impl ValidateFields for WithdrawRequest {
+    fn field_errors(body: &serde_json::Value) -> Vec<FieldError> {
+        DepositRequest::field_errors(body)
+    }
+}
+
+impl ValidateFields for TransferRequest {
+    fn field_errors(body: &serde_json::Value) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        check_uuid(body, "from_account_id", &mut errors);
+        check_uuid(body, "to_account_id", &mut errors);
+        check_amount(body, &mut errors);
+        check_optional_string(body, "idempotency_key", &mut errors);
+        check_optional_string(body, "description", &mut errors);
+        errors
+    }
+}
+
+/// Hold funds on an account until the authorization is captured or voided
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AuthorizeRequest {
+    pub account_id: Uuid,
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub amount: Decimal,
+    pub idempotency_key: Option<String>,
+}
+
+impl ValidateFields for AuthorizeRequest {
+    fn field_errors(body: &serde_json::Value) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        check_uuid(body, "account_id", &mut errors);
+        check_amount(body, &mut errors);
+        check_optional_string(body, "idempotency_key", &mut errors);
+        errors
+    }
+}
+
+/// Record a zero-amount verification transaction on an account
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct VerificationRequest {
+    pub account_id: Uuid,
+    pub idempotency_key: Option<String>,
+}
+
+/// One operation of a batch, tagged by `type`
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BatchOperation {
+    Deposit {
+        account_id: Uuid,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: Decimal,
+    },
+    Withdraw {
+        account_id: Uuid,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: Decimal,
+    },
+    Transfer {
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: Decimal,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BatchPreviewRequest {
+    pub operations: Vec<BatchOperation>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct ProjectedBalanceResponse {
+    pub account_id: Uuid,
+    #[serde(serialize_with = "money_format::serialize")]
+    pub current_balance: Decimal,
+    #[serde(serialize_with = "money_format::serialize")]
+    pub projected_balance: Decimal,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct BatchPreviewResponse {
+    /// Whether every operation would be accepted
+    pub would_succeed: bool,
+    /// Index of the first operation that would be rejected
+    pub failed_operation: Option<usize>,
+    /// Why that operation would be rejected
+    pub error: Option<String>,
+    /// Affected accounts; after a failure, projections stop before the failed operation
+    pub accounts: Vec<ProjectedBalanceResponse>,
+}
+
+impl From<BatchPreview> for BatchPreviewResponse {
+    fn from(preview: BatchPreview) -> Self {
+        Self {
+            would_succeed: preview.failure.is_none(),
+            failed_operation: preview.failure.as_ref().map(|failure| failure.index),
+            error: preview.failure.map(|failure| failure.reason),
+            accounts: preview
+                .balances
+                .into_iter()
+                .map(ProjectedBalanceResponse::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<ProjectedBalance> for ProjectedBalanceResponse {
+    fn from(balance: ProjectedBalance) -> Self {
+        Self {
+            account_id: balance.account_id,
+            current_balance: balance.current_balance,
+            projected_balance: balance.projected_balance,
+        }
+    }
+}
+
+/// Totals returned by `GET /accounts/{id}/stats`; amounts only count completed transactions
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct AccountStatsResponse {
+    /// Deposits into the account
+    #[serde(serialize_with = "money_format::serialize")]
+    pub total_credited: Decimal,
+    /// Withdrawals and captured authorizations
+    #[serde(serialize_with = "money_format::serialize")]
+    pub total_debited: Decimal,
+    #[serde(serialize_with = "money_format::serialize")]
+    pub transfer_in: Decimal,
+    #[serde(serialize_with = "money_format::serialize")]
+    pub transfer_out: Decimal,
+    /// Matching transactions of any status, as counted by the history endpoint
+    pub transaction_count: i64,
+    pub first_transaction_at: Option<DateTime<Utc>>,
+    pub last_transaction_at: Option<DateTime<Utc>>,
+}
+
+impl From<AccountStats> for AccountStatsResponse {
+    fn from(stats: AccountStats) -> Self {
+        Self {
+            total_credited: stats.total_credited,
+            total_debited: stats.total_debited,
+            transfer_in: stats.transfer_in,
+            transfer_out: stats.transfer_out,
+            transaction_count: stats.transaction_count,
+            first_transaction_at: stats.first_transaction_at,
+            last_transaction_at: stats.last_transaction_at,
+        }
+    }
+}
+
+/// Outcome of `POST /transactions/transfer?dry_run=true`; nothing was persisted
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct TransferDryRunResponse {
+    /// Whether the transfer would be accepted right now
+    pub would_succeed: bool,
+    /// Why it would be rejected
+    pub error: Option<String>,
+    /// Both accounts, with the balances the transfer would leave them with
+    pub accounts: Vec<ProjectedBalanceResponse>,
+}
+
+impl From<BatchPreview> for TransferDryRunResponse {
+    fn from(preview: BatchPreview) -> Self {
+        Self {
+            would_succeed: preview.failure.is_none(),
+            error: preview.failure.map(|failure| failure.reason),
+            accounts: preview.balances.into_iter().map(ProjectedBalanceResponse::from).collect(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
@@ -33,9 +283,19 @@ pub struct TransactionResponse {
     pub transaction_type: String,
     pub from_account_id: Option<Uuid>,
     pub to_account_id: Option<Uuid>,
+    #[serde(serialize_with = "money_format::serialize")]
     pub amount: Decimal,
     pub idempotency_key: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub status: TransactionStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Only present on authorizations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorization_status: Option<AuthorizationStatus>,
+    /// The affected account after the transaction; only present with `?expand=account`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<AccountResponse>,
 }
 
 impl From<Transaction> for TransactionResponse {
@@ -48,6 +308,10 @@ impl From<Transaction> for TransactionResponse {
             amount: transaction.amount.amount(),
             idempotency_key: transaction.idempotency_key,
             created_at: transaction.created_at,
+            status: transaction.status,
+            description: transaction.description,
+            authorization_status: transaction.authorization_status,
+            account: None,
         }
     }
 }
@@ -0,0 +1,30 @@
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::domain::errors::FieldError;
+
+/// Request bodies that can report every invalid field at once.
+///
+/// serde stops at the first problem; this walks the raw JSON so a client sees
+/// a bad `amount` and a missing `to_account_id` in the same response.
+pub trait ValidateFields {
+    /// Every problem with the fields of `body`, in declaration order
+    fn field_errors(body: &Value) -> Vec<FieldError>;
+}
+
+/// Check that `field` is present and holds a UUID
+pub fn check_uuid(body: &Value, field: &str, errors: &mut Vec<FieldError>) {
+    match body.get(field) {
+        None | Some(Value::Null) => errors.push(FieldError::new(field, "is required")),
+        Some(Value::String(text)) if Uuid::parse_str(text).is_ok() => {}
+        Some(_) => errors.push(FieldError::new(field, "must be a UUID")),
+    }
+}
+
+/// Check that `field`, when present, holds a string
+pub fn check_optional_string(body: &Value, field: &str, errors: &mut Vec<FieldError>) {
+    match body.get(field) {
+        None | Some(Value::Null) | Some(Value::String(_)) => {}
+        Some(_) => errors.push(FieldError::new(field, "must be a string")),
+    }
+}
@@ -3,12 +3,27 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 use crate::domain::entities::Webhook;
-use crate::domain::value_objects::WebhookEvent;
+use crate::domain::value_objects::{WebhookEvent, WebhookStatus};
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct CreateWebhookRequest {
     pub url: String,
     pub event: WebhookEvent,
+    /// Delivery timeout for this webhook in milliseconds, capped by the server maximum
+    #[serde(default)]
+    pub timeout_ms: Option<u32>,
+    /// Large payloads may be sent with `Content-Encoding: gzip`
+    #[serde(default)]
+    pub accepts_gzip: bool,
+}
+
+/// Fields to change on a webhook; omitted fields keep their current value
+#[derive(Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct UpdateWebhookRequest {
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub event: Option<WebhookEvent>,
 }
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
@@ -18,6 +33,17 @@ pub struct WebhookResponse {
     pub event: WebhookEvent,
     pub account_id: Uuid,
     pub created_at: DateTime<Utc>,
+    /// `pending` until the endpoint echoes its verification challenge
+    #[serde(default)]
+    pub status: WebhookStatus,
+    /// Delivery timeout override; absent when the global timeout applies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u32>,
+    #[serde(default)]
+    pub accepts_gzip: bool,
+    /// Signing secret; only returned when the webhook is created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
 }
 
 impl From<Webhook> for WebhookResponse {
@@ -28,6 +54,45 @@ impl From<Webhook> for WebhookResponse {
             event: webhook.event,
             account_id: webhook.account_id,
             created_at: webhook.created_at,
+            status: webhook.status,
+            timeout_ms: webhook.timeout_ms,
+            accepts_gzip: webhook.accepts_gzip,
+            secret: None,
+        }
+    }
+}
+
+/// A freshly rotated signing secret, only ever returned by the rotation itself.
+///
+/// Until `previous_secret_expires_at`, deliveries are signed with both the new
+/// and the previous secret so receivers can switch over without dropping events.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct WebhookSecretResponse {
+    pub webhook_id: Uuid,
+    pub secret: String,
+    pub previous_secret_expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<Webhook> for WebhookSecretResponse {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            webhook_id: webhook.id,
+            secret: webhook.secret,
+            previous_secret_expires_at: webhook.previous_secret_expires_at,
         }
     }
 }
+
+/// What the receiver did with a test ping.
+///
+/// `status_code` is the receiver's actual HTTP status, or `null` with `error`
+/// set when no response arrived (connection refused, timeout, ...).
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct WebhookTestResponse {
+    pub webhook_id: Uuid,
+    pub success: bool,
+    pub status_code: Option<u16>,
+    pub latency_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -1,33 +1,124 @@
 use std::sync::Arc;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use rust_decimal::dec;
 
-use crate::application::dto::{AccountResponse, CreateAccountRequest};
-use crate::domain::entities::Account;
-use crate::domain::errors::ServiceError;
-use crate::domain::repositories::AccountRepository;
-use crate::domain::value_objects::Money;
+use crate::application::dto::{
+    AccountResponse, BalanceSnapshotResponse, BatchAccountLookupRequest, BatchAccountLookupResponse, CreateAccountRequest, Paginated, TransactionLimitsResponse,
+    UpdateAccountRequest, UpdateTransactionLimitsRequest,
+};
+use crate::domain::entities::{Account, Transaction};
+use crate::domain::errors::{DomainError, RepositoryError, ServiceError};
+use crate::domain::repositories::{AccountRepository, BalanceSnapshotRepository, TransactionRepository};
+use crate::application::services::WebhookService;
+use crate::domain::value_objects::{Money, TransactionLimits, WebhookEvent};
+
+/// Most accounts a single batch lookup may request
+pub const MAX_BATCH_LOOKUP_IDS: usize = 200;
+
+/// Ledger note on the credit that funds a new account
+const OPENING_CREDIT_DESCRIPTION: &str = "Opening balance";
 
 pub struct AccountService {
     repository: Arc<dyn AccountRepository>,
+    unique_business_names: bool,
+    balance_snapshot_repository: Option<Arc<dyn BalanceSnapshotRepository>>,
+    transaction_repository: Option<Arc<dyn TransactionRepository>>,
+    webhook_service: Option<Arc<WebhookService>>,
 }
 
 impl AccountService {
     pub fn new(repository: Arc<dyn AccountRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            unique_business_names: false,
+            balance_snapshot_repository: None,
+            transaction_repository: None,
+            webhook_service: None,
+        }
+    }
+
+    /// Reject new accounts whose business name is already taken (case-insensitive).
+    pub fn with_unique_business_names(mut self, enabled: bool) -> Self {
+        self.unique_business_names = enabled;
+        self
+    }
+
+    /// Serve balance history from recorded snapshots.
+    pub fn with_balance_snapshots(mut self, repository: Arc<dyn BalanceSnapshotRepository>) -> Self {
+        self.balance_snapshot_repository = Some(repository);
+        self
+    }
+
+    /// Record opening balances as credit transactions in this store.
+    pub fn with_opening_credits(mut self, repository: Arc<dyn TransactionRepository>) -> Self {
+        self.transaction_repository = Some(repository);
+        self
+    }
+
+    /// Fire `account.updated` webhooks when an account is renamed, frozen or unfrozen.
+    pub fn with_webhooks(mut self, webhook_service: Arc<WebhookService>) -> Self {
+        self.webhook_service = Some(webhook_service);
+        self
     }
 
+    async fn notify_updated(&self, account: &AccountResponse) {
+        if let Some(webhook_service) = &self.webhook_service {
+            webhook_service
+                .notify_async(account.id, WebhookEvent::AccountUpdated, account.clone(), None)
+                .await;
+        }
+    }
+
+    /// Create an account, funded with `initial_balance` when one is given.
+    ///
+    /// The opening credit is written in the same database transaction as the
+    /// account, so a funded account never exists without the deposit explaining it.
     pub async fn create_account(
         &self,
         request: CreateAccountRequest,
     ) -> Result<AccountResponse, ServiceError> {
-        let account = Account::new(request.business_name, Money::new(dec!(0.00))?)
-            .map_err(ServiceError::from)?;
-        let created_account = self
-            .repository
-            .create(&account)
-            .await
+        let initial_balance = Money::new(request.initial_balance.unwrap_or(dec!(0.00)))?;
+        let account = Account::new(request.business_name, initial_balance)
             .map_err(ServiceError::from)?;
+
+        if self.unique_business_names
+            && self
+                .repository
+                .exists_by_business_name(&account.business_name)
+                .await
+                .map_err(ServiceError::from)?
+        {
+            return Err(DomainError::DuplicateBusinessName(account.business_name).into());
+        }
+
+        let created = match &self.transaction_repository {
+            _ if initial_balance.is_zero() => self.repository.create(&account).await,
+            Some(transactions) => {
+                let credit = Transaction::new_credit(
+                    account.id,
+                    initial_balance,
+                    None,
+                    Some(OPENING_CREDIT_DESCRIPTION.to_string()),
+                )?;
+                transactions
+                    .execute_opening_credit(&account, &credit)
+                    .await
+                    .map(|_| account.clone())
+            }
+            None => {
+                return Err(ServiceError::ConfigurationError(
+                    "Opening balances require a transaction repository".to_string(),
+                ));
+            }
+        };
+        let created_account = created.map_err(|e| match e {
+            // Lost a race with a concurrent create; the unique index has the final say
+            RepositoryError::DuplicateEntry(_) if self.unique_business_names => {
+                DomainError::DuplicateBusinessName(account.business_name.clone()).into()
+            }
+            e => ServiceError::from(e),
+        })?;
         Ok(AccountResponse::from(created_account))
     }
 
@@ -40,14 +131,212 @@ impl AccountService {
         Ok(AccountResponse::from(account))
     }
 
-    pub async fn list_accounts(&self) -> Result<Vec<AccountResponse>, ServiceError> {
-        // Default pagination for now
-        let accounts = self
+    /// Look up many accounts in one query, reporting the ids that were not found.
+    pub async fn get_accounts(
+        &self,
+        request: BatchAccountLookupRequest,
+    ) -> Result<BatchAccountLookupResponse, ServiceError> {
+        if request.ids.len() > MAX_BATCH_LOOKUP_IDS {
+            return Err(ServiceError::ValidationError(format!(
+                "At most {} account ids can be looked up at once, got {}",
+                MAX_BATCH_LOOKUP_IDS,
+                request.ids.len()
+            )));
+        }
+
+        let mut ids = request.ids;
+        let mut seen = std::collections::HashSet::new();
+        ids.retain(|id| seen.insert(*id));
+
+        let accounts = if ids.is_empty() {
+            Vec::new()
+        } else {
+            self.repository.find_by_ids(&ids).await.map_err(ServiceError::from)?
+        };
+        let found: std::collections::HashSet<Uuid> = accounts.iter().map(|account| account.id).collect();
+
+        Ok(BatchAccountLookupResponse {
+            not_found: ids.into_iter().filter(|id| !found.contains(id)).collect(),
+            accounts: accounts.into_iter().map(AccountResponse::from).collect(),
+        })
+    }
+
+    /// Rename an account, applying the same name rules as creation.
+    pub async fn update_account(
+        &self,
+        id: Uuid,
+        request: UpdateAccountRequest,
+    ) -> Result<AccountResponse, ServiceError> {
+        let mut account = self
             .repository
-            .list(100, 0)
+            .find_by_id(id)
             .await
             .map_err(ServiceError::from)?;
-        Ok(accounts.into_iter().map(AccountResponse::from).collect())
+        let previous_name = account.business_name.clone();
+        account.update_business_name(request.business_name)?;
+
+        if self.unique_business_names
+            && !account.business_name.eq_ignore_ascii_case(&previous_name)
+            && self
+                .repository
+                .exists_by_business_name(&account.business_name)
+                .await
+                .map_err(ServiceError::from)?
+        {
+            return Err(DomainError::DuplicateBusinessName(account.business_name).into());
+        }
+
+        self.repository
+            .update_business_name(id, account.business_name.clone())
+            .await
+            .map_err(|e| match e {
+                RepositoryError::DuplicateEntry(_) if self.unique_business_names => {
+                    DomainError::DuplicateBusinessName(account.business_name.clone()).into()
+                }
+                e => ServiceError::from(e),
+            })?;
+
+        // Re-read so the response carries the `updated_at` the database recorded
+        let account = self.get_account(id).await?;
+        self.notify_updated(&account).await;
+        Ok(account)
+    }
+
+    /// Undo an accidental soft delete.
+    pub async fn restore_account(&self, id: Uuid) -> Result<AccountResponse, ServiceError> {
+        let account = self
+            .repository
+            .restore(id)
+            .await
+            .map_err(ServiceError::from)?;
+        Ok(AccountResponse::from(account))
+    }
+
+    /// Block all transactions touching the account until it is unfrozen.
+    pub async fn freeze_account(&self, id: Uuid) -> Result<AccountResponse, ServiceError> {
+        let mut account = self
+            .repository
+            .find_by_id(id)
+            .await
+            .map_err(ServiceError::from)?;
+        account.freeze()?;
+        self.repository
+            .update_status(id, account.status)
+            .await
+            .map_err(ServiceError::from)?;
+        let account = AccountResponse::from(account);
+        self.notify_updated(&account).await;
+        Ok(account)
+    }
+
+    pub async fn unfreeze_account(&self, id: Uuid) -> Result<AccountResponse, ServiceError> {
+        let mut account = self
+            .repository
+            .find_by_id(id)
+            .await
+            .map_err(ServiceError::from)?;
+        account.unfreeze()?;
+        self.repository
+            .update_status(id, account.status)
+            .await
+            .map_err(ServiceError::from)?;
+        let account = AccountResponse::from(account);
+        self.notify_updated(&account).await;
+        Ok(account)
+    }
+
+    /// Set the account's own transaction limits, replacing any previous ones.
+    pub async fn update_limits(
+        &self,
+        id: Uuid,
+        request: UpdateTransactionLimitsRequest,
+    ) -> Result<TransactionLimitsResponse, ServiceError> {
+        let limit = |amount: Option<rust_decimal::Decimal>| -> Result<Option<Money>, ServiceError> {
+            match amount {
+                Some(amount) if amount.is_zero() => Err(ServiceError::ValidationError(
+                    "Limits must be positive; use null to fall back to the default".to_string(),
+                )),
+                Some(amount) => Ok(Some(Money::new(amount)?)),
+                None => Ok(None),
+            }
+        };
+        let limits = TransactionLimits::new(
+            limit(request.per_transaction_limit)?,
+            limit(request.daily_debit_limit)?,
+        )
+        .with_low_balance(limit(request.low_balance_threshold)?);
+
+        self.repository
+            .update_limits(id, &limits)
+            .await
+            .map_err(ServiceError::from)?;
+
+        Ok(TransactionLimitsResponse {
+            account_id: id,
+            per_transaction_limit: limits.per_transaction.map(|m| m.amount()),
+            daily_debit_limit: limits.daily_debit.map(|m| m.amount()),
+            low_balance_threshold: limits.low_balance.map(|m| m.amount()),
+        })
+    }
+
+    /// The account's balance over time, oldest first, within the inclusive bounds.
+    pub async fn balance_history(
+        &self,
+        id: Uuid,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<BalanceSnapshotResponse>, ServiceError> {
+        let repository = self.balance_snapshot_repository.as_ref().ok_or_else(|| {
+            ServiceError::ValidationError("Balance snapshots are not enabled".to_string())
+        })?;
+
+        if !self.repository.exists(id).await.map_err(ServiceError::from)? {
+            return Err(DomainError::AccountNotFound(format!("Account {} not found", id)).into());
+        }
+
+        let snapshots = repository
+            .list_by_account(id, from, to)
+            .await
+            .map_err(ServiceError::from)?;
+        Ok(snapshots.into_iter().map(BalanceSnapshotResponse::from).collect())
+    }
+
+    /// Secret the account's HMAC signatures (webhooks, signed responses) are keyed with.
+    pub async fn signing_secret(&self, id: Uuid) -> Result<String, ServiceError> {
+        let account = self
+            .repository
+            .find_by_id(id)
+            .await
+            .map_err(ServiceError::from)?;
+        Ok(account.webhook_secret)
+    }
+
+    /// List accounts, optionally only those whose business name contains `query`.
+    ///
+    /// A blank query lists every account.
+    pub async fn list_accounts(
+        &self,
+        query: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Paginated<AccountResponse>, ServiceError> {
+        let (accounts, total) = match query.map(str::trim).filter(|query| !query.is_empty()) {
+            Some(query) => tokio::try_join!(
+                self.repository.search(query, limit, offset),
+                self.repository.count_matching(query),
+            ),
+            None => tokio::try_join!(
+                self.repository.list(limit, offset),
+                self.repository.count(),
+            ),
+        }
+        .map_err(ServiceError::from)?;
+        Ok(Paginated {
+            items: accounts.into_iter().map(AccountResponse::from).collect(),
+            total,
+            limit,
+            offset,
+        })
     }
 
     pub async fn health_check(&self) -> Result<(), ServiceError> {
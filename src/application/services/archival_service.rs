@@ -0,0 +1,78 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::domain::errors::ServiceError;
+use crate::domain::repositories::TransactionRepository;
+use crate::infrastructure::health;
+
+/// How many transactions a single archive statement moves
+const DEFAULT_BATCH_SIZE: i64 = 1000;
+
+/// Name the archival loop reports its heartbeat under
+const WORKER_NAME: &str = "transaction_archival";
+
+/// Moves transactions past the retention window out of the live `transactions` table.
+///
+/// Archival only relocates transaction rows; account balances are never touched.
+pub struct ArchivalService {
+    repository: Arc<dyn TransactionRepository>,
+    retention: chrono::Duration,
+    batch_size: i64,
+}
+
+impl ArchivalService {
+    pub fn new(repository: Arc<dyn TransactionRepository>, retention: chrono::Duration) -> Self {
+        Self {
+            repository,
+            retention,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Limit how many rows each archive statement moves, keeping lock times short.
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Archive every transaction older than the retention window.
+    /// Returns the number of transactions archived.
+    pub async fn run_once(&self) -> Result<u64, ServiceError> {
+        let cutoff = Utc::now() - self.retention;
+        let mut archived = 0;
+
+        loop {
+            let moved = self
+                .repository
+                .archive_before(cutoff, self.batch_size)
+                .await
+                .map_err(ServiceError::from)?;
+            archived += moved;
+
+            if moved < self.batch_size as u64 {
+                break;
+            }
+        }
+
+        Ok(archived)
+    }
+
+    /// Spawn a background task that archives old transactions every `interval`.
+    pub fn spawn(self: Arc<Self>, interval: Duration) {
+        health::register_worker(WORKER_NAME, interval);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                match self.run_once().await {
+                    Ok(0) => {}
+                    Ok(archived) => tracing::info!("Archived {} transactions", archived),
+                    Err(e) => tracing::error!("Transaction archival failed: {}", e),
+                }
+                health::record_heartbeat(WORKER_NAME);
+            }
+        });
+    }
+}
@@ -1,37 +1,172 @@
+use chrono::{Duration, Utc};
+use rand::Rng;
 use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::domain::errors::{DomainError, ServiceError};
+use crate::application::dto::{ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse, RotatedApiKeyResponse};
+use crate::domain::entities::{Account, ApiKey};
+use crate::domain::errors::{DomainError, RepositoryError, ServiceError};
 use crate::domain::repositories::ApiKeyRepository;
+use crate::domain::value_objects::Money;
+use crate::infrastructure::http_client::hmac_sha256_hex;
+
+/// Business name of the account created for the bootstrap admin key.
+pub const BOOTSTRAP_ACCOUNT_NAME: &str = "Bootstrap Admin";
+
+/// Prefix of raw API keys minted by the service.
+const API_KEY_PREFIX: &str = "dodo_";
+
+/// How many leading characters of a raw key are stored in the clear as `key_prefix`.
+const KEY_PREFIX_LENGTH: usize = 8;
+
+/// Hash a raw API key with bare SHA-256, the scheme used before peppering.
+fn hash_api_key(raw_key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(raw_key.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Non-secret leading characters of a raw key, stored to find and identify the key.
+fn key_prefix(raw_key: &str) -> &str {
+    raw_key
+        .char_indices()
+        .nth(KEY_PREFIX_LENGTH)
+        .map_or(raw_key, |(end, _)| &raw_key[..end])
+}
+
+/// Compare two hashes without stopping at the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// A new raw API key: the prefix followed by 32 bytes from the thread-local CSPRNG
+fn generate_raw_key() -> String {
+    let mut secret = [0u8; 32];
+    rand::rng().fill(&mut secret);
+    format!("{}{}", API_KEY_PREFIX, hex::encode(secret))
+}
 
 /// Principal authenticated via API Key
 #[derive(Debug, Clone)]
 pub struct AuthPrincipal {
+    /// The key's own account, acted on unless a request says otherwise
     pub account_id: Uuid,
+    /// Every account the key may act for, `account_id` first
+    pub account_ids: Vec<Uuid>,
+    pub api_key_id: Uuid,
+    pub rate_limit_per_hour: u32,
+    /// Authenticated with an admin key, which may manage any account
+    pub is_admin: bool,
+}
+
+impl AuthPrincipal {
+    pub fn is_authorized_for(&self, account_id: Uuid) -> bool {
+        self.account_ids.contains(&account_id)
+    }
 }
 
 pub struct AuthService {
     repository: Arc<dyn ApiKeyRepository>,
+    rotation_grace: Duration,
+    pepper: Option<String>,
+    accept_legacy_hashes: bool,
 }
 
 impl AuthService {
     pub fn new(repository: Arc<dyn ApiKeyRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            rotation_grace: Duration::hours(1),
+            pepper: None,
+            accept_legacy_hashes: true,
+        }
+    }
+
+    /// How long a rotated-out key keeps authenticating alongside its replacement.
+    pub fn with_rotation_grace(mut self, grace: Duration) -> Self {
+        self.rotation_grace = grace;
+        self
+    }
+
+    /// Store keys as HMAC-SHA256 under a server-side `pepper`, so a leaked
+    /// `api_keys` table cannot be checked against precomputed hashes.
+    pub fn with_pepper(mut self, pepper: String) -> Self {
+        self.pepper = Some(pepper);
+        self
+    }
+
+    /// With a pepper set, whether keys still stored as bare SHA-256 authenticate.
+    /// Each such key is re-hashed under the pepper the first time it is used, so
+    /// this can be turned off once every active key has been seen.
+    pub fn with_legacy_hashes(mut self, accept: bool) -> Self {
+        self.accept_legacy_hashes = accept;
+        self
+    }
+
+    /// Hash a raw API key the way it is stored in `api_keys.key_hash`.
+    fn hash_key(&self, raw_key: &str) -> String {
+        match &self.pepper {
+            Some(pepper) => hmac_sha256_hex(pepper, raw_key.as_bytes()),
+            None => hash_api_key(raw_key),
+        }
+    }
+
+    /// Find the key stored under `hash`, confirming the stored hash in constant time
+    async fn find_key(&self, hash: &str) -> Result<ApiKey, RepositoryError> {
+        let api_key = self.repository.find_by_hash(hash).await?;
+        if !constant_time_eq(api_key.key_hash.as_bytes(), hash.as_bytes()) {
+            return Err(RepositoryError::NotFound("API key not found".to_string()));
+        }
+        Ok(api_key)
+    }
+
+    /// Build a new key for `raw_key`, recording its hash and displayable prefix
+    fn new_key(&self, account_id: Uuid, raw_key: &str) -> ApiKey {
+        let mut api_key = ApiKey::new(account_id, self.hash_key(raw_key));
+        api_key.key_prefix = Some(key_prefix(raw_key).to_string());
+        api_key
+    }
+
+    /// Find the key for `raw_key`: narrow by its prefix, then tell the candidates apart
+    /// by hash. Keys stored before prefixes were recorded are looked up by hash, and
+    /// during a pepper migration keys still hashed the legacy way are accepted too.
+    /// Either kind is upgraded to the current hash and given its prefix once matched.
+    async fn find_key_for(&self, raw_key: &str) -> Result<ApiKey, RepositoryError> {
+        let hash = self.hash_key(raw_key);
+        let legacy_hash = (self.pepper.is_some() && self.accept_legacy_hashes).then(|| hash_api_key(raw_key));
+        let prefix = key_prefix(raw_key);
+        let matches = |stored: &str| {
+            constant_time_eq(stored.as_bytes(), hash.as_bytes())
+                || legacy_hash.as_ref().is_some_and(|legacy| constant_time_eq(stored.as_bytes(), legacy.as_bytes()))
+        };
+
+        let candidates = self.repository.find_by_prefix(prefix).await?;
+        let mut api_key = match candidates.into_iter().find(|key| matches(&key.key_hash)) {
+            Some(api_key) => api_key,
+            None => match (self.find_key(&hash).await, &legacy_hash) {
+                (Err(RepositoryError::NotFound(_)), Some(legacy)) => self.find_key(legacy).await?,
+                (result, _) => result?,
+            },
+        };
+
+        if api_key.key_hash != hash || api_key.key_prefix.as_deref() != Some(prefix) {
+            match self.repository.update_hash(api_key.id, &hash, prefix).await {
+                Ok(()) => {
+                    api_key.key_hash = hash;
+                    api_key.key_prefix = Some(prefix.to_string());
+                }
+                Err(e) => tracing::warn!("Failed to upgrade stored hash of API key {}: {}", api_key.id, e),
+            }
+        }
+        Ok(api_key)
     }
 
     /// Verify an API key string against stored hashes
     pub async fn verify_api_key(&self, raw_key: &str) -> Result<AuthPrincipal, ServiceError> {
-        // Hash the raw key
-        let mut hasher = Sha256::new();
-        hasher.update(raw_key.as_bytes());
-        let hash_bytes = hasher.finalize();
-        let hash_string = hex::encode(hash_bytes);
-
         // Check if key exists in repository
         let api_key = self
-            .repository
-            .find_by_hash(&hash_string)
+            .find_key_for(raw_key)
             .await
             .map_err(|e| match e {
                 crate::domain::errors::RepositoryError::NotFound(_) => {
@@ -40,6 +175,13 @@ impl AuthService {
                 _ => ServiceError::from(e),
             })?;
 
+        // Expiry is decided here rather than in SQL so it stays testable with mock repositories.
+        // A rotated-out key has its expiry pulled in to the end of the grace period, so it
+        // keeps working alongside its replacement until then.
+        if api_key.is_expired(Utc::now()) {
+            return Err(ServiceError::Domain(DomainError::ApiKeyExpired));
+        }
+
         // Update last used timestamp (fire and forget or await?)
         // For strict reliability we await, but failures here shouldn't block auth ideally.
         // For this assignment, we await.
@@ -54,6 +196,183 @@ impl AuthService {
 
         Ok(AuthPrincipal {
             account_id: api_key.account_id,
+            account_ids: api_key.account_ids(),
+            api_key_id: api_key.id,
+            rate_limit_per_hour: api_key.rate_limit_per_hour,
+            is_admin: api_key.is_admin,
         })
     }
+
+    /// Mint a new API key for `account_id` on behalf of `principal`.
+    ///
+    /// Callers may only create keys for accounts their own key is authorized for,
+    /// with a quota no higher than their own key's, and may only pass on access to
+    /// those same accounts. The raw key is returned once and never stored.
+    pub async fn create_api_key(
+        &self,
+        principal: &AuthPrincipal,
+        account_id: Uuid,
+        request: CreateApiKeyRequest,
+    ) -> Result<CreatedApiKeyResponse, ServiceError> {
+        if !principal.is_authorized_for(account_id) {
+            return Err(ServiceError::AuthorizationError(
+                "Cannot create API keys for another account".to_string(),
+            ));
+        }
+        if let Some(id) = request
+            .additional_account_ids
+            .iter()
+            .find(|id| !principal.is_authorized_for(**id))
+        {
+            return Err(ServiceError::AuthorizationError(format!(
+                "Cannot grant access to account {}",
+                id
+            )));
+        }
+
+        let rate_limit_per_hour = request
+            .rate_limit_per_hour
+            .unwrap_or(principal.rate_limit_per_hour);
+        if rate_limit_per_hour == 0 || rate_limit_per_hour > principal.rate_limit_per_hour {
+            return Err(ServiceError::ValidationError(format!(
+                "rate_limit_per_hour must be between 1 and {}",
+                principal.rate_limit_per_hour
+            )));
+        }
+
+        let expires_at = match request.ttl_seconds {
+            Some(0) => {
+                return Err(ServiceError::ValidationError(
+                    "ttl_seconds must be greater than 0".to_string(),
+                ));
+            }
+            Some(ttl) => {
+                let ttl = i64::try_from(ttl)
+                    .ok()
+                    .and_then(Duration::try_seconds)
+                    .ok_or_else(|| ServiceError::ValidationError("ttl_seconds is too large".to_string()))?;
+                Some(
+                    Utc::now()
+                        .checked_add_signed(ttl)
+                        .ok_or_else(|| ServiceError::ValidationError("ttl_seconds is too large".to_string()))?,
+                )
+            }
+            None => None,
+        };
+
+        let raw_key = generate_raw_key();
+
+        let mut api_key = self.new_key(account_id, &raw_key);
+        api_key.rate_limit_per_hour = rate_limit_per_hour;
+        api_key.expires_at = expires_at;
+        api_key.additional_account_ids = request
+            .additional_account_ids
+            .into_iter()
+            .filter(|id| *id != account_id)
+            .collect();
+
+        let created = self
+            .repository
+            .create(&api_key)
+            .await
+            .map_err(ServiceError::from)?;
+
+        Ok(CreatedApiKeyResponse::new(created, raw_key))
+    }
+
+    /// List the keys of `account_id`, newest first. Callers may only list keys of accounts they are authorized for.
+    pub async fn list_api_keys(
+        &self,
+        principal: &AuthPrincipal,
+        account_id: Uuid,
+    ) -> Result<Vec<ApiKeyResponse>, ServiceError> {
+        if !principal.is_authorized_for(account_id) {
+            return Err(ServiceError::AuthorizationError(
+                "Cannot list API keys of another account".to_string(),
+            ));
+        }
+
+        let keys = self
+            .repository
+            .find_by_account(account_id)
+            .await
+            .map_err(ServiceError::from)?;
+
+        // A key may span accounts the caller cannot see; those are left out of its listing
+        Ok(keys
+            .into_iter()
+            .map(|mut key| {
+                key.additional_account_ids.retain(|id| principal.is_authorized_for(*id));
+                ApiKeyResponse::from(key)
+            })
+            .collect())
+    }
+
+    /// Replace API key `key_id` with a freshly minted one.
+    ///
+    /// The new key inherits the old one's quota, expiry, accounts and admin status. The old key keeps
+    /// working for the rotation grace period and is then rejected as expired. Keys acting
+    /// for any account the caller is not authorized for are reported as not found.
+    pub async fn rotate_api_key(
+        &self,
+        principal: &AuthPrincipal,
+        key_id: Uuid,
+    ) -> Result<RotatedApiKeyResponse, ServiceError> {
+        let not_found = || ServiceError::from(RepositoryError::NotFound(format!("API key {} not found", key_id)));
+        let old_key = match self.repository.find_by_id(key_id).await {
+            Ok(key) if key.account_ids().into_iter().all(|id| principal.is_authorized_for(id)) => key,
+            Ok(_) | Err(RepositoryError::NotFound(_)) => return Err(not_found()),
+            Err(e) => return Err(ServiceError::from(e)),
+        };
+
+        let now = Utc::now();
+        if old_key.is_expired(now) {
+            return Err(ServiceError::Domain(DomainError::ApiKeyExpired));
+        }
+        if old_key.superseded_by.is_some() {
+            return Err(ServiceError::from(RepositoryError::DuplicateEntry(format!(
+                "API key {} has already been rotated",
+                key_id
+            ))));
+        }
+
+        let raw_key = generate_raw_key();
+        let mut replacement = self.new_key(old_key.account_id, &raw_key);
+        replacement.rate_limit_per_hour = old_key.rate_limit_per_hour;
+        replacement.expires_at = old_key.expires_at;
+        replacement.additional_account_ids = old_key.additional_account_ids.clone();
+        replacement.is_admin = old_key.is_admin;
+
+        let grace_ends_at = now + self.rotation_grace;
+        let created = self
+            .repository
+            .supersede(old_key.id, &replacement, grace_ends_at)
+            .await
+            .map_err(ServiceError::from)?;
+
+        let previous_key_expires_at = old_key
+            .expires_at
+            .map_or(grace_ends_at, |expires_at| expires_at.min(grace_ends_at));
+        Ok(RotatedApiKeyResponse {
+            api_key: CreatedApiKeyResponse::new(created, raw_key),
+            previous_key_id: old_key.id,
+            previous_key_expires_at,
+        })
+    }
+
+    /// Seed `raw_key` for a new admin account on a deployment with no API keys.
+    ///
+    /// Returns the created key, or `None` without touching anything when any key
+    /// already exists, so it is safe to run on every start, even from several
+    /// instances at once.
+    pub async fn bootstrap_admin_key(&self, raw_key: &str) -> Result<Option<ApiKey>, ServiceError> {
+        let account = Account::new(BOOTSTRAP_ACCOUNT_NAME.to_string(), Money::new(rust_decimal::Decimal::ZERO)?)?;
+        let mut api_key = self.new_key(account.id, raw_key);
+        api_key.is_admin = true;
+
+        self.repository
+            .create_first_key(&account, &api_key)
+            .await
+            .map_err(ServiceError::from)
+    }
 }
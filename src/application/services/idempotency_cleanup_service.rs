@@ -0,0 +1,103 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::domain::errors::ServiceError;
+use crate::domain::repositories::{IdempotencyRepository, TransactionRepository};
+use crate::infrastructure::health;
+
+/// How many idempotency keys a single statement releases
+const DEFAULT_BATCH_SIZE: i64 = 1000;
+
+/// Name the cleanup loop reports its heartbeat under
+const WORKER_NAME: &str = "idempotency_cleanup";
+
+/// Result of one cleanup pass
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdempotencyCleanupReport {
+    /// Transaction idempotency keys released for reuse
+    pub released_keys: u64,
+    /// Expired response snapshots deleted
+    pub deleted_snapshots: u64,
+}
+
+/// Releases idempotency keys older than the TTL so they stop occupying the
+/// unique index and can be reused, and deletes expired response snapshots.
+///
+/// Transactions are never deleted; they only stop blocking their key.
+pub struct IdempotencyCleanupService {
+    repository: Arc<dyn TransactionRepository>,
+    snapshots: Option<Arc<dyn IdempotencyRepository>>,
+    ttl: chrono::Duration,
+    batch_size: i64,
+}
+
+impl IdempotencyCleanupService {
+    pub fn new(repository: Arc<dyn TransactionRepository>, ttl: chrono::Duration) -> Self {
+        Self {
+            repository,
+            snapshots: None,
+            ttl,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Also delete expired response snapshots from this store.
+    pub fn with_snapshots(mut self, snapshots: Arc<dyn IdempotencyRepository>) -> Self {
+        self.snapshots = Some(snapshots);
+        self
+    }
+
+    /// Limit how many keys each statement releases, keeping lock times short.
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Release every key older than the TTL and delete expired snapshots.
+    pub async fn run_once(&self) -> Result<IdempotencyCleanupReport, ServiceError> {
+        let cutoff = Utc::now() - self.ttl;
+        let mut report = IdempotencyCleanupReport::default();
+
+        loop {
+            let released = self
+                .repository
+                .expire_idempotency_keys(cutoff, self.batch_size)
+                .await
+                .map_err(ServiceError::from)?;
+            report.released_keys += released;
+
+            if released < self.batch_size as u64 {
+                break;
+            }
+        }
+
+        if let Some(snapshots) = &self.snapshots {
+            report.deleted_snapshots = snapshots.delete_expired().await.map_err(ServiceError::from)?;
+        }
+
+        Ok(report)
+    }
+
+    /// Spawn a background task that cleans up idempotency keys every `interval`.
+    pub fn spawn(self: Arc<Self>, interval: Duration) {
+        health::register_worker(WORKER_NAME, interval);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                match self.run_once().await {
+                    Ok(report) if report.released_keys == 0 && report.deleted_snapshots == 0 => {}
+                    Ok(report) => tracing::info!(
+                        "Released {} idempotency keys and deleted {} expired snapshots",
+                        report.released_keys,
+                        report.deleted_snapshots
+                    ),
+                    Err(e) => tracing::error!("Idempotency cleanup failed: {}", e),
+                }
+                health::record_heartbeat(WORKER_NAME);
+            }
+        });
+    }
+}
@@ -1,9 +1,17 @@
 pub mod account_service;
+pub mod archival_service;
 pub mod auth_service;
+pub mod idempotency_cleanup_service;
+pub mod outbox_service;
+pub mod reconciliation_service;
 pub mod transaction_service;
 pub mod webhook_service;
 
 pub use account_service::AccountService;
+pub use archival_service::ArchivalService;
 pub use auth_service::{AuthPrincipal, AuthService};
+pub use idempotency_cleanup_service::{IdempotencyCleanupReport, IdempotencyCleanupService};
+pub use outbox_service::OutboxService;
+pub use reconciliation_service::{ReconciliationReport, ReconciliationService};
 pub use transaction_service::TransactionService;
 pub use webhook_service::WebhookService;
\ No newline at end of file
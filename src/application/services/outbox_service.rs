@@ -0,0 +1,104 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::application::dto::TransactionResponse;
+use crate::application::services::WebhookService;
+use crate::domain::entities::OutboxEvent;
+use crate::domain::errors::{RepositoryError, ServiceError};
+use crate::domain::repositories::{OutboxRepository, TransactionRepository};
+use crate::infrastructure::health;
+
+/// How long a claimed event is hidden from other relays; long enough to cover the
+/// dispatcher's retries. An event not marked sent by then is delivered again.
+const CLAIM_LEASE: Duration = Duration::from_secs(300);
+
+/// Name the outbox relay reports its heartbeat under
+const WORKER_NAME: &str = "outbox_relay";
+
+/// Delivers the webhook events the transaction repository records in `outbox_events`.
+///
+/// An event is marked sent only after every matching webhook was handed it, so a crash
+/// at any point means it is delivered again: at least once, possibly more than once.
+pub struct OutboxService {
+    outbox_repository: Arc<dyn OutboxRepository>,
+    transaction_repository: Arc<dyn TransactionRepository>,
+    webhook_service: Arc<WebhookService>,
+    batch_size: i64,
+}
+
+impl OutboxService {
+    pub fn new(
+        outbox_repository: Arc<dyn OutboxRepository>,
+        transaction_repository: Arc<dyn TransactionRepository>,
+        webhook_service: Arc<WebhookService>,
+    ) -> Self {
+        Self {
+            outbox_repository,
+            transaction_repository,
+            webhook_service,
+            batch_size: 100,
+        }
+    }
+
+    /// Limit how many events each poll claims.
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Deliver one batch of pending events.
+    /// Returns the number of events marked sent; the rest are retried once their lease runs out.
+    pub async fn run_once(&self) -> Result<usize, ServiceError> {
+        let events = self
+            .outbox_repository
+            .claim_unsent(self.batch_size, CLAIM_LEASE)
+            .await
+            .map_err(ServiceError::from)?;
+
+        let mut sent = 0;
+        for event in events {
+            match self.relay(&event).await {
+                Ok(()) => sent += 1,
+                Err(e) => tracing::error!("Failed to relay outbox event {}, will retry: {}", event.id, e),
+            }
+        }
+        Ok(sent)
+    }
+
+    async fn relay(&self, event: &OutboxEvent) -> Result<(), RepositoryError> {
+        match self.transaction_repository.find_by_id(event.transaction_id).await {
+            Ok(transaction) => {
+                let payload = serde_json::to_value(TransactionResponse::from(transaction))
+                    .map_err(|e| RepositoryError::DatabaseError(format!("Failed to serialize payload: {}", e)))?;
+                self.webhook_service
+                    .deliver_now(event.account_id, &event.event, payload)
+                    .await?;
+            }
+            // Archived or otherwise gone; there is nothing left to announce
+            Err(RepositoryError::NotFound(_)) => {
+                tracing::warn!(
+                    "Dropping outbox event {}: transaction {} no longer exists",
+                    event.id, event.transaction_id
+                );
+            }
+            Err(e) => return Err(e),
+        }
+
+        self.outbox_repository.mark_sent(event.id).await
+    }
+
+    /// Spawn a background task that delivers pending events every `interval`.
+    pub fn spawn(self: Arc<Self>, interval: Duration) {
+        health::register_worker(WORKER_NAME, interval);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_once().await {
+                    tracing::error!("Outbox relay failed: {}", e);
+                }
+                health::record_heartbeat(WORKER_NAME);
+            }
+        });
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde_json::json;
+
+use crate::domain::errors::ServiceError;
+use crate::domain::repositories::{BalanceDrift, TransactionRepository};
+use crate::domain::services::{DeliveryContext, WebhookDispatcher};
+use crate::infrastructure::{health, metrics};
+
+/// How many accounts a single reconciliation query compares
+const DEFAULT_BATCH_SIZE: i64 = 100;
+
+/// Name the reconciliation loop reports its heartbeat under
+const WORKER_NAME: &str = "balance_reconciliation";
+
+/// Event name of the alert sent when drift is found
+pub const DRIFT_ALERT_EVENT: &str = "reconciliation.drift_detected";
+
+/// Where drift alerts are delivered, signed like account webhooks
+struct DriftAlert {
+    dispatcher: Arc<dyn WebhookDispatcher>,
+    url: String,
+    secret: String,
+}
+
+/// Result of one pass over every account
+#[derive(Debug, Clone, Default)]
+pub struct ReconciliationReport {
+    pub checked: usize,
+    pub drifts: Vec<BalanceDrift>,
+}
+
+/// Periodically compares every account's stored balance and held funds with
+/// the sum of its completed transactions, as a safety net against drift.
+///
+/// Accounts are checked in pages with a pause between them to keep the load
+/// on the database low. Drift is only reported; balances are never corrected.
+pub struct ReconciliationService {
+    repository: Arc<dyn TransactionRepository>,
+    batch_size: i64,
+    batch_pause: Duration,
+    alert: Option<DriftAlert>,
+}
+
+impl ReconciliationService {
+    pub fn new(repository: Arc<dyn TransactionRepository>) -> Self {
+        Self {
+            repository,
+            batch_size: DEFAULT_BATCH_SIZE,
+            batch_pause: Duration::ZERO,
+            alert: None,
+        }
+    }
+
+    /// Limit how many accounts each query compares.
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Wait this long between pages.
+    pub fn with_batch_pause(mut self, pause: Duration) -> Self {
+        self.batch_pause = pause;
+        self
+    }
+
+    /// POST a `reconciliation.drift_detected` alert to `url` after every run that finds drift.
+    pub fn with_alert(mut self, dispatcher: Arc<dyn WebhookDispatcher>, url: String, secret: String) -> Self {
+        self.alert = Some(DriftAlert { dispatcher, url, secret });
+        self
+    }
+
+    /// Check every account once, then log, record and alert on any drift found.
+    pub async fn run_once(&self) -> Result<ReconciliationReport, ServiceError> {
+        let mut report = ReconciliationReport::default();
+        let mut after = None;
+
+        loop {
+            let page = self
+                .repository
+                .find_balance_drift(after, self.batch_size)
+                .await
+                .map_err(ServiceError::from)?;
+            report.checked += page.checked;
+            report.drifts.extend(page.drifts);
+
+            match page.last_account_id {
+                Some(last) if page.checked as i64 >= self.batch_size => after = Some(last),
+                _ => break,
+            }
+            if !self.batch_pause.is_zero() {
+                tokio::time::sleep(self.batch_pause).await;
+            }
+        }
+
+        for drift in &report.drifts {
+            tracing::warn!(
+                account_id = %drift.account_id,
+                stored_balance = %drift.stored_balance,
+                ledger_balance = %drift.ledger_balance,
+                stored_held_balance = %drift.stored_held_balance,
+                ledger_held_balance = %drift.ledger_held_balance,
+                "Account balance does not match its transactions"
+            );
+        }
+        metrics::record_reconciliation(report.checked, report.drifts.len());
+
+        if !report.drifts.is_empty() {
+            self.send_alert(&report).await;
+        }
+
+        Ok(report)
+    }
+
+    async fn send_alert(&self, report: &ReconciliationReport) {
+        let Some(alert) = &self.alert else {
+            return;
+        };
+
+        let payload = json!({
+            "event": DRIFT_ALERT_EVENT,
+            "checked": report.checked,
+            "detected_at": Utc::now(),
+            "drifts": report.drifts.iter().map(|drift| json!({
+                "account_id": drift.account_id,
+                "stored_balance": drift.stored_balance,
+                "ledger_balance": drift.ledger_balance,
+                "stored_held_balance": drift.stored_held_balance,
+                "ledger_held_balance": drift.ledger_held_balance,
+            })).collect::<Vec<_>>(),
+        });
+        let context = DeliveryContext {
+            event: DRIFT_ALERT_EVENT.to_string(),
+            ..DeliveryContext::default()
+        };
+
+        if let Err(e) = alert.dispatcher.dispatch(&alert.url, &payload, &alert.secret, &context).await {
+            tracing::error!("Failed to deliver balance drift alert: {}", e);
+        }
+    }
+
+    /// Spawn a background task that reconciles every account every `interval`.
+    pub fn spawn(self: Arc<Self>, interval: Duration) {
+        health::register_worker(WORKER_NAME, interval);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                match self.run_once().await {
+                    Ok(report) if report.drifts.is_empty() => {
+                        tracing::debug!("Reconciled {} accounts without drift", report.checked)
+                    }
+                    Ok(report) => tracing::error!(
+                        "Balance drift on {} of {} accounts",
+                        report.drifts.len(),
+                        report.checked
+                    ),
+                    Err(e) => tracing::error!("Balance reconciliation failed: {}", e),
+                }
+                health::record_heartbeat(WORKER_NAME);
+            }
+        });
+    }
+}
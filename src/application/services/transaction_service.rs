@@ -1,16 +1,66 @@
+use chrono::{NaiveTime, Utc};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::application::dto::{DepositRequest, TransactionResponse, TransferRequest, WithdrawRequest};
+use crate::application::dto::{
+    money_format, AccountStatsResponse, AuthorizeRequest, BatchOperation, BatchPreviewRequest, BatchPreviewResponse,
+    DepositRequest, Paginated, TransactionResponse, TransferDryRunResponse, TransferRequest, VerificationRequest,
+    WithdrawRequest,
+};
 use crate::application::services::WebhookService;
-use crate::domain::entities::Transaction;
-use crate::domain::errors::ServiceError;
-use crate::domain::repositories::TransactionRepository;
-use crate::domain::value_objects::{Money, WebhookEvent};
+use crate::domain::entities::{IdempotencyRecord, Transaction};
+use crate::domain::errors::{DomainError, RepositoryError, ServiceError};
+use crate::domain::repositories::{AccountRepository, IdempotencyRepository, TransactionFilter, TransactionRepository};
+use crate::domain::value_objects::{AuthorizationStatus, IdempotencyKeyFormat, Money, TransactionLimits, WebhookEvent};
+use crate::infrastructure::metrics;
+
+/// Fingerprint of the transaction a request asks for, used to detect a key reused for a different request.
+/// Hashes the parsed transaction rather than the request body, so `100` and `100.00` match.
+fn request_fingerprint(transaction: &Transaction) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(transaction.transaction_type.as_str().as_bytes());
+    for account_id in [transaction.from_account_id, transaction.to_account_id] {
+        match account_id {
+            Some(id) => hasher.update(id.as_bytes()),
+            None => hasher.update([0u8; 16]),
+        }
+    }
+    hasher.update(transaction.amount.to_minor_units().to_be_bytes());
+    if let Some(description) = &transaction.description {
+        hasher.update(b":");
+        hasher.update(description.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Build the (unsaved) transaction a batch operation stands for
+fn batch_transaction(operation: BatchOperation) -> Result<Transaction, DomainError> {
+    match operation {
+        BatchOperation::Deposit { account_id, amount } => {
+            Transaction::new_credit(account_id, Money::new(amount)?, None, None)
+        }
+        BatchOperation::Withdraw { account_id, amount } => {
+            Transaction::new_debit(account_id, Money::new(amount)?, None, None)
+        }
+        BatchOperation::Transfer { from_account_id, to_account_id, amount } => {
+            Transaction::new_transfer(from_account_id, to_account_id, Money::new(amount)?, None, None)
+        }
+    }
+}
 
 pub struct TransactionService {
     repository: Arc<dyn TransactionRepository>,
     webhook_service: Option<Arc<WebhookService>>,
+    idempotency_repository: Option<Arc<dyn IdempotencyRepository>>,
+    idempotency_ttl: chrono::Duration,
+    idempotency_key_format: IdempotencyKeyFormat,
+    allow_zero_amount: bool,
+    min_amount: Option<Money>,
+    account_repository: Option<Arc<dyn AccountRepository>>,
+    default_limits: TransactionLimits,
+    max_batch_legs: usize,
+    outbox_delivery: bool,
 }
 
 impl TransactionService {
@@ -21,6 +71,203 @@ impl TransactionService {
         Self { 
             repository,
             webhook_service,
+            idempotency_repository: None,
+            idempotency_ttl: chrono::Duration::hours(24),
+            idempotency_key_format: IdempotencyKeyFormat::Any,
+            allow_zero_amount: false,
+            min_amount: None,
+            account_repository: None,
+            default_limits: TransactionLimits::default(),
+            max_batch_legs: 100,
+            outbox_delivery: false,
+        }
+    }
+
+    /// Replay idempotent requests from a dedicated store of response snapshots,
+    /// kept for `ttl`, instead of re-querying the original transaction.
+    /// `ttl` is also how long a transaction's own key is honoured (24 hours by default).
+    pub fn with_idempotency_repository(
+        mut self,
+        repository: Arc<dyn IdempotencyRepository>,
+        ttl: chrono::Duration,
+    ) -> Self {
+        self.idempotency_repository = Some(repository);
+        self.idempotency_ttl = ttl;
+        self
+    }
+
+    /// Restrict which idempotency keys clients may send (any non-empty key by default).
+    pub fn with_idempotency_key_format(mut self, format: IdempotencyKeyFormat) -> Self {
+        self.idempotency_key_format = format;
+        self
+    }
+
+    /// Reject a request whose idempotency key does not match the configured format
+    fn check_idempotency_key(&self, key: Option<&str>) -> Result<(), ServiceError> {
+        match key {
+            Some(key) => Transaction::validate_idempotency_key(key, self.idempotency_key_format)
+                .map_err(ServiceError::Domain),
+            None => Ok(()),
+        }
+    }
+
+    /// Accept zero-amount `verification` transactions (disabled by default).
+    /// Deposits, withdrawals and transfers always require a positive amount.
+    pub fn with_zero_amount_transactions(mut self, enabled: bool) -> Self {
+        self.allow_zero_amount = enabled;
+        self
+    }
+
+    /// Reject deposits, withdrawals and transfers below `min_amount` (any positive amount by default).
+    pub fn with_min_amount(mut self, min_amount: Option<Money>) -> Self {
+        self.min_amount = min_amount;
+        self
+    }
+
+    /// Parse a deposit, withdrawal or transfer amount, enforcing the configured minimum.
+    /// Zero and negative amounts are left to the transaction itself to reject.
+    fn money_movement_amount(&self, amount: rust_decimal::Decimal) -> Result<Money, ServiceError> {
+        let money = Money::new(amount).map_err(ServiceError::Domain)?;
+        match self.min_amount {
+            // Compared within one currency; `Money` has a single currency for now
+            Some(min) if money.is_positive() && money < min => Err(ServiceError::Domain(DomainError::InvalidAmount(
+                format!("Amount must be at least {} {}", min, min.currency()),
+            ))),
+            _ => Ok(money),
+        }
+    }
+
+    /// Leave deposit, withdrawal and transfer webhooks to the outbox relay (disabled by default).
+    /// Enable together with the transaction repository's outbox events, or they are never sent.
+    pub fn with_outbox_delivery(mut self, enabled: bool) -> Self {
+        self.outbox_delivery = enabled;
+        self
+    }
+
+    /// The webhook service for deposits, withdrawals and transfers, unless the outbox relay sends them
+    fn money_movement_webhooks(&self) -> Option<&Arc<WebhookService>> {
+        self.webhook_service.as_ref().filter(|_| !self.outbox_delivery)
+    }
+
+    /// Reject batches of more than `max` operations before any account is locked (100 by default).
+    pub fn with_max_batch_legs(mut self, max: usize) -> Self {
+        self.max_batch_legs = max;
+        self
+    }
+
+    /// Enforce per-account transaction limits on withdrawals and outgoing transfers.
+    /// Limits an account does not set itself fall back to `defaults`.
+    pub fn with_transaction_limits(
+        mut self,
+        account_repository: Arc<dyn AccountRepository>,
+        defaults: TransactionLimits,
+    ) -> Self {
+        self.account_repository = Some(account_repository);
+        self.default_limits = defaults;
+        self
+    }
+
+    /// Reject a debit of `amount` that would exceed the account's limits.
+    /// Checked before the debit runs, so concurrent debits can each pass against the same daily usage.
+    async fn check_limits(&self, account_id: Uuid, amount: Money) -> Result<(), ServiceError> {
+        let Some(accounts) = &self.account_repository else {
+            return Ok(());
+        };
+
+        let limits = accounts
+            .find_limits(account_id)
+            .await
+            .map_err(ServiceError::from)?
+            .or(self.default_limits);
+        if limits.is_unlimited() {
+            return Ok(());
+        }
+
+        let debited_today = match limits.daily_debit {
+            Some(_) => {
+                let midnight = Utc::now().date_naive().and_time(NaiveTime::MIN).and_utc();
+                let total = self
+                    .repository
+                    .sum_debits_since(account_id, midnight)
+                    .await
+                    .map_err(ServiceError::from)?;
+                Money::new(total)?
+            }
+            None => Money::new(rust_decimal::Decimal::ZERO)?,
+        };
+
+        limits.check(amount, debited_today).map_err(ServiceError::Domain)
+    }
+
+    /// Look up a stored response for `key`.
+    /// A key first used with a different request is rejected as a conflict.
+    async fn replay_snapshot(
+        &self,
+        key: Option<&str>,
+        fingerprint: &str,
+    ) -> Result<Option<TransactionResponse>, ServiceError> {
+        let (Some(repository), Some(key)) = (&self.idempotency_repository, key) else {
+            return Ok(None);
+        };
+
+        match repository.find_by_key(key).await {
+            Ok(record) => {
+                if record.request_fingerprint != fingerprint {
+                    return Err(ServiceError::Domain(DomainError::DuplicateTransaction(
+                        key.to_string(),
+                    )));
+                }
+                tracing::info!("Idempotency replay for key: {}", key);
+                serde_json::from_value(record.response)
+                    .map(Some)
+                    .map_err(|e| ServiceError::InternalError(format!("Corrupt idempotency snapshot: {}", e)))
+            }
+            Err(RepositoryError::NotFound(_)) => Ok(None),
+            Err(e) => Err(ServiceError::from(e)),
+        }
+    }
+
+    /// Return the transaction already recorded under `key`, via the unique index on live keys.
+    /// A transaction created by a different request is a conflict, not a replay; ones
+    /// recorded before fingerprints were stored are returned as-is.
+    async fn replay_transaction(&self, key: &str, fingerprint: &str) -> Result<TransactionResponse, ServiceError> {
+        let since = Utc::now() - self.idempotency_ttl;
+        let existing = match self.repository.find_recent_by_idempotency_key(key, since).await {
+            Ok(existing) => existing,
+            // The key is past its window but the cleanup job has not released it yet
+            Err(RepositoryError::NotFound(_)) => {
+                return Err(ServiceError::Domain(DomainError::DuplicateTransaction(key.to_string())));
+            }
+            Err(e) => return Err(ServiceError::from(e)),
+        };
+
+        match existing.request_fingerprint.as_deref() {
+            Some(stored) if stored != fingerprint => Err(ServiceError::Domain(
+                DomainError::DuplicateTransaction(key.to_string()),
+            )),
+            _ => Ok(TransactionResponse::from(existing)),
+        }
+    }
+
+    /// Store the response for `key` so retries replay it.
+    /// The transaction has already committed, so failures are logged rather than returned.
+    async fn store_snapshot(&self, key: Option<&str>, fingerprint: String, response: &TransactionResponse) {
+        let (Some(repository), Some(key)) = (&self.idempotency_repository, key) else {
+            return;
+        };
+
+        // Replays are re-serialized in the format of the request that replays them
+        let snapshot = match money_format::plain(|| serde_json::to_value(response)) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                tracing::warn!("Failed to serialize idempotency snapshot for key {}: {}", key, e);
+                return;
+            }
+        };
+
+        let record = IdempotencyRecord::new(key.to_string(), fingerprint, snapshot, self.idempotency_ttl);
+        if let Err(e) = repository.save(&record).await {
+            tracing::warn!("Failed to store idempotency snapshot for key {}: {}", key, e);
         }
     }
 
@@ -28,13 +275,25 @@ impl TransactionService {
     pub async fn deposit(
         &self,
         request: DepositRequest,
+        request_id: Option<String>,
     ) -> Result<TransactionResponse, ServiceError> {
-        let money = Money::new(request.amount).map_err(ServiceError::Domain)?;
+        self.check_idempotency_key(request.idempotency_key.as_deref())?;
+        let money = self.money_movement_amount(request.amount)?;
         let transaction = Transaction::new_credit(
             request.account_id, 
             money, 
-            request.idempotency_key.clone()
-        ).map_err(ServiceError::Domain)?;
+            request.idempotency_key.clone(),
+            request.description.clone(),
+        )
+        .map_err(ServiceError::Domain)?;
+        let fingerprint = request_fingerprint(&transaction);
+        if let Some(replay) = self
+            .replay_snapshot(request.idempotency_key.as_deref(), &fingerprint)
+            .await?
+        {
+            return Ok(replay);
+        }
+        let transaction = transaction.with_request_fingerprint(fingerprint.clone());
 
         let created_transaction = match self.repository.execute_credit(&transaction).await {
             Ok(tx) => tx,
@@ -43,24 +302,25 @@ impl TransactionService {
                     // Idempotency hit: return existing transaction
                     tracing::info!("Idempotency hit for key: {:?}", request.idempotency_key);
                     if let Some(key) = &request.idempotency_key {
-                        return self.repository.find_by_idempotency_key(key)
-                            .await
-                            .map(TransactionResponse::from)
-                            .map_err(ServiceError::from);
+                        return self.replay_transaction(key, &fingerprint).await;
                     }
                 }
                 return Err(ServiceError::from(e));
             }
         };
 
+        metrics::record_transaction(created_transaction.transaction_type.as_str(), created_transaction.amount.amount());
         let response = TransactionResponse::from(created_transaction);
+        self.store_snapshot(request.idempotency_key.as_deref(), fingerprint, &response)
+            .await;
         
-        if let Some(webhook_service) = &self.webhook_service {
+        if let Some(webhook_service) = self.money_movement_webhooks() {
              webhook_service.notify_async(
                 request.account_id,
-                WebhookEvent::TransactionCompleted, 
+                WebhookEvent::TransactionDepositCompleted, 
                 response.clone(),
-            );
+                request_id,
+            ).await;
         }
 
         Ok(response)
@@ -70,13 +330,26 @@ impl TransactionService {
     pub async fn withdraw(
         &self,
         request: WithdrawRequest,
+        request_id: Option<String>,
     ) -> Result<TransactionResponse, ServiceError> {
-        let money = Money::new(request.amount).map_err(ServiceError::Domain)?;
+        self.check_idempotency_key(request.idempotency_key.as_deref())?;
+        let money = self.money_movement_amount(request.amount)?;
         let transaction = Transaction::new_debit(
             request.account_id, 
             money, 
-            request.idempotency_key.clone()
-        ).map_err(ServiceError::Domain)?;
+            request.idempotency_key.clone(),
+            request.description.clone(),
+        )
+        .map_err(ServiceError::Domain)?;
+        let fingerprint = request_fingerprint(&transaction);
+        if let Some(replay) = self
+            .replay_snapshot(request.idempotency_key.as_deref(), &fingerprint)
+            .await?
+        {
+            return Ok(replay);
+        }
+        let transaction = transaction.with_request_fingerprint(fingerprint.clone());
+        self.check_limits(request.account_id, money).await?;
 
         let created_transaction = match self.repository.execute_debit(&transaction).await {
             Ok(tx) => tx,
@@ -85,24 +358,30 @@ impl TransactionService {
                     // Idempotency hit
                     tracing::info!("Idempotency hit for key: {:?}", request.idempotency_key);
                     if let Some(key) = &request.idempotency_key {
-                        return self.repository.find_by_idempotency_key(key)
-                            .await
-                            .map(TransactionResponse::from)
-                            .map_err(ServiceError::from);
+                        return self.replay_transaction(key, &fingerprint).await;
                     }
                 }
                 return Err(ServiceError::from(e));
             }
         };
 
+        metrics::record_transaction(created_transaction.transaction_type.as_str(), created_transaction.amount.amount());
         let response = TransactionResponse::from(created_transaction);
+        self.store_snapshot(request.idempotency_key.as_deref(), fingerprint, &response)
+            .await;
         
-        if let Some(webhook_service) = &self.webhook_service {
+        if let Some(webhook_service) = self.money_movement_webhooks() {
              webhook_service.notify_async(
                 request.account_id,
-                WebhookEvent::TransactionCompleted, 
+                WebhookEvent::TransactionWithdrawalCompleted, 
                 response.clone(),
-            );
+                request_id.clone(),
+            ).await;
+        }
+        if let Some(webhook_service) = &self.webhook_service {
+            webhook_service
+                .notify_balance_threshold(request.account_id, response.id, money, request_id)
+                .await;
         }
 
         Ok(response)
@@ -112,15 +391,27 @@ impl TransactionService {
     pub async fn transfer(
         &self,
         request: TransferRequest,
+        request_id: Option<String>,
     ) -> Result<TransactionResponse, ServiceError> {
-        let money = Money::new(request.amount).map_err(ServiceError::Domain)?;
+        self.check_idempotency_key(request.idempotency_key.as_deref())?;
+        let money = self.money_movement_amount(request.amount)?;
         let transaction = Transaction::new_transfer(
             request.from_account_id,
             request.to_account_id,
             money,
             request.idempotency_key.clone(),
+            request.description.clone(),
         )
         .map_err(ServiceError::Domain)?;
+        let fingerprint = request_fingerprint(&transaction);
+        if let Some(replay) = self
+            .replay_snapshot(request.idempotency_key.as_deref(), &fingerprint)
+            .await?
+        {
+            return Ok(replay);
+        }
+        let transaction = transaction.with_request_fingerprint(fingerprint.clone());
+        self.check_limits(request.from_account_id, money).await?;
 
         let created_transaction = match self.repository.execute_transfer(&transaction).await {
             Ok(tx) => tx,
@@ -129,44 +420,315 @@ impl TransactionService {
                     // Idempotency hit
                     tracing::info!("Idempotency hit for key: {:?}", request.idempotency_key);
                     if let Some(key) = &request.idempotency_key {
-                        return self.repository.find_by_idempotency_key(key)
-                            .await
-                            .map(TransactionResponse::from)
-                            .map_err(ServiceError::from);
+                        return self.replay_transaction(key, &fingerprint).await;
                     }
                 }
                 return Err(ServiceError::from(e));
             }
         };
 
+        metrics::record_transaction(created_transaction.transaction_type.as_str(), created_transaction.amount.amount());
         let response = TransactionResponse::from(created_transaction);
+        self.store_snapshot(request.idempotency_key.as_deref(), fingerprint, &response)
+            .await;
         
-        if let Some(webhook_service) = &self.webhook_service {
+        if let Some(webhook_service) = self.money_movement_webhooks() {
              webhook_service.notify_async(
                 request.from_account_id,
+                WebhookEvent::TransactionTransferCompleted,
+                response.clone(),
+                request_id.clone(),
+            ).await;
+        }
+        if let Some(webhook_service) = &self.webhook_service {
+            webhook_service
+                .notify_balance_threshold(request.from_account_id, response.id, money, request_id)
+                .await;
+        }
+
+        Ok(response)
+    }
+
+    /// Report whether `request` would go through and the balances it would leave, without
+    /// persisting a transaction, storing an idempotency key or sending webhooks.
+    /// Requests `transfer` rejects before reaching the ledger (bad amount, over a limit) fail the same way.
+    pub async fn simulate_transfer(&self, request: TransferRequest) -> Result<TransferDryRunResponse, ServiceError> {
+        let money = self.money_movement_amount(request.amount)?;
+        let transaction = Transaction::new_transfer(request.from_account_id, request.to_account_id, money, None, None)
+            .map_err(ServiceError::Domain)?;
+        self.check_limits(request.from_account_id, money).await?;
+
+        let preview = self
+            .repository
+            .simulate_transfer(&transaction)
+            .await
+            .map_err(ServiceError::from)?;
+        Ok(TransferDryRunResponse::from(preview))
+    }
+
+    /// Record a zero-amount heartbeat on an account. The balance never changes.
+    #[tracing::instrument(skip(self))]
+    pub async fn verify(
+        &self,
+        request: VerificationRequest,
+        request_id: Option<String>,
+    ) -> Result<TransactionResponse, ServiceError> {
+        if !self.allow_zero_amount {
+            return Err(ServiceError::Domain(DomainError::InvalidAmount(
+                "Zero-amount transactions are disabled".to_string(),
+            )));
+        }
+
+        self.check_idempotency_key(request.idempotency_key.as_deref())?;
+        let transaction = Transaction::new_verification(request.account_id, request.idempotency_key.clone())
+            .map_err(ServiceError::Domain)?;
+        let fingerprint = request_fingerprint(&transaction);
+        if let Some(replay) = self
+            .replay_snapshot(request.idempotency_key.as_deref(), &fingerprint)
+            .await?
+        {
+            return Ok(replay);
+        }
+        let transaction = transaction.with_request_fingerprint(fingerprint.clone());
+
+        let created_transaction = match self.repository.execute_verification(&transaction).await {
+            Ok(tx) => tx,
+            Err(RepositoryError::DuplicateEntry(_)) if request.idempotency_key.is_some() => {
+                tracing::info!("Idempotency hit for key: {:?}", request.idempotency_key);
+                let key = request.idempotency_key.as_deref().unwrap_or_default();
+                return self.replay_transaction(key, &fingerprint).await;
+            }
+            Err(e) => return Err(ServiceError::from(e)),
+        };
+
+        metrics::record_transaction(created_transaction.transaction_type.as_str(), created_transaction.amount.amount());
+        let response = TransactionResponse::from(created_transaction);
+        self.store_snapshot(request.idempotency_key.as_deref(), fingerprint, &response)
+            .await;
+
+        if let Some(webhook_service) = &self.webhook_service {
+            webhook_service.notify_async(
+                request.account_id,
                 WebhookEvent::TransactionCompleted,
                 response.clone(),
-            );
+                request_id,
+            ).await;
         }
 
         Ok(response)
     }
 
+    /// Hold funds on an account. Counts against the account's limits like a withdrawal.
+    #[tracing::instrument(skip(self))]
+    pub async fn authorize(&self, request: AuthorizeRequest) -> Result<TransactionResponse, ServiceError> {
+        self.check_idempotency_key(request.idempotency_key.as_deref())?;
+        let money = Money::new(request.amount).map_err(ServiceError::Domain)?;
+        let transaction = Transaction::new_authorization(request.account_id, money, request.idempotency_key.clone())
+            .map_err(ServiceError::Domain)?;
+        let fingerprint = request_fingerprint(&transaction);
+        if let Some(replay) = self
+            .replay_snapshot(request.idempotency_key.as_deref(), &fingerprint)
+            .await?
+        {
+            return Ok(replay);
+        }
+        let transaction = transaction.with_request_fingerprint(fingerprint.clone());
+        self.check_limits(request.account_id, money).await?;
+
+        let created_transaction = match self.repository.execute_authorization(&transaction).await {
+            Ok(tx) => tx,
+            Err(RepositoryError::DuplicateEntry(_)) if request.idempotency_key.is_some() => {
+                tracing::info!("Idempotency hit for key: {:?}", request.idempotency_key);
+                let key = request.idempotency_key.as_deref().unwrap_or_default();
+                return self.replay_transaction(key, &fingerprint).await;
+            }
+            Err(e) => return Err(ServiceError::from(e)),
+        };
+
+        let response = TransactionResponse::from(created_transaction);
+        self.store_snapshot(request.idempotency_key.as_deref(), fingerprint, &response)
+            .await;
+
+        Ok(response)
+    }
+
+    /// Debit the funds held by a pending authorization
+    #[tracing::instrument(skip(self))]
+    pub async fn capture(&self, id: Uuid, request_id: Option<String>) -> Result<TransactionResponse, ServiceError> {
+        self.ensure_can_settle(id, AuthorizationStatus::Captured).await?;
+
+        let captured = self.repository.execute_capture(id).await.map_err(ServiceError::from)?;
+
+        metrics::record_transaction(captured.transaction_type.as_str(), captured.amount.amount());
+        let response = TransactionResponse::from(captured);
+
+        if let (Some(webhook_service), Some(account_id)) = (&self.webhook_service, response.from_account_id) {
+            webhook_service.notify_async(
+                account_id,
+                WebhookEvent::TransactionCompleted,
+                response.clone(),
+                request_id,
+            ).await;
+        }
+
+        Ok(response)
+    }
+
+    /// Release the funds held by a pending authorization
+    #[tracing::instrument(skip(self))]
+    pub async fn void(&self, id: Uuid) -> Result<TransactionResponse, ServiceError> {
+        self.ensure_can_settle(id, AuthorizationStatus::Voided).await?;
+
+        let voided = self.repository.execute_void(id).await.map_err(ServiceError::from)?;
+        Ok(TransactionResponse::from(voided))
+    }
+
+    /// Reject settling anything but a pending authorization with a clear error.
+    /// The repository repeats the check under a row lock.
+    async fn ensure_can_settle(&self, id: Uuid, next: AuthorizationStatus) -> Result<(), ServiceError> {
+        let mut authorization = self.repository.find_by_id(id).await.map_err(ServiceError::from)?;
+        authorization
+            .transition_authorization(next)
+            .map_err(ServiceError::Domain)
+    }
+
+    /// Project the balances a batch of operations would produce without persisting anything.
+    /// Malformed operations (e.g. non-positive amounts) and oversized batches are rejected up front.
+    pub async fn preview_batch(&self, request: BatchPreviewRequest) -> Result<BatchPreviewResponse, ServiceError> {
+        if request.operations.is_empty() {
+            return Err(ServiceError::ValidationError("Batch must contain at least one operation".to_string()));
+        }
+        if request.operations.len() > self.max_batch_legs {
+            return Err(ServiceError::ValidationError(format!(
+                "Batch has {} operations; at most {} are allowed",
+                request.operations.len(),
+                self.max_batch_legs
+            )));
+        }
+
+        let operations = request
+            .operations
+            .into_iter()
+            .enumerate()
+            .map(|(index, operation)| {
+                batch_transaction(operation)
+                    .map_err(|e| ServiceError::ValidationError(format!("Operation {}: {}", index, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let preview = self
+            .repository
+            .preview_batch(&operations)
+            .await
+            .map_err(ServiceError::from)?;
+
+        Ok(BatchPreviewResponse::from(preview))
+    }
+
+    pub async fn get_transaction(&self, id: Uuid) -> Result<TransactionResponse, ServiceError> {
+        let transaction = self
+            .repository
+            .find_by_id(id)
+            .await
+            .map_err(ServiceError::from)?;
+        Ok(TransactionResponse::from(transaction))
+    }
+
     pub async fn get_history(
         &self,
         account_id: Uuid,
+        filter: TransactionFilter,
         limit: i64,
         offset: i64,
-    ) -> Result<Vec<TransactionResponse>, ServiceError> {
-        let transactions = self
+    ) -> Result<Paginated<TransactionResponse>, ServiceError> {
+        let (transactions, total) = tokio::try_join!(
+            self.repository.list_by_account_filtered(account_id, &filter, limit, offset),
+            self.repository.count_by_account(account_id, &filter),
+        )
+        .map_err(ServiceError::from)?;
+
+        Ok(Paginated {
+            items: transactions
+                .into_iter()
+                .map(TransactionResponse::from)
+                .collect(),
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    pub async fn account_stats(
+        &self,
+        account_id: Uuid,
+        filter: TransactionFilter,
+    ) -> Result<AccountStatsResponse, ServiceError> {
+        let stats = self
             .repository
-            .list_by_account(account_id, limit, offset)
+            .account_stats(account_id, &filter)
             .await
             .map_err(ServiceError::from)?;
+        Ok(AccountStatsResponse::from(stats))
+    }
 
-        Ok(transactions
-            .into_iter()
-            .map(TransactionResponse::from)
-            .collect())
+    /// One page of the combined history of `account_ids`, newest first
+    pub async fn get_history_for_accounts(
+        &self,
+        account_ids: &[Uuid],
+        filter: TransactionFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Paginated<TransactionResponse>, ServiceError> {
+        let (transactions, total) = tokio::try_join!(
+            self.repository.list_by_accounts_filtered(account_ids, &filter, limit, offset),
+            self.repository.count_by_accounts(account_ids, &filter),
+        )
+        .map_err(ServiceError::from)?;
+
+        Ok(Paginated {
+            items: transactions
+                .into_iter()
+                .map(TransactionResponse::from)
+                .collect(),
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Read an account's history matching `filter` in pages of `page_size`, newest first.
+    ///
+    /// Pages are fetched by a background task as the receiver drains them, so at most a
+    /// couple of pages are held in memory. An open-ended range is pinned to the current
+    /// time so transactions created mid-export cannot shift the pages. The task stops
+    /// once the receiver is dropped.
+    pub fn export_history(
+        &self,
+        account_id: Uuid,
+        mut filter: TransactionFilter,
+        page_size: i64,
+    ) -> tokio::sync::mpsc::Receiver<Result<Vec<TransactionResponse>, ServiceError>> {
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let repository = self.repository.clone();
+        filter.to = Some(filter.to.map_or_else(Utc::now, |to| to.min(Utc::now())));
+
+        tokio::spawn(async move {
+            let mut offset = 0;
+            loop {
+                let page = repository
+                    .list_by_account_filtered(account_id, &filter, page_size, offset)
+                    .await
+                    .map_err(ServiceError::from);
+                let last_page = !matches!(&page, Ok(rows) if rows.len() as i64 == page_size);
+                let page = page.map(|rows| rows.into_iter().map(TransactionResponse::from).collect());
+
+                if sender.send(page).await.is_err() || last_page {
+                    return;
+                }
+                offset += page_size;
+            }
+        });
+
+        receiver
     }
 }
@@ -1,17 +1,58 @@
-use std::sync::Arc;
-use tokio::task;
-use tracing::{error, Instrument};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use serde_json::{json, Value};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
+use tracing::{error, warn, Instrument};
 use uuid::Uuid;
 
-use crate::domain::services::WebhookDispatcher;
+use crate::application::dto::money_format;
+use crate::domain::entities::{Webhook, WebhookDeadLetter, WebhookOutboxEntry};
+use crate::domain::errors::RepositoryError;
+use crate::domain::services::webhook_payload_schema;
+use crate::domain::services::{DeliveryContext, WebhookDispatcher};
 use crate::domain::repositories::{AccountRepository, WebhookRepository};
-use crate::domain::value_objects::WebhookEvent;
+use crate::domain::value_objects::{Money, WebhookEvent, WebhookOverflowPolicy};
+use crate::infrastructure::health;
+
+/// Dead-letter reason for deliveries dropped because the dispatch pool was full
+const SATURATED_REASON: &str = "Webhook dispatch queue saturated";
+
+/// Dead-letter reason for events whose payload could not be serialized to JSON
+const UNSERIALIZABLE_REASON: &str = "Webhook payload could not be serialized";
+
+/// Name the outbox poller reports its heartbeat under
+const OUTBOX_WORKER_NAME: &str = "webhook_outbox";
 
 #[derive(Clone)]
 pub struct WebhookService {
     webhook_repository: Arc<dyn WebhookRepository>,
     account_repository: Arc<dyn AccountRepository>,
     dispatcher: Arc<dyn WebhookDispatcher>,
+    validate_payloads: bool,
+    propagate_request_id: bool,
+    sequence_numbers: bool,
+    dispatch_tasks: Arc<Mutex<JoinSet<()>>>,
+    dispatch_slots: Option<Arc<Semaphore>>,
+    overflow_policy: WebhookOverflowPolicy,
+    block_timeout: Duration,
+}
+
+/// Outcome of asking for room in the dispatch pool
+enum DispatchSlot {
+    /// No limit is configured
+    Unbounded,
+    /// Held by the dispatch task until it finishes
+    Acquired(OwnedSemaphorePermit),
+    /// The pool stayed full; the overflow policy applies
+    Saturated,
+}
+
+/// Per-delivery settings copied into spawned tasks
+#[derive(Clone, Copy)]
+struct DeliveryOptions {
+    validate_payloads: bool,
+    sequence_numbers: bool,
 }
 
 impl WebhookService {
@@ -24,53 +65,143 @@ impl WebhookService {
             webhook_repository,
             account_repository,
             dispatcher,
+            validate_payloads: true,
+            propagate_request_id: true,
+            sequence_numbers: true,
+            dispatch_tasks: Arc::new(Mutex::new(JoinSet::new())),
+            dispatch_slots: None,
+            overflow_policy: WebhookOverflowPolicy::default(),
+            block_timeout: Duration::from_secs(1),
         }
     }
 
+    /// Toggle schema validation of outbound payloads (enabled by default).
+    /// Payloads that fail validation are dead-lettered instead of dispatched.
+    pub fn with_payload_validation(mut self, enabled: bool) -> Self {
+        self.validate_payloads = enabled;
+        self
+    }
+
+    /// Toggle forwarding the triggering request's id to webhook consumers (enabled by default).
+    /// The id is added to the payload as `request_id` and sent as `X-Dodo-Request-Id`.
+    pub fn with_request_id_propagation(mut self, enabled: bool) -> Self {
+        self.propagate_request_id = enabled;
+        self
+    }
+
+    /// Toggle numbering deliveries per webhook (enabled by default).
+    /// Each event gets the next value of a counter stored with the webhook, sent as
+    /// `sequence` in the payload and `X-Dodo-Sequence`, so receivers can detect gaps and reorder.
+    pub fn with_sequence_numbers(mut self, enabled: bool) -> Self {
+        self.sequence_numbers = enabled;
+        self
+    }
+
+    /// Allow at most `max_pending` dispatch tasks in flight (unbounded by default).
+    /// Events arriving while the pool is full are handled by `policy`; `block_timeout`
+    /// bounds how long the `block` policy holds up the triggering request.
+    pub fn with_dispatch_limit(
+        mut self,
+        max_pending: usize,
+        policy: WebhookOverflowPolicy,
+        block_timeout: Duration,
+    ) -> Self {
+        self.dispatch_slots = Some(Arc::new(Semaphore::new(max_pending)));
+        self.overflow_policy = policy;
+        self.block_timeout = block_timeout;
+        self
+    }
+
+    /// Number of spawned dispatch tasks that have not finished yet
+    pub fn pending_dispatches(&self) -> usize {
+        let mut tasks = self.dispatch_tasks.lock().unwrap();
+        reap_finished(&mut tasks);
+        tasks.len()
+    }
+
+    /// Wait for every dispatch spawned so far, including ones spawned while waiting.
+    pub async fn flush(&self) {
+        loop {
+            let mut tasks = std::mem::take(&mut *self.dispatch_tasks.lock().unwrap());
+            if tasks.is_empty() {
+                return;
+            }
+            while let Some(result) = tasks.join_next().await {
+                log_join_error(result);
+            }
+        }
+    }
+
+    /// Dispatch `payload` to the account's webhooks in the background.
+    ///
+    /// Only waits when a dispatch limit is set, the pool is full and the overflow
+    /// policy is `block`, or to park or dead-letter deliveries under the other policies.
     #[tracing::instrument(skip(self, payload))]
-    pub fn notify_async<T>(
+    pub async fn notify_async<T>(
         &self,
         account_id: Uuid,
         event: WebhookEvent,
         payload: T,
+        request_id: Option<String>,
     ) where
-        T: serde::Serialize + Send + Sync + 'static,
+        T: serde::Serialize,
     {
+        let request_id = request_id.filter(|_| self.propagate_request_id);
+
+        // Serialized once for every webhook; a payload that cannot be is never dispatched.
+        // Receivers always get plain amounts, whatever format the triggering request asked for
+        let value = match money_format::plain(|| serde_json::to_value(&payload)) {
+            Ok(value) => value,
+            Err(e) => {
+                self.dead_letter_unserializable(account_id, &event, &e).await;
+                return;
+            }
+        };
+
+        let permit = match self.acquire_dispatch_slot().await {
+            DispatchSlot::Unbounded => None,
+            DispatchSlot::Acquired(permit) => Some(permit),
+            DispatchSlot::Saturated => {
+                self.handle_overflow(account_id, event, value, request_id).await;
+                return;
+            }
+        };
+
         let repo = self.webhook_repository.clone();
         let account_repo = self.account_repository.clone();
         let dispatcher = self.dispatcher.clone();
+        let options = self.delivery_options();
 
         // Capture current span
         let span = tracing::Span::current();
 
-        task::spawn(async move {
-            // First, fetch the account to get the secret
-            let account = match account_repo.find_by_id(account_id).await {
-                Ok(acc) => acc,
-                Err(e) => {
-                    error!("Failed to fetch account {} for webhook dispatch: {}", account_id, e);
-                    return;
-                }
-            };
-            let secret = account.webhook_secret;
+        let mut tasks = self.dispatch_tasks.lock().unwrap();
+        reap_finished(&mut tasks);
+        tasks.spawn(async move {
+            // Released when the task ends, whichever way it ends
+            let _permit = permit;
+
+            // Skip accounts deleted since the event fired
+            if let Err(e) = account_repo.find_by_id(account_id).await {
+                error!("Failed to fetch account {} for webhook dispatch: {}", account_id, e);
+                return;
+            }
 
             match repo.list_by_account(account_id).await {
                 Ok(webhooks) => {
                     for webhook in webhooks {
-                        if webhook.event == event {
-                            match serde_json::to_value(&payload) {
-                                Ok(value) => {
-                                    if let Err(e) = dispatcher.dispatch(&webhook.url, &value, &secret).await {
-                                        error!(
-                                            "Failed to dispatch webhook {} to {}: {}",
-                                            webhook.id, webhook.url, e
-                                        );
-                                    }
-                                }
-                                Err(e) => {
-                                     error!("Failed to serialize webhook payload: {}", e);
-                                }
-                            }
+                        // Pending webhooks have not proven they control their URL yet
+                        if webhook.is_active() && webhook.event.covers(&event) {
+                            deliver(
+                                repo.as_ref(),
+                                dispatcher.as_ref(),
+                                options,
+                                &webhook,
+                                &event,
+                                value.clone(),
+                                request_id.clone(),
+                            )
+                            .await;
                         }
                     }
                 }
@@ -80,4 +211,321 @@ impl WebhookService {
             }
         }.instrument(span));
     }
+
+    /// Fire `balance.low` if debiting `debited` took the account from at or above its
+    /// low-balance threshold to below it. Called after the debit has committed, so
+    /// failures to look up the balance or threshold are logged rather than returned.
+    pub async fn notify_balance_threshold(
+        &self,
+        account_id: Uuid,
+        transaction_id: Uuid,
+        debited: Money,
+        request_id: Option<String>,
+    ) {
+        let (account, limits) = match tokio::try_join!(
+            self.account_repository.find_by_id(account_id),
+            self.account_repository.find_limits(account_id),
+        ) {
+            Ok(found) => found,
+            Err(e) => {
+                warn!("Failed to check low-balance threshold for account {}: {}", account_id, e);
+                return;
+            }
+        };
+        let Some(threshold) = limits.low_balance else {
+            return;
+        };
+
+        let current = account.balance;
+        let previous = match current.checked_add(debited) {
+            Ok(previous) => previous,
+            Err(e) => {
+                warn!("Failed to compute previous balance for account {}: {}", account_id, e);
+                return;
+            }
+        };
+        if !limits.crossed_low_balance(previous, current) {
+            return;
+        }
+
+        let payload = json!({
+            "account_id": account_id,
+            "balance": current.amount(),
+            "previous_balance": previous.amount(),
+            "threshold": threshold.amount(),
+            "transaction_id": transaction_id,
+        });
+        self.notify_async(account_id, WebhookEvent::BalanceLow, payload, request_id).await;
+    }
+
+    /// Send `payload` to each of the account's webhooks subscribed to `event` and wait for
+    /// every dispatch to finish. Bypasses the dispatch limit; used by the outbox relay,
+    /// which already paces itself. Returns the number of webhooks the event went to.
+    pub async fn deliver_now(&self, account_id: Uuid, event: &WebhookEvent, payload: Value) -> Result<usize, RepositoryError> {
+        // Skip accounts deleted since the event fired
+        match self.account_repository.find_by_id(account_id).await {
+            Ok(_) => {}
+            Err(RepositoryError::NotFound(_)) => {
+                warn!("Skipping {} webhooks for deleted account {}", event, account_id);
+                return Ok(0);
+            }
+            Err(e) => return Err(e),
+        }
+
+        let webhooks = self.webhook_repository.list_by_account(account_id).await?;
+        let options = self.delivery_options();
+        let mut delivered = 0;
+        // Pending webhooks have not proven they control their URL yet
+        for webhook in webhooks.iter().filter(|w| w.is_active() && w.event.covers(event)) {
+            deliver(
+                self.webhook_repository.as_ref(),
+                self.dispatcher.as_ref(),
+                options,
+                webhook,
+                event,
+                payload.clone(),
+                None,
+            )
+            .await;
+            delivered += 1;
+        }
+        Ok(delivered)
+    }
+
+    /// Dispatch deliveries parked by the `outbox` overflow policy, as far as free slots allow.
+    /// Returns the number of deliveries handed to dispatch tasks.
+    pub async fn drain_outbox(&self, limit: i64) -> Result<usize, RepositoryError> {
+        let capacity = match &self.dispatch_slots {
+            Some(slots) => limit.min(slots.available_permits() as i64),
+            None => limit,
+        };
+        if capacity <= 0 {
+            return Ok(0);
+        }
+
+        let entries = self.webhook_repository.claim_outbox(capacity).await?;
+        let mut dispatched = 0;
+        for entry in entries {
+            let permit = match &self.dispatch_slots {
+                Some(slots) => match slots.clone().try_acquire_owned() {
+                    Ok(permit) => Some(permit),
+                    // Filled up since the claim; park the entry again for the next drain
+                    Err(_) => {
+                        self.webhook_repository.enqueue_outbox(entry).await?;
+                        continue;
+                    }
+                },
+                None => None,
+            };
+
+            let repo = self.webhook_repository.clone();
+            let dispatcher = self.dispatcher.clone();
+            let options = self.delivery_options();
+
+            let mut tasks = self.dispatch_tasks.lock().unwrap();
+            reap_finished(&mut tasks);
+            tasks.spawn(async move {
+                let _permit = permit;
+
+                let webhook = match repo.find_by_id(entry.webhook_id).await {
+                    Ok(webhook) => webhook,
+                    Err(e) => {
+                        error!("Dropping outbox entry {} for webhook {}: {}", entry.id, entry.webhook_id, e);
+                        return;
+                    }
+                };
+                if !webhook.is_active() {
+                    warn!("Dropping outbox entry {}: webhook {} is not active", entry.id, webhook.id);
+                    return;
+                }
+
+                deliver(
+                    repo.as_ref(),
+                    dispatcher.as_ref(),
+                    options,
+                    &webhook,
+                    &entry.event,
+                    entry.payload,
+                    entry.request_id,
+                )
+                .await;
+            });
+            dispatched += 1;
+        }
+
+        Ok(dispatched)
+    }
+
+    /// Spawn a background task that drains the outbox every `interval`.
+    pub fn spawn_outbox_drain(self: Arc<Self>, interval: Duration, batch_size: i64) {
+        health::register_worker(OUTBOX_WORKER_NAME, interval);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.drain_outbox(batch_size).await {
+                    error!("Failed to drain webhook outbox: {}", e);
+                }
+                health::record_heartbeat(OUTBOX_WORKER_NAME);
+            }
+        });
+    }
+
+    fn delivery_options(&self) -> DeliveryOptions {
+        DeliveryOptions {
+            validate_payloads: self.validate_payloads,
+            sequence_numbers: self.sequence_numbers,
+        }
+    }
+
+    async fn acquire_dispatch_slot(&self) -> DispatchSlot {
+        let Some(slots) = &self.dispatch_slots else {
+            return DispatchSlot::Unbounded;
+        };
+
+        if let Ok(permit) = slots.clone().try_acquire_owned() {
+            return DispatchSlot::Acquired(permit);
+        }
+
+        if self.overflow_policy == WebhookOverflowPolicy::Block
+            && let Ok(Ok(permit)) = tokio::time::timeout(self.block_timeout, slots.clone().acquire_owned()).await
+        {
+            return DispatchSlot::Acquired(permit);
+        }
+
+        DispatchSlot::Saturated
+    }
+
+    /// Park (`outbox`) or dead-letter (`shed`, and `block` after its timeout) every
+    /// delivery the event would have caused
+    async fn handle_overflow(&self, account_id: Uuid, event: WebhookEvent, mut value: Value, request_id: Option<String>) {
+        let webhooks = match self.webhook_repository.list_by_account(account_id).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                error!("Failed to fetch webhooks for account {}: {}", account_id, e);
+                return;
+            }
+        };
+        // Outbox entries keep the request id apart; letters carry it for whoever inspects them
+        if self.overflow_policy != WebhookOverflowPolicy::Outbox
+            && let (Some(request_id), Some(envelope)) = (&request_id, value.as_object_mut())
+        {
+            envelope.insert("request_id".to_string(), request_id.clone().into());
+        }
+
+        for webhook in webhooks.iter().filter(|w| w.is_active() && w.event.covers(&event)) {
+            let result = if self.overflow_policy == WebhookOverflowPolicy::Outbox {
+                let entry = WebhookOutboxEntry::new(webhook.id, event.clone(), value.clone(), request_id.clone());
+                self.webhook_repository.enqueue_outbox(entry).await.map(|_| ())
+            } else {
+                let letter = WebhookDeadLetter::new(webhook.id, event.clone(), value.clone(), SATURATED_REASON.to_string());
+                self.webhook_repository.dead_letter(letter).await.map(|_| ())
+            };
+
+            match result {
+                Ok(()) => warn!(
+                    "Webhook dispatch pool full, applied {} policy to {} delivery for {}",
+                    self.overflow_policy, event, webhook.id
+                ),
+                Err(e) => error!("Failed to {} webhook delivery for {}: {}", self.overflow_policy, webhook.id, e),
+            }
+        }
+    }
+
+    /// Dead-letter an event whose payload failed to serialize, once per webhook that would
+    /// have received it, so the loss shows up next to ordinary failed deliveries
+    async fn dead_letter_unserializable(&self, account_id: Uuid, event: &WebhookEvent, err: &serde_json::Error) {
+        error!("Failed to serialize {} webhook payload for account {}: {}", event, account_id, err);
+
+        let webhooks = match self.webhook_repository.list_by_account(account_id).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                error!("Failed to fetch webhooks for account {}: {}", account_id, e);
+                return;
+            }
+        };
+        for webhook in webhooks.iter().filter(|w| w.is_active() && w.event.covers(event)) {
+            let reason = format!("{}: {}", UNSERIALIZABLE_REASON, err);
+            let letter = WebhookDeadLetter::new(webhook.id, event.clone(), Value::Null, reason);
+            if let Err(e) = self.webhook_repository.dead_letter(letter).await {
+                error!("Failed to dead-letter webhook payload for {}: {}", webhook.id, e);
+            }
+        }
+    }
+}
+
+/// Validate, number and send one event to one webhook
+async fn deliver(
+    repo: &dyn WebhookRepository,
+    dispatcher: &dyn WebhookDispatcher,
+    options: DeliveryOptions,
+    webhook: &Webhook,
+    event: &WebhookEvent,
+    mut value: Value,
+    request_id: Option<String>,
+) {
+    if options.validate_payloads
+        && let Err(reason) = webhook_payload_schema::validate_payload(event, &value)
+    {
+        error!(
+            "Webhook payload for {} failed {} schema validation, dead-lettering: {}",
+            webhook.id, event, reason
+        );
+        let letter = WebhookDeadLetter::new(webhook.id, event.clone(), value, reason);
+        if let Err(e) = repo.dead_letter(letter).await {
+            error!("Failed to dead-letter webhook payload for {}: {}", webhook.id, e);
+        }
+        return;
+    }
+
+    if let (Some(request_id), Some(envelope)) = (&request_id, value.as_object_mut()) {
+        envelope.insert("request_id".to_string(), request_id.clone().into());
+    }
+
+    let sequence = if options.sequence_numbers {
+        match repo.next_sequence(webhook.id).await {
+            Ok(sequence) => Some(sequence),
+            Err(e) => {
+                error!("Failed to assign a sequence number for webhook {}: {}", webhook.id, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if let (Some(sequence), Some(envelope)) = (sequence, value.as_object_mut()) {
+        envelope.insert("sequence".to_string(), sequence.into());
+    }
+
+    let context = DeliveryContext {
+        webhook_id: webhook.id,
+        event: event.to_string(),
+        request_id,
+        previous_secret: webhook.active_previous_secret().map(str::to_string),
+        timeout: webhook.timeout(),
+        accepts_gzip: webhook.accepts_gzip,
+        sequence,
+    };
+    if let Err(e) = dispatcher
+        .dispatch(&webhook.url, &value, &webhook.secret, &context)
+        .await
+    {
+        error!(
+            "Failed to dispatch webhook {} to {}: {}",
+            webhook.id, webhook.url, e
+        );
+    }
+}
+
+/// Drop finished tasks so the set only holds pending dispatches
+fn reap_finished(tasks: &mut JoinSet<()>) {
+    while let Some(result) = tasks.try_join_next() {
+        log_join_error(result);
+    }
+}
+
+fn log_join_error(result: Result<(), tokio::task::JoinError>) {
+    if let Err(e) = result {
+        error!("Webhook dispatch task failed: {}", e);
+    }
 }
@@ -1,6 +1,9 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
 use std::sync::Arc;
 use crate::application::services::{AccountService, AuthService, TransactionService};
 use crate::infrastructure::database::PostgresWebhookRepository;
+use crate::infrastructure::http_client::{ReqwestWebhookDispatcher, WebhookUrlPolicy};
 
 #[derive(Clone)]
 pub struct AppState {
@@ -8,4 +11,16 @@ pub struct AppState {
     pub transaction_service: Arc<TransactionService>,
     pub auth_service: Arc<AuthService>,
     pub webhook_repository: Arc<PostgresWebhookRepository>,
+    /// Sends test pings straight to a webhook, outside the retrying delivery path.
+    pub webhook_dispatcher: Arc<ReqwestWebhookDispatcher>,
+    /// Checked against webhook URLs on create and update.
+    pub webhook_url_policy: WebhookUrlPolicy,
+    /// Hold new webhooks (and changed URLs) back until the endpoint echoes a challenge.
+    pub require_webhook_verification: bool,
+    /// Upper bound applied to `limit` on list endpoints.
+    pub max_page_size: i64,
+    /// Connection pool, probed directly by the readiness check.
+    pub pool: PgPool,
+    /// When the server started, reported by `/health`.
+    pub started_at: DateTime<Utc>,
 }
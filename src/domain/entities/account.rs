@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::domain::errors::DomainError;
-use crate::domain::value_objects::Money;
+use crate::domain::value_objects::{AccountStatus, Money};
 
 /// Account entity representing a business account
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +16,23 @@ pub struct Account {
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
     pub webhook_secret: String,
+    pub status: AccountStatus,
+    /// Bumped on every balance or name change; see `AccountRepository::update_balance`
+    pub version: i64,
+}
+
+/// Column values of a stored account, as read back by a repository
+#[derive(Debug, Clone)]
+pub struct AccountRecord {
+    pub id: Uuid,
+    pub business_name: String,
+    pub balance: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub webhook_secret: String,
+    pub status: AccountStatus,
+    pub version: i64,
 }
 
 #[allow(dead_code)]
@@ -33,27 +50,23 @@ impl Account {
             updated_at: Utc::now(),
             deleted_at: None,
             webhook_secret: Uuid::new_v4().simple().to_string(),
+            status: AccountStatus::Active,
+            version: 0,
         })
     }
 
     /// Create account from database record
-    pub fn from_db(
-        id: Uuid,
-        business_name: String,
-        balance: Decimal,
-        created_at: DateTime<Utc>,
-        updated_at: DateTime<Utc>,
-        deleted_at: Option<DateTime<Utc>>,
-        webhook_secret: String,
-    ) -> Result<Self, DomainError> {
+    pub fn from_db(record: AccountRecord) -> Result<Self, DomainError> {
         Ok(Self {
-            id,
-            business_name,
-            balance: Money::new(balance)?,
-            created_at,
-            updated_at,
-            deleted_at,
-            webhook_secret,
+            id: record.id,
+            business_name: record.business_name,
+            balance: Money::new(record.balance)?,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+            deleted_at: record.deleted_at,
+            webhook_secret: record.webhook_secret,
+            status: record.status,
+            version: record.version,
         })
     }
 
@@ -113,6 +126,35 @@ impl Account {
         Ok(())
     }
 
+    /// Check if the account may take part in transactions
+    pub fn is_active(&self) -> bool {
+        self.status == AccountStatus::Active
+    }
+
+    /// Block the account from transacting
+    pub fn freeze(&mut self) -> Result<(), DomainError> {
+        if self.status == AccountStatus::Closed {
+            return Err(DomainError::InvalidAccountState(
+                "Cannot freeze a closed account".to_string(),
+            ));
+        }
+        self.status = AccountStatus::Frozen;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Allow a frozen account to transact again
+    pub fn unfreeze(&mut self) -> Result<(), DomainError> {
+        if self.status == AccountStatus::Closed {
+            return Err(DomainError::InvalidAccountState(
+                "Cannot unfreeze a closed account".to_string(),
+            ));
+        }
+        self.status = AccountStatus::Active;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     /// Update business name
     pub fn update_business_name(&mut self, new_name: String) -> Result<(), DomainError> {
         Self::validate_business_name(&new_name)?;
@@ -254,19 +296,52 @@ mod tests {
         let created_at = Utc::now();
         let updated_at = Utc::now();
 
-        let account = Account::from_db(
+        let account = Account::from_db(AccountRecord {
             id,
-            "Test Business".to_string(),
-            dec!(100.00),
+            business_name: "Test Business".to_string(),
+            balance: dec!(100.00),
             created_at,
             updated_at,
-            None,
-            "secret".to_string(),
-        )
-            .unwrap();
+            deleted_at: None,
+            webhook_secret: "secret".to_string(),
+            status: AccountStatus::Active,
+            version: 3,
+        })
+        .unwrap();
 
         assert_eq!(account.id, id);
         assert_eq!(account.business_name, "Test Business");
         assert_eq!(account.balance, Money::new(dec!(100.00)).unwrap());
+        assert!(account.is_active());
+        assert_eq!(account.version, 3);
+    }
+
+    #[test]
+    fn test_freeze_and_unfreeze() {
+        let mut account = Account::new(
+            "Test Business".to_string(),
+            Money::new(dec!(100.00)).unwrap(),
+        )
+            .unwrap();
+
+        account.freeze().unwrap();
+        assert_eq!(account.status, AccountStatus::Frozen);
+        assert!(!account.is_active());
+
+        account.unfreeze().unwrap();
+        assert_eq!(account.status, AccountStatus::Active);
+    }
+
+    #[test]
+    fn test_cannot_freeze_closed_account() {
+        let mut account = Account::new(
+            "Test Business".to_string(),
+            Money::new(dec!(100.00)).unwrap(),
+        )
+            .unwrap();
+        account.status = AccountStatus::Closed;
+
+        assert!(matches!(account.freeze(), Err(DomainError::InvalidAccountState(_))));
+        assert!(matches!(account.unfreeze(), Err(DomainError::InvalidAccountState(_))));
     }
 }
\ No newline at end of file
@@ -6,10 +6,36 @@ use uuid::Uuid;
 pub struct ApiKey {
     pub id: Uuid,
     pub key_hash: String,
+    /// Leading characters of the raw key; not secret, used to find and identify the key.
+    /// `None` for keys stored before prefixes were recorded.
+    pub key_prefix: Option<String>,
     pub account_id: Uuid,
+    /// Other accounts this key may act for, e.g. the rest of a tenant's accounts
+    pub additional_account_ids: Vec<Uuid>,
     pub rate_limit_per_hour: u32,
     pub created_at: DateTime<Utc>,
     pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Key that replaced this one in a rotation; this key stops working at `expires_at`
+    pub superseded_by: Option<Uuid>,
+    /// May change any account's limits and lift freezes; only the bootstrap key is minted as one
+    pub is_admin: bool,
+}
+
+/// Column values of a stored API key, as read back by a repository
+#[derive(Debug, Clone)]
+pub struct ApiKeyRecord {
+    pub id: Uuid,
+    pub key_hash: String,
+    pub key_prefix: Option<String>,
+    pub account_id: Uuid,
+    pub additional_account_ids: Vec<Uuid>,
+    pub rate_limit_per_hour: i32,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub superseded_by: Option<Uuid>,
+    pub is_admin: bool,
 }
 
 impl ApiKey {
@@ -19,29 +45,48 @@ impl ApiKey {
         Self {
             id: Uuid::new_v4(),
             key_hash,
+            key_prefix: None,
             account_id,
+            additional_account_ids: Vec::new(),
             rate_limit_per_hour: 1000,
             created_at: Utc::now(),
             last_used_at: None,
+            expires_at: None,
+            superseded_by: None,
+            is_admin: false,
         }
     }
 
     /// Reconstruct from database
-    pub fn from_db(
-        id: Uuid,
-        key_hash: String,
-        account_id: Uuid,
-        rate_limit_per_hour: i32,
-        created_at: DateTime<Utc>,
-        last_used_at: Option<DateTime<Utc>>,
-    ) -> Self {
+    pub fn from_db(record: ApiKeyRecord) -> Self {
         Self {
-            id,
-            key_hash,
-            account_id,
-            rate_limit_per_hour: rate_limit_per_hour as u32,
-            created_at,
-            last_used_at,
+            id: record.id,
+            key_hash: record.key_hash,
+            key_prefix: record.key_prefix,
+            account_id: record.account_id,
+            additional_account_ids: record.additional_account_ids,
+            rate_limit_per_hour: record.rate_limit_per_hour as u32,
+            created_at: record.created_at,
+            last_used_at: record.last_used_at,
+            expires_at: record.expires_at,
+            superseded_by: record.superseded_by,
+            is_admin: record.is_admin,
         }
     }
+
+    /// Every account the key may act for: its own first, then the additional ones
+    pub fn account_ids(&self) -> Vec<Uuid> {
+        let mut account_ids = vec![self.account_id];
+        for id in &self.additional_account_ids {
+            if !account_ids.contains(id) {
+                account_ids.push(*id);
+            }
+        }
+        account_ids
+    }
+
+    /// Whether the key has expired as of `now`. Keys without `expires_at` never expire.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
 }
@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::value_objects::Money;
+
+/// An account's balance immediately after a transaction, recorded for charting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    pub account_id: Uuid,
+    pub transaction_id: Uuid,
+    pub balance: Money,
+    pub recorded_at: DateTime<Utc>,
+}
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A stored response for an idempotency key.
+///
+/// The fingerprint identifies the request the key was first used with, so a
+/// retry can be told apart from a different request reusing the same key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    pub key: String,
+    pub request_fingerprint: String,
+    pub response: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl IdempotencyRecord {
+    /// Create a new record that expires `ttl` from now
+    pub fn new(
+        key: String,
+        request_fingerprint: String,
+        response: serde_json::Value,
+        ttl: chrono::Duration,
+    ) -> Self {
+        let created_at = Utc::now();
+        Self {
+            key,
+            request_fingerprint,
+            response,
+            created_at,
+            expires_at: created_at + ttl,
+        }
+    }
+
+    /// Whether the record has expired as of `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
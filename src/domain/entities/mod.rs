@@ -2,8 +2,18 @@ pub mod account;
 pub mod transaction;
 pub mod api_key;
 pub mod webhook;
+pub mod idempotency_record;
+pub mod webhook_dead_letter;
+pub mod balance_snapshot;
+pub mod webhook_outbox_entry;
+pub mod outbox_event;
 
-pub use account::Account;
-pub use transaction::Transaction;
-pub use api_key::ApiKey;
-pub use webhook::Webhook;
\ No newline at end of file
+pub use account::{Account, AccountRecord};
+pub use transaction::{Transaction, TransactionRecord};
+pub use api_key::{ApiKey, ApiKeyRecord};
+pub use webhook::Webhook;
+pub use idempotency_record::IdempotencyRecord;
+pub use webhook_dead_letter::WebhookDeadLetter;
+pub use balance_snapshot::BalanceSnapshot;
+pub use webhook_outbox_entry::WebhookOutboxEntry;
+pub use outbox_event::OutboxEvent;
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::entities::Transaction;
+use crate::domain::value_objects::{TransactionType, WebhookEvent};
+
+/// A webhook event recorded in the same database transaction as the money movement it
+/// announces, so it survives a crash between the commit and the dispatch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEvent {
+    pub id: Uuid,
+    /// The account whose webhooks receive the event
+    pub account_id: Uuid,
+    pub event: WebhookEvent,
+    pub transaction_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// When every matching webhook was handed the event; `None` while it is pending
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+impl OutboxEvent {
+    /// The event a completed deposit, withdrawal or transfer announces, addressed to the
+    /// account the API request named. Other transaction types are not recorded.
+    pub fn for_transaction(transaction: &Transaction) -> Option<Self> {
+        let (account_id, event) = match transaction.transaction_type {
+            TransactionType::Credit => (transaction.to_account_id?, WebhookEvent::TransactionDepositCompleted),
+            TransactionType::Debit => (transaction.from_account_id?, WebhookEvent::TransactionWithdrawalCompleted),
+            TransactionType::Transfer => (transaction.from_account_id?, WebhookEvent::TransactionTransferCompleted),
+            TransactionType::Verification | TransactionType::Authorization => return None,
+        };
+
+        Some(Self {
+            id: Uuid::new_v4(),
+            account_id,
+            event,
+            transaction_id: transaction.id,
+            created_at: Utc::now(),
+            sent_at: None,
+        })
+    }
+}
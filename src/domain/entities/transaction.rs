@@ -1,9 +1,12 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::domain::errors::DomainError;
-use crate::domain::value_objects::{Money, TransactionType};
+use crate::domain::value_objects::{
+    AuthorizationStatus, IdempotencyKeyFormat, Money, TransactionStatus, TransactionType,
+};
 
 /// Transaction entity representing a financial transaction
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +18,30 @@ pub struct Transaction {
     pub amount: Money,
     pub idempotency_key: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Where an authorization stands; `None` for every other type
+    pub authorization_status: Option<AuthorizationStatus>,
+    /// `Pending` until a repository applies it; the synchronous execute paths record `Completed`
+    pub status: TransactionStatus,
+    /// Free-form note from the client, e.g. an invoice reference
+    pub description: Option<String>,
+    /// Hash of the request that created it, so a retry can be told apart from a reused idempotency key
+    pub request_fingerprint: Option<String>,
+}
+
+/// Column values of a stored transaction, as read back by a repository
+#[derive(Debug, Clone)]
+pub struct TransactionRecord {
+    pub id: Uuid,
+    pub transaction_type: TransactionType,
+    pub from_account_id: Option<Uuid>,
+    pub to_account_id: Option<Uuid>,
+    pub amount: Money,
+    pub idempotency_key: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub authorization_status: Option<AuthorizationStatus>,
+    pub status: TransactionStatus,
+    pub description: Option<String>,
+    pub request_fingerprint: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -24,6 +51,7 @@ impl Transaction {
         to_account_id: Uuid,
         amount: Money,
         idempotency_key: Option<String>,
+        description: Option<String>,
     ) -> Result<Self, DomainError> {
         amount.validate()?;
 
@@ -34,9 +62,11 @@ impl Transaction {
         }
 
         if let Some(ref key) = idempotency_key {
-            Self::validate_idempotency_key(key)?;
+            Self::validate_idempotency_key(key, IdempotencyKeyFormat::Any)?;
         }
 
+        let description = Self::normalize_description(description)?;
+
         Ok(Self {
             id: Uuid::new_v4(),
             transaction_type: TransactionType::Credit,
@@ -45,6 +75,10 @@ impl Transaction {
             amount,
             idempotency_key,
             created_at: Utc::now(),
+            authorization_status: None,
+            status: TransactionStatus::Pending,
+            description,
+            request_fingerprint: None,
         })
     }
 
@@ -53,6 +87,7 @@ impl Transaction {
         from_account_id: Uuid,
         amount: Money,
         idempotency_key: Option<String>,
+        description: Option<String>,
     ) -> Result<Self, DomainError> {
         amount.validate()?;
 
@@ -63,9 +98,11 @@ impl Transaction {
         }
 
         if let Some(ref key) = idempotency_key {
-            Self::validate_idempotency_key(key)?;
+            Self::validate_idempotency_key(key, IdempotencyKeyFormat::Any)?;
         }
 
+        let description = Self::normalize_description(description)?;
+
         Ok(Self {
             id: Uuid::new_v4(),
             transaction_type: TransactionType::Debit,
@@ -74,6 +111,10 @@ impl Transaction {
             amount,
             idempotency_key,
             created_at: Utc::now(),
+            authorization_status: None,
+            status: TransactionStatus::Pending,
+            description,
+            request_fingerprint: None,
         })
     }
 
@@ -83,6 +124,7 @@ impl Transaction {
         to_account_id: Uuid,
         amount: Money,
         idempotency_key: Option<String>,
+        description: Option<String>,
     ) -> Result<Self, DomainError> {
         amount.validate()?;
 
@@ -97,9 +139,11 @@ impl Transaction {
         }
 
         if let Some(ref key) = idempotency_key {
-            Self::validate_idempotency_key(key)?;
+            Self::validate_idempotency_key(key, IdempotencyKeyFormat::Any)?;
         }
 
+        let description = Self::normalize_description(description)?;
+
         Ok(Self {
             id: Uuid::new_v4(),
             transaction_type: TransactionType::Transfer,
@@ -108,27 +152,86 @@ impl Transaction {
             amount,
             idempotency_key,
             created_at: Utc::now(),
+            authorization_status: None,
+            status: TransactionStatus::Pending,
+            description,
+            request_fingerprint: None,
         })
     }
 
-    /// Reconstruct transaction from database
-    pub fn from_db(
-        id: Uuid,
-        transaction_type: TransactionType,
-        from_account_id: Option<Uuid>,
-        to_account_id: Option<Uuid>,
+    /// Create a new zero-amount verification transaction
+    /// Records a heartbeat or liveness marker on an account without changing its balance
+    pub fn new_verification(
+        account_id: Uuid,
+        idempotency_key: Option<String>,
+    ) -> Result<Self, DomainError> {
+        if let Some(ref key) = idempotency_key {
+            Self::validate_idempotency_key(key, IdempotencyKeyFormat::Any)?;
+        }
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            transaction_type: TransactionType::Verification,
+            from_account_id: None,
+            to_account_id: Some(account_id),
+            amount: Money::new(Decimal::ZERO)?,
+            idempotency_key,
+            created_at: Utc::now(),
+            authorization_status: None,
+            status: TransactionStatus::Pending,
+            description: None,
+            request_fingerprint: None,
+        })
+    }
+
+    /// Create a new authorization (hold on funds of an account)
+    /// The funds stay on the account until the hold is captured or voided
+    pub fn new_authorization(
+        from_account_id: Uuid,
         amount: Money,
         idempotency_key: Option<String>,
-        created_at: DateTime<Utc>,
     ) -> Result<Self, DomainError> {
-        let transaction = Self {
-            id,
-            transaction_type,
-            from_account_id,
-            to_account_id,
+        amount.validate()?;
+
+        if !amount.is_positive() {
+            return Err(DomainError::InvalidAmount(
+                "Transaction amount must be positive".to_string(),
+            ));
+        }
+
+        if let Some(ref key) = idempotency_key {
+            Self::validate_idempotency_key(key, IdempotencyKeyFormat::Any)?;
+        }
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            transaction_type: TransactionType::Authorization,
+            from_account_id: Some(from_account_id),
+            to_account_id: None,
             amount,
             idempotency_key,
-            created_at,
+            created_at: Utc::now(),
+            authorization_status: Some(AuthorizationStatus::Pending),
+            status: TransactionStatus::Pending,
+            description: None,
+            request_fingerprint: None,
+        })
+    }
+
+    /// Reconstruct transaction from database
+    pub fn from_db(record: TransactionRecord) -> Result<Self, DomainError> {
+        let transaction = Self {
+            id: record.id,
+            transaction_type: record.transaction_type,
+            from_account_id: record.from_account_id,
+            to_account_id: record.to_account_id,
+            amount: record.amount,
+            idempotency_key: record.idempotency_key,
+            created_at: record.created_at,
+            authorization_status: record.authorization_status,
+            status: record.status,
+            description: record.description,
+            request_fingerprint: record.request_fingerprint,
         };
 
         transaction.validate_invariants()?;
@@ -137,6 +240,12 @@ impl Transaction {
 
     /// Validate transaction invariants based on type
     fn validate_invariants(&self) -> Result<(), DomainError> {
+        if self.is_authorization() != self.authorization_status.is_some() {
+            return Err(DomainError::InvalidTransactionType(
+                "Only authorization transactions carry an authorization status".to_string(),
+            ));
+        }
+
         match self.transaction_type {
             TransactionType::Credit => {
                 if self.from_account_id.is_some() {
@@ -177,13 +286,33 @@ impl Transaction {
                     return Err(DomainError::SelfTransferNotAllowed);
                 }
             }
+            TransactionType::Verification => {
+                if self.from_account_id.is_some() || self.to_account_id.is_none() {
+                    return Err(DomainError::InvalidTransactionType(
+                        "Verification transaction must only have to_account".to_string(),
+                    ));
+                }
+                if !self.amount.is_zero() {
+                    return Err(DomainError::InvalidAmount(
+                        "Verification transaction amount must be zero".to_string(),
+                    ));
+                }
+            }
+            TransactionType::Authorization => {
+                if self.from_account_id.is_none() || self.to_account_id.is_some() {
+                    return Err(DomainError::InvalidTransactionType(
+                        "Authorization transaction must only have from_account".to_string(),
+                    ));
+                }
+            }
         }
 
         Ok(())
     }
 
     /// Validate idempotency key format
-    fn validate_idempotency_key(key: &str) -> Result<(), DomainError> {
+    /// Constructors only apply the `Any` rules; callers enforce a stricter configured `format`
+    pub fn validate_idempotency_key(key: &str, format: IdempotencyKeyFormat) -> Result<(), DomainError> {
         if key.trim().is_empty() {
             return Err(DomainError::InvalidAmount(
                 "Idempotency key cannot be empty".to_string(),
@@ -196,9 +325,36 @@ impl Transaction {
             ));
         }
 
+        if format == IdempotencyKeyFormat::Uuid && Uuid::parse_str(key).is_err() {
+            return Err(DomainError::InvalidAmount(
+                "Idempotency key must be a UUID".to_string(),
+            ));
+        }
+
         Ok(())
     }
 
+    /// Record the fingerprint of the request creating this transaction
+    pub fn with_request_fingerprint(mut self, fingerprint: String) -> Self {
+        self.request_fingerprint = Some(fingerprint);
+        self
+    }
+
+    /// Treat blank descriptions as absent and reject ones over 500 characters
+    pub fn normalize_description(description: Option<String>) -> Result<Option<String>, DomainError> {
+        let Some(description) = description.filter(|d| !d.trim().is_empty()) else {
+            return Ok(None);
+        };
+
+        if description.trim().len() > 500 {
+            return Err(DomainError::InvalidAmount(
+                "Description cannot exceed 500 characters".to_string(),
+            ));
+        }
+
+        Ok(Some(description))
+    }
+
     /// Check if this is a credit transaction
     pub fn is_credit(&self) -> bool {
         matches!(self.transaction_type, TransactionType::Credit)
@@ -213,6 +369,33 @@ impl Transaction {
     pub fn is_transfer(&self) -> bool {
         matches!(self.transaction_type, TransactionType::Transfer)
     }
+
+    /// Check if this is a verification transaction
+    pub fn is_verification(&self) -> bool {
+        matches!(self.transaction_type, TransactionType::Verification)
+    }
+
+    /// Check if this is an authorization (hold)
+    pub fn is_authorization(&self) -> bool {
+        matches!(self.transaction_type, TransactionType::Authorization)
+    }
+
+    /// Move an authorization to `next`, rejecting anything but a pending hold settling
+    pub fn transition_authorization(&mut self, next: AuthorizationStatus) -> Result<(), DomainError> {
+        let current = self.authorization_status.ok_or_else(|| {
+            DomainError::InvalidTransactionType(format!("Transaction {} is not an authorization", self.id))
+        })?;
+
+        if !current.can_transition_to(next) {
+            return Err(DomainError::InvalidTransactionState(format!(
+                "Authorization {} is {} and cannot be {}",
+                self.id, current, next
+            )));
+        }
+
+        self.authorization_status = Some(next);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -229,6 +412,7 @@ mod tests {
             account_id,
             amount,
             Some("idempotency-key-123".to_string()),
+            None,
         )
             .unwrap();
 
@@ -236,6 +420,7 @@ mod tests {
         assert_eq!(tx.to_account_id, Some(account_id));
         assert_eq!(tx.from_account_id, None);
         assert_eq!(tx.amount, amount);
+        assert_eq!(tx.status, TransactionStatus::Pending);
         assert!(tx.is_credit());
     }
 
@@ -248,6 +433,7 @@ mod tests {
             account_id,
             amount,
             None,
+            None,
         )
             .unwrap();
 
@@ -268,6 +454,7 @@ mod tests {
             to_id,
             amount,
             Some("transfer-key".to_string()),
+            None,
         )
             .unwrap();
 
@@ -277,6 +464,21 @@ mod tests {
         assert!(tx.is_transfer());
     }
 
+    #[test]
+    fn test_description_is_normalized_and_bounded() {
+        let account_id = Uuid::new_v4();
+        let amount = Money::new(dec!(10.00)).unwrap();
+
+        let tx = Transaction::new_credit(account_id, amount, None, Some("Invoice #42".to_string())).unwrap();
+        assert_eq!(tx.description.as_deref(), Some("Invoice #42"));
+
+        let tx = Transaction::new_debit(account_id, amount, None, Some("   ".to_string())).unwrap();
+        assert_eq!(tx.description, None);
+
+        let result = Transaction::new_transfer(account_id, Uuid::new_v4(), amount, None, Some("a".repeat(501)));
+        assert!(matches!(result, Err(DomainError::InvalidAmount(_))));
+    }
+
     #[test]
     fn test_transfer_to_self_fails() {
         let account_id = Uuid::new_v4();
@@ -287,6 +489,7 @@ mod tests {
             account_id,
             amount,
             None,
+            None,
         );
 
         assert!(result.is_err());
@@ -301,38 +504,110 @@ mod tests {
         let account_id = Uuid::new_v4();
         let amount = Money::new(dec!(0.00)).unwrap();
 
-        let result = Transaction::new_credit(account_id, amount, None);
+        let result = Transaction::new_credit(account_id, amount, None, None);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_create_verification_transaction() {
+        let account_id = Uuid::new_v4();
+
+        let tx = Transaction::new_verification(account_id, None).unwrap();
+
+        assert_eq!(tx.transaction_type, TransactionType::Verification);
+        assert_eq!(tx.to_account_id, Some(account_id));
+        assert_eq!(tx.from_account_id, None);
+        assert!(tx.amount.is_zero());
+        assert!(tx.is_verification());
+    }
+
+    #[test]
+    fn test_from_db_rejects_non_zero_verification() {
+        let result = Transaction::from_db(TransactionRecord {
+            id: Uuid::new_v4(),
+            transaction_type: TransactionType::Verification,
+            from_account_id: None,
+            to_account_id: Some(Uuid::new_v4()),
+            amount: Money::new(dec!(1.00)).unwrap(),
+            idempotency_key: None,
+            created_at: Utc::now(),
+            authorization_status: None,
+            status: TransactionStatus::Completed,
+            description: None,
+            request_fingerprint: None,
+        });
+
+        assert!(matches!(result, Err(DomainError::InvalidAmount(_))));
+    }
+
+    #[test]
+    fn test_authorization_lifecycle() {
+        let account_id = Uuid::new_v4();
+        let amount = Money::new(dec!(25.00)).unwrap();
+
+        let mut tx = Transaction::new_authorization(account_id, amount, None).unwrap();
+        assert!(tx.is_authorization());
+        assert_eq!(tx.from_account_id, Some(account_id));
+        assert_eq!(tx.authorization_status, Some(AuthorizationStatus::Pending));
+
+        tx.transition_authorization(AuthorizationStatus::Captured).unwrap();
+        assert_eq!(tx.authorization_status, Some(AuthorizationStatus::Captured));
+        assert!(matches!(
+            tx.transition_authorization(AuthorizationStatus::Voided),
+            Err(DomainError::InvalidTransactionState(_))
+        ));
+
+        let mut credit = Transaction::new_credit(account_id, amount, None, None).unwrap();
+        assert!(matches!(
+            credit.transition_authorization(AuthorizationStatus::Captured),
+            Err(DomainError::InvalidTransactionType(_))
+        ));
+    }
+
     #[test]
     fn test_validate_idempotency_key_empty() {
-        let result = Transaction::validate_idempotency_key("");
+        let result = Transaction::validate_idempotency_key("", IdempotencyKeyFormat::Any);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_validate_idempotency_key_too_long() {
         let long_key = "a".repeat(256);
-        let result = Transaction::validate_idempotency_key(&long_key);
+        let result = Transaction::validate_idempotency_key(&long_key, IdempotencyKeyFormat::Any);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_validate_idempotency_key_uuid_format() {
+        assert!(Transaction::validate_idempotency_key("abc", IdempotencyKeyFormat::Any).is_ok());
+        assert!(matches!(
+            Transaction::validate_idempotency_key("abc", IdempotencyKeyFormat::Uuid),
+            Err(DomainError::InvalidAmount(_))
+        ));
+
+        let key = Uuid::new_v4().to_string();
+        assert!(Transaction::validate_idempotency_key(&key, IdempotencyKeyFormat::Uuid).is_ok());
+    }
+
     #[test]
     fn test_from_db_validates_invariants() {
         let from_id = Uuid::new_v4();
         let amount = Money::new(dec!(100.00)).unwrap();
 
         // Invalid: Credit with from_account
-        let result = Transaction::from_db(
-            Uuid::new_v4(),
-            TransactionType::Credit,
-            Some(from_id),
-            Some(Uuid::new_v4()),
+        let result = Transaction::from_db(TransactionRecord {
+            id: Uuid::new_v4(),
+            transaction_type: TransactionType::Credit,
+            from_account_id: Some(from_id),
+            to_account_id: Some(Uuid::new_v4()),
             amount,
-            None,
-            Utc::now(),
-        );
+            idempotency_key: None,
+            created_at: Utc::now(),
+            authorization_status: None,
+            status: TransactionStatus::Completed,
+            description: None,
+            request_fingerprint: None,
+        });
 
         assert!(result.is_err());
     }
@@ -342,7 +617,7 @@ mod tests {
         let account_id = Uuid::new_v4();
         let amount = Money::new(dec!(100.00)).unwrap();
 
-        let tx = Transaction::new_credit(account_id, amount, None).unwrap();
+        let tx = Transaction::new_credit(account_id, amount, None, None).unwrap();
 
         assert!(tx.is_credit());
         assert!(!tx.is_debit());
@@ -354,7 +629,7 @@ mod tests {
         let account_id = Uuid::new_v4();
         let amount = Money::new(dec!(100.00)).unwrap();
 
-        let tx = Transaction::new_debit(account_id, amount, None).unwrap();
+        let tx = Transaction::new_debit(account_id, amount, None, None).unwrap();
 
         assert!(!tx.is_credit());
         assert!(tx.is_debit());
@@ -367,7 +642,7 @@ mod tests {
         let to_id = Uuid::new_v4();
         let amount = Money::new(dec!(100.00)).unwrap();
 
-        let tx = Transaction::new_transfer(from_id, to_id, amount, None).unwrap();
+        let tx = Transaction::new_transfer(from_id, to_id, amount, None, None).unwrap();
 
         assert!(!tx.is_credit());
         assert!(!tx.is_debit());
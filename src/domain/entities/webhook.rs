@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::value_objects::WebhookEvent;
+use crate::domain::value_objects::{WebhookEvent, WebhookStatus};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Webhook {
@@ -11,17 +11,23 @@ pub struct Webhook {
     pub event: WebhookEvent,
     pub account_id: Uuid,
     pub created_at: DateTime<Utc>,
+    /// Signing secret for deliveries to this webhook
+    pub secret: String,
+    /// Secret replaced by the last rotation, still honoured until `previous_secret_expires_at`
+    pub previous_secret: Option<String>,
+    pub previous_secret_expires_at: Option<DateTime<Utc>>,
+    /// Delivery timeout override; `None` uses the global timeout
+    pub timeout_ms: Option<u32>,
+    /// Receiver accepts gzip-compressed bodies for large payloads
+    pub accepts_gzip: bool,
+    pub status: WebhookStatus,
+    /// Challenge the endpoint must echo back while `Pending`
+    pub verification_token: Option<String>,
 }
 
 impl Webhook {
     pub fn new(account_id: Uuid, url: String, event: WebhookEvent) -> Result<Self, String> {
-        if url.trim().is_empty() {
-            return Err("URL cannot be empty".to_string());
-        }
-        // Basic URL validation
-        if !url.starts_with("http") {
-             return Err("URL must start with http or https".to_string());
-        }
+        Self::validate_url(&url)?;
 
         Ok(Self {
             id: Uuid::new_v4(),
@@ -29,6 +35,91 @@ impl Webhook {
             event,
             account_id,
             created_at: Utc::now(),
+            secret: Self::generate_secret(),
+            previous_secret: None,
+            previous_secret_expires_at: None,
+            timeout_ms: None,
+            accepts_gzip: false,
+            status: WebhookStatus::Active,
+            verification_token: None,
         })
     }
+
+    /// Change the delivery URL and/or subscribed event, keeping the id and secrets.
+    pub fn update(&mut self, url: Option<String>, event: Option<WebhookEvent>) -> Result<(), String> {
+        if let Some(url) = url {
+            Self::validate_url(&url)?;
+            self.url = url;
+        }
+        if let Some(event) = event {
+            self.event = event;
+        }
+        Ok(())
+    }
+
+    /// Hold events back until the endpoint echoes a fresh challenge token.
+    pub fn require_verification(&mut self) {
+        self.status = WebhookStatus::Pending;
+        self.verification_token = Some(Self::generate_secret());
+    }
+
+    /// Mark the endpoint as verified so it starts receiving events.
+    pub fn activate(&mut self) {
+        self.status = WebhookStatus::Active;
+        self.verification_token = None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.status == WebhookStatus::Active
+    }
+
+    /// Override the delivery timeout for this webhook (`None` restores the global one).
+    pub fn with_timeout_ms(mut self, timeout_ms: Option<u32>) -> Result<Self, String> {
+        if timeout_ms == Some(0) {
+            return Err("Timeout must be greater than zero".to_string());
+        }
+        self.timeout_ms = timeout_ms;
+        Ok(self)
+    }
+
+    /// Let large payloads be delivered gzip-compressed.
+    pub fn with_gzip(mut self, accepts_gzip: bool) -> Self {
+        self.accepts_gzip = accepts_gzip;
+        self
+    }
+
+    /// The delivery timeout override, if any
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout_ms.map(|ms| std::time::Duration::from_millis(ms as u64))
+    }
+
+    /// Replace the signing secret, keeping the old one valid for `grace`.
+    pub fn rotate_secret(&mut self, grace: chrono::Duration) {
+        let previous = std::mem::replace(&mut self.secret, Self::generate_secret());
+        self.previous_secret = Some(previous);
+        self.previous_secret_expires_at = Some(Utc::now() + grace);
+    }
+
+    /// The previous secret, if its grace period has not ended yet
+    pub fn active_previous_secret(&self) -> Option<&str> {
+        match (&self.previous_secret, self.previous_secret_expires_at) {
+            (Some(secret), Some(expires_at)) if expires_at > Utc::now() => Some(secret),
+            _ => None,
+        }
+    }
+
+    fn validate_url(url: &str) -> Result<(), String> {
+        if url.trim().is_empty() {
+            return Err("URL cannot be empty".to_string());
+        }
+        // Basic URL validation
+        if !url.starts_with("http") {
+             return Err("URL must start with http or https".to_string());
+        }
+        Ok(())
+    }
+
+    fn generate_secret() -> String {
+        Uuid::new_v4().simple().to_string()
+    }
 }
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::value_objects::WebhookEvent;
+
+/// A webhook payload that was withheld from dispatch, kept for inspection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeadLetter {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: WebhookEvent,
+    pub payload: serde_json::Value,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WebhookDeadLetter {
+    pub fn new(webhook_id: Uuid, event: WebhookEvent, payload: serde_json::Value, reason: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            webhook_id,
+            event,
+            payload,
+            reason,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::value_objects::WebhookEvent;
+
+/// A delivery parked because the dispatch pool was full, waiting to be sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookOutboxEntry {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: WebhookEvent,
+    pub payload: serde_json::Value,
+    /// Id of the request that triggered the event, sent as `X-Dodo-Request-Id`
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WebhookOutboxEntry {
+    pub fn new(
+        webhook_id: Uuid,
+        event: WebhookEvent,
+        payload: serde_json::Value,
+        request_id: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            webhook_id,
+            event,
+            payload,
+            request_id,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -1,4 +1,6 @@
+use serde::Serialize;
 use thiserror::Error;
+use utoipa::ToSchema;
 
 /// Domain-level errors that represent business rule violations
 #[allow(dead_code)]
@@ -16,10 +18,13 @@ pub enum DomainError {
     #[error("Invalid account state: {0}")]
     InvalidAccountState(String),
 
+    #[error("Business name already in use: {0}")]
+    DuplicateBusinessName(String),
+
     #[error("Transaction not found: {0}")]
     TransactionNotFound(String),
 
-    #[error("Duplicate transaction: idempotency key {0} already exists")]
+    #[error("Idempotency key {0} was already used for a request with different parameters")]
     DuplicateTransaction(String),
 
     #[error("Invalid transaction type: {0}")]
@@ -34,6 +39,9 @@ pub enum DomainError {
     #[error("Invalid API key")]
     InvalidApiKey,
 
+    #[error("API key expired")]
+    ApiKeyExpired,
+
     #[error("Rate limit exceeded: {0}")]
     RateLimitExceeded(String),
 
@@ -45,6 +53,12 @@ pub enum DomainError {
 
     #[error("Invalid webhook event: {0}")]
     InvalidWebhookEvent(String),
+
+    #[error("Transaction limit exceeded: {0}")]
+    LimitExceeded(String),
+
+    #[error("Invalid transaction state: {0}")]
+    InvalidTransactionState(String),
 }
 
 /// Repository-level errors for data access failures
@@ -71,6 +85,28 @@ pub enum RepositoryError {
 
     #[error("Duplicate entry: {0}")]
     DuplicateEntry(String),
+
+    /// The row changed since it was read; re-read it and retry
+    #[error("Version conflict: {0}")]
+    VersionConflict(String),
+
+    #[error("Invalid state: {0}")]
+    InvalidState(String),
+
+    /// A transient failure persisted through every retry
+    #[error("Retries exhausted: {0}")]
+    RetriesExhausted(String),
+}
+
+impl RepositoryError {
+    /// Failures worth retrying as-is: serialization failures, deadlocks and pool timeouts.
+    /// Business rejections such as insufficient funds or a missing account never are.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            RepositoryError::TransactionError(_) | RepositoryError::ConnectionError(_)
+        )
+    }
 }
 
 /// Service-level errors for application logic failures
@@ -109,6 +145,10 @@ pub enum ApiError {
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    /// Request fields that failed validation, all reported at once
+    #[error("Validation failed: {}", describe_fields(.0))]
+    Validation(Vec<FieldError>),
+
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
@@ -121,6 +161,9 @@ pub enum ApiError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
     #[error("Too many requests: {0}")]
     TooManyRequests(String),
 
@@ -129,27 +172,172 @@ pub enum ApiError {
 
     #[error("Service unavailable: {0}")]
     ServiceUnavailable(String),
+
+    /// `inner` answered with a more specific machine-readable code
+    #[error("{inner}")]
+    Coded { code: ErrorCode, inner: Box<ApiError> },
+}
+
+/// One invalid request field and what is wrong with it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Name of the field in the request body
+    pub field: String,
+    /// Why the value was rejected, e.g. "is required"
+    pub issue: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, issue: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            issue: issue.into(),
+        }
+    }
+}
+
+fn describe_fields(fields: &[FieldError]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{} {}", field.field, field.issue))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Stable machine-readable error codes returned as `error_code`.
+///
+/// Clients should branch on these rather than on the human-readable message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    BadRequest,
+    ValidationFailed,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    PayloadTooLarge,
+    RateLimited,
+    InternalError,
+    ServiceUnavailable,
+    AccountNotFound,
+    InsufficientBalance,
+    InvalidAmount,
+    InvalidAccountState,
+    DuplicateBusinessName,
+    TransactionNotFound,
+    DuplicateTransaction,
+    InvalidTransactionType,
+    SelfTransferNotAllowed,
+    InvalidApiKey,
+    ApiKeyExpired,
+    WebhookNotFound,
+    InvalidWebhookUrl,
+    InvalidWebhookEvent,
+    LimitExceeded,
+    InvalidTransactionState,
+}
+
+impl ApiError {
+    /// Attach a specific error code, keeping the status and message of `self`
+    pub fn with_code(self, code: ErrorCode) -> Self {
+        ApiError::Coded {
+            code,
+            inner: Box::new(self.base_owned()),
+        }
+    }
+
+    /// The machine-readable code for this error
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            ApiError::BadRequest(_) => ErrorCode::BadRequest,
+            ApiError::Validation(_) => ErrorCode::ValidationFailed,
+            ApiError::Unauthorized(_) => ErrorCode::Unauthorized,
+            ApiError::Forbidden(_) => ErrorCode::Forbidden,
+            ApiError::NotFound(_) => ErrorCode::NotFound,
+            ApiError::Conflict(_) => ErrorCode::Conflict,
+            ApiError::PayloadTooLarge(_) => ErrorCode::PayloadTooLarge,
+            ApiError::TooManyRequests(_) => ErrorCode::RateLimited,
+            ApiError::InternalServerError(_) => ErrorCode::InternalError,
+            ApiError::ServiceUnavailable(_) => ErrorCode::ServiceUnavailable,
+            ApiError::Coded { code, .. } => *code,
+        }
+    }
+
+    /// The error without any attached code, which determines the HTTP status
+    pub fn base(&self) -> &ApiError {
+        match self {
+            ApiError::Coded { inner, .. } => inner.base(),
+            other => other,
+        }
+    }
+
+    /// Per-field detail, only present on validation errors
+    pub fn fields(&self) -> &[FieldError] {
+        match self.base() {
+            ApiError::Validation(fields) => fields,
+            _ => &[],
+        }
+    }
+
+    fn base_owned(self) -> ApiError {
+        match self {
+            ApiError::Coded { inner, .. } => inner.base_owned(),
+            other => other,
+        }
+    }
+}
+
+impl DomainError {
+    /// The machine-readable code for this error
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            DomainError::AccountNotFound(_) => ErrorCode::AccountNotFound,
+            DomainError::InsufficientBalance { .. } => ErrorCode::InsufficientBalance,
+            DomainError::InvalidAmount(_) => ErrorCode::InvalidAmount,
+            DomainError::InvalidAccountState(_) => ErrorCode::InvalidAccountState,
+            DomainError::DuplicateBusinessName(_) => ErrorCode::DuplicateBusinessName,
+            DomainError::TransactionNotFound(_) => ErrorCode::TransactionNotFound,
+            DomainError::DuplicateTransaction(_) => ErrorCode::DuplicateTransaction,
+            DomainError::InvalidTransactionType(_) => ErrorCode::InvalidTransactionType,
+            DomainError::SelfTransferNotAllowed => ErrorCode::SelfTransferNotAllowed,
+            DomainError::ApiKeyNotFound | DomainError::InvalidApiKey => ErrorCode::InvalidApiKey,
+            DomainError::ApiKeyExpired => ErrorCode::ApiKeyExpired,
+            DomainError::RateLimitExceeded(_) => ErrorCode::RateLimited,
+            DomainError::WebhookNotFound(_) => ErrorCode::WebhookNotFound,
+            DomainError::InvalidWebhookUrl(_) => ErrorCode::InvalidWebhookUrl,
+            DomainError::InvalidWebhookEvent(_) => ErrorCode::InvalidWebhookEvent,
+            DomainError::LimitExceeded(_) => ErrorCode::LimitExceeded,
+            DomainError::InvalidTransactionState(_) => ErrorCode::InvalidTransactionState,
+        }
+    }
 }
 
 // Conversions from domain/service errors to API errors
 impl From<DomainError> for ApiError {
     fn from(err: DomainError) -> Self {
-        match err {
+        let code = err.error_code();
+        let api_err = match err {
             DomainError::AccountNotFound(msg) => ApiError::NotFound(msg),
             DomainError::InsufficientBalance { .. } => ApiError::BadRequest(err.to_string()),
             DomainError::InvalidAmount(msg) => ApiError::BadRequest(msg),
             DomainError::InvalidAccountState(msg) => ApiError::BadRequest(msg),
+            DomainError::DuplicateBusinessName(_) => ApiError::Conflict(err.to_string()),
             DomainError::TransactionNotFound(msg) => ApiError::NotFound(msg),
             DomainError::DuplicateTransaction(_) => ApiError::Conflict(err.to_string()),
             DomainError::InvalidTransactionType(msg) => ApiError::BadRequest(msg),
             DomainError::SelfTransferNotAllowed => ApiError::BadRequest(err.to_string()),
             DomainError::ApiKeyNotFound => ApiError::Unauthorized("Invalid API key".to_string()),
             DomainError::InvalidApiKey => ApiError::Unauthorized("Invalid API key".to_string()),
+            DomainError::ApiKeyExpired => ApiError::Unauthorized("API key expired".to_string()),
             DomainError::RateLimitExceeded(msg) => ApiError::TooManyRequests(msg),
             DomainError::WebhookNotFound(msg) => ApiError::NotFound(msg),
             DomainError::InvalidWebhookUrl(msg) => ApiError::BadRequest(msg),
             DomainError::InvalidWebhookEvent(msg) => ApiError::BadRequest(msg),
-        }
+            DomainError::LimitExceeded(msg) => ApiError::BadRequest(msg),
+            DomainError::InvalidTransactionState(msg) => ApiError::Conflict(msg),
+        };
+        api_err.with_code(code)
     }
 }
 
@@ -160,7 +348,11 @@ impl From<ServiceError> for ApiError {
             ServiceError::Repository(e) => match e {
                 RepositoryError::NotFound(msg) => ApiError::NotFound(msg),
                 RepositoryError::DuplicateEntry(msg) => ApiError::Conflict(msg),
+                RepositoryError::VersionConflict(msg) => ApiError::Conflict(msg),
                 RepositoryError::ConstraintViolation(msg) => ApiError::BadRequest(msg),
+                RepositoryError::RetriesExhausted(_) => {
+                    ApiError::ServiceUnavailable("Database is busy, please retry".to_string())
+                }
                 _ => ApiError::InternalServerError("Database error".to_string()),
             },
             ServiceError::ValidationError(msg) => ApiError::BadRequest(msg),
@@ -181,9 +373,21 @@ impl From<ServiceError> for ApiError {
     }
 }
 
+impl From<RepositoryError> for ApiError {
+    fn from(err: RepositoryError) -> Self {
+        ServiceError::from(err).into()
+    }
+}
+
 impl From<RepositoryError> for ServiceError {
     fn from(err: RepositoryError) -> Self {
-        ServiceError::Repository(err)
+        match err {
+            // Only accounts carry a lifecycle state the database enforces
+            RepositoryError::InvalidState(msg) => {
+                ServiceError::Domain(DomainError::InvalidAccountState(msg))
+            }
+            err => ServiceError::Repository(err),
+        }
     }
 }
 
@@ -214,6 +418,12 @@ impl From<sqlx::Error> for RepositoryError {
                                 db_err.message().to_string(),
                             )
                         }
+                        "40001" | "40P01" => {
+                            // Serialization failure / deadlock detected
+                            RepositoryError::TransactionError(
+                                db_err.message().to_string(),
+                            )
+                        }
                         _ => RepositoryError::DatabaseError(db_err.message().to_string()),
                     }
                 } else {
@@ -251,14 +461,44 @@ mod tests {
     fn test_domain_to_api_error_conversion() {
         let domain_err = DomainError::AccountNotFound("acc_123".to_string());
         let api_err: ApiError = domain_err.into();
-        assert!(matches!(api_err, ApiError::NotFound(_)));
+        assert!(matches!(api_err.base(), ApiError::NotFound(_)));
+        assert_eq!(api_err.error_code(), ErrorCode::AccountNotFound);
     }
 
     #[test]
     fn test_rate_limit_error_conversion() {
         let domain_err = DomainError::RateLimitExceeded("Too many requests".to_string());
         let api_err: ApiError = domain_err.into();
-        assert!(matches!(api_err, ApiError::TooManyRequests(_)));
+        assert!(matches!(api_err.base(), ApiError::TooManyRequests(_)));
+        assert_eq!(api_err.error_code(), ErrorCode::RateLimited);
+    }
+
+    #[test]
+    fn test_limit_exceeded_is_a_bad_request() {
+        let api_err: ApiError = DomainError::LimitExceeded("Daily limit reached".to_string()).into();
+        assert!(matches!(api_err.base(), ApiError::BadRequest(_)));
+        assert_eq!(api_err.error_code(), ErrorCode::LimitExceeded);
+    }
+
+    #[test]
+    fn test_validation_error_lists_every_field() {
+        let api_err = ApiError::Validation(vec![
+            FieldError::new("amount", "must be greater than zero"),
+            FieldError::new("to_account_id", "is required"),
+        ]);
+        assert_eq!(api_err.error_code(), ErrorCode::ValidationFailed);
+        assert_eq!(api_err.fields().len(), 2);
+        assert_eq!(
+            api_err.to_string(),
+            "Validation failed: amount must be greater than zero; to_account_id is required"
+        );
+    }
+
+    #[test]
+    fn test_invalid_transaction_state_is_a_conflict() {
+        let api_err: ApiError = DomainError::InvalidTransactionState("Authorization is voided".to_string()).into();
+        assert!(matches!(api_err.base(), ApiError::Conflict(_)));
+        assert_eq!(api_err.error_code(), ErrorCode::InvalidTransactionState);
     }
 
     #[test]
@@ -275,4 +515,16 @@ mod tests {
         let api_err: ApiError = service_err.into();
         assert!(matches!(api_err, ApiError::NotFound(_)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_only_transient_repository_errors_are_retryable() {
+        assert!(RepositoryError::TransactionError("deadlock detected".to_string()).is_transient());
+        assert!(RepositoryError::ConnectionError("Connection pool timeout".to_string()).is_transient());
+        assert!(!RepositoryError::ConstraintViolation("Insufficient funds".to_string()).is_transient());
+        assert!(!RepositoryError::NotFound("Account not found".to_string()).is_transient());
+        assert!(!RepositoryError::RetriesExhausted("gave up".to_string()).is_transient());
+
+        let api_err: ApiError = ServiceError::Repository(RepositoryError::RetriesExhausted("gave up".to_string())).into();
+        assert!(matches!(api_err, ApiError::ServiceUnavailable(_)));
+    }
+}
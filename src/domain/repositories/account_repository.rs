@@ -3,7 +3,7 @@ use uuid::Uuid;
 
 use crate::domain::entities::Account;
 use crate::domain::errors::RepositoryError;
-use crate::domain::value_objects::Money;
+use crate::domain::value_objects::{AccountStatus, Money, TransactionLimits};
 
 /// Repository trait for Account persistence operations
 #[async_trait]
@@ -15,21 +15,49 @@ pub trait AccountRepository: Send + Sync {
     /// Find account by ID
     async fn find_by_id(&self, id: Uuid) -> Result<Account, RepositoryError>;
 
-    /// Update account balance
-    async fn update_balance(&self, id: Uuid, new_balance: Money) -> Result<(), RepositoryError>;
+    /// Find every non-deleted account among `ids`; missing ids are simply absent
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Account>, RepositoryError>;
 
-    /// Update account business name
+    /// Update account balance, provided the account is still at `expected_version`.
+    /// Fails with `VersionConflict` if it changed since it was read. Returns the new version.
+    async fn update_balance(&self, id: Uuid, new_balance: Money, expected_version: i64) -> Result<i64, RepositoryError>;
+
+    /// Update account business name, bumping its version
     async fn update_business_name(&self, id: Uuid, name: String) -> Result<(), RepositoryError>;
 
+    /// Update account status (freeze/unfreeze)
+    async fn update_status(&self, id: Uuid, status: AccountStatus) -> Result<(), RepositoryError>;
+
+    /// The account's own transaction limits; unset limits fall back to the configured defaults
+    async fn find_limits(&self, id: Uuid) -> Result<TransactionLimits, RepositoryError>;
+
+    /// Replace the account's own transaction limits
+    async fn update_limits(&self, id: Uuid, limits: &TransactionLimits) -> Result<(), RepositoryError>;
+
     /// Check if account exists
     async fn exists(&self, id: Uuid) -> Result<bool, RepositoryError>;
 
+    /// Check if a non-deleted account uses this business name (case-insensitive)
+    async fn exists_by_business_name(&self, business_name: &str) -> Result<bool, RepositoryError>;
+
     /// List all accounts (paginated)
     async fn list(&self, limit: i64, offset: i64) -> Result<Vec<Account>, RepositoryError>;
 
+    /// List accounts whose business name contains `query`, case-insensitively (paginated)
+    async fn search(&self, query: &str, limit: i64, offset: i64) -> Result<Vec<Account>, RepositoryError>;
+
+    /// Count accounts (excluding soft-deleted)
+    async fn count(&self) -> Result<i64, RepositoryError>;
+
+    /// Count accounts whose business name contains `query`, case-insensitively
+    async fn count_matching(&self, query: &str) -> Result<i64, RepositoryError>;
+
     /// Delete account (soft delete recommended in production)
     async fn delete(&self, id: Uuid) -> Result<(), RepositoryError>;
 
+    /// Undo a soft delete. Fails with `InvalidState` if the account is not deleted
+    async fn restore(&self, id: Uuid) -> Result<Account, RepositoryError>;
+
     /// Check database connectivity
     async fn health_check(&self) -> Result<(), RepositoryError>;
 }
\ No newline at end of file
@@ -1,7 +1,8 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::domain::entities::ApiKey;
+use crate::domain::entities::{Account, ApiKey};
 use crate::domain::errors::RepositoryError;
 
 /// Repository trait for API Key persistence operations
@@ -14,15 +15,42 @@ pub trait ApiKeyRepository: Send + Sync {
     /// Find API key by hash
     async fn find_by_hash(&self, key_hash: &str) -> Result<ApiKey, RepositoryError>;
 
+    /// Find every API key whose raw key starts with `key_prefix`
+    async fn find_by_prefix(&self, key_prefix: &str) -> Result<Vec<ApiKey>, RepositoryError>;
+
+    /// Find API key by ID
+    async fn find_by_id(&self, id: Uuid) -> Result<ApiKey, RepositoryError>;
+
     /// Find API keys by account ID
     async fn find_by_account(&self, account_id: Uuid) -> Result<Vec<ApiKey>, RepositoryError>;
 
+    /// Atomically store `replacement` and mark `old_id` as superseded by it, expiring the
+    /// old key no later than `old_expires_at`. Fails with `DuplicateEntry` if `old_id`
+    /// was already rotated.
+    async fn supersede(
+        &self,
+        old_id: Uuid,
+        replacement: &ApiKey,
+        old_expires_at: DateTime<Utc>,
+    ) -> Result<ApiKey, RepositoryError>;
+
     /// Update last used timestamp
     async fn update_last_used(&self, id: Uuid) -> Result<(), RepositoryError>;
 
+    /// Replace the stored hash and prefix of a key, e.g. when upgrading it to a new hashing scheme
+    async fn update_hash(&self, id: Uuid, key_hash: &str, key_prefix: &str) -> Result<(), RepositoryError>;
+
     /// Delete API key
     async fn delete(&self, id: Uuid) -> Result<(), RepositoryError>;
 
     /// Check if key hash exists
     async fn exists(&self, key_hash: &str) -> Result<bool, RepositoryError>;
+
+    /// Count all API keys, including expired ones
+    async fn count(&self) -> Result<i64, RepositoryError>;
+
+    /// Store `account` and `api_key` for it together, but only if no API key exists yet.
+    /// Concurrent callers are serialized, so at most one of them seeds a key; the others
+    /// get `None`.
+    async fn create_first_key(&self, account: &Account, api_key: &ApiKey) -> Result<Option<ApiKey>, RepositoryError>;
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::entities::BalanceSnapshot;
+use crate::domain::errors::RepositoryError;
+
+/// Read access to recorded balance snapshots.
+/// Snapshots are written by the transaction repository inside each `execute_*` call.
+#[async_trait]
+pub trait BalanceSnapshotRepository: Send + Sync {
+    /// List an account's snapshots within the inclusive bounds, oldest first
+    async fn list_by_account(
+        &self,
+        account_id: Uuid,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<BalanceSnapshot>, RepositoryError>;
+}
@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+
+use crate::domain::entities::IdempotencyRecord;
+use crate::domain::errors::RepositoryError;
+
+/// Repository trait for idempotency key persistence, usable by any endpoint
+#[async_trait]
+#[allow(dead_code)]
+pub trait IdempotencyRepository: Send + Sync {
+    /// Find a live (unexpired) record by key
+    async fn find_by_key(&self, key: &str) -> Result<IdempotencyRecord, RepositoryError>;
+
+    /// Store a record
+    /// Returns DuplicateEntry if a live record already exists for the key;
+    /// an expired record is replaced
+    async fn save(&self, record: &IdempotencyRecord) -> Result<IdempotencyRecord, RepositoryError>;
+
+    /// Delete expired records, returning how many were removed
+    async fn delete_expired(&self) -> Result<u64, RepositoryError>;
+}
@@ -1,9 +1,18 @@
 mod account_repository;
 mod api_key_repository;
+mod balance_snapshot_repository;
+mod idempotency_repository;
+mod outbox_repository;
 mod transaction_repository;
 mod webhook_repository;
 
 pub use account_repository::AccountRepository;
 pub use api_key_repository::ApiKeyRepository;
-pub use transaction_repository::TransactionRepository;
-pub use webhook_repository::WebhookRepository;
\ No newline at end of file
+pub use balance_snapshot_repository::BalanceSnapshotRepository;
+pub use idempotency_repository::IdempotencyRepository;
+pub use outbox_repository::OutboxRepository;
+pub use transaction_repository::{
+    AccountStats, BalanceDrift, BatchFailure, BatchPreview, ProjectedBalance, ReconciliationPage, TransactionFilter,
+    TransactionRepository,
+};
+pub use webhook_repository::WebhookRepository;
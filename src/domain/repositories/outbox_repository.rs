@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::domain::entities::OutboxEvent;
+use crate::domain::errors::RepositoryError;
+
+/// Pending webhook events for the outbox relay.
+/// Events are written by the transaction repository inside each `execute_*` call.
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// Lease up to `limit` of the oldest unsent events for `lease`, so concurrent relays
+    /// skip them. An event whose lease runs out before it is marked sent is claimed again.
+    async fn claim_unsent(&self, limit: i64, lease: Duration) -> Result<Vec<OutboxEvent>, RepositoryError>;
+
+    /// Record that the event was handed to every matching webhook
+    async fn mark_sent(&self, id: Uuid) -> Result<(), RepositoryError>;
+}
@@ -1,9 +1,82 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use uuid::Uuid;
 
-use crate::domain::entities::Transaction;
+use crate::domain::entities::{Account, Transaction};
 use crate::domain::errors::RepositoryError;
-use crate::domain::value_objects::TransactionType;
+use crate::domain::value_objects::{TransactionStatus, TransactionType};
+
+/// Optional filters for listing an account's transactions. All bounds are inclusive.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionFilter {
+    pub transaction_type: Option<TransactionType>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Also match transactions moved to the archive by the retention job
+    pub include_archived: bool,
+}
+
+/// Balance of one account before and after a previewed batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectedBalance {
+    pub account_id: Uuid,
+    pub current_balance: Decimal,
+    pub projected_balance: Decimal,
+}
+
+/// The first operation of a previewed batch that would be rejected
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchFailure {
+    pub index: usize,
+    pub reason: String,
+}
+
+/// Outcome of applying a batch in a transaction that is rolled back
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchPreview {
+    /// Every existing account the batch touches, ordered by id
+    pub balances: Vec<ProjectedBalance>,
+    /// Set when an operation would fail; balances then reflect the operations before it
+    pub failure: Option<BatchFailure>,
+}
+
+/// An account whose stored balances disagree with its completed transactions
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceDrift {
+    pub account_id: Uuid,
+    pub stored_balance: Decimal,
+    /// Deposits and incoming transfers minus withdrawals, outgoing transfers and captured holds
+    pub ledger_balance: Decimal,
+    pub stored_held_balance: Decimal,
+    /// Sum of the account's pending authorizations
+    pub ledger_held_balance: Decimal,
+}
+
+/// Totals over an account's transactions. Amounts only count completed transactions.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AccountStats {
+    /// Deposits into the account
+    pub total_credited: Decimal,
+    /// Withdrawals and captured holds
+    pub total_debited: Decimal,
+    pub transfer_in: Decimal,
+    pub transfer_out: Decimal,
+    /// Every matching transaction, whatever its status
+    pub transaction_count: i64,
+    pub first_transaction_at: Option<DateTime<Utc>>,
+    pub last_transaction_at: Option<DateTime<Utc>>,
+}
+
+/// One page of accounts checked against their transactions
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReconciliationPage {
+    /// Number of accounts compared; accounts that were locked are not counted
+    pub checked: usize,
+    /// Cursor for the next page; `None` once no accounts were left
+    pub last_account_id: Option<Uuid>,
+    pub drifts: Vec<BalanceDrift>,
+}
 
 /// Repository trait for Transaction persistence operations
 #[async_trait]
@@ -16,9 +89,25 @@ pub trait TransactionRepository: Send + Sync {
     /// Find transaction by ID
     async fn find_by_id(&self, id: Uuid) -> Result<Transaction, RepositoryError>;
 
-    /// Find transaction by idempotency key
+    /// Find the most recent transaction recorded under an idempotency key
     async fn find_by_idempotency_key(&self, key: &str) -> Result<Transaction, RepositoryError>;
 
+    /// Find the transaction whose idempotency key is still live, created at or after `since`
+    async fn find_recent_by_idempotency_key(
+        &self,
+        key: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Transaction, RepositoryError>;
+
+    /// Release the idempotency keys of up to `limit` transactions created before `cutoff`
+    /// so the keys can be reused. The transactions keep their keys for reference.
+    /// Returns the number of keys released
+    async fn expire_idempotency_keys(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<u64, RepositoryError>;
+
     /// Check if idempotency key exists
     async fn idempotency_key_exists(&self, key: &str) -> Result<bool, RepositoryError>;
 
@@ -30,6 +119,39 @@ pub trait TransactionRepository: Send + Sync {
         offset: i64,
     ) -> Result<Vec<Transaction>, RepositoryError>;
 
+    /// List transactions for an account matching `filter` (paginated)
+    /// An empty filter behaves like `list_by_account`
+    async fn list_by_account_filtered(
+        &self,
+        account_id: Uuid,
+        filter: &TransactionFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError>;
+
+    /// Count transactions for an account matching `filter`
+    async fn count_by_account(
+        &self,
+        account_id: Uuid,
+        filter: &TransactionFilter,
+    ) -> Result<i64, RepositoryError>;
+
+    /// List the transactions touching any of `account_ids` matching `filter` (paginated).
+    /// A transfer between two of the accounts is listed once
+    async fn list_by_accounts_filtered(
+        &self,
+        account_ids: &[Uuid],
+        filter: &TransactionFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError>;
+
+    /// Count the transactions `list_by_accounts_filtered` would return
+    async fn count_by_accounts(&self, account_ids: &[Uuid], filter: &TransactionFilter) -> Result<i64, RepositoryError>;
+
+    /// Aggregate the account's transactions matching `filter`; all zeros when there are none
+    async fn account_stats(&self, account_id: Uuid, filter: &TransactionFilter) -> Result<AccountStats, RepositoryError>;
+
     /// List transactions by type (paginated)
     async fn list_by_type(
         &self,
@@ -38,16 +160,33 @@ pub trait TransactionRepository: Send + Sync {
         offset: i64,
     ) -> Result<Vec<Transaction>, RepositoryError>;
 
+    /// List transactions in `status`, newest first (paginated)
+    /// For operational queries such as finding transactions stuck in `Pending`
+    async fn list_by_status(
+        &self,
+        status: TransactionStatus,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError>;
+
     /// List all transactions (paginated)
     async fn list(&self, limit: i64, offset: i64) -> Result<Vec<Transaction>, RepositoryError>;
 
     /// Execute credit transaction atomically
-    /// Updates account balance and creates transaction record
+    /// Updates account balance and creates transaction record with status `Completed`
     async fn execute_credit(
         &self,
         transaction: &Transaction,
     ) -> Result<Transaction, RepositoryError>;
 
+    /// Create `account` already holding its opening balance together with the
+    /// `transaction` crediting it, in one database transaction
+    async fn execute_opening_credit(
+        &self,
+        account: &Account,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError>;
+
     /// Execute debit transaction atomically
     /// Updates account balance and creates transaction record
     async fn execute_debit(
@@ -61,4 +200,57 @@ pub trait TransactionRepository: Send + Sync {
         &self,
         transaction: &Transaction,
     ) -> Result<Transaction, RepositoryError>;
+
+    /// Record a zero-amount verification transaction
+    /// Requires an active account; the balance is untouched
+    async fn execute_verification(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError>;
+
+    /// Place a hold for a pending authorization
+    /// Requires an active account with enough unheld funds; the balance itself is untouched
+    async fn execute_authorization(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError>;
+
+    /// Capture a pending authorization: debit the held funds and release the hold
+    async fn execute_capture(&self, authorization_id: Uuid) -> Result<Transaction, RepositoryError>;
+
+    /// Void a pending authorization: release the hold without debiting anything
+    async fn execute_void(&self, authorization_id: Uuid) -> Result<Transaction, RepositoryError>;
+
+    /// Move up to `limit` transactions created before `cutoff` into the archive
+    /// Balances are untouched. Returns the number of transactions archived
+    async fn archive_before(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<u64, RepositoryError>;
+
+    /// Sum of the account's withdrawals, outgoing transfers and unvoided authorizations
+    /// created at or after `since`
+    async fn sum_debits_since(
+        &self,
+        account_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Decimal, RepositoryError>;
+
+    /// Apply `operations` in order inside a database transaction that is always
+    /// rolled back, reporting the resulting balances. Nothing is persisted
+    async fn preview_batch(&self, operations: &[Transaction]) -> Result<BatchPreview, RepositoryError>;
+
+    /// Run `transfer` inside a database transaction that is always rolled back, with the
+    /// same checks as `execute_transfer`. Nothing is persisted and no events are recorded
+    async fn simulate_transfer(&self, transfer: &Transaction) -> Result<BatchPreview, RepositoryError>;
+
+    /// Compare the stored balances of up to `limit` accounts ordered by id, starting
+    /// after `after`, with their live and archived completed transactions.
+    /// Accounts locked by an in-flight money movement are skipped rather than waited for
+    async fn find_balance_drift(
+        &self,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<ReconciliationPage, RepositoryError>;
 }
\ No newline at end of file
@@ -1,12 +1,37 @@
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use crate::domain::entities::Webhook;
+use crate::domain::entities::{Webhook, WebhookDeadLetter, WebhookOutboxEntry};
 use crate::domain::errors::RepositoryError;
 
 #[async_trait]
 pub trait WebhookRepository: Send + Sync {
+    /// Fails with `DuplicateEntry` if the account already subscribes `url` to `event`.
+    /// Disabled webhooks don't count.
     async fn create(&self, webhook: Webhook) -> Result<Webhook, RepositoryError>;
+    /// Insert `webhook`, or return the account's existing subscription to the same
+    /// `url` and `event` untouched. The flag is true when `webhook` was inserted.
+    async fn create_or_get(&self, webhook: Webhook) -> Result<(Webhook, bool), RepositoryError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Webhook, RepositoryError>;
+    /// Like `find_by_id`, but also finds webhooks that have been disabled
+    async fn find_by_id_including_deleted(&self, id: Uuid) -> Result<Webhook, RepositoryError>;
     async fn list_by_account(&self, account_id: Uuid) -> Result<Vec<Webhook>, RepositoryError>;
+    /// Persist `url`, `event` and the verification state. Fails with `DuplicateEntry`
+    /// if that would duplicate another of the account's subscriptions.
+    async fn update(&self, webhook: &Webhook) -> Result<(), RepositoryError>;
+    /// Persist `secret`, `previous_secret` and `previous_secret_expires_at`
+    async fn update_secret(&self, webhook: &Webhook) -> Result<(), RepositoryError>;
+    /// Disable the webhook. It stops receiving events and no longer shows up in lookups,
+    /// but the row and its delivery history are kept.
     async fn delete(&self, id: Uuid) -> Result<(), RepositoryError>;
+    /// Permanently remove the webhook, disabled or not, with its delivery history
+    async fn purge(&self, id: Uuid) -> Result<(), RepositoryError>;
+    /// Atomically bump the webhook's delivery counter and return the new value (first is 1)
+    async fn next_sequence(&self, id: Uuid) -> Result<i64, RepositoryError>;
+    async fn dead_letter(&self, letter: WebhookDeadLetter) -> Result<WebhookDeadLetter, RepositoryError>;
+    async fn list_dead_letters(&self, webhook_id: Uuid) -> Result<Vec<WebhookDeadLetter>, RepositoryError>;
+    async fn enqueue_outbox(&self, entry: WebhookOutboxEntry) -> Result<WebhookOutboxEntry, RepositoryError>;
+    /// Remove and return up to `limit` of the oldest outbox entries.
+    /// Concurrent callers never receive the same entry.
+    async fn claim_outbox(&self, limit: i64) -> Result<Vec<WebhookOutboxEntry>, RepositoryError>;
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
 pub mod webhook_dispatcher;
+pub mod webhook_payload_schema;
 
-pub use webhook_dispatcher::WebhookDispatcher;
+pub use webhook_dispatcher::{DeliveryContext, WebhookDispatcher};
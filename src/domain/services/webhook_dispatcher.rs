@@ -1,7 +1,34 @@
 use async_trait::async_trait;
+use uuid::Uuid;
 
+/// What a dispatch is delivering, for delivery logs and headers
+#[derive(Debug, Clone, Default)]
+pub struct DeliveryContext {
+    /// Subscription being delivered to
+    pub webhook_id: Uuid,
+    /// Event name, e.g. `transaction.deposit.completed`
+    pub event: String,
+    /// Id of the API request that triggered the event, if any
+    pub request_id: Option<String>,
+    /// Secret replaced by a rotation that is still in its grace period.
+    /// Deliveries carry a second signature made with it.
+    pub previous_secret: Option<String>,
+    /// Per-webhook timeout override, capped by the dispatcher's maximum
+    pub timeout: Option<std::time::Duration>,
+    /// The receiver accepts `Content-Encoding: gzip` bodies
+    pub accepts_gzip: bool,
+    /// Per-webhook delivery number, increasing by one with every event
+    pub sequence: Option<i64>,
+}
 
 #[async_trait]
 pub trait WebhookDispatcher: Send + Sync {
-    async fn dispatch(&self, url: &str, payload: &serde_json::Value, secret: &str) -> Result<(), String>;
+    /// Deliver `payload` to `url`, signed with `secret`.
+    async fn dispatch(
+        &self,
+        url: &str,
+        payload: &serde_json::Value,
+        secret: &str,
+        context: &DeliveryContext,
+    ) -> Result<(), String>;
 }
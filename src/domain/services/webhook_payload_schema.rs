@@ -0,0 +1,145 @@
+use serde_json::{json, Value};
+
+use crate::domain::value_objects::WebhookEvent;
+
+/// JSON Schema declared for the payload of each webhook event.
+pub fn payload_schema(event: &WebhookEvent) -> Value {
+    match event {
+        WebhookEvent::TransactionCompleted
+        | WebhookEvent::TransactionDepositCompleted
+        | WebhookEvent::TransactionWithdrawalCompleted
+        | WebhookEvent::TransactionTransferCompleted => json!({
+            "type": "object",
+            "required": ["id", "transaction_type", "amount", "created_at"],
+            "properties": {
+                "id": { "type": "string" },
+                "transaction_type": { "type": "string" },
+                "from_account_id": { "type": ["string", "null"] },
+                "to_account_id": { "type": ["string", "null"] },
+                "amount": { "type": ["string", "number"] },
+                "idempotency_key": { "type": ["string", "null"] },
+                "created_at": { "type": "string" },
+                "status": { "type": "string" },
+                "request_id": { "type": "string" }
+            }
+        }),
+        WebhookEvent::AccountCreated | WebhookEvent::AccountUpdated => json!({
+            "type": "object",
+            "required": ["id", "business_name", "balance", "created_at"],
+            "properties": {
+                "id": { "type": "string" },
+                "business_name": { "type": "string" },
+                "balance": { "type": ["string", "number"] },
+                "created_at": { "type": "string" },
+                "status": { "type": "string" },
+                "request_id": { "type": "string" }
+            }
+        }),
+        WebhookEvent::BalanceLow => json!({
+            "type": "object",
+            "required": ["account_id", "balance", "previous_balance", "threshold", "transaction_id"],
+            "properties": {
+                "account_id": { "type": "string" },
+                "balance": { "type": ["string", "number"] },
+                "previous_balance": { "type": ["string", "number"] },
+                "threshold": { "type": ["string", "number"] },
+                "transaction_id": { "type": "string" },
+                "request_id": { "type": "string" }
+            }
+        }),
+    }
+}
+
+/// Validate `payload` against the schema declared for `event`.
+pub fn validate_payload(event: &WebhookEvent, payload: &Value) -> Result<(), String> {
+    validate(&payload_schema(event), payload, "$")
+}
+
+/// Validate `value` against the subset of JSON Schema used by `payload_schema`:
+/// `type` (single or list), `required` and `properties`.
+fn validate(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(ty) => vec![ty.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.iter().any(|ty| matches_type(ty, value)) {
+            return Err(format!("{}: expected {}, got {}", path, allowed.join(" or "), type_name(value)));
+        }
+    }
+
+    if let (Some(required), Some(object)) = (schema.get("required").and_then(Value::as_array), value.as_object()) {
+        for field in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(field) {
+                return Err(format!("{}: missing required field `{}`", path, field));
+            }
+        }
+    }
+
+    if let (Some(properties), Some(object)) = (schema.get("properties").and_then(Value::as_object), value.as_object()) {
+        for (field, field_schema) in properties {
+            if let Some(field_value) = object.get(field) {
+                validate(field_schema, field_value, &format!("{}.{}", path, field))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_type(ty: &str, value: &Value) -> bool {
+    match ty {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_transaction_payload() {
+        let payload = json!({
+            "id": "5f0c6f5e-8a3b-4f7e-9a59-0e4b7c1d2e3f",
+            "transaction_type": "credit",
+            "from_account_id": null,
+            "to_account_id": "0b7e2d7a-3c1f-4b8e-8f0a-6d5c4b3a2910",
+            "amount": "100.00",
+            "idempotency_key": null,
+            "created_at": "2025-01-01T00:00:00Z"
+        });
+        assert!(validate_payload(&WebhookEvent::TransactionDepositCompleted, &payload).is_ok());
+    }
+
+    #[test]
+    fn test_malformed_payloads_are_rejected() {
+        let missing_amount = json!({ "id": "x", "transaction_type": "credit", "created_at": "now" });
+        let err = validate_payload(&WebhookEvent::TransactionCompleted, &missing_amount).unwrap_err();
+        assert!(err.contains("amount"));
+
+        let wrong_type = json!({ "id": 42, "transaction_type": "credit", "amount": "1", "created_at": "now" });
+        let err = validate_payload(&WebhookEvent::TransactionCompleted, &wrong_type).unwrap_err();
+        assert!(err.contains("$.id"));
+
+        assert!(validate_payload(&WebhookEvent::AccountCreated, &json!("garbage")).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+use crate::domain::errors::DomainError;
+
+/// Lifecycle status of an account. Only `Active` accounts can move money.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountStatus {
+    #[default]
+    Active,
+    /// Temporarily blocked from transacting, e.g. pending a fraud review
+    Frozen,
+    /// Permanently closed
+    Closed,
+}
+
+impl FromStr for AccountStatus {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(Self::Active),
+            "frozen" => Ok(Self::Frozen),
+            "closed" => Ok(Self::Closed),
+            _ => Err(DomainError::InvalidAccountState(format!(
+                "Invalid account status: {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl AccountStatus {
+    /// Convert to database string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Frozen => "frozen",
+            Self::Closed => "closed",
+        }
+    }
+}
+
+impl fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_status_round_trip() {
+        for status in [AccountStatus::Active, AccountStatus::Frozen, AccountStatus::Closed] {
+            assert_eq!(status.as_str().parse::<AccountStatus>().unwrap(), status);
+        }
+        assert!("suspended".parse::<AccountStatus>().is_err());
+    }
+}
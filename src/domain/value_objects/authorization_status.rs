@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+use crate::domain::errors::DomainError;
+
+/// Lifecycle of an authorization (hold). Only `Pending` holds can be captured or voided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorizationStatus {
+    /// Funds are held on the account but not yet debited
+    Pending,
+    /// The held funds were debited
+    Captured,
+    /// The hold was released without debiting anything
+    Voided,
+}
+
+impl FromStr for AuthorizationStatus {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(Self::Pending),
+            "captured" => Ok(Self::Captured),
+            "voided" => Ok(Self::Voided),
+            _ => Err(DomainError::InvalidTransactionState(format!(
+                "Invalid authorization status: {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl AuthorizationStatus {
+    /// Convert to database string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Captured => "captured",
+            Self::Voided => "voided",
+        }
+    }
+
+    /// Whether a hold in this status may move to `next`
+    pub fn can_transition_to(&self, next: AuthorizationStatus) -> bool {
+        matches!(
+            (self, next),
+            (Self::Pending, Self::Captured) | (Self::Pending, Self::Voided)
+        )
+    }
+}
+
+impl fmt::Display for AuthorizationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorization_status_round_trip() {
+        for status in [
+            AuthorizationStatus::Pending,
+            AuthorizationStatus::Captured,
+            AuthorizationStatus::Voided,
+        ] {
+            assert_eq!(status.as_str().parse::<AuthorizationStatus>().unwrap(), status);
+        }
+        assert!("settled".parse::<AuthorizationStatus>().is_err());
+    }
+
+    #[test]
+    fn test_only_pending_holds_can_settle() {
+        use AuthorizationStatus::*;
+        assert!(Pending.can_transition_to(Captured));
+        assert!(Pending.can_transition_to(Voided));
+        assert!(!Captured.can_transition_to(Voided));
+        assert!(!Voided.can_transition_to(Captured));
+        assert!(!Captured.can_transition_to(Captured));
+    }
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+/// Currency of a monetary amount. The ledger holds a single currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    #[default]
+    Usd,
+}
+
+impl Currency {
+    /// ISO 4217 code
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Usd => "USD",
+        }
+    }
+
+    /// Decimal places of the minor unit (cents for USD)
+    pub fn minor_unit_scale(&self) -> u32 {
+        match self {
+            Self::Usd => 2,
+        }
+    }
+}
+
+impl FromStr for Currency {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "USD" => Ok(Self::Usd),
+            _ => Err(DomainError::InvalidAmount(format!("Unsupported currency: {}", s))),
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+/// Which idempotency keys clients may send
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdempotencyKeyFormat {
+    /// Any non-empty key of up to 255 characters
+    #[default]
+    Any,
+    /// Only keys that parse as a UUID, so weak client-chosen keys (e.g. `"1"`) cannot collide
+    Uuid,
+}
+
+impl FromStr for IdempotencyKeyFormat {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "any" => Ok(Self::Any),
+            "uuid" => Ok(Self::Uuid),
+            _ => Err(DomainError::InvalidAmount(format!(
+                "Invalid idempotency key format: {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl IdempotencyKeyFormat {
+    /// Convert to configuration string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::Uuid => "uuid",
+        }
+    }
+}
+
+impl fmt::Display for IdempotencyKeyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -1,8 +1,28 @@
 pub mod money;
+pub mod currency;
 pub mod transaction_type;
 pub mod webhook_event;
 pub mod delivery_status;
+pub mod account_status;
+pub mod webhook_status;
+pub mod transaction_limits;
+pub mod authorization_status;
+pub mod idempotency_key_format;
+pub mod transaction_status;
+pub mod webhook_overflow_policy;
+pub mod log_format;
+pub mod money_format;
 
-pub use money::Money;
+pub use money::{Money, RichMoney};
+pub use currency::Currency;
 pub use transaction_type::TransactionType;
-pub use webhook_event::WebhookEvent;
\ No newline at end of file
+pub use webhook_event::WebhookEvent;
+pub use account_status::AccountStatus;
+pub use webhook_status::WebhookStatus;
+pub use transaction_limits::TransactionLimits;
+pub use authorization_status::AuthorizationStatus;
+pub use idempotency_key_format::IdempotencyKeyFormat;
+pub use transaction_status::TransactionStatus;
+pub use webhook_overflow_policy::WebhookOverflowPolicy;
+pub use log_format::LogFormat;
+pub use money_format::MoneyFormat;
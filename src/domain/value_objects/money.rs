@@ -1,16 +1,44 @@
-use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::ops::{Add, Sub};
 
 use crate::domain::errors::DomainError;
-
-/// Money value object representing monetary amounts with precision
+use crate::domain::value_objects::Currency;
+
+/// Money value object representing monetary amounts with precision.
+///
+/// The amount is held as a whole number of minor units (cents), so `100`, `100.0`
+/// and `100.0000` are the same amount whatever scale the `Decimal` they came from
+/// carried. `Decimal` remains the interface: `amount()` always has two decimal
+/// places, matching the `DECIMAL(20, 2)` columns amounts are stored in.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "MoneyRepr", into = "MoneyRepr")]
 pub struct Money {
+    minor_units: i64,
+}
+
+/// Serialized form of `Money`, a decimal amount
+#[derive(Serialize, Deserialize)]
+struct MoneyRepr {
     amount: Decimal,
 }
 
+impl From<Money> for MoneyRepr {
+    fn from(money: Money) -> Self {
+        Self { amount: money.amount() }
+    }
+}
+
+impl TryFrom<MoneyRepr> for Money {
+    type Error = DomainError;
+
+    fn try_from(repr: MoneyRepr) -> Result<Self, Self::Error> {
+        Money::new(repr.amount)
+    }
+}
+
 use std::str::FromStr;
 
 impl FromStr for Money {
@@ -25,36 +53,73 @@ impl FromStr for Money {
 }
 
  impl Money {
+    /// Largest representable amount (~999 trillion), keeping arithmetic clear of overflow
+    pub const MAX_AMOUNT: Decimal = rust_decimal::dec!(999999999999999);
+
+    /// `MAX_AMOUNT` in minor units
+    const MAX_MINOR_UNITS: i64 = 99_999_999_999_999_900;
+
+    /// The single currency the ledger holds
+    pub const CURRENCY: Currency = Currency::Usd;
+
     /// Create a new Money instance
     pub fn new(amount: Decimal) -> Result<Self, DomainError> {
-        let money = Self { amount };
+        if amount.is_sign_negative() {
+            return Err(DomainError::InvalidAmount(
+                "Amount cannot be negative".to_string(),
+            ));
+        }
+
+        // Only the value counts, not the scale: 100.0000 is a whole number of cents
+        let minor_units = amount
+            .checked_mul(Decimal::ONE_HUNDRED)
+            .ok_or_else(|| DomainError::InvalidAmount("Amount exceeds maximum allowed value".to_string()))?;
+        if !minor_units.fract().is_zero() {
+            return Err(DomainError::InvalidAmount(
+                "Amount cannot have more than 2 decimal places".to_string(),
+            ));
+        }
+
+        let minor_units = minor_units
+            .to_i64()
+            .ok_or_else(|| DomainError::InvalidAmount("Amount exceeds maximum allowed value".to_string()))?;
+        Self::from_minor_units(minor_units, Self::CURRENCY)
+    }
+
+    /// Create a Money instance from a whole number of minor units, e.g. cents
+    pub fn from_minor_units(minor_units: i64, currency: Currency) -> Result<Self, DomainError> {
+        if currency != Self::CURRENCY {
+            return Err(DomainError::InvalidAmount(format!("Unsupported currency: {}", currency)));
+        }
+        let money = Self { minor_units };
         money.validate()?;
         Ok(money)
     }
 
-    /// Get the amount as Decimal
+    /// Get the amount as Decimal, with exactly two decimal places
     pub fn amount(&self) -> Decimal {
-        self.amount
+        Decimal::new(self.minor_units, Self::CURRENCY.minor_unit_scale())
+    }
+
+    /// Get the amount in minor units, e.g. cents
+    pub fn to_minor_units(self) -> i64 {
+        self.minor_units
+    }
+
+    pub fn currency(&self) -> Currency {
+        Self::CURRENCY
     }
 
     /// Validate money constraints
     pub fn validate(&self) -> Result<(), DomainError> {
-        if self.amount.is_sign_negative() {
+        if self.minor_units < 0 {
             return Err(DomainError::InvalidAmount(
                 "Amount cannot be negative".to_string(),
             ));
         }
 
-        // Check for reasonable precision (2 decimal places for currency)
-        if self.amount.scale() > 2 {
-            return Err(DomainError::InvalidAmount(
-                "Amount cannot have more than 2 decimal places".to_string(),
-            ));
-        }
-
         // Check for reasonable maximum (avoid overflow)
-        let max = Decimal::from(999_999_999_999_999i64); // ~999 trillion
-        if self.amount > max {
+        if self.minor_units > Self::MAX_MINOR_UNITS {
             return Err(DomainError::InvalidAmount(
                 "Amount exceeds maximum allowed value".to_string(),
             ));
@@ -65,30 +130,77 @@ impl FromStr for Money {
 
     /// Add two Money values
     pub fn checked_add(self, other: Money) -> Result<Money, DomainError> {
-        let result = self.amount + other.amount;
-        Money::new(result)
+        Money::from_minor_units(self.minor_units + other.minor_units, Self::CURRENCY)
     }
 
     /// Subtract two Money values
     pub fn checked_sub(self, other: Money) -> Result<Money, DomainError> {
-        let result = self.amount - other.amount;
-        Money::new(result)
+        Money::from_minor_units(self.minor_units - other.minor_units, Self::CURRENCY)
+    }
+
+    /// Multiply by `factor`, e.g. a fee rate, rounding half a cent away from zero
+    pub fn multiply(self, factor: Decimal) -> Result<Money, DomainError> {
+        let product = self
+            .amount()
+            .checked_mul(factor)
+            .ok_or_else(|| DomainError::InvalidAmount("Amount exceeds maximum allowed value".to_string()))?;
+        Money::new(product.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero))
+    }
+
+    /// Split into `parts` amounts as equal as possible that add up to exactly this one
+    pub fn split(self, parts: usize) -> Result<Vec<Money>, DomainError> {
+        self.allocate(&vec![1; parts])
+    }
+
+    /// Distribute this amount in proportion to `ratios` without losing or creating a cent.
+    ///
+    /// Each share is rounded down to the cent; the cents left over go one each to the
+    /// shares with a non-zero ratio, in order, so the same input always splits the same way.
+    pub fn allocate(self, ratios: &[u32]) -> Result<Vec<Money>, DomainError> {
+        let total_ratio: i128 = ratios.iter().map(|&ratio| i128::from(ratio)).sum();
+        if total_ratio == 0 {
+            return Err(DomainError::InvalidAmount(
+                "Cannot allocate an amount without a non-zero ratio".to_string(),
+            ));
+        }
+
+        let cents = i128::from(self.minor_units);
+        let mut shares: Vec<i128> = ratios
+            .iter()
+            .map(|&ratio| cents * i128::from(ratio) / total_ratio)
+            .collect();
+
+        let mut remainder = cents - shares.iter().sum::<i128>();
+        for (share, &ratio) in shares.iter_mut().zip(ratios) {
+            if remainder == 0 {
+                break;
+            }
+            if ratio > 0 {
+                *share += 1;
+                remainder -= 1;
+            }
+        }
+
+        shares
+            .into_iter()
+            .map(|share| Money::from_minor_units(share as i64, Self::CURRENCY))
+            .collect()
     }
 
     /// Check if amount is zero
     pub fn is_zero(&self) -> bool {
-        self.amount.is_zero()
+        self.minor_units == 0
     }
 
     /// Check if amount is positive
     pub fn is_positive(&self) -> bool {
-        self.amount > Decimal::ZERO
+        self.minor_units > 0
     }
 }
 
 impl fmt::Display for Money {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:.2}", self.amount)
+        write!(f, "{:.2}", self.amount())
     }
 }
 
@@ -110,7 +222,43 @@ impl Sub for Money {
 
 impl From<Money> for Decimal {
     fn from(money: Money) -> Self {
-        money.amount
+        money.amount()
+    }
+}
+
+/// An amount in every representation at once, so clients need not guess the
+/// precision or currency of a bare decimal string
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RichMoney {
+    /// The amount with exactly two decimal places, e.g. `"100.50"`
+    pub decimal: String,
+    /// The amount in cents, e.g. `10050`
+    pub minor_units: i64,
+    pub currency: String,
+}
+
+impl RichMoney {
+    /// Describe an amount with at most two decimal places. Minor units saturate
+    /// outside the `i64` range, far beyond `Money::MAX_AMOUNT`
+    pub fn from_decimal(amount: Decimal) -> Self {
+        let minor_units = (amount * Decimal::ONE_HUNDRED).round();
+        Self {
+            decimal: format!("{:.2}", amount),
+            minor_units: minor_units
+                .to_i64()
+                .unwrap_or(if minor_units.is_sign_negative() { i64::MIN } else { i64::MAX }),
+            currency: Money::CURRENCY.to_string(),
+        }
+    }
+}
+
+impl From<Money> for RichMoney {
+    fn from(money: Money) -> Self {
+        Self {
+            decimal: money.to_string(),
+            minor_units: money.to_minor_units(),
+            currency: money.currency().to_string(),
+        }
     }
 }
 
@@ -125,6 +273,49 @@ mod tests {
         assert_eq!(money.amount(), dec!(100.50));
     }
 
+    #[test]
+    fn test_rich_money_representations_agree() {
+        let rich = RichMoney::from(Money::new(dec!(100.5)).unwrap());
+        assert_eq!(rich.decimal, "100.50");
+        assert_eq!(rich.minor_units, 10050);
+        assert_eq!(rich.currency, "USD");
+
+        let max = RichMoney::from_decimal(Money::MAX_AMOUNT);
+        assert_eq!(max.minor_units, 99_999_999_999_999_900);
+    }
+
+    #[test]
+    fn test_scale_does_not_change_the_amount() {
+        let whole = Money::new(dec!(100)).unwrap();
+        assert_eq!(Money::new(dec!(100.0)).unwrap(), whole);
+        assert_eq!(Money::new(dec!(100.0000)).unwrap(), whole);
+        assert_eq!(whole.amount().scale(), 2);
+        assert_eq!(whole.to_minor_units(), 10000);
+
+        assert_eq!(Money::new(dec!(10.1200)).unwrap().to_minor_units(), 1012);
+        assert!(Money::new(dec!(10.1201)).is_err());
+    }
+
+    #[test]
+    fn test_minor_units_round_trip() {
+        let money = Money::from_minor_units(10050, Currency::Usd).unwrap();
+        assert_eq!(money.amount(), dec!(100.50));
+        assert_eq!(money.to_minor_units(), 10050);
+        assert_eq!(Money::new(money.amount()).unwrap(), money);
+
+        assert!(Money::from_minor_units(-1, Currency::Usd).is_err());
+        assert!(Money::from_minor_units(i64::MAX, Currency::Usd).is_err());
+    }
+
+    #[test]
+    fn test_money_serializes_as_decimal_amount() {
+        let money = Money::new(dec!(5)).unwrap();
+        let json = serde_json::to_value(money).unwrap();
+        assert_eq!(json, serde_json::json!({ "amount": "5.00" }));
+        assert_eq!(serde_json::from_value::<Money>(json).unwrap(), money);
+        assert!(serde_json::from_value::<Money>(serde_json::json!({ "amount": "-1" })).is_err());
+    }
+
     #[test]
     fn test_create_zero_money() {
         let money = Money::new(dec!(0.00)).unwrap();
@@ -168,6 +359,59 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_multiply_rounds_to_cents() {
+        let amount = Money::new(dec!(10.00)).unwrap();
+        // 2.9% + $0.30
+        let fee = amount.multiply(dec!(0.029)).unwrap().checked_add(Money::new(dec!(0.30)).unwrap()).unwrap();
+        assert_eq!(fee.amount(), dec!(0.59));
+
+        assert_eq!(Money::new(dec!(0.05)).unwrap().multiply(dec!(0.5)).unwrap().amount(), dec!(0.03));
+        assert!(amount.multiply(dec!(-1)).is_err());
+        assert!(Money::new(Money::MAX_AMOUNT).unwrap().multiply(dec!(2)).is_err());
+    }
+
+    #[test]
+    fn test_split_and_allocate_assign_remainder_in_order() {
+        let amount = Money::new(dec!(100.00)).unwrap();
+        let parts: Vec<Decimal> = amount.split(3).unwrap().into_iter().map(|m| m.amount()).collect();
+        assert_eq!(parts, vec![dec!(33.34), dec!(33.33), dec!(33.33)]);
+
+        let parts: Vec<Decimal> = Money::new(dec!(0.05)).unwrap()
+            .allocate(&[0, 3, 7])
+            .unwrap()
+            .into_iter()
+            .map(|m| m.amount())
+            .collect();
+        assert_eq!(parts, vec![dec!(0.00), dec!(0.02), dec!(0.03)]);
+
+        assert!(amount.split(0).is_err());
+        assert!(amount.allocate(&[0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_allocate_never_loses_or_creates_a_cent() {
+        let ratio_sets: [&[u32]; 6] = [&[1], &[1, 1], &[1, 1, 1], &[70, 20, 10], &[0, 1, 2, 3], &[3, 7, 11, 13, 17]];
+        let amounts = (0..1000i64)
+            .map(|cents| Decimal::new(cents, 2))
+            .chain([dec!(1234567.89), Money::MAX_AMOUNT]);
+
+        for amount in amounts {
+            let money = Money::new(amount).unwrap();
+            for ratios in ratio_sets {
+                let shares = money.allocate(ratios).unwrap();
+                assert_eq!(shares.len(), ratios.len());
+                let total: Decimal = shares.iter().map(|share| share.amount()).sum();
+                assert_eq!(total, amount, "allocating {} by {:?}", amount, ratios);
+                for (share, &ratio) in shares.iter().zip(ratios) {
+                    if ratio == 0 {
+                        assert!(share.is_zero());
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_money_display() {
         let money = Money::new(dec!(1234.56)).unwrap();
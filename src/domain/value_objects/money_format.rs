@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+/// How monetary amounts are written in API responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MoneyFormat {
+    /// A bare decimal string, e.g. `"100.50"`
+    #[default]
+    Plain,
+    /// An object with the decimal string, minor units and currency
+    Rich,
+}
+
+impl FromStr for MoneyFormat {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "plain" => Ok(Self::Plain),
+            "rich" => Ok(Self::Rich),
+            _ => Err(DomainError::InvalidAmount(format!(
+                "Invalid money format: {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl MoneyFormat {
+    /// Convert to configuration string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Plain => "plain",
+            Self::Rich => "rich",
+        }
+    }
+}
+
+impl fmt::Display for MoneyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
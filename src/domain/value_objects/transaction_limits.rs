@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::errors::DomainError;
+use crate::domain::value_objects::Money;
+
+/// Caps on how much an account may move. `None` means unlimited.
+///
+/// Also carries the balance below which the account is alerted, which never blocks a debit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TransactionLimits {
+    /// Largest single withdrawal or outgoing transfer
+    pub per_transaction: Option<Money>,
+    /// Total withdrawals and outgoing transfers per UTC day
+    pub daily_debit: Option<Money>,
+    /// A debit leaving the balance below this fires `balance.low`
+    #[serde(default)]
+    pub low_balance: Option<Money>,
+}
+
+impl TransactionLimits {
+    pub fn new(per_transaction: Option<Money>, daily_debit: Option<Money>) -> Self {
+        Self {
+            per_transaction,
+            daily_debit,
+            low_balance: None,
+        }
+    }
+
+    /// Alert when a debit takes the balance below `threshold`
+    pub fn with_low_balance(mut self, threshold: Option<Money>) -> Self {
+        self.low_balance = threshold;
+        self
+    }
+
+    /// Fill in any limit not set here from `defaults`
+    pub fn or(self, defaults: TransactionLimits) -> Self {
+        Self {
+            per_transaction: self.per_transaction.or(defaults.per_transaction),
+            daily_debit: self.daily_debit.or(defaults.daily_debit),
+            low_balance: self.low_balance.or(defaults.low_balance),
+        }
+    }
+
+    /// Whether the balance just fell from at or above the low-balance threshold to below it.
+    /// Only the crossing counts, so an account already below the threshold is not alerted again.
+    pub fn crossed_low_balance(&self, previous: Money, current: Money) -> bool {
+        match self.low_balance {
+            Some(threshold) => previous >= threshold && current < threshold,
+            None => false,
+        }
+    }
+
+    /// Whether no cap applies; the low-balance threshold is not a cap
+    pub fn is_unlimited(&self) -> bool {
+        self.per_transaction.is_none() && self.daily_debit.is_none()
+    }
+
+    /// Check a debit of `amount` on top of `debited_today`
+    pub fn check(&self, amount: Money, debited_today: Money) -> Result<(), DomainError> {
+        if let Some(max) = self.per_transaction
+            && amount > max
+        {
+            return Err(DomainError::LimitExceeded(format!(
+                "Amount {} exceeds the per-transaction limit of {}",
+                amount, max
+            )));
+        }
+
+        if let Some(daily) = self.daily_debit
+            && debited_today.amount() + amount.amount() > daily.amount()
+        {
+            return Err(DomainError::LimitExceeded(format!(
+                "Amount {} exceeds the remaining daily limit ({} of {} already used)",
+                amount, debited_today, daily
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn money(amount: rust_decimal::Decimal) -> Money {
+        Money::new(amount).unwrap()
+    }
+
+    #[test]
+    fn test_account_limits_fall_back_to_defaults() {
+        let defaults = TransactionLimits::new(Some(money(dec!(500))), Some(money(dec!(1000))));
+        let account = TransactionLimits::new(Some(money(dec!(50))), None);
+
+        let effective = account.or(defaults);
+        assert_eq!(effective.per_transaction, Some(money(dec!(50))));
+        assert_eq!(effective.daily_debit, Some(money(dec!(1000))));
+        assert!(TransactionLimits::default().is_unlimited());
+    }
+
+    #[test]
+    fn test_check_enforces_both_limits() {
+        let limits = TransactionLimits::new(Some(money(dec!(100))), Some(money(dec!(250))));
+
+        assert!(limits.check(money(dec!(100)), money(dec!(150))).is_ok());
+        assert!(matches!(
+            limits.check(money(dec!(100.01)), money(dec!(0))),
+            Err(DomainError::LimitExceeded(_))
+        ));
+        assert!(matches!(
+            limits.check(money(dec!(100)), money(dec!(150.01))),
+            Err(DomainError::LimitExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn test_low_balance_fires_only_on_crossing() {
+        let limits = TransactionLimits::default().with_low_balance(Some(money(dec!(100))));
+
+        assert!(limits.crossed_low_balance(money(dec!(150)), money(dec!(99.99))));
+        assert!(limits.crossed_low_balance(money(dec!(100)), money(dec!(50))));
+        assert!(!limits.crossed_low_balance(money(dec!(150)), money(dec!(100))));
+        assert!(!limits.crossed_low_balance(money(dec!(90)), money(dec!(80))));
+        assert!(!TransactionLimits::default().crossed_low_balance(money(dec!(150)), money(dec!(0))));
+        assert!(limits.is_unlimited());
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+use crate::domain::errors::DomainError;
+
+/// Whether a transaction has taken effect. Synchronous operations are recorded as `Completed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionStatus {
+    /// Accepted but not yet settled
+    Pending,
+    /// Balances reflect the transaction
+    Completed,
+    /// Settlement was attempted and rejected; balances are untouched
+    Failed,
+}
+
+impl FromStr for TransactionStatus {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(Self::Pending),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            _ => Err(DomainError::InvalidTransactionState(format!(
+                "Invalid transaction status: {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl TransactionStatus {
+    /// Convert to database string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_status_round_trip() {
+        for status in [
+            TransactionStatus::Pending,
+            TransactionStatus::Completed,
+            TransactionStatus::Failed,
+        ] {
+            assert_eq!(status.as_str().parse::<TransactionStatus>().unwrap(), status);
+        }
+        assert_eq!("COMPLETED".parse::<TransactionStatus>().unwrap(), TransactionStatus::Completed);
+        assert!("settled".parse::<TransactionStatus>().is_err());
+    }
+}
@@ -3,7 +3,7 @@ use std::fmt;
 
 use crate::domain::errors::DomainError;
 
-/// Type of transaction: Credit, Debit, or Transfer
+/// Type of transaction: Credit, Debit, Transfer, Verification, or Authorization
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TransactionType {
@@ -13,6 +13,10 @@ pub enum TransactionType {
     Debit,
     /// Transfer: Move money between two accounts
     Transfer,
+    /// Verification: Zero-amount marker on an account (to_account); never moves money
+    Verification,
+    /// Authorization: Hold on funds of an account (from_account) until captured or voided
+    Authorization,
 }
 
 use std::str::FromStr;
@@ -25,6 +29,8 @@ impl FromStr for TransactionType {
             "credit" => Ok(Self::Credit),
             "debit" => Ok(Self::Debit),
             "transfer" => Ok(Self::Transfer),
+            "verification" => Ok(Self::Verification),
+            "authorization" => Ok(Self::Authorization),
             _ => Err(DomainError::InvalidTransactionType(format!(
                 "Invalid transaction type: {}",
                 s
@@ -40,6 +46,8 @@ impl FromStr for TransactionType {
             Self::Credit => "credit",
             Self::Debit => "debit",
             Self::Transfer => "transfer",
+            Self::Verification => "verification",
+            Self::Authorization => "authorization",
         }
     }
 }
@@ -85,6 +93,7 @@ mod tests {
         assert_eq!(TransactionType::Credit.as_str(), "credit");
         assert_eq!(TransactionType::Debit.as_str(), "debit");
         assert_eq!(TransactionType::Transfer.as_str(), "transfer");
+        assert_eq!(TransactionType::Verification.as_str(), "verification");
     }
 
     #[test]
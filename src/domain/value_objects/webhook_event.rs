@@ -3,17 +3,54 @@ use utoipa::ToSchema;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum WebhookEvent {
+    /// Any completed transaction. Receives every transaction-specific event below.
     #[serde(rename = "transaction.completed")]
     TransactionCompleted,
+    #[serde(rename = "transaction.deposit.completed")]
+    TransactionDepositCompleted,
+    #[serde(rename = "transaction.withdrawal.completed")]
+    TransactionWithdrawalCompleted,
+    #[serde(rename = "transaction.transfer.completed")]
+    TransactionTransferCompleted,
     #[serde(rename = "account.created")]
     AccountCreated,
+    /// An account was renamed, frozen or unfrozen
+    #[serde(rename = "account.updated")]
+    AccountUpdated,
+    /// A withdrawal or outgoing transfer took the balance below the account's low-balance threshold
+    #[serde(rename = "balance.low")]
+    BalanceLow,
+}
+
+impl WebhookEvent {
+    /// Whether a webhook subscribed to `self` should receive the `fired` event.
+    ///
+    /// `transaction.completed` subscribers keep receiving every completed
+    /// transaction; all other subscriptions match their exact event.
+    pub fn covers(&self, fired: &WebhookEvent) -> bool {
+        match self {
+            WebhookEvent::TransactionCompleted => matches!(
+                fired,
+                WebhookEvent::TransactionCompleted
+                    | WebhookEvent::TransactionDepositCompleted
+                    | WebhookEvent::TransactionWithdrawalCompleted
+                    | WebhookEvent::TransactionTransferCompleted
+            ),
+            _ => self == fired,
+        }
+    }
 }
 
 impl std::fmt::Display for WebhookEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             WebhookEvent::TransactionCompleted => write!(f, "transaction.completed"),
+            WebhookEvent::TransactionDepositCompleted => write!(f, "transaction.deposit.completed"),
+            WebhookEvent::TransactionWithdrawalCompleted => write!(f, "transaction.withdrawal.completed"),
+            WebhookEvent::TransactionTransferCompleted => write!(f, "transaction.transfer.completed"),
             WebhookEvent::AccountCreated => write!(f, "account.created"),
+            WebhookEvent::AccountUpdated => write!(f, "account.updated"),
+            WebhookEvent::BalanceLow => write!(f, "balance.low"),
         }
     }
 }
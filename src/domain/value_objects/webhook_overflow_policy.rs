@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+/// What happens to an event when the webhook dispatch pool is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookOverflowPolicy {
+    /// Dead-letter the deliveries straight away; the triggering request never waits
+    #[default]
+    Shed,
+    /// Make the triggering request wait for a free slot, dead-lettering after a timeout
+    Block,
+    /// Park the deliveries in the outbox table, to be dispatched once slots free up
+    Outbox,
+}
+
+impl FromStr for WebhookOverflowPolicy {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "shed" => Ok(Self::Shed),
+            "block" => Ok(Self::Block),
+            "outbox" => Ok(Self::Outbox),
+            _ => Err(DomainError::InvalidWebhookEvent(format!(
+                "Invalid webhook overflow policy: {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl WebhookOverflowPolicy {
+    /// Convert to configuration string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Shed => "shed",
+            Self::Block => "block",
+            Self::Outbox => "outbox",
+        }
+    }
+}
+
+impl fmt::Display for WebhookOverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -1,5 +1,16 @@
+use ipnet::IpNet;
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use std::env;
+use std::net::IpAddr;
+
+use crate::domain::value_objects::{IdempotencyKeyFormat, LogFormat, Money, MoneyFormat, WebhookOverflowPolicy};
+
+/// Shortest `BOOTSTRAP_ADMIN_KEY` accepted
+const MIN_BOOTSTRAP_KEY_LENGTH: usize = 32;
+
+/// Minimum length accepted for API_KEY_PEPPER
+const MIN_KEY_PEPPER_LENGTH: usize = 32;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Deserialize)]
@@ -9,6 +20,13 @@ pub struct Config {
     pub rate_limiting: RateLimitConfig,
     pub webhook: WebhookConfig,
     pub logging: LoggingConfig,
+    pub idempotency: IdempotencyConfig,
+    pub accounts: AccountConfig,
+    pub retention: RetentionConfig,
+    pub transactions: TransactionConfig,
+    pub auth: AuthConfig,
+    pub cors: CorsConfig,
+    pub reconciliation: ReconciliationConfig,
 }
 
 #[allow(dead_code)]
@@ -18,6 +36,18 @@ pub struct DatabaseConfig {
     pub max_connections: u32,
     pub min_connections: u32,
     pub acquire_timeout_seconds: u64,
+    /// Connection acquires slower than this are logged as warnings.
+    pub acquire_warn_ms: u64,
+    /// Tries a credit, debit or transfer gets when it fails with a transient error; 1 disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each one after it.
+    pub retry_base_delay_ms: u64,
+    /// Log and skip transaction rows that fail validation in list queries instead of failing the request.
+    pub skip_invalid_transaction_rows: bool,
+    /// How often pool utilization is logged and recorded as metrics; 0 disables it.
+    pub pool_metrics_interval_seconds: u64,
+    /// Apply the embedded SQL migrations on startup, for deployments without the Liquibase step.
+    pub run_migrations: bool,
 }
 
 #[allow(dead_code)]
@@ -25,26 +55,184 @@ pub struct DatabaseConfig {
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    /// Sign authenticated responses with the caller's account secret (`X-Response-Signature`).
+    pub sign_responses: bool,
+    /// Reject mutating authenticated requests without a valid `X-Request-Signature`.
+    pub require_request_signatures: bool,
+    /// How far `X-Request-Timestamp` may be from the server clock.
+    pub request_signature_tolerance_seconds: u64,
+    /// Largest `limit` accepted by list endpoints; larger values are clamped.
+    pub max_page_size: i64,
+    /// Largest request body accepted, in bytes; larger bodies are refused with 413.
+    pub max_body_bytes: usize,
+    /// How response amounts are written unless the request's `Accept` header says otherwise.
+    pub money_format: MoneyFormat,
+    /// How long shutdown waits for in-flight requests and webhook dispatches before exiting.
+    pub shutdown_timeout_seconds: u64,
 }
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Deserialize)]
 pub struct RateLimitConfig {
     pub requests_per_hour: u32,
+    /// Reverse proxies whose `X-Forwarded-For` header is trusted. Empty means the header is ignored.
+    #[serde(skip)]
+    pub trusted_proxies: Vec<IpNet>,
+    /// Per-IP limiters unused for this long are dropped by the background reaper.
+    pub idle_ttl_seconds: u64,
+    /// How often the reaper sweeps for idle limiters.
+    pub sweep_interval_seconds: u64,
+    /// Maximum number of IPs tracked at once; least recently seen ones are evicted first.
+    pub max_tracked_ips: usize,
 }
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Deserialize)]
 pub struct WebhookConfig {
+    /// Total time allowed for one delivery attempt, connecting included.
     pub timeout_seconds: u64,
+    /// Time allowed to establish the connection, so unreachable hosts fail fast.
+    pub connect_timeout_ms: u64,
+    /// Cap on per-webhook `timeout_ms` overrides.
+    pub max_timeout_ms: u64,
     pub max_retries: u32,
     pub initial_backoff_ms: u64,
+    /// Ceiling on the delay between retries, which otherwise doubles each time.
+    pub max_backoff_ms: u64,
+    /// Total time one dispatch may spend retrying before it is given up.
+    pub max_elapsed_ms: u64,
+    pub validate_payloads: bool,
+    pub propagate_request_id: bool,
+    /// Number deliveries per webhook with a persisted, gap-free `sequence`.
+    pub sequence_numbers: bool,
+    /// Log successful deliveries; failed and retried attempts are always logged.
+    pub log_successful_deliveries: bool,
+    /// Only accept https webhook URLs.
+    pub require_https: bool,
+    /// Accept webhook URLs on loopback, link-local and private addresses or
+    /// non-standard ports. For local development only.
+    pub allow_private_networks: bool,
+    /// New webhooks stay pending until their endpoint echoes a challenge.
+    pub require_verification: bool,
+    /// Payloads at least this large are gzipped for webhooks flagged `accepts_gzip`
+    pub gzip_min_bytes: usize,
+    /// Dispatch tasks allowed in flight at once. Zero means unbounded.
+    pub max_pending_dispatches: usize,
+    /// What to do with events once `max_pending_dispatches` is reached.
+    pub overflow_policy: WebhookOverflowPolicy,
+    /// How long the `block` policy holds up a request waiting for a free slot.
+    pub overflow_block_timeout_ms: u64,
+    /// Record deposit, withdrawal and transfer events in `outbox_events` with the
+    /// transaction and deliver them from a poller, instead of from a detached task.
+    pub transactional_outbox: bool,
+    /// How often the outbox poller looks for unsent events.
+    pub outbox_poll_interval_ms: u64,
+    /// Most events the outbox poller delivers per poll.
+    pub outbox_batch_size: i64,
 }
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Deserialize)]
 pub struct LoggingConfig {
     pub level: String,
+    /// `pretty` for humans, `json` for one structured object per line.
+    pub format: LogFormat,
+}
+
+/// Cross-origin access for browser clients. With no allowed origins no CORS
+/// headers are sent, so browsers keep the same-origin policy.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API; `*` allows any origin and must be the only entry.
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    /// Let browsers send cookies and auth headers cross-origin. Not allowed with `*`.
+    pub allow_credentials: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReconciliationConfig {
+    /// How often every account's balance is compared with its transactions; 0 disables it.
+    pub interval_seconds: u64,
+    /// Accounts compared per query.
+    pub batch_size: i64,
+    /// Pause between queries, spreading a run out to limit database load.
+    pub batch_pause_ms: u64,
+    /// Where a `reconciliation.drift_detected` alert is POSTed when drift is found.
+    pub alert_url: Option<String>,
+    /// Secret the alert is signed with; required with `alert_url`.
+    pub alert_secret: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountConfig {
+    /// Reject new accounts whose business name is already in use.
+    pub unique_business_names: bool,
+    /// Append a `balance_snapshots` row for every balance change.
+    pub record_balance_snapshots: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdempotencyConfig {
+    /// How long stored idempotent responses are replayed before the key can be reused.
+    pub ttl_seconds: u64,
+    /// `uuid` rejects idempotency keys that are not UUIDs; `any` accepts any non-empty key.
+    pub key_format: IdempotencyKeyFormat,
+    /// How often expired idempotency keys are released for reuse. Zero disables the cleanup job.
+    pub cleanup_interval_seconds: u64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct RetentionConfig {
+    /// Transactions older than this many days are moved to `archived_transactions`. Zero disables archival.
+    pub transaction_retention_days: u32,
+    /// How often the archival job runs.
+    pub archival_interval_seconds: u64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionConfig {
+    /// Accept zero-amount `verification` transactions. Money-moving types always reject zero.
+    pub allow_zero_amount_transactions: bool,
+    /// Smallest deposit, withdrawal or transfer accepted; unset accepts any positive amount
+    pub min_transaction_amount: Option<Decimal>,
+    /// Default cap on a single withdrawal or outgoing transfer; accounts may override it
+    pub max_transaction_amount: Option<Decimal>,
+    /// Default cap on an account's withdrawals and outgoing transfers per UTC day
+    pub daily_debit_limit: Option<Decimal>,
+    /// Most operations accepted in one batch; each locks its accounts until the batch ends
+    pub max_batch_legs: usize,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Deserialize)]
+pub struct AuthConfig {
+    /// Raw API key seeded for a bootstrap admin account when no keys exist yet.
+    pub bootstrap_admin_key: Option<String>,
+    /// How long a rotated-out API key keeps working alongside its replacement
+    pub key_rotation_grace_seconds: u64,
+    /// Server-side secret API keys are HMAC-hashed with; bare SHA-256 when unset
+    pub key_pepper: Option<String>,
+    /// Whether keys still stored as bare SHA-256 authenticate once a pepper is set
+    pub accept_legacy_key_hashes: bool,
+}
+
+impl std::fmt::Debug for AuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthConfig")
+            .field("bootstrap_admin_key", &self.bootstrap_admin_key.as_ref().map(|_| "<redacted>"))
+            .field("key_rotation_grace_seconds", &self.key_rotation_grace_seconds)
+            .field("key_pepper", &self.key_pepper.as_ref().map(|_| "<redacted>"))
+            .field("accept_legacy_key_hashes", &self.accept_legacy_key_hashes)
+            .finish()
+    }
 }
 
 impl Config {
@@ -66,6 +254,30 @@ impl Config {
                 .unwrap_or_else(|_| "30".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("DATABASE_ACQUIRE_TIMEOUT_SECONDS"))?,
+            acquire_warn_ms: env::var("DATABASE_ACQUIRE_WARN_MS")
+                .unwrap_or_else(|_| "2000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("DATABASE_ACQUIRE_WARN_MS"))?,
+            max_attempts: env::var("DATABASE_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("DATABASE_MAX_ATTEMPTS"))?,
+            retry_base_delay_ms: env::var("DATABASE_RETRY_BASE_DELAY_MS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("DATABASE_RETRY_BASE_DELAY_MS"))?,
+            skip_invalid_transaction_rows: env::var("SKIP_INVALID_TRANSACTION_ROWS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("SKIP_INVALID_TRANSACTION_ROWS"))?,
+            pool_metrics_interval_seconds: env::var("DATABASE_POOL_METRICS_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("DATABASE_POOL_METRICS_INTERVAL_SECONDS"))?,
+            run_migrations: env::var("RUN_MIGRATIONS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RUN_MIGRATIONS"))?,
         };
 
         let server = ServerConfig {
@@ -74,6 +286,38 @@ impl Config {
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("SERVER_PORT"))?,
+            sign_responses: env::var("RESPONSE_SIGNING_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RESPONSE_SIGNING_ENABLED"))?,
+            require_request_signatures: env::var("REQUEST_SIGNING_REQUIRED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("REQUEST_SIGNING_REQUIRED"))?,
+            request_signature_tolerance_seconds: env::var("REQUEST_SIGNING_TOLERANCE_SECONDS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("REQUEST_SIGNING_TOLERANCE_SECONDS"))?,
+            max_page_size: env::var("MAX_PAGE_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse::<i64>()
+                .ok()
+                .filter(|&max| max > 0)
+                .ok_or(ConfigError::InvalidValue("MAX_PAGE_SIZE"))?,
+            max_body_bytes: env::var("MAX_REQUEST_BODY_BYTES")
+                .unwrap_or_else(|_| "65536".to_string())
+                .parse::<usize>()
+                .ok()
+                .filter(|&max| max > 0)
+                .ok_or(ConfigError::InvalidValue("MAX_REQUEST_BODY_BYTES"))?,
+            money_format: env::var("MONEY_FORMAT")
+                .unwrap_or_else(|_| "plain".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("MONEY_FORMAT"))?,
+            shutdown_timeout_seconds: env::var("SHUTDOWN_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("SHUTDOWN_TIMEOUT_SECONDS"))?,
         };
 
         let rate_limiting = RateLimitConfig {
@@ -81,6 +325,25 @@ impl Config {
                 .unwrap_or_else(|_| "1000".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("RATE_LIMIT_PER_HOUR"))?,
+            trusted_proxies: parse_trusted_proxies(
+                &env::var("RATE_LIMIT_TRUSTED_PROXIES").unwrap_or_default(),
+            )?,
+            idle_ttl_seconds: env::var("RATE_LIMIT_IDLE_TTL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RATE_LIMIT_IDLE_TTL_SECONDS"))?,
+            sweep_interval_seconds: env::var("RATE_LIMIT_SWEEP_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse::<u64>()
+                .ok()
+                .filter(|&secs| secs > 0)
+                .ok_or(ConfigError::InvalidValue("RATE_LIMIT_SWEEP_INTERVAL_SECONDS"))?,
+            max_tracked_ips: env::var("RATE_LIMIT_MAX_TRACKED_IPS")
+                .unwrap_or_else(|_| "100000".to_string())
+                .parse::<usize>()
+                .ok()
+                .filter(|&max| max > 0)
+                .ok_or(ConfigError::InvalidValue("RATE_LIMIT_MAX_TRACKED_IPS"))?,
         };
 
         let webhook = WebhookConfig {
@@ -88,6 +351,14 @@ impl Config {
                 .unwrap_or_else(|_| "30".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("WEBHOOK_TIMEOUT_SECONDS"))?,
+            connect_timeout_ms: env::var("WEBHOOK_CONNECT_TIMEOUT_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_CONNECT_TIMEOUT_MS"))?,
+            max_timeout_ms: env::var("WEBHOOK_MAX_TIMEOUT_MS")
+                .unwrap_or_else(|_| "60000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_MAX_TIMEOUT_MS"))?,
             max_retries: env::var("WEBHOOK_MAX_RETRIES")
                 .unwrap_or_else(|_| "3".to_string())
                 .parse()
@@ -96,18 +367,216 @@ impl Config {
                 .unwrap_or_else(|_| "500".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("WEBHOOK_INITIAL_BACKOFF_MS"))?,
+            max_backoff_ms: env::var("WEBHOOK_MAX_BACKOFF_MS")
+                .unwrap_or_else(|_| "30000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_MAX_BACKOFF_MS"))?,
+            max_elapsed_ms: env::var("WEBHOOK_MAX_ELAPSED_MS")
+                .unwrap_or_else(|_| "300000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_MAX_ELAPSED_MS"))?,
+            validate_payloads: env::var("WEBHOOK_VALIDATE_PAYLOADS")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_VALIDATE_PAYLOADS"))?,
+            propagate_request_id: env::var("WEBHOOK_PROPAGATE_REQUEST_ID")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_PROPAGATE_REQUEST_ID"))?,
+            sequence_numbers: env::var("WEBHOOK_SEQUENCE_NUMBERS")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_SEQUENCE_NUMBERS"))?,
+            log_successful_deliveries: env::var("WEBHOOK_LOG_SUCCESSFUL_DELIVERIES")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_LOG_SUCCESSFUL_DELIVERIES"))?,
+            require_https: env::var("WEBHOOK_REQUIRE_HTTPS")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_REQUIRE_HTTPS"))?,
+            allow_private_networks: env::var("WEBHOOK_ALLOW_PRIVATE_NETWORKS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_ALLOW_PRIVATE_NETWORKS"))?,
+            require_verification: env::var("WEBHOOK_REQUIRE_VERIFICATION")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_REQUIRE_VERIFICATION"))?,
+            gzip_min_bytes: env::var("WEBHOOK_GZIP_MIN_BYTES")
+                .unwrap_or_else(|_| "1024".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_GZIP_MIN_BYTES"))?,
+            max_pending_dispatches: env::var("WEBHOOK_MAX_PENDING_DISPATCHES")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_MAX_PENDING_DISPATCHES"))?,
+            overflow_policy: env::var("WEBHOOK_OVERFLOW_POLICY")
+                .unwrap_or_else(|_| "shed".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_OVERFLOW_POLICY"))?,
+            overflow_block_timeout_ms: env::var("WEBHOOK_OVERFLOW_BLOCK_TIMEOUT_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_OVERFLOW_BLOCK_TIMEOUT_MS"))?,
+            transactional_outbox: env::var("WEBHOOK_TRANSACTIONAL_OUTBOX")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_TRANSACTIONAL_OUTBOX"))?,
+            outbox_poll_interval_ms: env::var("WEBHOOK_OUTBOX_POLL_INTERVAL_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_OUTBOX_POLL_INTERVAL_MS"))?,
+            outbox_batch_size: env::var("WEBHOOK_OUTBOX_BATCH_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_OUTBOX_BATCH_SIZE"))?,
         };
 
         let logging = LoggingConfig {
             level: env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+            format: env::var("LOG_FORMAT")
+                .unwrap_or_else(|_| "pretty".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("LOG_FORMAT"))?,
+        };
+
+        let idempotency = IdempotencyConfig {
+            ttl_seconds: env::var("IDEMPOTENCY_TTL_SECONDS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("IDEMPOTENCY_TTL_SECONDS"))?,
+            key_format: env::var("IDEMPOTENCY_KEY_FORMAT")
+                .unwrap_or_else(|_| "any".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("IDEMPOTENCY_KEY_FORMAT"))?,
+            cleanup_interval_seconds: env::var("IDEMPOTENCY_CLEANUP_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("IDEMPOTENCY_CLEANUP_INTERVAL_SECONDS"))?,
+        };
+
+        let accounts = AccountConfig {
+            unique_business_names: env::var("UNIQUE_BUSINESS_NAMES")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("UNIQUE_BUSINESS_NAMES"))?,
+            record_balance_snapshots: env::var("RECORD_BALANCE_SNAPSHOTS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RECORD_BALANCE_SNAPSHOTS"))?,
+        };
+
+        let retention = RetentionConfig {
+            transaction_retention_days: env::var("TRANSACTION_RETENTION_DAYS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse::<u32>()
+                .ok()
+                // Archived transactions no longer block idempotency key reuse, so keep them live past the TTL
+                .filter(|&days| days == 0 || u64::from(days) * 86_400 > idempotency.ttl_seconds)
+                .ok_or(ConfigError::InvalidValue("TRANSACTION_RETENTION_DAYS"))?,
+            archival_interval_seconds: env::var("TRANSACTION_ARCHIVAL_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse::<u64>()
+                .ok()
+                .filter(|&secs| secs > 0)
+                .ok_or(ConfigError::InvalidValue("TRANSACTION_ARCHIVAL_INTERVAL_SECONDS"))?,
         };
 
+        let transactions = TransactionConfig {
+            allow_zero_amount_transactions: env::var("ALLOW_ZERO_AMOUNT_TRANSACTIONS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("ALLOW_ZERO_AMOUNT_TRANSACTIONS"))?,
+            min_transaction_amount: parse_limit("MIN_TRANSACTION_AMOUNT")?,
+            max_transaction_amount: parse_limit("MAX_TRANSACTION_AMOUNT")?,
+            daily_debit_limit: parse_limit("DAILY_DEBIT_LIMIT")?,
+            max_batch_legs: env::var("MAX_BATCH_LEGS")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("MAX_BATCH_LEGS"))?,
+        };
+
+        let auth = AuthConfig {
+            bootstrap_admin_key: match env::var("BOOTSTRAP_ADMIN_KEY") {
+                Ok(key) if key.trim().is_empty() => None,
+                // A guessable bootstrap key would hand out full access to a fresh deployment
+                Ok(key) if key.len() < MIN_BOOTSTRAP_KEY_LENGTH => {
+                    return Err(ConfigError::InvalidValue("BOOTSTRAP_ADMIN_KEY"));
+                }
+                Ok(key) => Some(key),
+                Err(_) => None,
+            },
+            key_rotation_grace_seconds: env::var("API_KEY_ROTATION_GRACE_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("API_KEY_ROTATION_GRACE_SECONDS"))?,
+            key_pepper: match env::var("API_KEY_PEPPER") {
+                Ok(pepper) if pepper.trim().is_empty() => None,
+                Ok(pepper) if pepper.len() < MIN_KEY_PEPPER_LENGTH => {
+                    return Err(ConfigError::InvalidValue("API_KEY_PEPPER"));
+                }
+                Ok(pepper) => Some(pepper),
+                Err(_) => None,
+            },
+            accept_legacy_key_hashes: env::var("API_KEY_ACCEPT_LEGACY_HASHES")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("API_KEY_ACCEPT_LEGACY_HASHES"))?,
+        };
+
+        let cors = CorsConfig {
+            allowed_origins: parse_list(&env::var("CORS_ALLOWED_ORIGINS").unwrap_or_default()),
+            allowed_methods: parse_list(
+                &env::var("CORS_ALLOWED_METHODS").unwrap_or_else(|_| "GET,POST,PUT,PATCH,DELETE".to_string()),
+            ),
+            allowed_headers: parse_list(&env::var("CORS_ALLOWED_HEADERS").unwrap_or_else(|_| {
+                "content-type,x-api-key,x-request-id,x-request-signature,x-request-timestamp".to_string()
+            })),
+            allow_credentials: env::var("CORS_ALLOW_CREDENTIALS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("CORS_ALLOW_CREDENTIALS"))?,
+        };
+
+        let reconciliation = ReconciliationConfig {
+            interval_seconds: env::var("RECONCILIATION_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RECONCILIATION_INTERVAL_SECONDS"))?,
+            batch_size: env::var("RECONCILIATION_BATCH_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse::<i64>()
+                .ok()
+                .filter(|&size| size > 0)
+                .ok_or(ConfigError::InvalidValue("RECONCILIATION_BATCH_SIZE"))?,
+            batch_pause_ms: env::var("RECONCILIATION_BATCH_PAUSE_MS")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RECONCILIATION_BATCH_PAUSE_MS"))?,
+            alert_url: env::var("RECONCILIATION_ALERT_URL").ok().filter(|url| !url.trim().is_empty()),
+            alert_secret: env::var("RECONCILIATION_ALERT_SECRET")
+                .ok()
+                .filter(|secret| !secret.trim().is_empty()),
+        };
+        // An unsigned alert could be forged by anyone who learns the URL
+        if reconciliation.alert_url.is_some() && reconciliation.alert_secret.is_none() {
+            return Err(ConfigError::InvalidValue("RECONCILIATION_ALERT_SECRET"));
+        }
+
         Ok(Config {
             database,
             server,
             rate_limiting,
             webhook,
             logging,
+            idempotency,
+            accounts,
+            retention,
+            transactions,
+            auth,
+            cors,
+            reconciliation,
         })
     }
 
@@ -120,6 +589,45 @@ impl Config {
     }
 }
 
+/// Parse a comma-separated list of CIDRs or bare IP addresses
+/// Read an optional positive amount; unset or empty means no limit
+fn parse_limit(var: &'static str) -> Result<Option<Decimal>, ConfigError> {
+    match env::var(var) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse::<Money>()
+            .ok()
+            .filter(Money::is_positive)
+            .map(|amount| Some(amount.amount()))
+            .ok_or(ConfigError::InvalidValue(var)),
+        _ => Ok(None),
+    }
+}
+
+/// Split a comma-separated list, dropping blank entries
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_trusted_proxies(value: &str) -> Result<Vec<IpNet>, ConfigError> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| ConfigError::InvalidValue("RATE_LIMIT_TRUSTED_PROXIES"))
+        })
+        .collect()
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
     #[error("Missing required environment variable: {0}")]
@@ -153,6 +661,30 @@ mod tests {
                 .unwrap_or_else(|_| "30".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("DATABASE_ACQUIRE_TIMEOUT_SECONDS"))?,
+            acquire_warn_ms: env::var("DATABASE_ACQUIRE_WARN_MS")
+                .unwrap_or_else(|_| "2000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("DATABASE_ACQUIRE_WARN_MS"))?,
+            max_attempts: env::var("DATABASE_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("DATABASE_MAX_ATTEMPTS"))?,
+            retry_base_delay_ms: env::var("DATABASE_RETRY_BASE_DELAY_MS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("DATABASE_RETRY_BASE_DELAY_MS"))?,
+            skip_invalid_transaction_rows: env::var("SKIP_INVALID_TRANSACTION_ROWS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("SKIP_INVALID_TRANSACTION_ROWS"))?,
+            pool_metrics_interval_seconds: env::var("DATABASE_POOL_METRICS_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("DATABASE_POOL_METRICS_INTERVAL_SECONDS"))?,
+            run_migrations: env::var("RUN_MIGRATIONS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RUN_MIGRATIONS"))?,
         };
 
         let server = ServerConfig {
@@ -161,6 +693,38 @@ mod tests {
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("SERVER_PORT"))?,
+            sign_responses: env::var("RESPONSE_SIGNING_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RESPONSE_SIGNING_ENABLED"))?,
+            require_request_signatures: env::var("REQUEST_SIGNING_REQUIRED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("REQUEST_SIGNING_REQUIRED"))?,
+            request_signature_tolerance_seconds: env::var("REQUEST_SIGNING_TOLERANCE_SECONDS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("REQUEST_SIGNING_TOLERANCE_SECONDS"))?,
+            max_page_size: env::var("MAX_PAGE_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse::<i64>()
+                .ok()
+                .filter(|&max| max > 0)
+                .ok_or(ConfigError::InvalidValue("MAX_PAGE_SIZE"))?,
+            max_body_bytes: env::var("MAX_REQUEST_BODY_BYTES")
+                .unwrap_or_else(|_| "65536".to_string())
+                .parse::<usize>()
+                .ok()
+                .filter(|&max| max > 0)
+                .ok_or(ConfigError::InvalidValue("MAX_REQUEST_BODY_BYTES"))?,
+            money_format: env::var("MONEY_FORMAT")
+                .unwrap_or_else(|_| "plain".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("MONEY_FORMAT"))?,
+            shutdown_timeout_seconds: env::var("SHUTDOWN_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("SHUTDOWN_TIMEOUT_SECONDS"))?,
         };
 
         let rate_limiting = RateLimitConfig {
@@ -168,6 +732,25 @@ mod tests {
                 .unwrap_or_else(|_| "1000".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("RATE_LIMIT_PER_HOUR"))?,
+            trusted_proxies: parse_trusted_proxies(
+                &env::var("RATE_LIMIT_TRUSTED_PROXIES").unwrap_or_default(),
+            )?,
+            idle_ttl_seconds: env::var("RATE_LIMIT_IDLE_TTL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RATE_LIMIT_IDLE_TTL_SECONDS"))?,
+            sweep_interval_seconds: env::var("RATE_LIMIT_SWEEP_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse::<u64>()
+                .ok()
+                .filter(|&secs| secs > 0)
+                .ok_or(ConfigError::InvalidValue("RATE_LIMIT_SWEEP_INTERVAL_SECONDS"))?,
+            max_tracked_ips: env::var("RATE_LIMIT_MAX_TRACKED_IPS")
+                .unwrap_or_else(|_| "100000".to_string())
+                .parse::<usize>()
+                .ok()
+                .filter(|&max| max > 0)
+                .ok_or(ConfigError::InvalidValue("RATE_LIMIT_MAX_TRACKED_IPS"))?,
         };
 
         let webhook = WebhookConfig {
@@ -175,6 +758,14 @@ mod tests {
                 .unwrap_or_else(|_| "30".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("WEBHOOK_TIMEOUT_SECONDS"))?,
+            connect_timeout_ms: env::var("WEBHOOK_CONNECT_TIMEOUT_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_CONNECT_TIMEOUT_MS"))?,
+            max_timeout_ms: env::var("WEBHOOK_MAX_TIMEOUT_MS")
+                .unwrap_or_else(|_| "60000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_MAX_TIMEOUT_MS"))?,
             max_retries: env::var("WEBHOOK_MAX_RETRIES")
                 .unwrap_or_else(|_| "3".to_string())
                 .parse()
@@ -183,18 +774,216 @@ mod tests {
                 .unwrap_or_else(|_| "500".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("WEBHOOK_INITIAL_BACKOFF_MS"))?,
+            max_backoff_ms: env::var("WEBHOOK_MAX_BACKOFF_MS")
+                .unwrap_or_else(|_| "30000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_MAX_BACKOFF_MS"))?,
+            max_elapsed_ms: env::var("WEBHOOK_MAX_ELAPSED_MS")
+                .unwrap_or_else(|_| "300000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_MAX_ELAPSED_MS"))?,
+            validate_payloads: env::var("WEBHOOK_VALIDATE_PAYLOADS")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_VALIDATE_PAYLOADS"))?,
+            propagate_request_id: env::var("WEBHOOK_PROPAGATE_REQUEST_ID")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_PROPAGATE_REQUEST_ID"))?,
+            sequence_numbers: env::var("WEBHOOK_SEQUENCE_NUMBERS")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_SEQUENCE_NUMBERS"))?,
+            log_successful_deliveries: env::var("WEBHOOK_LOG_SUCCESSFUL_DELIVERIES")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_LOG_SUCCESSFUL_DELIVERIES"))?,
+            require_https: env::var("WEBHOOK_REQUIRE_HTTPS")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_REQUIRE_HTTPS"))?,
+            allow_private_networks: env::var("WEBHOOK_ALLOW_PRIVATE_NETWORKS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_ALLOW_PRIVATE_NETWORKS"))?,
+            require_verification: env::var("WEBHOOK_REQUIRE_VERIFICATION")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_REQUIRE_VERIFICATION"))?,
+            gzip_min_bytes: env::var("WEBHOOK_GZIP_MIN_BYTES")
+                .unwrap_or_else(|_| "1024".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_GZIP_MIN_BYTES"))?,
+            max_pending_dispatches: env::var("WEBHOOK_MAX_PENDING_DISPATCHES")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_MAX_PENDING_DISPATCHES"))?,
+            overflow_policy: env::var("WEBHOOK_OVERFLOW_POLICY")
+                .unwrap_or_else(|_| "shed".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_OVERFLOW_POLICY"))?,
+            overflow_block_timeout_ms: env::var("WEBHOOK_OVERFLOW_BLOCK_TIMEOUT_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_OVERFLOW_BLOCK_TIMEOUT_MS"))?,
+            transactional_outbox: env::var("WEBHOOK_TRANSACTIONAL_OUTBOX")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_TRANSACTIONAL_OUTBOX"))?,
+            outbox_poll_interval_ms: env::var("WEBHOOK_OUTBOX_POLL_INTERVAL_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_OUTBOX_POLL_INTERVAL_MS"))?,
+            outbox_batch_size: env::var("WEBHOOK_OUTBOX_BATCH_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("WEBHOOK_OUTBOX_BATCH_SIZE"))?,
         };
 
         let logging = LoggingConfig {
             level: env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+            format: env::var("LOG_FORMAT")
+                .unwrap_or_else(|_| "pretty".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("LOG_FORMAT"))?,
+        };
+
+        let idempotency = IdempotencyConfig {
+            ttl_seconds: env::var("IDEMPOTENCY_TTL_SECONDS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("IDEMPOTENCY_TTL_SECONDS"))?,
+            key_format: env::var("IDEMPOTENCY_KEY_FORMAT")
+                .unwrap_or_else(|_| "any".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("IDEMPOTENCY_KEY_FORMAT"))?,
+            cleanup_interval_seconds: env::var("IDEMPOTENCY_CLEANUP_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("IDEMPOTENCY_CLEANUP_INTERVAL_SECONDS"))?,
+        };
+
+        let accounts = AccountConfig {
+            unique_business_names: env::var("UNIQUE_BUSINESS_NAMES")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("UNIQUE_BUSINESS_NAMES"))?,
+            record_balance_snapshots: env::var("RECORD_BALANCE_SNAPSHOTS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RECORD_BALANCE_SNAPSHOTS"))?,
         };
 
+        let retention = RetentionConfig {
+            transaction_retention_days: env::var("TRANSACTION_RETENTION_DAYS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse::<u32>()
+                .ok()
+                // Archived transactions no longer block idempotency key reuse, so keep them live past the TTL
+                .filter(|&days| days == 0 || u64::from(days) * 86_400 > idempotency.ttl_seconds)
+                .ok_or(ConfigError::InvalidValue("TRANSACTION_RETENTION_DAYS"))?,
+            archival_interval_seconds: env::var("TRANSACTION_ARCHIVAL_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse::<u64>()
+                .ok()
+                .filter(|&secs| secs > 0)
+                .ok_or(ConfigError::InvalidValue("TRANSACTION_ARCHIVAL_INTERVAL_SECONDS"))?,
+        };
+
+        let transactions = TransactionConfig {
+            allow_zero_amount_transactions: env::var("ALLOW_ZERO_AMOUNT_TRANSACTIONS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("ALLOW_ZERO_AMOUNT_TRANSACTIONS"))?,
+            min_transaction_amount: parse_limit("MIN_TRANSACTION_AMOUNT")?,
+            max_transaction_amount: parse_limit("MAX_TRANSACTION_AMOUNT")?,
+            daily_debit_limit: parse_limit("DAILY_DEBIT_LIMIT")?,
+            max_batch_legs: env::var("MAX_BATCH_LEGS")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("MAX_BATCH_LEGS"))?,
+        };
+
+        let auth = AuthConfig {
+            bootstrap_admin_key: match env::var("BOOTSTRAP_ADMIN_KEY") {
+                Ok(key) if key.trim().is_empty() => None,
+                // A guessable bootstrap key would hand out full access to a fresh deployment
+                Ok(key) if key.len() < MIN_BOOTSTRAP_KEY_LENGTH => {
+                    return Err(ConfigError::InvalidValue("BOOTSTRAP_ADMIN_KEY"));
+                }
+                Ok(key) => Some(key),
+                Err(_) => None,
+            },
+            key_rotation_grace_seconds: env::var("API_KEY_ROTATION_GRACE_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("API_KEY_ROTATION_GRACE_SECONDS"))?,
+            key_pepper: match env::var("API_KEY_PEPPER") {
+                Ok(pepper) if pepper.trim().is_empty() => None,
+                Ok(pepper) if pepper.len() < MIN_KEY_PEPPER_LENGTH => {
+                    return Err(ConfigError::InvalidValue("API_KEY_PEPPER"));
+                }
+                Ok(pepper) => Some(pepper),
+                Err(_) => None,
+            },
+            accept_legacy_key_hashes: env::var("API_KEY_ACCEPT_LEGACY_HASHES")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("API_KEY_ACCEPT_LEGACY_HASHES"))?,
+        };
+
+        let cors = CorsConfig {
+            allowed_origins: parse_list(&env::var("CORS_ALLOWED_ORIGINS").unwrap_or_default()),
+            allowed_methods: parse_list(
+                &env::var("CORS_ALLOWED_METHODS").unwrap_or_else(|_| "GET,POST,PUT,PATCH,DELETE".to_string()),
+            ),
+            allowed_headers: parse_list(&env::var("CORS_ALLOWED_HEADERS").unwrap_or_else(|_| {
+                "content-type,x-api-key,x-request-id,x-request-signature,x-request-timestamp".to_string()
+            })),
+            allow_credentials: env::var("CORS_ALLOW_CREDENTIALS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("CORS_ALLOW_CREDENTIALS"))?,
+        };
+
+        let reconciliation = ReconciliationConfig {
+            interval_seconds: env::var("RECONCILIATION_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RECONCILIATION_INTERVAL_SECONDS"))?,
+            batch_size: env::var("RECONCILIATION_BATCH_SIZE")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse::<i64>()
+                .ok()
+                .filter(|&size| size > 0)
+                .ok_or(ConfigError::InvalidValue("RECONCILIATION_BATCH_SIZE"))?,
+            batch_pause_ms: env::var("RECONCILIATION_BATCH_PAUSE_MS")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RECONCILIATION_BATCH_PAUSE_MS"))?,
+            alert_url: env::var("RECONCILIATION_ALERT_URL").ok().filter(|url| !url.trim().is_empty()),
+            alert_secret: env::var("RECONCILIATION_ALERT_SECRET")
+                .ok()
+                .filter(|secret| !secret.trim().is_empty()),
+        };
+        // An unsigned alert could be forged by anyone who learns the URL
+        if reconciliation.alert_url.is_some() && reconciliation.alert_secret.is_none() {
+            return Err(ConfigError::InvalidValue("RECONCILIATION_ALERT_SECRET"));
+        }
+
         Ok(Config {
             database,
             server,
             rate_limiting,
             webhook,
             logging,
+            idempotency,
+            accounts,
+            retention,
+            transactions,
+            auth,
+            cors,
+            reconciliation,
         })
     }
 
@@ -211,8 +1000,65 @@ mod tests {
         let config = config_from_test_env().expect("Failed to load config");
 
         assert_eq!(config.server.port, 8080);
+        assert!(!config.database.skip_invalid_transaction_rows);
+        assert_eq!(config.database.pool_metrics_interval_seconds, 60);
+        assert_eq!(config.database.acquire_warn_ms, 2000);
+        assert_eq!(config.database.max_attempts, 3);
+        assert_eq!(config.database.retry_base_delay_ms, 10);
+        assert!(!config.database.run_migrations);
+        assert!(!config.server.sign_responses);
+        assert!(!config.server.require_request_signatures);
+        assert_eq!(config.server.request_signature_tolerance_seconds, 300);
+        assert_eq!(config.server.max_page_size, 100);
+        assert_eq!(config.server.max_body_bytes, 65536);
+        assert_eq!(config.server.money_format, MoneyFormat::Plain);
+        assert_eq!(config.server.shutdown_timeout_seconds, 30);
         assert_eq!(config.rate_limiting.requests_per_hour, 1000);
+        assert_eq!(config.rate_limiting.idle_ttl_seconds, 3600);
+        assert_eq!(config.rate_limiting.sweep_interval_seconds, 300);
+        assert_eq!(config.rate_limiting.max_tracked_ips, 100_000);
         assert_eq!(config.webhook.max_retries, 3);
+        assert_eq!(config.webhook.timeout_seconds, 30);
+        assert_eq!(config.webhook.connect_timeout_ms, 5000);
+        assert_eq!(config.webhook.max_timeout_ms, 60000);
+        assert_eq!(config.webhook.max_backoff_ms, 30000);
+        assert_eq!(config.webhook.max_elapsed_ms, 300000);
+        assert!(config.webhook.validate_payloads);
+        assert!(config.webhook.propagate_request_id);
+        assert!(config.webhook.sequence_numbers);
+        assert!(config.webhook.log_successful_deliveries);
+        assert!(config.webhook.require_https);
+        assert!(!config.webhook.allow_private_networks);
+        assert!(!config.webhook.require_verification);
+        assert_eq!(config.webhook.gzip_min_bytes, 1024);
+        assert_eq!(config.webhook.max_pending_dispatches, 0);
+        assert_eq!(config.webhook.overflow_policy, WebhookOverflowPolicy::Shed);
+        assert_eq!(config.webhook.overflow_block_timeout_ms, 1000);
+        assert!(!config.webhook.transactional_outbox);
+        assert_eq!(config.webhook.outbox_poll_interval_ms, 1000);
+        assert_eq!(config.webhook.outbox_batch_size, 100);
+        assert_eq!(config.logging.format, LogFormat::Pretty);
+        assert_eq!(config.idempotency.ttl_seconds, 86400);
+        assert_eq!(config.idempotency.key_format, IdempotencyKeyFormat::Any);
+        assert_eq!(config.idempotency.cleanup_interval_seconds, 3600);
+        assert!(!config.accounts.unique_business_names);
+        assert!(!config.accounts.record_balance_snapshots);
+        assert_eq!(config.retention.transaction_retention_days, 0);
+        assert_eq!(config.retention.archival_interval_seconds, 3600);
+        assert!(!config.transactions.allow_zero_amount_transactions);
+        assert!(config.transactions.min_transaction_amount.is_none());
+        assert!(config.transactions.max_transaction_amount.is_none());
+        assert!(config.transactions.daily_debit_limit.is_none());
+        assert_eq!(config.transactions.max_batch_legs, 100);
+        assert!(config.auth.bootstrap_admin_key.is_none());
+        assert!(config.cors.allowed_origins.is_empty());
+        assert!(!config.cors.allow_credentials);
+        assert_eq!(config.reconciliation.interval_seconds, 0);
+        assert_eq!(config.reconciliation.batch_size, 100);
+        assert!(config.reconciliation.alert_url.is_none());
+        assert_eq!(config.auth.key_rotation_grace_seconds, 3600);
+        assert!(config.auth.key_pepper.is_none());
+        assert!(config.auth.accept_legacy_key_hashes);
 
         unsafe {
             env::remove_var("DATABASE_URL");
@@ -262,6 +1108,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_trusted_proxies() {
+        let proxies = parse_trusted_proxies("10.0.0.0/8, 127.0.0.1").unwrap();
+        assert_eq!(proxies.len(), 2);
+        assert!(proxies[1].contains(&"127.0.0.1".parse::<IpAddr>().unwrap()));
+
+        assert!(parse_trusted_proxies("").unwrap().is_empty());
+        assert!(matches!(
+            parse_trusted_proxies("not-a-cidr"),
+            Err(ConfigError::InvalidValue("RATE_LIMIT_TRUSTED_PROXIES"))
+        ));
+    }
+
     #[test]
     #[serial]
     fn test_config_invalid_port() {
@@ -279,4 +1138,118 @@ mod tests {
             env::remove_var("SERVER_PORT");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[serial]
+    fn test_config_rejects_retention_shorter_than_idempotency_ttl() {
+        unsafe {
+            env::set_var("DATABASE_URL", "postgresql://localhost/test");
+            env::set_var("IDEMPOTENCY_TTL_SECONDS", "172800");
+            env::set_var("TRANSACTION_RETENTION_DAYS", "1");
+        }
+
+        let result = config_from_test_env();
+        assert!(matches!(
+            result.unwrap_err(),
+            ConfigError::InvalidValue("TRANSACTION_RETENTION_DAYS")
+        ));
+
+        unsafe {
+            env::set_var("TRANSACTION_RETENTION_DAYS", "3");
+        }
+        let config = config_from_test_env().expect("Failed to load config");
+        assert_eq!(config.retention.transaction_retention_days, 3);
+
+        unsafe {
+            env::remove_var("DATABASE_URL");
+            env::remove_var("IDEMPOTENCY_TTL_SECONDS");
+            env::remove_var("TRANSACTION_RETENTION_DAYS");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_config_rejects_short_bootstrap_admin_key() {
+        unsafe {
+            env::set_var("DATABASE_URL", "postgresql://localhost/test");
+            env::set_var("BOOTSTRAP_ADMIN_KEY", "too-short");
+        }
+
+        let result = config_from_test_env();
+        assert!(matches!(
+            result.unwrap_err(),
+            ConfigError::InvalidValue("BOOTSTRAP_ADMIN_KEY")
+        ));
+
+        unsafe {
+            env::set_var("BOOTSTRAP_ADMIN_KEY", "a".repeat(32));
+        }
+        let config = config_from_test_env().expect("Failed to load config");
+        assert_eq!(config.auth.bootstrap_admin_key.as_deref(), Some("a".repeat(32).as_str()));
+        assert!(!format!("{:?}", config.auth).contains("aaaa"));
+
+        unsafe {
+            env::remove_var("DATABASE_URL");
+            env::remove_var("BOOTSTRAP_ADMIN_KEY");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_config_parses_transaction_limits() {
+        unsafe {
+            env::set_var("DATABASE_URL", "postgresql://localhost/test");
+            env::set_var("MIN_TRANSACTION_AMOUNT", "0.50");
+            env::set_var("MAX_TRANSACTION_AMOUNT", "5000");
+            env::set_var("DAILY_DEBIT_LIMIT", "");
+        }
+
+        let config = config_from_test_env().expect("Failed to load config");
+        assert_eq!(config.transactions.min_transaction_amount, Some(Decimal::new(50, 2)));
+        assert_eq!(config.transactions.max_transaction_amount, Some(Decimal::from(5000)));
+        assert!(config.transactions.daily_debit_limit.is_none());
+        assert_eq!(config.transactions.max_batch_legs, 100);
+
+        for invalid in ["0", "-10", "12.345", "lots"] {
+            unsafe {
+                env::set_var("DAILY_DEBIT_LIMIT", invalid);
+            }
+            assert!(matches!(
+                config_from_test_env().unwrap_err(),
+                ConfigError::InvalidValue("DAILY_DEBIT_LIMIT")
+            ));
+        }
+
+        unsafe {
+            env::remove_var("DATABASE_URL");
+            env::remove_var("MIN_TRANSACTION_AMOUNT");
+            env::remove_var("MAX_TRANSACTION_AMOUNT");
+            env::remove_var("DAILY_DEBIT_LIMIT");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_config_parses_idempotency_key_format() {
+        unsafe {
+            env::set_var("DATABASE_URL", "postgresql://localhost/test");
+            env::set_var("IDEMPOTENCY_KEY_FORMAT", "uuid");
+        }
+
+        let config = config_from_test_env().expect("Failed to load config");
+        assert_eq!(config.idempotency.key_format, IdempotencyKeyFormat::Uuid);
+
+        unsafe {
+            env::set_var("IDEMPOTENCY_KEY_FORMAT", "ulid");
+        }
+        assert!(matches!(
+            config_from_test_env().unwrap_err(),
+            ConfigError::InvalidValue("IDEMPOTENCY_KEY_FORMAT")
+        ));
+
+        unsafe {
+            env::remove_var("DATABASE_URL");
+            env::remove_var("IDEMPOTENCY_KEY_FORMAT");
+        }
+    }
+}
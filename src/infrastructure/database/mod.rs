@@ -2,16 +2,24 @@ use sqlx::postgres::{PgPool, PgPoolOptions};
 use std::time::Duration;
 
 use crate::infrastructure::config::Config;
+use crate::infrastructure::health;
+use crate::infrastructure::metrics;
 
 pub mod postgres_account_repository;
 pub mod postgres_transaction_repository;
 pub mod postgres_api_key_repository;
 pub mod postgres_webhook_repository;
+pub mod postgres_idempotency_repository;
+pub mod postgres_balance_snapshot_repository;
+pub mod postgres_outbox_repository;
 
 pub use postgres_account_repository::PostgresAccountRepository;
 pub use postgres_transaction_repository::PostgresTransactionRepository;
 pub use postgres_api_key_repository::PostgresApiKeyRepository;
 pub use postgres_webhook_repository::PostgresWebhookRepository;
+pub use postgres_idempotency_repository::PostgresIdempotencyRepository;
+pub use postgres_balance_snapshot_repository::PostgresBalanceSnapshotRepository;
+pub use postgres_outbox_repository::PostgresOutboxRepository;
 
 /// Create a PostgreSQL connection pool
 pub async fn create_pool(config: &Config) -> Result<PgPool, sqlx::Error> {
@@ -19,14 +27,81 @@ pub async fn create_pool(config: &Config) -> Result<PgPool, sqlx::Error> {
         .max_connections(config.database.max_connections)
         .min_connections(config.database.min_connections)
         .acquire_timeout(Duration::from_secs(config.database.acquire_timeout_seconds))
+        // sqlx logs a warning for every acquire that waits longer than this
+        .acquire_slow_threshold(Duration::from_millis(config.database.acquire_warn_ms))
         .connect(&config.database.url)
         .await
 }
 
+/// Apply the SQL migrations embedded from `migrations/`, skipping any already applied
+pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::migrate::MigrateError> {
+    sqlx::migrate!("./migrations").run(pool).await
+}
+
+/// Point-in-time utilization of a connection pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Open connections, idle or in use
+    pub size: u32,
+    pub idle: u32,
+    pub in_use: u32,
+}
+
+pub fn pool_stats(pool: &PgPool) -> PoolStats {
+    let size = pool.size();
+    let idle = pool.num_idle() as u32;
+    PoolStats {
+        size,
+        idle,
+        in_use: size.saturating_sub(idle),
+    }
+}
+
+/// Name the pool metrics loop reports its heartbeat under
+const POOL_METRICS_WORKER_NAME: &str = "pool_metrics";
+
+/// Spawn a background task that logs and records pool utilization every `interval`,
+/// for spotting saturation trends without scraping `/metrics`.
+///
+/// sqlx does not expose how many tasks are waiting to acquire a connection; a pool
+/// that stays at `in_use == max` is the signal that callers are queueing.
+pub fn spawn_pool_metrics(pool: PgPool, interval: Duration) -> tokio::task::JoinHandle<()> {
+    health::register_worker(POOL_METRICS_WORKER_NAME, interval);
+    tokio::spawn(async move {
+        let max = pool.options().get_max_connections();
+        let mut interval = tokio::time::interval(interval);
+        while !pool.is_closed() {
+            interval.tick().await;
+            health::record_heartbeat(POOL_METRICS_WORKER_NAME);
+            let stats = pool_stats(&pool);
+            metrics::record_pool_stats(stats.size, stats.idle, stats.in_use, max);
+            if stats.in_use >= max {
+                tracing::warn!(size = stats.size, idle = stats.idle, in_use = stats.in_use, max, "Connection pool saturated");
+            } else {
+                tracing::info!(size = stats.size, idle = stats.idle, in_use = stats.in_use, max, "Connection pool utilization");
+            }
+        }
+    })
+}
+
+/// Escape `\`, `%` and `_` so user input matches literally inside a `LIKE` pattern.
+///
+/// Queries using the result must declare `ESCAPE '\'`.
+pub(crate) fn escape_like(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::infrastructure::config::{Config, DatabaseConfig, ServerConfig, RateLimitConfig, WebhookConfig, LoggingConfig};
+    use crate::infrastructure::config::{Config, DatabaseConfig, ServerConfig, RateLimitConfig, WebhookConfig, LoggingConfig, IdempotencyConfig, AccountConfig, RetentionConfig, TransactionConfig, AuthConfig, CorsConfig, ReconciliationConfig};
 
     #[tokio::test]
     async fn test_create_pool_with_invalid_url() {
@@ -36,25 +111,107 @@ mod tests {
                 max_connections: 5,
                 min_connections: 1,
                 acquire_timeout_seconds: 5,
+                acquire_warn_ms: 2000,
+                max_attempts: 3,
+                retry_base_delay_ms: 10,
+                skip_invalid_transaction_rows: false,
+                pool_metrics_interval_seconds: 0,
+                run_migrations: false,
             },
             server: ServerConfig {
                 host: "0.0.0.0".to_string(),
                 port: 8080,
+                sign_responses: false,
+                require_request_signatures: false,
+                request_signature_tolerance_seconds: 300,
+                max_page_size: 100,
+                max_body_bytes: 65536,
+                money_format: crate::domain::value_objects::MoneyFormat::Plain,
+                shutdown_timeout_seconds: 30,
             },
             rate_limiting: RateLimitConfig {
                 requests_per_hour: 1000,
+                trusted_proxies: vec![],
+                idle_ttl_seconds: 3600,
+                sweep_interval_seconds: 300,
+                max_tracked_ips: 100_000,
             },
             webhook: WebhookConfig {
                 timeout_seconds: 30,
+                connect_timeout_ms: 5000,
+                max_timeout_ms: 60000,
                 max_retries: 5,
                 initial_backoff_ms: 1000,
+                max_backoff_ms: 30000,
+                max_elapsed_ms: 300000,
+                validate_payloads: true,
+                propagate_request_id: true,
+                sequence_numbers: true,
+                log_successful_deliveries: true,
+                require_https: true,
+                allow_private_networks: false,
+                require_verification: false,
+                gzip_min_bytes: 1024,
+                max_pending_dispatches: 0,
+                overflow_policy: crate::domain::value_objects::WebhookOverflowPolicy::Shed,
+                overflow_block_timeout_ms: 1000,
+                transactional_outbox: false,
+                outbox_poll_interval_ms: 1000,
+                outbox_batch_size: 100,
             },
             logging: LoggingConfig {
                 level: "info".to_string(),
+                format: crate::domain::value_objects::LogFormat::Pretty,
+            },
+            idempotency: IdempotencyConfig {
+                ttl_seconds: 86400,
+                key_format: crate::domain::value_objects::IdempotencyKeyFormat::Any,
+                cleanup_interval_seconds: 3600,
+            },
+            accounts: AccountConfig {
+                unique_business_names: false,
+                record_balance_snapshots: false,
+            },
+            retention: RetentionConfig {
+                transaction_retention_days: 0,
+                archival_interval_seconds: 3600,
+            },
+            transactions: TransactionConfig {
+                allow_zero_amount_transactions: false,
+                min_transaction_amount: None,
+                max_transaction_amount: None,
+                daily_debit_limit: None,
+                max_batch_legs: 100,
+            },
+            auth: AuthConfig {
+                bootstrap_admin_key: None,
+                key_rotation_grace_seconds: 3600,
+                key_pepper: None,
+                accept_legacy_key_hashes: true,
+            },
+            cors: CorsConfig {
+                allowed_origins: Vec::new(),
+                allowed_methods: Vec::new(),
+                allowed_headers: Vec::new(),
+                allow_credentials: false,
+            },
+            reconciliation: ReconciliationConfig {
+                interval_seconds: 0,
+                batch_size: 100,
+                batch_pause_ms: 100,
+                alert_url: None,
+                alert_secret: None,
             },
         };
 
         let result = create_pool(&config).await;
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("Acme"), "Acme");
+        assert_eq!(escape_like("100%_off"), "100\\%\\_off");
+        assert_eq!(escape_like("a\\b"), "a\\\\b");
+    }
+}
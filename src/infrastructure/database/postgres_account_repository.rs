@@ -2,10 +2,11 @@ use async_trait::async_trait;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::domain::entities::Account;
-use crate::domain::errors::RepositoryError;
+use crate::domain::entities::{Account, AccountRecord};
+use crate::domain::errors::{DomainError, RepositoryError};
 use crate::domain::repositories::AccountRepository;
-use crate::domain::value_objects::Money;
+use crate::domain::value_objects::{AccountStatus, Money, TransactionLimits};
+use crate::infrastructure::database::escape_like;
 
 /// PostgreSQL implementation of the AccountRepository
 pub struct PostgresAccountRepository {
@@ -19,14 +20,31 @@ impl PostgresAccountRepository {
     }
 }
 
+/// Rebuild an account from a row
+fn account_from_row(row: &sqlx::postgres::PgRow) -> Result<Account, DomainError> {
+    use sqlx::Row;
+    let status_str: String = row.get("status");
+    Account::from_db(AccountRecord {
+        id: row.get("id"),
+        business_name: row.get("business_name"),
+        balance: row.get("balance"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        deleted_at: row.get("deleted_at"),
+        webhook_secret: row.get("webhook_secret"),
+        status: status_str.parse::<AccountStatus>()?,
+        version: row.get("version"),
+    })
+}
+
 #[async_trait]
 impl AccountRepository for PostgresAccountRepository {
     async fn create(&self, account: &Account) -> Result<Account, RepositoryError> {
         let row = sqlx::query(
             r#"
-            INSERT INTO accounts (id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret
+            INSERT INTO accounts (id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret, status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8::account_status)
+            RETURNING id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret, status::text as status, version
             "#,
         )
         .bind(account.id)
@@ -36,18 +54,8 @@ impl AccountRepository for PostgresAccountRepository {
         .bind(account.updated_at)
         .bind(account.deleted_at)
         .bind(&account.webhook_secret)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            Account::from_db(
-                row.get("id"),
-                row.get("business_name"),
-                row.get("balance"),
-                row.get("created_at"),
-                row.get("updated_at"),
-                row.get("deleted_at"),
-                row.get("webhook_secret"),
-            )
-        })
+        .bind(account.status.as_str())
+        .map(|row: sqlx::postgres::PgRow| account_from_row(&row))
         .fetch_one(&self.pool)
         .await
         .map_err(RepositoryError::from)?;
@@ -58,24 +66,13 @@ impl AccountRepository for PostgresAccountRepository {
     async fn find_by_id(&self, id: Uuid) -> Result<Account, RepositoryError> {
         let row = sqlx::query(
             r#"
-            SELECT id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret 
+            SELECT id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret, status::text as status, version
             FROM accounts 
             WHERE id = $1 AND deleted_at IS NULL
             "#,
         )
         .bind(id)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            Account::from_db(
-                row.get("id"),
-                row.get("business_name"),
-                row.get("balance"),
-                row.get("created_at"),
-                row.get("updated_at"),
-                row.get("deleted_at"),
-                row.get("webhook_secret"),
-            )
-        })
+        .map(|row: sqlx::postgres::PgRow| account_from_row(&row))
         .fetch_optional(&self.pool)
         .await
         .map_err(RepositoryError::from)?;
@@ -87,21 +84,67 @@ impl AccountRepository for PostgresAccountRepository {
         }
     }
 
-    async fn update_balance(&self, id: Uuid, new_balance: Money) -> Result<(), RepositoryError> {
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Account>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret, status::text as status, version
+            FROM accounts
+            WHERE id = ANY($1) AND deleted_at IS NULL
+            "#,
+        )
+        .bind(ids)
+        .map(|row: sqlx::postgres::PgRow| account_from_row(&row))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        let mut accounts = Vec::new();
+        for account_result in rows {
+            accounts.push(account_result.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))?);
+        }
+
+        Ok(accounts)
+    }
+
+    async fn update_balance(&self, id: Uuid, new_balance: Money, expected_version: i64) -> Result<i64, RepositoryError> {
+        let version: Option<i64> = sqlx::query_scalar(
+            r#"
+            UPDATE accounts 
+            SET balance = $1, version = version + 1, updated_at = NOW() 
+            WHERE id = $2 AND version = $3 AND deleted_at IS NULL
+            RETURNING version
+            "#,
+        )
+        .bind(new_balance.amount())
+        .bind(id)
+        .bind(expected_version)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match version {
+            Some(version) => Ok(version),
+            None if self.exists(id).await? => Err(RepositoryError::VersionConflict(format!(
+                "Account {} was modified since version {}",
+                id, expected_version
+            ))),
+            None => Err(RepositoryError::NotFound(format!("Account {} not found or deleted", id))),
+        }
+    }
+
+    async fn update_business_name(&self, id: Uuid, name: String) -> Result<(), RepositoryError> {
         let result = sqlx::query(
             r#"
             UPDATE accounts 
-            SET balance = $1, updated_at = NOW() 
+            SET business_name = $1, version = version + 1, updated_at = NOW() 
             WHERE id = $2 AND deleted_at IS NULL
             "#,
         )
-        .bind(new_balance.amount())
+        .bind(name)
         .bind(id)
         .execute(&self.pool)
         .await?;
 
         if result.rows_affected() == 0 {
-            // Check if account exists but is deleted, or doesn't exist
             let exists = self.exists(id).await?;
             if !exists {
                 return Err(RepositoryError::NotFound(format!("Account {} not found or deleted", id)));
@@ -111,24 +154,71 @@ impl AccountRepository for PostgresAccountRepository {
         Ok(())
     }
 
-    async fn update_business_name(&self, id: Uuid, name: String) -> Result<(), RepositoryError> {
+    async fn update_status(&self, id: Uuid, status: AccountStatus) -> Result<(), RepositoryError> {
         let result = sqlx::query(
             r#"
-            UPDATE accounts 
-            SET business_name = $1, updated_at = NOW() 
+            UPDATE accounts
+            SET status = $1::account_status, updated_at = NOW()
             WHERE id = $2 AND deleted_at IS NULL
             "#,
         )
-        .bind(name)
+        .bind(status.as_str())
         .bind(id)
         .execute(&self.pool)
         .await?;
 
         if result.rows_affected() == 0 {
-            let exists = self.exists(id).await?;
-            if !exists {
-                return Err(RepositoryError::NotFound(format!("Account {} not found or deleted", id)));
-            }
+            return Err(RepositoryError::NotFound(format!("Account {} not found or deleted", id)));
+        }
+
+        Ok(())
+    }
+
+    async fn find_limits(&self, id: Uuid) -> Result<TransactionLimits, RepositoryError> {
+        let row = sqlx::query(
+            r#"
+            SELECT per_transaction_limit, daily_debit_limit, low_balance_threshold
+            FROM accounts
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| RepositoryError::NotFound(format!("Account {} not found", id)))?;
+
+        use sqlx::Row;
+        let limit = |column: &str| -> Result<Option<Money>, RepositoryError> {
+            row.get::<Option<rust_decimal::Decimal>, _>(column)
+                .map(Money::new)
+                .transpose()
+                .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))
+        };
+
+        Ok(TransactionLimits::new(
+            limit("per_transaction_limit")?,
+            limit("daily_debit_limit")?,
+        )
+        .with_low_balance(limit("low_balance_threshold")?))
+    }
+
+    async fn update_limits(&self, id: Uuid, limits: &TransactionLimits) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE accounts
+            SET per_transaction_limit = $1, daily_debit_limit = $2, low_balance_threshold = $3, updated_at = NOW()
+            WHERE id = $4 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(limits.per_transaction.map(|m| m.amount()))
+        .bind(limits.daily_debit.map(|m| m.amount()))
+        .bind(limits.low_balance.map(|m| m.amount()))
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound(format!("Account {} not found or deleted", id)));
         }
 
         Ok(())
@@ -149,10 +239,25 @@ impl AccountRepository for PostgresAccountRepository {
         Ok(result.is_some())
     }
 
+    async fn exists_by_business_name(&self, business_name: &str) -> Result<bool, RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            SELECT 1 as exists
+            FROM accounts
+            WHERE LOWER(business_name) = LOWER($1) AND deleted_at IS NULL
+            "#,
+        )
+        .bind(business_name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.is_some())
+    }
+
     async fn list(&self, limit: i64, offset: i64) -> Result<Vec<Account>, RepositoryError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret 
+            SELECT id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret, status::text as status, version
             FROM accounts 
             WHERE deleted_at IS NULL
             ORDER BY created_at DESC
@@ -161,18 +266,33 @@ impl AccountRepository for PostgresAccountRepository {
         )
         .bind(limit)
         .bind(offset)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            Account::from_db(
-                row.get("id"),
-                row.get("business_name"),
-                row.get("balance"),
-                row.get("created_at"),
-                row.get("updated_at"),
-                row.get("deleted_at"),
-                row.get("webhook_secret"),
-            )
-        })
+        .map(|row: sqlx::postgres::PgRow| account_from_row(&row))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        let mut accounts = Vec::new();
+        for account_result in rows {
+            accounts.push(account_result.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))?);
+        }
+
+        Ok(accounts)
+    }
+
+    async fn search(&self, query: &str, limit: i64, offset: i64) -> Result<Vec<Account>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret, status::text as status, version
+            FROM accounts
+            WHERE deleted_at IS NULL AND business_name ILIKE '%' || $1 || '%' ESCAPE '\'
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(escape_like(query))
+        .bind(limit)
+        .bind(offset)
+        .map(|row: sqlx::postgres::PgRow| account_from_row(&row))
         .fetch_all(&self.pool)
         .await
         .map_err(RepositoryError::from)?;
@@ -204,6 +324,66 @@ impl AccountRepository for PostgresAccountRepository {
         Ok(())
     }
 
+    async fn restore(&self, id: Uuid) -> Result<Account, RepositoryError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE accounts
+            SET deleted_at = NULL, updated_at = NOW()
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            RETURNING id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret, status::text as status, version
+            "#,
+        )
+        .bind(id)
+        .map(|row: sqlx::postgres::PgRow| account_from_row(&row))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        match row {
+            Some(account_result) => Ok(account_result
+                .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))?),
+            None => {
+                // Either the account was never deleted or it doesn't exist at all
+                if self.exists(id).await? {
+                    Err(RepositoryError::InvalidState(format!("Account {} is not deleted", id)))
+                } else {
+                    Err(RepositoryError::NotFound(format!("Account {} not found", id)))
+                }
+            }
+        }
+    }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM accounts
+            WHERE deleted_at IS NULL
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        Ok(count)
+    }
+
+    async fn count_matching(&self, query: &str) -> Result<i64, RepositoryError> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM accounts
+            WHERE deleted_at IS NULL AND business_name ILIKE '%' || $1 || '%' ESCAPE '\'
+            "#,
+        )
+        .bind(escape_like(query))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        Ok(count)
+    }
+
     async fn health_check(&self) -> Result<(), RepositoryError> {
         sqlx::query("SELECT 1")
             .execute(&self.pool)
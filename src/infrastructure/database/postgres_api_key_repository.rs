@@ -1,11 +1,16 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::domain::entities::ApiKey;
+use crate::domain::entities::{Account, ApiKey, ApiKeyRecord};
 use crate::domain::errors::RepositoryError;
 use crate::domain::repositories::ApiKeyRepository;
 
+/// Advisory lock held while seeding the first API key, so instances starting together
+/// cannot each find the table empty
+const FIRST_KEY_LOCK_ID: i64 = 0x646f_646f_5f6b_6579;
+
 pub struct PostgresApiKeyRepository {
     pool: PgPool,
 }
@@ -16,42 +21,53 @@ impl PostgresApiKeyRepository {
     }
 }
 
+fn api_key_from_row(row: &sqlx::postgres::PgRow) -> ApiKey {
+    use sqlx::Row;
+    ApiKey::from_db(ApiKeyRecord {
+        id: row.get("id"),
+        key_hash: row.get("key_hash"),
+        key_prefix: row.get("key_prefix"),
+        account_id: row.get("account_id"),
+        additional_account_ids: row.get("additional_account_ids"),
+        rate_limit_per_hour: row.get("rate_limit_per_hour"),
+        created_at: row.get("created_at"),
+        last_used_at: row.get("last_used_at"),
+        expires_at: row.get("expires_at"),
+        superseded_by: row.get("superseded_by"),
+        is_admin: row.get("is_admin"),
+    })
+}
+
 #[async_trait]
 impl ApiKeyRepository for PostgresApiKeyRepository {
     async fn create(&self, api_key: &ApiKey) -> Result<ApiKey, RepositoryError> {
         let row = sqlx::query(
             r#"
-            INSERT INTO api_keys (id, key_hash, account_id, rate_limit_per_hour, created_at, last_used_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, key_hash, account_id, rate_limit_per_hour, created_at, last_used_at
+            INSERT INTO api_keys (id, key_hash, key_prefix, account_id, additional_account_ids, rate_limit_per_hour, created_at, last_used_at, expires_at, is_admin)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, key_hash, key_prefix, account_id, additional_account_ids, rate_limit_per_hour, created_at, last_used_at, expires_at, superseded_by, is_admin
             "#,
         )
         .bind(api_key.id)
         .bind(&api_key.key_hash)
+        .bind(&api_key.key_prefix)
         .bind(api_key.account_id)
+        .bind(&api_key.additional_account_ids)
         .bind(api_key.rate_limit_per_hour as i32)
         .bind(api_key.created_at)
         .bind(api_key.last_used_at)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            ApiKey::from_db(
-                row.get("id"),
-                row.get("key_hash"),
-                row.get("account_id"),
-                row.get("rate_limit_per_hour"),
-                row.get("created_at"),
-                row.get("last_used_at"),
-            )
-        })
+        .bind(api_key.expires_at)
+        .bind(api_key.is_admin)
+        .map(|row: sqlx::postgres::PgRow| api_key_from_row(&row))
         .fetch_one(&self.pool)
         .await
         .map_err(|e| {
-            if let Some(db_err) = e.as_database_error() {
-                if db_err.is_unique_violation() {
-                    return RepositoryError::DuplicateEntry(
-                        "API key hash already exists".to_string()
-                    );
-                }
+            if let Some(db_err) = e.as_database_error()
+                && db_err.is_unique_violation()
+            {
+                return RepositoryError::DuplicateEntry(
+                    "API key hash already exists".to_string()
+                );
             }
             RepositoryError::from(e)
         })?;
@@ -62,23 +78,13 @@ impl ApiKeyRepository for PostgresApiKeyRepository {
     async fn find_by_hash(&self, key_hash: &str) -> Result<ApiKey, RepositoryError> {
         let row = sqlx::query(
             r#"
-            SELECT id, key_hash, account_id, rate_limit_per_hour, created_at, last_used_at
+            SELECT id, key_hash, key_prefix, account_id, additional_account_ids, rate_limit_per_hour, created_at, last_used_at, expires_at, superseded_by, is_admin
             FROM api_keys
             WHERE key_hash = $1
             "#,
         )
         .bind(key_hash)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            ApiKey::from_db(
-                row.get("id"),
-                row.get("key_hash"),
-                row.get("account_id"),
-                row.get("rate_limit_per_hour"),
-                row.get("created_at"),
-                row.get("last_used_at"),
-            )
-        })
+        .map(|row: sqlx::postgres::PgRow| api_key_from_row(&row))
         .fetch_optional(&self.pool)
         .await
         .map_err(RepositoryError::from)?;
@@ -89,27 +95,48 @@ impl ApiKeyRepository for PostgresApiKeyRepository {
         }
     }
 
+    async fn find_by_prefix(&self, key_prefix: &str) -> Result<Vec<ApiKey>, RepositoryError> {
+        sqlx::query(
+            r#"
+            SELECT id, key_hash, key_prefix, account_id, additional_account_ids, rate_limit_per_hour, created_at, last_used_at, expires_at, superseded_by, is_admin
+            FROM api_keys
+            WHERE key_prefix = $1
+            "#,
+        )
+        .bind(key_prefix)
+        .map(|row: sqlx::postgres::PgRow| api_key_from_row(&row))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<ApiKey, RepositoryError> {
+        sqlx::query(
+            r#"
+            SELECT id, key_hash, key_prefix, account_id, additional_account_ids, rate_limit_per_hour, created_at, last_used_at, expires_at, superseded_by, is_admin
+            FROM api_keys
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .map(|row: sqlx::postgres::PgRow| api_key_from_row(&row))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?
+        .ok_or_else(|| RepositoryError::NotFound(format!("API key {} not found", id)))
+    }
+
     async fn find_by_account(&self, account_id: Uuid) -> Result<Vec<ApiKey>, RepositoryError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, key_hash, account_id, rate_limit_per_hour, created_at, last_used_at
+            SELECT id, key_hash, key_prefix, account_id, additional_account_ids, rate_limit_per_hour, created_at, last_used_at, expires_at, superseded_by, is_admin
             FROM api_keys
             WHERE account_id = $1
             ORDER BY created_at DESC
             "#,
         )
         .bind(account_id)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            ApiKey::from_db(
-                row.get("id"),
-                row.get("key_hash"),
-                row.get("account_id"),
-                row.get("rate_limit_per_hour"),
-                row.get("created_at"),
-                row.get("last_used_at"),
-            )
-        })
+        .map(|row: sqlx::postgres::PgRow| api_key_from_row(&row))
         .fetch_all(&self.pool)
         .await
         .map_err(RepositoryError::from)?;
@@ -117,6 +144,62 @@ impl ApiKeyRepository for PostgresApiKeyRepository {
         Ok(rows)
     }
 
+    async fn supersede(
+        &self,
+        old_id: Uuid,
+        replacement: &ApiKey,
+        old_expires_at: DateTime<Utc>,
+    ) -> Result<ApiKey, RepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::from)?;
+
+        let created = sqlx::query(
+            r#"
+            INSERT INTO api_keys (id, key_hash, key_prefix, account_id, additional_account_ids, rate_limit_per_hour, created_at, last_used_at, expires_at, is_admin)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, key_hash, key_prefix, account_id, additional_account_ids, rate_limit_per_hour, created_at, last_used_at, expires_at, superseded_by, is_admin
+            "#,
+        )
+        .bind(replacement.id)
+        .bind(&replacement.key_hash)
+        .bind(&replacement.key_prefix)
+        .bind(replacement.account_id)
+        .bind(&replacement.additional_account_ids)
+        .bind(replacement.rate_limit_per_hour as i32)
+        .bind(replacement.created_at)
+        .bind(replacement.last_used_at)
+        .bind(replacement.expires_at)
+        .bind(replacement.is_admin)
+        .map(|row: sqlx::postgres::PgRow| api_key_from_row(&row))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        // Only one rotation of a key may win; a concurrent one finds it already superseded
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET superseded_by = $1, expires_at = LEAST(COALESCE(expires_at, $2), $2)
+            WHERE id = $3 AND superseded_by IS NULL
+            "#,
+        )
+        .bind(replacement.id)
+        .bind(old_expires_at)
+        .bind(old_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::DuplicateEntry(format!(
+                "API key {} has already been rotated",
+                old_id
+            )));
+        }
+
+        tx.commit().await.map_err(RepositoryError::from)?;
+        Ok(created)
+    }
+
     async fn update_last_used(&self, id: Uuid) -> Result<(), RepositoryError> {
         let result = sqlx::query(
             r#"
@@ -137,6 +220,28 @@ impl ApiKeyRepository for PostgresApiKeyRepository {
         Ok(())
     }
 
+    async fn update_hash(&self, id: Uuid, key_hash: &str, key_prefix: &str) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET key_hash = $2, key_prefix = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(key_hash)
+        .bind(key_prefix)
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        if result.rows_affected() == 0 {
+             return Err(RepositoryError::NotFound(format!("API key {} not found", id)));
+        }
+
+        Ok(())
+    }
+
     async fn delete(&self, id: Uuid) -> Result<(), RepositoryError> {
         let result = sqlx::query(
             r#"
@@ -165,4 +270,72 @@ impl ApiKeyRepository for PostgresApiKeyRepository {
 
         Ok(result.is_some())
     }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM api_keys")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    async fn create_first_key(&self, account: &Account, api_key: &ApiKey) -> Result<Option<ApiKey>, RepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::from)?;
+
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(FIRST_KEY_LOCK_ID)
+            .execute(&mut *tx)
+            .await
+            .map_err(RepositoryError::from)?;
+
+        let existing: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM api_keys")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(RepositoryError::from)?;
+        if existing > 0 {
+            return Ok(None);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO accounts (id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret, status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8::account_status)
+            "#,
+        )
+        .bind(account.id)
+        .bind(&account.business_name)
+        .bind(account.balance_as_decimal())
+        .bind(account.created_at)
+        .bind(account.updated_at)
+        .bind(account.deleted_at)
+        .bind(&account.webhook_secret)
+        .bind(account.status.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        let created = sqlx::query(
+            r#"
+            INSERT INTO api_keys (id, key_hash, key_prefix, account_id, additional_account_ids, rate_limit_per_hour, created_at, last_used_at, expires_at, is_admin)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, key_hash, key_prefix, account_id, additional_account_ids, rate_limit_per_hour, created_at, last_used_at, expires_at, superseded_by, is_admin
+            "#,
+        )
+        .bind(api_key.id)
+        .bind(&api_key.key_hash)
+        .bind(&api_key.key_prefix)
+        .bind(api_key.account_id)
+        .bind(&api_key.additional_account_ids)
+        .bind(api_key.rate_limit_per_hour as i32)
+        .bind(api_key.created_at)
+        .bind(api_key.last_used_at)
+        .bind(api_key.expires_at)
+        .bind(api_key.is_admin)
+        .map(|row: sqlx::postgres::PgRow| api_key_from_row(&row))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        tx.commit().await.map_err(RepositoryError::from)?;
+        Ok(Some(created))
+    }
 }
@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::entities::BalanceSnapshot;
+use crate::domain::errors::RepositoryError;
+use crate::domain::repositories::BalanceSnapshotRepository;
+use crate::domain::value_objects::Money;
+
+/// PostgreSQL implementation of the BalanceSnapshotRepository
+pub struct PostgresBalanceSnapshotRepository {
+    pool: PgPool,
+}
+
+impl PostgresBalanceSnapshotRepository {
+    /// Create a new PostgresBalanceSnapshotRepository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BalanceSnapshotRepository for PostgresBalanceSnapshotRepository {
+    async fn list_by_account(
+        &self,
+        account_id: Uuid,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<BalanceSnapshot>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT account_id, transaction_id, balance, recorded_at
+            FROM balance_snapshots
+            WHERE account_id = $1
+              AND ($2::timestamptz IS NULL OR recorded_at >= $2)
+              AND ($3::timestamptz IS NULL OR recorded_at <= $3)
+            ORDER BY recorded_at ASC, id ASC
+            "#,
+        )
+        .bind(account_id)
+        .bind(from)
+        .bind(to)
+        .map(|row: sqlx::postgres::PgRow| {
+            use sqlx::Row;
+            let balance: rust_decimal::Decimal = row.get("balance");
+            Ok::<_, crate::domain::errors::DomainError>(BalanceSnapshot {
+                account_id: row.get("account_id"),
+                transaction_id: row.get("transaction_id"),
+                balance: Money::new(balance)?,
+                recorded_at: row.get("recorded_at"),
+            })
+        })
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        let mut snapshots = Vec::new();
+        for snapshot_result in rows {
+            snapshots.push(snapshot_result.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))?);
+        }
+
+        Ok(snapshots)
+    }
+}
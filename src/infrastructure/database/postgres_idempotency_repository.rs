@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use crate::domain::entities::IdempotencyRecord;
+use crate::domain::errors::RepositoryError;
+use crate::domain::repositories::IdempotencyRepository;
+
+/// PostgreSQL implementation of the IdempotencyRepository
+pub struct PostgresIdempotencyRepository {
+    pool: PgPool,
+}
+
+impl PostgresIdempotencyRepository {
+    /// Create a new PostgresIdempotencyRepository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for PostgresIdempotencyRepository {
+    async fn find_by_key(&self, key: &str) -> Result<IdempotencyRecord, RepositoryError> {
+        let row = sqlx::query(
+            r#"
+            SELECT key, request_fingerprint, response, created_at, expires_at
+            FROM idempotency_keys
+            WHERE key = $1 AND expires_at > NOW()
+            "#,
+        )
+        .bind(key)
+        .map(|row: sqlx::postgres::PgRow| {
+            use sqlx::Row;
+            IdempotencyRecord {
+                key: row.get("key"),
+                request_fingerprint: row.get("request_fingerprint"),
+                response: row.get("response"),
+                created_at: row.get("created_at"),
+                expires_at: row.get("expires_at"),
+            }
+        })
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        match row {
+            Some(record) => Ok(record),
+            None => Err(RepositoryError::NotFound(format!("Idempotency key {} not found", key))),
+        }
+    }
+
+    async fn save(&self, record: &IdempotencyRecord) -> Result<IdempotencyRecord, RepositoryError> {
+        // Only an expired record may be overwritten; a live one means the key is taken
+        let row = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (key, request_fingerprint, response, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (key) DO UPDATE
+            SET request_fingerprint = EXCLUDED.request_fingerprint,
+                response = EXCLUDED.response,
+                created_at = EXCLUDED.created_at,
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at <= NOW()
+            RETURNING key, request_fingerprint, response, created_at, expires_at
+            "#,
+        )
+        .bind(&record.key)
+        .bind(&record.request_fingerprint)
+        .bind(&record.response)
+        .bind(record.created_at)
+        .bind(record.expires_at)
+        .map(|row: sqlx::postgres::PgRow| {
+            use sqlx::Row;
+            IdempotencyRecord {
+                key: row.get("key"),
+                request_fingerprint: row.get("request_fingerprint"),
+                response: row.get("response"),
+                created_at: row.get("created_at"),
+                expires_at: row.get("expires_at"),
+            }
+        })
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        match row {
+            Some(record) => Ok(record),
+            None => Err(RepositoryError::DuplicateEntry(format!(
+                "Idempotency key {} already exists",
+                record.key
+            ))),
+        }
+    }
+
+    async fn delete_expired(&self) -> Result<u64, RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE expires_at <= NOW()
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::domain::entities::OutboxEvent;
+use crate::domain::errors::RepositoryError;
+use crate::domain::repositories::OutboxRepository;
+use crate::domain::value_objects::WebhookEvent;
+
+/// PostgreSQL implementation of the OutboxRepository
+pub struct PostgresOutboxRepository {
+    pool: PgPool,
+}
+
+impl PostgresOutboxRepository {
+    /// Create a new PostgresOutboxRepository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for PostgresOutboxRepository {
+    async fn claim_unsent(&self, limit: i64, lease: Duration) -> Result<Vec<OutboxEvent>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            UPDATE outbox_events
+            SET locked_until = NOW() + make_interval(secs => $2)
+            WHERE id IN (
+                SELECT id FROM outbox_events
+                WHERE sent_at IS NULL AND (locked_until IS NULL OR locked_until < NOW())
+                ORDER BY created_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, account_id, event, transaction_id, created_at, sent_at
+            "#,
+        )
+        .bind(limit)
+        .bind(lease.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            let event_str: String = row.get("event");
+            let event = serde_json::from_str::<WebhookEvent>(&format!("\"{}\"", event_str))
+                .map_err(|e| RepositoryError::DatabaseError(format!("Invalid event type: {}", e)))?;
+
+            events.push(OutboxEvent {
+                id: row.get("id"),
+                account_id: row.get("account_id"),
+                event,
+                transaction_id: row.get("transaction_id"),
+                created_at: row.get("created_at"),
+                sent_at: row.get("sent_at"),
+            });
+        }
+        // RETURNING does not preserve the subquery's order
+        events.sort_by_key(|event| event.created_at);
+
+        Ok(events)
+    }
+
+    async fn mark_sent(&self, id: Uuid) -> Result<(), RepositoryError> {
+        let result = sqlx::query("UPDATE outbox_events SET sent_at = NOW(), locked_until = NULL WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(RepositoryError::from)?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound(format!("Outbox event {} not found", id)));
+        }
+
+        Ok(())
+    }
+}
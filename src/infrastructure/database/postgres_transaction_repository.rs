@@ -1,315 +1,300 @@
 use async_trait::async_trait;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::Duration;
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
-use crate::domain::entities::Transaction;
-use crate::domain::errors::RepositoryError;
-use crate::domain::repositories::TransactionRepository;
-use crate::domain::value_objects::TransactionType;
+use crate::domain::entities::{Account, OutboxEvent, Transaction, TransactionRecord};
+use crate::domain::errors::{DomainError, RepositoryError};
+use crate::domain::repositories::{
+    AccountStats, BalanceDrift, BatchFailure, BatchPreview, ProjectedBalance, ReconciliationPage, TransactionFilter,
+    TransactionRepository,
+};
+use crate::domain::value_objects::{AccountStatus, AuthorizationStatus, Money, TransactionStatus, TransactionType};
+use crate::infrastructure::fault_injection::{self, Fault};
 
-/// PostgreSQL implementation of the TransactionRepository
-pub struct PostgresTransactionRepository {
-    pool: PgPool,
+/// Live and archived transactions, for filters with `include_archived` set
+const TRANSACTIONS_WITH_ARCHIVE: &str = r#"(
+    SELECT id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint FROM transactions
+    UNION ALL
+    SELECT id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint FROM archived_transactions
+) AS transactions"#;
+
+/// The relation an account listing reads from
+fn transactions_source(filter: &TransactionFilter) -> &'static str {
+    if filter.include_archived {
+        TRANSACTIONS_WITH_ARCHIVE
+    } else {
+        "transactions"
+    }
 }
 
-impl PostgresTransactionRepository {
-    /// Create a new PostgresTransactionRepository instance
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+/// Append the `WHERE` clause selecting the transactions of any of `account_ids` matching `filter`
+fn push_account_filter(query: &mut QueryBuilder<'_, Postgres>, account_ids: &[Uuid], filter: &TransactionFilter) {
+    query.push(" WHERE (from_account_id = ANY(");
+    query.push_bind(account_ids.to_vec());
+    query.push(") OR to_account_id = ANY(");
+    query.push_bind(account_ids.to_vec());
+    query.push("))");
+
+    if let Some(transaction_type) = filter.transaction_type {
+        query.push(" AND transaction_type = ");
+        query.push_bind(transaction_type.as_str());
+        query.push("::transaction_type");
+    }
+    if let Some(from) = filter.from {
+        query.push(" AND created_at >= ");
+        query.push_bind(from);
+    }
+    if let Some(to) = filter.to {
+        query.push(" AND created_at <= ");
+        query.push_bind(to);
     }
 }
 
-#[async_trait]
-impl TransactionRepository for PostgresTransactionRepository {
-    async fn create(&self, transaction: &Transaction) -> Result<Transaction, RepositoryError> {
-        let row = sqlx::query(
-            r#"
-            INSERT INTO transactions (
-                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
-            )
-            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7)
-            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
-            "#,
-        )
-        .bind(transaction.id)
-        .bind(transaction.transaction_type.as_str())
-        .bind(transaction.from_account_id)
-        .bind(transaction.to_account_id)
-        .bind(transaction.amount.amount())
-        .bind(&transaction.idempotency_key)
-        .bind(transaction.created_at)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            let type_str: String = row.get("transaction_type");
-            let transaction_type = type_str.parse::<TransactionType>()?;
-            let amount_decimal: rust_decimal::Decimal = row.get("amount");
-            let amount = crate::domain::value_objects::Money::new(amount_decimal)?;
-
-            Transaction::from_db(
-                row.get("id"),
-                transaction_type,
-                row.get("from_account_id"),
-                row.get("to_account_id"),
-                amount,
-                row.get("idempotency_key"),
-                row.get("created_at"),
-            )
-        })
-        .fetch_one(&self.pool)
-        .await
-        .map_err(|e| {
-            if let Some(db_err) = e.as_database_error() {
-                if db_err.is_unique_violation() {
-                    return RepositoryError::DuplicateEntry(format!(
-                        "Transaction with idempotency key {:?} already exists",
-                        transaction.idempotency_key
-                    ));
-                }
-            }
-            RepositoryError::from(e)
-        })?;
+/// Rebuild a transaction from a row, enforcing the entity's invariants
+fn transaction_from_row(row: &sqlx::postgres::PgRow) -> Result<Transaction, DomainError> {
+    use sqlx::Row;
+    let type_str: String = row.get("transaction_type");
+    let transaction_type = type_str.parse::<TransactionType>()?;
+    let amount_decimal: rust_decimal::Decimal = row.get("amount");
+    let amount = Money::new(amount_decimal)?;
+    let authorization_status = row
+        .get::<Option<String>, _>("authorization_status")
+        .map(|status| status.parse::<AuthorizationStatus>())
+        .transpose()?;
+    let status = row.get::<String, _>("status").parse::<TransactionStatus>()?;
 
-        row.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))
+    Transaction::from_db(TransactionRecord {
+        id: row.get("id"),
+        transaction_type,
+        from_account_id: row.get("from_account_id"),
+        to_account_id: row.get("to_account_id"),
+        amount,
+        idempotency_key: row.get("idempotency_key"),
+        created_at: row.get("created_at"),
+        authorization_status,
+        status,
+        description: row.get("description"),
+        request_fingerprint: row.get("request_fingerprint"),
+    })
+}
+
+/// Sums come back at whatever scale Postgres picks (none at all for zero); report them to the minor unit
+fn to_minor_unit_scale(mut amount: Decimal) -> Decimal {
+    amount.rescale(Money::CURRENCY.minor_unit_scale());
+    amount
+}
+
+/// Fail with an injected fault, if one is armed (`test-fault-injection` only)
+fn injected_fault() -> Result<(), RepositoryError> {
+    if fault_injection::should_fail(Fault::DbTimeout) {
+        return Err(RepositoryError::ConnectionError("Connection pool timeout".to_string()));
+    }
+    if fault_injection::should_fail(Fault::SerializationConflict) {
+        return Err(RepositoryError::TransactionError(
+            "could not serialize access due to concurrent update".to_string(),
+        ));
     }
+    Ok(())
+}
 
-    async fn find_by_id(&self, id: Uuid) -> Result<Transaction, RepositoryError> {
-        let row = sqlx::query(
-            r#"
-            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
-            FROM transactions
-            WHERE id = $1
-            "#,
-        )
-        .bind(id)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            let type_str: String = row.get("transaction_type");
-            let transaction_type = type_str.parse::<TransactionType>()?;
-            let amount_decimal: rust_decimal::Decimal = row.get("amount");
-            let amount = crate::domain::value_objects::Money::new(amount_decimal)?;
-
-            Transaction::from_db(
-                row.get("id"),
-                transaction_type,
-                row.get("from_account_id"),
-                row.get("to_account_id"),
-                amount,
-                row.get("idempotency_key"),
-                row.get("created_at"),
-            )
-        })
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(RepositoryError::from)?;
+/// Reject money movement on accounts that are not active (e.g. frozen)
+fn ensure_active(account_id: Uuid, status: &str) -> Result<(), RepositoryError> {
+    ensure_party_active("Account", account_id, status)
+}
 
-        match row {
-            Some(tx_result) => tx_result
-                .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e))),
-            None => Err(RepositoryError::NotFound(format!("Transaction {} not found", id))),
-        }
+/// Like `ensure_active`, but names the account's role (e.g. "Destination account")
+/// so a transfer's sender can tell which side is blocked
+fn ensure_party_active(party: &str, account_id: Uuid, status: &str) -> Result<(), RepositoryError> {
+    let status = status
+        .parse::<AccountStatus>()
+        .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))?;
+
+    if status != AccountStatus::Active {
+        return Err(RepositoryError::InvalidState(format!(
+            "{} {} is {}",
+            party, account_id, status
+        )));
     }
 
-    async fn find_by_idempotency_key(&self, key: &str) -> Result<Transaction, RepositoryError> {
-        let row = sqlx::query(
-            r#"
-            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
-            FROM transactions
-            WHERE idempotency_key = $1
-            "#,
-        )
-        .bind(key)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            let type_str: String = row.get("transaction_type");
-            let transaction_type = type_str.parse::<TransactionType>()?;
-            let amount_decimal: rust_decimal::Decimal = row.get("amount");
-            let amount = crate::domain::value_objects::Money::new(amount_decimal)?;
-
-            Transaction::from_db(
-                row.get("id"),
-                transaction_type,
-                row.get("from_account_id"),
-                row.get("to_account_id"),
-                amount,
-                row.get("idempotency_key"),
-                row.get("created_at"),
-            )
-        })
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(RepositoryError::from)?;
+    Ok(())
+}
 
-        match row {
-            Some(tx_result) => tx_result
-                .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e))),
-            None => Err(RepositoryError::NotFound(format!("Transaction with key {} not found", key))),
+/// PostgreSQL implementation of the TransactionRepository
+pub struct PostgresTransactionRepository {
+    pool: PgPool,
+    record_balance_snapshots: bool,
+    record_outbox_events: bool,
+    skip_invalid_rows: bool,
+    max_attempts: u32,
+    retry_base_delay: Duration,
+}
+
+impl PostgresTransactionRepository {
+    /// Create a new PostgresTransactionRepository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            record_balance_snapshots: false,
+            record_outbox_events: false,
+            skip_invalid_rows: false,
+            max_attempts: 1,
+            retry_base_delay: Duration::from_millis(10),
         }
     }
 
-    async fn idempotency_key_exists(&self, key: &str) -> Result<bool, RepositoryError> {
-        let result = sqlx::query(
-            r#"
-            SELECT 1 as exists
-            FROM transactions
-            WHERE idempotency_key = $1
-            "#,
-        )
-        .bind(key)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(RepositoryError::from)?;
+    /// Retry credits, debits and transfers that fail with a serialization failure, deadlock or
+    /// pool timeout, up to `max_attempts` tries in total (1, the default, disables retries).
+    /// The delay before the n-th retry is `base_delay * 2^(n-1)`.
+    pub fn with_retries(mut self, max_attempts: u32, base_delay: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.retry_base_delay = base_delay;
+        self
+    }
 
-        Ok(result.is_some())
+    /// Log and drop rows that violate transaction invariants when listing,
+    /// instead of failing the whole page on the first one.
+    pub fn with_invalid_row_skipping(mut self, enabled: bool) -> Self {
+        self.skip_invalid_rows = enabled;
+        self
     }
 
-    async fn list_by_account(
+    /// Apply one previewed operation to the running balances and the (rolled back) accounts.
+    /// Funds in `held` stay reserved, as they do for the execute_* calls.
+    async fn apply_preview_operation(
         &self,
-        account_id: Uuid,
-        limit: i64,
-        offset: i64,
-    ) -> Result<Vec<Transaction>, RepositoryError> {
-        let rows = sqlx::query(
-            r#"
-            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
-            FROM transactions
-            WHERE from_account_id = $1 OR to_account_id = $1
-            ORDER BY created_at DESC
-            LIMIT $2 OFFSET $3
-            "#,
-        )
-        .bind(account_id)
-        .bind(limit)
-        .bind(offset)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            let type_str: String = row.get("transaction_type");
-            let transaction_type = type_str.parse::<TransactionType>()?;
-            let amount_decimal: rust_decimal::Decimal = row.get("amount");
-            let amount = crate::domain::value_objects::Money::new(amount_decimal)?;
-
-            Transaction::from_db(
-                row.get("id"),
-                transaction_type,
-                row.get("from_account_id"),
-                row.get("to_account_id"),
-                amount,
-                row.get("idempotency_key"),
-                row.get("created_at"),
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        accounts: &mut BTreeMap<Uuid, (Decimal, String)>,
+        held: &BTreeMap<Uuid, Decimal>,
+        operation: &Transaction,
+    ) -> Result<(), RepositoryError> {
+        let amount = operation.amount.amount();
+        let legs = [
+            operation.from_account_id.map(|id| (id, -amount)),
+            operation.to_account_id.map(|id| (id, amount)),
+        ];
+
+        // Validate both sides before touching either, as the execute_* calls do
+        for (account_id, delta) in legs.iter().flatten() {
+            let (balance, status) = accounts
+                .get(account_id)
+                .ok_or_else(|| RepositoryError::NotFound(format!("Account {} not found", account_id)))?;
+            ensure_active(*account_id, status)?;
+            let reserved = held.get(account_id).copied().unwrap_or(Decimal::ZERO);
+            if balance + delta < reserved {
+                return Err(RepositoryError::ConstraintViolation(format!(
+                    "Insufficient funds for account {}", account_id
+                )));
+            }
+        }
+
+        for (account_id, delta) in legs.into_iter().flatten() {
+            let balance: Decimal = sqlx::query_scalar(
+                "UPDATE accounts SET balance = balance + $1, version = version + 1 WHERE id = $2 RETURNING balance",
             )
-        })
-        .fetch_all(&self.pool)
-        .await
-        .map_err(RepositoryError::from)?;
+            .bind(delta)
+            .bind(account_id)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(RepositoryError::from)?;
 
-        let mut transactions = Vec::new();
-        for tx_result in rows {
-            transactions.push(tx_result.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))?);
+            if let Some(entry) = accounts.get_mut(&account_id) {
+                entry.0 = balance;
+            }
         }
 
-        Ok(transactions)
+        Ok(())
     }
 
-    async fn list_by_type(
+    /// Collect listed rows, failing fast or skipping invalid ones per configuration
+    fn collect_rows(
         &self,
-        transaction_type: TransactionType,
-        limit: i64,
-        offset: i64,
+        rows: Vec<(Uuid, Result<Transaction, DomainError>)>,
     ) -> Result<Vec<Transaction>, RepositoryError> {
-        let rows = sqlx::query(
-            r#"
-            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
-            FROM transactions
-            WHERE transaction_type = $1::transaction_type
-            ORDER BY created_at DESC
-            LIMIT $2 OFFSET $3
-            "#,
-        )
-        .bind(transaction_type.as_str())
-        .bind(limit)
-        .bind(offset)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            let type_str: String = row.get("transaction_type");
-            let transaction_type = type_str.parse::<TransactionType>()?;
-            let amount_decimal: rust_decimal::Decimal = row.get("amount");
-            let amount = crate::domain::value_objects::Money::new(amount_decimal)?;
-
-            Transaction::from_db(
-                row.get("id"),
-                transaction_type,
-                row.get("from_account_id"),
-                row.get("to_account_id"),
-                amount,
-                row.get("idempotency_key"),
-                row.get("created_at"),
-            )
-        })
-        .fetch_all(&self.pool)
-        .await
-        .map_err(RepositoryError::from)?;
-
-        let mut transactions = Vec::new();
-        for tx_result in rows {
-            transactions.push(tx_result.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))?);
+        let mut transactions = Vec::with_capacity(rows.len());
+        for (id, tx_result) in rows {
+            match tx_result {
+                Ok(transaction) => transactions.push(transaction),
+                Err(e) if self.skip_invalid_rows => {
+                    tracing::error!("Skipping invalid transaction row {}: {}", id, e);
+                }
+                Err(e) => {
+                    return Err(RepositoryError::DatabaseError(format!("Data integrity error: {}", e)));
+                }
+            }
         }
 
         Ok(transactions)
     }
 
-    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<Transaction>, RepositoryError> {
-         let rows = sqlx::query(
-            r#"
-            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
-            FROM transactions
-            ORDER BY created_at DESC
-            LIMIT $1 OFFSET $2
-            "#,
-        )
-        .bind(limit)
-        .bind(offset)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            let type_str: String = row.get("transaction_type");
-            let transaction_type = type_str.parse::<TransactionType>()?;
-            let amount_decimal: rust_decimal::Decimal = row.get("amount");
-            let amount = crate::domain::value_objects::Money::new(amount_decimal)?;
-
-            Transaction::from_db(
-                row.get("id"),
-                transaction_type,
-                row.get("from_account_id"),
-                row.get("to_account_id"),
-                amount,
-                row.get("idempotency_key"),
-                row.get("created_at"),
-            )
-        })
-        .fetch_all(&self.pool)
-        .await
-        .map_err(RepositoryError::from)?;
+    /// Append a `balance_snapshots` row for each account whose balance an `execute_*` call changes
+    pub fn with_balance_snapshots(mut self, enabled: bool) -> Self {
+        self.record_balance_snapshots = enabled;
+        self
+    }
 
-        let mut transactions = Vec::new();
-        for tx_result in rows {
-            transactions.push(tx_result.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))?);
+    /// Run `attempt` until it succeeds, fails with a non-transient error, or has been
+    /// tried `max_attempts` times, doubling the delay between tries.
+    async fn retrying<F, Fut>(&self, mut attempt: F) -> Result<Transaction, RepositoryError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Transaction, RepositoryError>>,
+    {
+        let mut delay = self.retry_base_delay;
+        let mut attempts = 1;
+        loop {
+            match attempt().await {
+                Err(e) if e.is_transient() && attempts < self.max_attempts => {
+                    tracing::warn!("Transient database error on attempt {}, retrying: {}", attempts, e);
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempts += 1;
+                }
+                Err(e) if e.is_transient() && self.max_attempts > 1 => {
+                    return Err(RepositoryError::RetriesExhausted(format!(
+                        "gave up after {} attempts: {}",
+                        attempts, e
+                    )));
+                }
+                result => return result,
+            }
         }
-
-        Ok(transactions)
     }
 
-    async fn execute_credit(
+    /// One attempt at `execute_credit`
+    async fn credit_once(
         &self,
         transaction: &Transaction,
     ) -> Result<Transaction, RepositoryError> {
+        injected_fault()?;
+
         let mut tx = self.pool.begin().await.map_err(RepositoryError::from)?;
         let to_account_id = transaction.to_account_id.ok_or_else(|| {
              RepositoryError::ConstraintViolation("Credit transaction must have to_account_id".to_string())
         })?;
 
-        // 1. Update Account Balance
-        let update_result = sqlx::query(
+        // 1. Check Status and Lock Row. A soft-delete committed while we wait for the
+        // lock is re-checked against `deleted_at`, so the credit fails with NotFound.
+        let status: String = sqlx::query_scalar(
+            "SELECT status::text FROM accounts WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"
+        )
+        .bind(to_account_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?
+        .ok_or_else(|| RepositoryError::NotFound(format!("Account {} not found", to_account_id)))?;
+
+        ensure_active(to_account_id, &status)?;
+
+        // 2. Update Account Balance, only while the locked row is still live
+        let updated = sqlx::query(
             r#"
             UPDATE accounts
-            SET balance = balance + $1, updated_at = NOW()
+            SET balance = balance + $1, version = version + 1, updated_at = NOW()
             WHERE id = $2 AND deleted_at IS NULL
             "#,
         )
@@ -318,19 +303,18 @@ impl TransactionRepository for PostgresTransactionRepository {
         .execute(&mut *tx)
         .await
         .map_err(RepositoryError::from)?;
-
-        if update_result.rows_affected() == 0 {
-             return Err(RepositoryError::NotFound(format!("Account {} not found", to_account_id)));
+        if updated.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound(format!("Account {} not found", to_account_id)));
         }
 
-        // 2. Create Transaction Record
+        // 3. Create Transaction Record
         let tx_row = sqlx::query(
              r#"
             INSERT INTO transactions (
-                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
+                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint
             )
-            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7)
-            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
+            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7, $8::authorization_status, $9::transaction_status, $10, $11)
+            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
             "#,
         )
         .bind(transaction.id)
@@ -340,54 +324,113 @@ impl TransactionRepository for PostgresTransactionRepository {
         .bind(transaction.amount.amount())
         .bind(&transaction.idempotency_key)
         .bind(transaction.created_at)
-        .map(|row: sqlx::postgres::PgRow| {
-            use sqlx::Row;
-            let type_str: String = row.get("transaction_type");
-            let transaction_type = type_str.parse::<TransactionType>()?;
-            let amount_decimal: rust_decimal::Decimal = row.get("amount");
-            let amount = crate::domain::value_objects::Money::new(amount_decimal)?;
-
-             Transaction::from_db(
-                row.get("id"),
-                transaction_type,
-                row.get("from_account_id"),
-                row.get("to_account_id"),
-                amount,
-                row.get("idempotency_key"),
-                row.get("created_at"),
-            )
-        })
+        .bind(transaction.authorization_status.map(|status| status.as_str()))
+        .bind(TransactionStatus::Completed.as_str())
+        .bind(&transaction.description)
+        .bind(&transaction.request_fingerprint)
+        .map(|row: sqlx::postgres::PgRow| transaction_from_row(&row))
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
-             if let Some(db_err) = e.as_database_error() {
-                if db_err.is_unique_violation() {
-                    return RepositoryError::DuplicateEntry(format!(
-                        "Transaction with idempotency key {:?} already exists",
-                        transaction.idempotency_key
-                    ));
-                }
+            if let Some(db_err) = e.as_database_error()
+                && db_err.is_unique_violation()
+            {
+                return RepositoryError::DuplicateEntry(format!(
+                    "Transaction with idempotency key {:?} already exists",
+                    transaction.idempotency_key
+                ));
             }
             RepositoryError::from(e)
         })?;
 
+        // 4. Record Balance Snapshot and Outbox Event (when enabled)
+        self.record_balance_snapshot(&mut tx, to_account_id, transaction.id).await?;
+        self.record_outbox_event(&mut tx, transaction).await?;
+
         tx.commit().await.map_err(RepositoryError::from)?;
 
         tx_row.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))
     }
 
-    async fn execute_debit(
+    /// One attempt at `execute_opening_credit`
+    async fn opening_credit_once(
+        &self,
+        account: &Account,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError> {
+        injected_fault()?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::from)?;
+
+        // 1. Create the account with its opening balance; nobody else can see it yet
+        sqlx::query(
+            r#"
+            INSERT INTO accounts (id, business_name, balance, created_at, updated_at, deleted_at, webhook_secret, status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8::account_status)
+            "#,
+        )
+        .bind(account.id)
+        .bind(&account.business_name)
+        .bind(account.balance_as_decimal())
+        .bind(account.created_at)
+        .bind(account.updated_at)
+        .bind(account.deleted_at)
+        .bind(&account.webhook_secret)
+        .bind(account.status.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        // 2. Create the Transaction Record explaining that balance
+        let tx_row = sqlx::query(
+            r#"
+            INSERT INTO transactions (
+                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint
+            )
+            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7, $8::authorization_status, $9::transaction_status, $10, $11)
+            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            "#,
+        )
+        .bind(transaction.id)
+        .bind(transaction.transaction_type.as_str())
+        .bind(transaction.from_account_id)
+        .bind(transaction.to_account_id)
+        .bind(transaction.amount.amount())
+        .bind(&transaction.idempotency_key)
+        .bind(transaction.created_at)
+        .bind(transaction.authorization_status.map(|status| status.as_str()))
+        .bind(TransactionStatus::Completed.as_str())
+        .bind(&transaction.description)
+        .bind(&transaction.request_fingerprint)
+        .map(|row: sqlx::postgres::PgRow| transaction_from_row(&row))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        // 3. Record Balance Snapshot and Outbox Event (when enabled)
+        self.record_balance_snapshot(&mut tx, account.id, transaction.id).await?;
+        self.record_outbox_event(&mut tx, transaction).await?;
+
+        tx.commit().await.map_err(RepositoryError::from)?;
+
+        tx_row.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))
+    }
+
+    /// One attempt at `execute_debit`
+    async fn debit_once(
         &self,
         transaction: &Transaction,
     ) -> Result<Transaction, RepositoryError> {
+        injected_fault()?;
+
         let mut tx = self.pool.begin().await.map_err(RepositoryError::from)?;
         let from_account_id = transaction.from_account_id.ok_or_else(|| {
              RepositoryError::ConstraintViolation("Debit transaction must have from_account_id".to_string())
         })?;
 
-        // 1. Check Balance and Lock Row (SELECT FOR UPDATE)
+        // 1. Check Status and Balance, Lock Row (SELECT FOR UPDATE); held funds cannot be spent
         let account_row = sqlx::query(
-            "SELECT balance FROM accounts WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"
+            "SELECT balance - held_balance AS available, status::text as status FROM accounts WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"
         )
         .bind(from_account_id)
         .fetch_optional(&mut *tx)
@@ -397,7 +440,8 @@ impl TransactionRepository for PostgresTransactionRepository {
         let account_balance = match account_row {
             Some(row) => {
                 use sqlx::Row;
-                row.get::<rust_decimal::Decimal, _>("balance")
+                ensure_active(from_account_id, row.get("status"))?;
+                row.get::<rust_decimal::Decimal, _>("available")
             },
             None => return Err(RepositoryError::NotFound(format!("Account {} not found", from_account_id))),
         };
@@ -412,7 +456,7 @@ impl TransactionRepository for PostgresTransactionRepository {
         sqlx::query(
              r#"
             UPDATE accounts
-            SET balance = balance - $1, updated_at = NOW()
+            SET balance = balance - $1, version = version + 1, updated_at = NOW()
             WHERE id = $2
             "#,
         )
@@ -426,10 +470,10 @@ impl TransactionRepository for PostgresTransactionRepository {
         let tx_row = sqlx::query(
              r#"
             INSERT INTO transactions (
-                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
+                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint
             )
-            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7)
-            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
+            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7, $8::authorization_status, $9::transaction_status, $10, $11)
+            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
             "#,
         )
         .bind(transaction.id)
@@ -439,46 +483,41 @@ impl TransactionRepository for PostgresTransactionRepository {
         .bind(transaction.amount.amount())
         .bind(&transaction.idempotency_key)
         .bind(transaction.created_at)
-        .map(|row: sqlx::postgres::PgRow| {
-             use sqlx::Row;
-            let type_str: String = row.get("transaction_type");
-            let transaction_type = type_str.parse::<TransactionType>()?;
-            let amount_decimal: rust_decimal::Decimal = row.get("amount");
-            let amount = crate::domain::value_objects::Money::new(amount_decimal)?;
-
-             Transaction::from_db(
-                row.get("id"),
-                transaction_type,
-                row.get("from_account_id"),
-                row.get("to_account_id"),
-                amount,
-                row.get("idempotency_key"),
-                row.get("created_at"),
-            )
-        })
+        .bind(transaction.authorization_status.map(|status| status.as_str()))
+        .bind(TransactionStatus::Completed.as_str())
+        .bind(&transaction.description)
+        .bind(&transaction.request_fingerprint)
+        .map(|row: sqlx::postgres::PgRow| transaction_from_row(&row))
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
-             if let Some(db_err) = e.as_database_error() {
-                if db_err.is_unique_violation() {
-                    return RepositoryError::DuplicateEntry(format!(
-                        "Transaction with idempotency key {:?} already exists",
-                        transaction.idempotency_key
-                    ));
-                }
+            if let Some(db_err) = e.as_database_error()
+                && db_err.is_unique_violation()
+            {
+                return RepositoryError::DuplicateEntry(format!(
+                    "Transaction with idempotency key {:?} already exists",
+                    transaction.idempotency_key
+                ));
             }
             RepositoryError::from(e)
         })?;
 
+        // 4. Record Balance Snapshot and Outbox Event (when enabled)
+        self.record_balance_snapshot(&mut tx, from_account_id, transaction.id).await?;
+        self.record_outbox_event(&mut tx, transaction).await?;
+
         tx.commit().await.map_err(RepositoryError::from)?;
 
         tx_row.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))
     }
 
-    async fn execute_transfer(
+    /// One attempt at `execute_transfer`
+    async fn transfer_once(
         &self,
         transaction: &Transaction,
     ) -> Result<Transaction, RepositoryError> {
+        injected_fault()?;
+
         // Enforce ordering to prevent deadlocks: lock smaller ID first
         let from_id = transaction.from_account_id.ok_or_else(|| {
               RepositoryError::ConstraintViolation("Transfer must have from_account_id".to_string())
@@ -497,24 +536,32 @@ impl TransactionRepository for PostgresTransactionRepository {
         let first_id = if from_id < to_id { from_id } else { to_id };
         let second_id = if from_id < to_id { to_id } else { from_id };
 
-        // 1. Check existence and lock both accounts
-        let _ = sqlx::query("SELECT id FROM accounts WHERE id = $1 AND deleted_at IS NULL FOR UPDATE")
+        // 1. Check existence and lock both accounts; neither side may be frozen or closed
+        let first_status: String = sqlx::query_scalar("SELECT status::text FROM accounts WHERE id = $1 AND deleted_at IS NULL FOR UPDATE")
             .bind(first_id)
             .fetch_optional(&mut *tx)
             .await
             .map_err(RepositoryError::from)?
             .ok_or_else(|| RepositoryError::NotFound(format!("Account {} not found", first_id)))?;
 
-        let _ = sqlx::query("SELECT id FROM accounts WHERE id = $1 AND deleted_at IS NULL FOR UPDATE")
+        let second_status: String = sqlx::query_scalar("SELECT status::text FROM accounts WHERE id = $1 AND deleted_at IS NULL FOR UPDATE")
             .bind(second_id)
             .fetch_optional(&mut *tx)
             .await
             .map_err(RepositoryError::from)?
             .ok_or_else(|| RepositoryError::NotFound(format!("Account {} not found", second_id)))?;
 
+        let (from_status, to_status) = if first_id == from_id {
+            (first_status, second_status)
+        } else {
+            (second_status, first_status)
+        };
+        ensure_party_active("Source account", from_id, &from_status)?;
+        ensure_party_active("Destination account", to_id, &to_status)?;
+
 
-        // 2. Check Balance of From Account
-        let from_balance_row = sqlx::query("SELECT balance FROM accounts WHERE id = $1")
+        // 2. Check Balance of From Account, excluding funds held by authorizations
+        let from_balance_row = sqlx::query("SELECT balance - held_balance AS balance FROM accounts WHERE id = $1")
             .bind(from_id)
             .fetch_one(&mut *tx)
             .await
@@ -535,7 +582,7 @@ impl TransactionRepository for PostgresTransactionRepository {
         sqlx::query(
              r#"
             UPDATE accounts
-            SET balance = balance - $1, updated_at = NOW()
+            SET balance = balance - $1, version = version + 1, updated_at = NOW()
             WHERE id = $2
             "#,
         )
@@ -549,7 +596,7 @@ impl TransactionRepository for PostgresTransactionRepository {
         sqlx::query(
              r#"
             UPDATE accounts
-            SET balance = balance + $1, updated_at = NOW()
+            SET balance = balance + $1, version = version + 1, updated_at = NOW()
             WHERE id = $2
             "#,
         )
@@ -563,10 +610,10 @@ impl TransactionRepository for PostgresTransactionRepository {
         let tx_row = sqlx::query(
              r#"
             INSERT INTO transactions (
-                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
+                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint
             )
-            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7)
-            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at
+            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7, $8::authorization_status, $9::transaction_status, $10, $11)
+            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
             "#,
         )
         .bind(transaction.id)
@@ -576,39 +623,977 @@ impl TransactionRepository for PostgresTransactionRepository {
         .bind(transaction.amount.amount())
         .bind(&transaction.idempotency_key)
         .bind(transaction.created_at)
-        .map(|row: sqlx::postgres::PgRow| {
-             use sqlx::Row;
-            let type_str: String = row.get("transaction_type");
-            let transaction_type = type_str.parse::<TransactionType>()?;
-            let amount_decimal: rust_decimal::Decimal = row.get("amount");
-            let amount = crate::domain::value_objects::Money::new(amount_decimal)?;
-
-             Transaction::from_db(
-                row.get("id"),
-                transaction_type,
-                row.get("from_account_id"),
-                row.get("to_account_id"),
-                amount,
-                row.get("idempotency_key"),
-                row.get("created_at"),
-            )
-        })
+        .bind(transaction.authorization_status.map(|status| status.as_str()))
+        .bind(TransactionStatus::Completed.as_str())
+        .bind(&transaction.description)
+        .bind(&transaction.request_fingerprint)
+        .map(|row: sqlx::postgres::PgRow| transaction_from_row(&row))
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
-             if let Some(db_err) = e.as_database_error() {
-                if db_err.is_unique_violation() {
-                    return RepositoryError::DuplicateEntry(format!(
-                        "Transaction with idempotency key {:?} already exists",
-                        transaction.idempotency_key
-                    ));
-                }
+            if let Some(db_err) = e.as_database_error()
+                && db_err.is_unique_violation()
+            {
+                return RepositoryError::DuplicateEntry(format!(
+                    "Transaction with idempotency key {:?} already exists",
+                    transaction.idempotency_key
+                ));
             }
             RepositoryError::from(e)
         })?;
 
+        // 6. Record Balance Snapshots and Outbox Event (when enabled)
+        self.record_balance_snapshot(&mut tx, from_id, transaction.id).await?;
+        self.record_balance_snapshot(&mut tx, to_id, transaction.id).await?;
+        self.record_outbox_event(&mut tx, transaction).await?;
+
         tx.commit().await.map_err(RepositoryError::from)?;
 
         tx_row.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))
     }
+
+    /// Write an `outbox_events` row for every deposit, withdrawal and transfer an `execute_*`
+    /// call commits, for the outbox relay to deliver as a webhook
+    pub fn with_outbox_events(mut self, enabled: bool) -> Self {
+        self.record_outbox_events = enabled;
+        self
+    }
+
+    /// Capture or void a pending authorization, adjusting the account's held funds
+    async fn settle_authorization(
+        &self,
+        authorization_id: Uuid,
+        next: AuthorizationStatus,
+    ) -> Result<Transaction, RepositoryError> {
+        injected_fault()?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::from)?;
+
+        // 1. Lock the authorization so it settles exactly once
+        let row = sqlx::query(
+            r#"
+            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            FROM transactions
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(authorization_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?
+        .ok_or_else(|| RepositoryError::NotFound(format!("Transaction {} not found", authorization_id)))?;
+
+        let mut authorization = transaction_from_row(&row)
+            .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))?;
+        authorization
+            .transition_authorization(next)
+            .map_err(|e| RepositoryError::ConstraintViolation(e.to_string()))?;
+        let account_id = authorization.from_account_id.ok_or_else(|| {
+            RepositoryError::ConstraintViolation("Authorization must have from_account_id".to_string())
+        })?;
+
+        // 2. Lock the account; a void still releases the hold on a frozen account
+        let status: String = sqlx::query_scalar("SELECT status::text FROM accounts WHERE id = $1 FOR UPDATE")
+            .bind(account_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(RepositoryError::from)?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Account {} not found", account_id)))?;
+
+        let debit = match next {
+            AuthorizationStatus::Captured => {
+                ensure_active(account_id, &status)?;
+                authorization.amount.amount()
+            }
+            _ => Decimal::ZERO,
+        };
+
+        // 3. Release the hold, debiting the balance on capture
+        sqlx::query(
+            r#"
+            UPDATE accounts
+            SET balance = balance - $1, held_balance = held_balance - $2, version = version + 1, updated_at = NOW()
+            WHERE id = $3
+            "#,
+        )
+        .bind(debit)
+        .bind(authorization.amount.amount())
+        .bind(account_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        // 4. Record the new status
+        sqlx::query("UPDATE transactions SET authorization_status = $1::authorization_status WHERE id = $2")
+            .bind(next.as_str())
+            .bind(authorization_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(RepositoryError::from)?;
+
+        // 5. Record Balance Snapshot (when enabled and the balance changed)
+        if next == AuthorizationStatus::Captured {
+            self.record_balance_snapshot(&mut tx, account_id, authorization_id).await?;
+        }
+
+        tx.commit().await.map_err(RepositoryError::from)?;
+
+        Ok(authorization)
+    }
+
+    /// Record the webhook event announcing `transaction` within the same database transaction
+    async fn record_outbox_event(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        transaction: &Transaction,
+    ) -> Result<(), RepositoryError> {
+        if !self.record_outbox_events {
+            return Ok(());
+        }
+        let Some(event) = OutboxEvent::for_transaction(transaction) else {
+            return Ok(());
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO outbox_events (id, account_id, event, transaction_id, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(event.id)
+        .bind(event.account_id)
+        .bind(event.event.to_string())
+        .bind(event.transaction_id)
+        .bind(event.created_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        Ok(())
+    }
+
+    /// Record the account's post-transaction balance within the same database transaction
+    async fn record_balance_snapshot(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        account_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<(), RepositoryError> {
+        if !self.record_balance_snapshots {
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO balance_snapshots (account_id, transaction_id, balance, recorded_at)
+            SELECT id, $2, balance, clock_timestamp()
+            FROM accounts
+            WHERE id = $1
+            "#,
+        )
+        .bind(account_id)
+        .bind(transaction_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TransactionRepository for PostgresTransactionRepository {
+    async fn create(&self, transaction: &Transaction) -> Result<Transaction, RepositoryError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO transactions (
+                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint
+            )
+            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7, $8::authorization_status, $9::transaction_status, $10, $11)
+            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            "#,
+        )
+        .bind(transaction.id)
+        .bind(transaction.transaction_type.as_str())
+        .bind(transaction.from_account_id)
+        .bind(transaction.to_account_id)
+        .bind(transaction.amount.amount())
+        .bind(&transaction.idempotency_key)
+        .bind(transaction.created_at)
+        .bind(transaction.authorization_status.map(|status| status.as_str()))
+        .bind(transaction.status.as_str())
+        .bind(&transaction.description)
+        .bind(&transaction.request_fingerprint)
+        .map(|row: sqlx::postgres::PgRow| transaction_from_row(&row))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if let Some(db_err) = e.as_database_error()
+                && db_err.is_unique_violation()
+            {
+                return RepositoryError::DuplicateEntry(format!(
+                    "Transaction with idempotency key {:?} already exists",
+                    transaction.idempotency_key
+                ));
+            }
+            RepositoryError::from(e)
+        })?;
+
+        row.map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Transaction, RepositoryError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            FROM transactions
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .map(|row: sqlx::postgres::PgRow| transaction_from_row(&row))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        match row {
+            Some(tx_result) => tx_result
+                .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e))),
+            None => Err(RepositoryError::NotFound(format!("Transaction {} not found", id))),
+        }
+    }
+
+    async fn find_by_idempotency_key(&self, key: &str) -> Result<Transaction, RepositoryError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            FROM transactions
+            WHERE idempotency_key = $1
+            -- Expired keys may have been reused since
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(key)
+        .map(|row: sqlx::postgres::PgRow| transaction_from_row(&row))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        match row {
+            Some(tx_result) => tx_result
+                .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e))),
+            None => Err(RepositoryError::NotFound(format!("Transaction with key {} not found", key))),
+        }
+    }
+
+    async fn find_recent_by_idempotency_key(
+        &self,
+        key: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Transaction, RepositoryError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            FROM transactions
+            WHERE idempotency_key = $1
+              AND NOT idempotency_key_expired
+              AND created_at >= $2
+            "#,
+        )
+        .bind(key)
+        .bind(since)
+        .map(|row: sqlx::postgres::PgRow| transaction_from_row(&row))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        match row {
+            Some(tx_result) => tx_result
+                .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e))),
+            None => Err(RepositoryError::NotFound(format!("Transaction with key {} not found", key))),
+        }
+    }
+
+    async fn idempotency_key_exists(&self, key: &str) -> Result<bool, RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            SELECT 1 as exists
+            FROM transactions
+            WHERE idempotency_key = $1
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        Ok(result.is_some())
+    }
+
+    async fn list_by_account(
+        &self,
+        account_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            FROM transactions
+            WHERE from_account_id = $1 OR to_account_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(account_id)
+        .bind(limit)
+        .bind(offset)
+        .map(|row: sqlx::postgres::PgRow| {
+            use sqlx::Row;
+            (row.get::<Uuid, _>("id"), transaction_from_row(&row))
+        })
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        self.collect_rows(rows)
+    }
+
+    async fn list_by_account_filtered(
+        &self,
+        account_id: Uuid,
+        filter: &TransactionFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError> {
+        self.list_by_accounts_filtered(&[account_id], filter, limit, offset).await
+    }
+
+    async fn list_by_accounts_filtered(
+        &self,
+        account_ids: &[Uuid],
+        filter: &TransactionFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            FROM "#,
+        );
+        query.push(transactions_source(filter));
+        push_account_filter(&mut query, account_ids, filter);
+
+        query.push(" ORDER BY created_at DESC LIMIT ");
+        query.push_bind(limit);
+        query.push(" OFFSET ");
+        query.push_bind(offset);
+
+        let rows = query
+            .build()
+            .map(|row: sqlx::postgres::PgRow| {
+                use sqlx::Row;
+                (row.get::<Uuid, _>("id"), transaction_from_row(&row))
+            })
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)?;
+
+        self.collect_rows(rows)
+    }
+
+    async fn count_by_account(
+        &self,
+        account_id: Uuid,
+        filter: &TransactionFilter,
+    ) -> Result<i64, RepositoryError> {
+        self.count_by_accounts(&[account_id], filter).await
+    }
+
+    async fn count_by_accounts(
+        &self,
+        account_ids: &[Uuid],
+        filter: &TransactionFilter,
+    ) -> Result<i64, RepositoryError> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM ");
+        query.push(transactions_source(filter));
+        push_account_filter(&mut query, account_ids, filter);
+
+        let count: i64 = query
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(RepositoryError::from)?;
+
+        Ok(count)
+    }
+
+    async fn account_stats(&self, account_id: Uuid, filter: &TransactionFilter) -> Result<AccountStats, RepositoryError> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT COALESCE(SUM(amount) FILTER (WHERE status = 'completed' AND transaction_type = 'credit' AND to_account_id = ",
+        );
+        query.push_bind(account_id);
+        query.push(
+            "), 0.00) AS total_credited, \
+             COALESCE(SUM(amount) FILTER (WHERE status = 'completed' \
+                 AND (transaction_type = 'debit' OR authorization_status = 'captured') AND from_account_id = ",
+        );
+        query.push_bind(account_id);
+        query.push(
+            "), 0.00) AS total_debited, \
+             COALESCE(SUM(amount) FILTER (WHERE status = 'completed' AND transaction_type = 'transfer' AND to_account_id = ",
+        );
+        query.push_bind(account_id);
+        query.push(
+            "), 0.00) AS transfer_in, \
+             COALESCE(SUM(amount) FILTER (WHERE status = 'completed' AND transaction_type = 'transfer' AND from_account_id = ",
+        );
+        query.push_bind(account_id);
+        query.push(
+            "), 0.00) AS transfer_out, \
+             COUNT(*) AS transaction_count, \
+             MIN(created_at) AS first_transaction_at, \
+             MAX(created_at) AS last_transaction_at \
+             FROM ",
+        );
+        query.push(transactions_source(filter));
+        push_account_filter(&mut query, &[account_id], filter);
+
+        let row = query
+            .build()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(RepositoryError::from)?;
+
+        use sqlx::Row;
+        Ok(AccountStats {
+            total_credited: to_minor_unit_scale(row.get("total_credited")),
+            total_debited: to_minor_unit_scale(row.get("total_debited")),
+            transfer_in: to_minor_unit_scale(row.get("transfer_in")),
+            transfer_out: to_minor_unit_scale(row.get("transfer_out")),
+            transaction_count: row.get("transaction_count"),
+            first_transaction_at: row.get("first_transaction_at"),
+            last_transaction_at: row.get("last_transaction_at"),
+        })
+    }
+
+    async fn list_by_type(
+        &self,
+        transaction_type: TransactionType,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            FROM transactions
+            WHERE transaction_type = $1::transaction_type
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(transaction_type.as_str())
+        .bind(limit)
+        .bind(offset)
+        .map(|row: sqlx::postgres::PgRow| {
+            use sqlx::Row;
+            (row.get::<Uuid, _>("id"), transaction_from_row(&row))
+        })
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        self.collect_rows(rows)
+    }
+
+    async fn list_by_status(
+        &self,
+        status: TransactionStatus,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            FROM transactions
+            WHERE status = $1::transaction_status
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status.as_str())
+        .bind(limit)
+        .bind(offset)
+        .map(|row: sqlx::postgres::PgRow| {
+            use sqlx::Row;
+            (row.get::<Uuid, _>("id"), transaction_from_row(&row))
+        })
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        self.collect_rows(rows)
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<Transaction>, RepositoryError> {
+         let rows = sqlx::query(
+            r#"
+            SELECT id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            FROM transactions
+            ORDER BY created_at DESC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .map(|row: sqlx::postgres::PgRow| {
+            use sqlx::Row;
+            (row.get::<Uuid, _>("id"), transaction_from_row(&row))
+        })
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        self.collect_rows(rows)
+    }
+
+    async fn execute_credit(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError> {
+        self.retrying(|| self.credit_once(transaction)).await
+    }
+
+    async fn execute_opening_credit(
+        &self,
+        account: &Account,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError> {
+        self.retrying(|| self.opening_credit_once(account, transaction)).await
+    }
+
+    async fn execute_debit(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError> {
+        self.retrying(|| self.debit_once(transaction)).await
+    }
+
+    async fn execute_transfer(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError> {
+        self.retrying(|| self.transfer_once(transaction)).await
+    }
+
+    async fn execute_verification(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError> {
+        injected_fault()?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::from)?;
+        let account_id = transaction.to_account_id.ok_or_else(|| {
+            RepositoryError::ConstraintViolation("Verification transaction must have to_account_id".to_string())
+        })?;
+
+        // 1. Check Status (shared lock keeps the account from closing underneath us)
+        let status: String = sqlx::query_scalar(
+            "SELECT status::text FROM accounts WHERE id = $1 AND deleted_at IS NULL FOR SHARE"
+        )
+        .bind(account_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?
+        .ok_or_else(|| RepositoryError::NotFound(format!("Account {} not found", account_id)))?;
+
+        ensure_active(account_id, &status)?;
+
+        // 2. Create Transaction Record; no balance change, so no snapshot
+        let row = sqlx::query(
+            r#"
+            INSERT INTO transactions (
+                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint
+            )
+            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7, $8::authorization_status, $9::transaction_status, $10, $11)
+            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            "#,
+        )
+        .bind(transaction.id)
+        .bind(transaction.transaction_type.as_str())
+        .bind(transaction.from_account_id)
+        .bind(transaction.to_account_id)
+        .bind(transaction.amount.amount())
+        .bind(&transaction.idempotency_key)
+        .bind(transaction.created_at)
+        .bind(transaction.authorization_status.map(|status| status.as_str()))
+        .bind(TransactionStatus::Completed.as_str())
+        .bind(&transaction.description)
+        .bind(&transaction.request_fingerprint)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if let Some(db_err) = e.as_database_error()
+                && db_err.is_unique_violation()
+            {
+                return RepositoryError::DuplicateEntry(format!(
+                    "Transaction with idempotency key {:?} already exists",
+                    transaction.idempotency_key
+                ));
+            }
+            RepositoryError::from(e)
+        })?;
+
+        tx.commit().await.map_err(RepositoryError::from)?;
+
+        transaction_from_row(&row)
+            .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))
+    }
+
+    async fn execute_authorization(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError> {
+        injected_fault()?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::from)?;
+        let from_account_id = transaction.from_account_id.ok_or_else(|| {
+            RepositoryError::ConstraintViolation("Authorization must have from_account_id".to_string())
+        })?;
+
+        // 1. Check Status and Available Funds, Lock Row (SELECT FOR UPDATE)
+        let account_row = sqlx::query(
+            "SELECT balance - held_balance AS available, status::text as status FROM accounts WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"
+        )
+        .bind(from_account_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?
+        .ok_or_else(|| RepositoryError::NotFound(format!("Account {} not found", from_account_id)))?;
+
+        let available: Decimal = {
+            use sqlx::Row;
+            ensure_active(from_account_id, account_row.get("status"))?;
+            account_row.get("available")
+        };
+
+        if available < transaction.amount.amount() {
+            return Err(RepositoryError::ConstraintViolation(format!(
+                "Insufficient funds for account {}", from_account_id
+            )));
+        }
+
+        // 2. Hold the funds; the balance changes only on capture
+        sqlx::query(
+            r#"
+            UPDATE accounts
+            SET held_balance = held_balance + $1, updated_at = NOW()
+            WHERE id = $2
+            "#,
+        )
+        .bind(transaction.amount.amount())
+        .bind(from_account_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        // 3. Create Transaction Record
+        let row = sqlx::query(
+            r#"
+            INSERT INTO transactions (
+                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint
+            )
+            VALUES ($1, $2::transaction_type, $3, $4, $5, $6, $7, $8::authorization_status, $9::transaction_status, $10, $11)
+            RETURNING id, transaction_type::text as transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status::text as authorization_status, status::text as status, description, request_fingerprint
+            "#,
+        )
+        .bind(transaction.id)
+        .bind(transaction.transaction_type.as_str())
+        .bind(transaction.from_account_id)
+        .bind(transaction.to_account_id)
+        .bind(transaction.amount.amount())
+        .bind(&transaction.idempotency_key)
+        .bind(transaction.created_at)
+        .bind(transaction.authorization_status.map(|status| status.as_str()))
+        .bind(TransactionStatus::Completed.as_str())
+        .bind(&transaction.description)
+        .bind(&transaction.request_fingerprint)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if let Some(db_err) = e.as_database_error()
+                && db_err.is_unique_violation()
+            {
+                return RepositoryError::DuplicateEntry(format!(
+                    "Transaction with idempotency key {:?} already exists",
+                    transaction.idempotency_key
+                ));
+            }
+            RepositoryError::from(e)
+        })?;
+
+        tx.commit().await.map_err(RepositoryError::from)?;
+
+        transaction_from_row(&row)
+            .map_err(|e| RepositoryError::DatabaseError(format!("Data integrity error: {}", e)))
+    }
+
+    async fn execute_capture(&self, authorization_id: Uuid) -> Result<Transaction, RepositoryError> {
+        self.settle_authorization(authorization_id, AuthorizationStatus::Captured).await
+    }
+
+    async fn execute_void(&self, authorization_id: Uuid) -> Result<Transaction, RepositoryError> {
+        self.settle_authorization(authorization_id, AuthorizationStatus::Voided).await
+    }
+
+    async fn sum_debits_since(
+        &self,
+        account_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Decimal, RepositoryError> {
+        // Debits, transfers and authorizations all record the paying account as `from_account_id`.
+        // A voided authorization or a failed transaction never moved money, so it does not count.
+        let total: Decimal = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(amount), 0)
+            FROM transactions
+            WHERE from_account_id = $1
+              AND (
+                transaction_type IN ('debit', 'transfer')
+                OR (transaction_type = 'authorization' AND authorization_status != 'voided')
+              )
+              AND status != 'failed'
+              AND created_at >= $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        Ok(total)
+    }
+
+    async fn archive_before(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<u64, RepositoryError> {
+        // Move and delete in one statement so a row is never in both tables or neither
+        let result = sqlx::query(
+            r#"
+            WITH moved AS (
+                DELETE FROM transactions
+                WHERE id IN (
+                    SELECT id
+                    FROM transactions
+                    WHERE created_at < $1
+                      -- An open hold must stay capturable
+                      AND authorization_status IS DISTINCT FROM 'pending'
+                      -- Unsettled transactions are still in flight
+                      AND status != 'pending'
+                    ORDER BY created_at
+                    LIMIT $2
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint
+            )
+            INSERT INTO archived_transactions (
+                id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint
+            )
+            SELECT id, transaction_type, from_account_id, to_account_id, amount, idempotency_key, created_at, authorization_status, status, description, request_fingerprint
+            FROM moved
+            "#,
+        )
+        .bind(cutoff)
+        .bind(limit)
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn expire_idempotency_keys(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<u64, RepositoryError> {
+        // Flagged keys drop out of the partial unique index, so they can be used again
+        let result = sqlx::query(
+            r#"
+            UPDATE transactions
+            SET idempotency_key_expired = TRUE
+            WHERE id IN (
+                SELECT id
+                FROM transactions
+                WHERE idempotency_key IS NOT NULL
+                  AND NOT idempotency_key_expired
+                  AND created_at < $1
+                ORDER BY created_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            "#,
+        )
+        .bind(cutoff)
+        .bind(limit)
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn simulate_transfer(&self, transfer: &Transaction) -> Result<BatchPreview, RepositoryError> {
+        self.preview_batch(std::slice::from_ref(transfer)).await
+    }
+
+    async fn preview_batch(&self, operations: &[Transaction]) -> Result<BatchPreview, RepositoryError> {
+        let mut account_ids: Vec<Uuid> = operations
+            .iter()
+            .flat_map(|operation| [operation.from_account_id, operation.to_account_id])
+            .flatten()
+            .collect();
+        account_ids.sort();
+        account_ids.dedup();
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::from)?;
+
+        // Lock every affected account up front, in id order like execute_transfer
+        let rows = sqlx::query(
+            r#"
+            SELECT id, balance, held_balance, status::text as status
+            FROM accounts
+            WHERE id = ANY($1) AND deleted_at IS NULL
+            ORDER BY id
+            FOR UPDATE
+            "#,
+        )
+        .bind(&account_ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        let mut accounts: BTreeMap<Uuid, (Decimal, String)> = BTreeMap::new();
+        let mut held: BTreeMap<Uuid, Decimal> = BTreeMap::new();
+        for row in &rows {
+            use sqlx::Row;
+            accounts.insert(row.get("id"), (row.get("balance"), row.get("status")));
+            held.insert(row.get("id"), row.get("held_balance"));
+        }
+        let current: BTreeMap<Uuid, Decimal> = accounts
+            .iter()
+            .map(|(id, (balance, _))| (*id, *balance))
+            .collect();
+
+        let mut failure = None;
+        for (index, operation) in operations.iter().enumerate() {
+            match self.apply_preview_operation(&mut tx, &mut accounts, &held, operation).await {
+                Ok(()) => {}
+                Err(
+                    e @ (RepositoryError::NotFound(_)
+                    | RepositoryError::InvalidState(_)
+                    | RepositoryError::ConstraintViolation(_)),
+                ) => {
+                    failure = Some(BatchFailure { index, reason: e.to_string() });
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        tx.rollback().await.map_err(RepositoryError::from)?;
+
+        let balances = accounts
+            .into_iter()
+            .map(|(account_id, (projected_balance, _))| ProjectedBalance {
+                account_id,
+                current_balance: current[&account_id],
+                projected_balance,
+            })
+            .collect();
+
+        Ok(BatchPreview { balances, failure })
+    }
+
+    async fn find_balance_drift(
+        &self,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<ReconciliationPage, RepositoryError> {
+        // One statement, so balances and transactions come from the same snapshot.
+        // FOR SHARE keeps the page's balances from moving while they are compared;
+        // accounts already locked by a money movement are left for the next run.
+        let query = format!(
+            r#"
+            WITH checked AS (
+                SELECT id, balance, held_balance
+                FROM accounts
+                WHERE $1::uuid IS NULL OR id > $1
+                ORDER BY id
+                LIMIT $2
+                FOR SHARE SKIP LOCKED
+            ),
+            ledger AS (
+                SELECT transaction_type, from_account_id, to_account_id, amount, authorization_status
+                FROM {}
+                WHERE status = 'completed'
+                  AND (from_account_id IN (SELECT id FROM checked) OR to_account_id IN (SELECT id FROM checked))
+            )
+            SELECT
+                c.id,
+                c.balance,
+                c.held_balance,
+                COALESCE((
+                    SELECT SUM(amount) FROM ledger
+                    WHERE to_account_id = c.id AND transaction_type IN ('credit', 'transfer')
+                ), 0) - COALESCE((
+                    SELECT SUM(amount) FROM ledger
+                    WHERE from_account_id = c.id
+                      AND (transaction_type IN ('debit', 'transfer') OR authorization_status = 'captured')
+                ), 0) AS ledger_balance,
+                COALESCE((
+                    SELECT SUM(amount) FROM ledger
+                    WHERE from_account_id = c.id AND authorization_status = 'pending'
+                ), 0) AS ledger_held_balance
+            FROM checked c
+            ORDER BY c.id
+            "#,
+            TRANSACTIONS_WITH_ARCHIVE
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(after)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)?;
+
+        use sqlx::Row;
+        let mut page = ReconciliationPage {
+            checked: rows.len(),
+            last_account_id: rows.last().map(|row| row.get("id")),
+            drifts: Vec::new(),
+        };
+        for row in &rows {
+            let drift = BalanceDrift {
+                account_id: row.get("id"),
+                stored_balance: row.get("balance"),
+                ledger_balance: row.get("ledger_balance"),
+                stored_held_balance: row.get("held_balance"),
+                ledger_held_balance: row.get("ledger_held_balance"),
+            };
+            if drift.stored_balance != drift.ledger_balance
+                || drift.stored_held_balance != drift.ledger_held_balance
+            {
+                page.drifts.push(drift);
+            }
+        }
+
+        Ok(page)
+    }
 }
@@ -1,11 +1,11 @@
 use async_trait::async_trait;
-use sqlx::PgPool;
+use sqlx::{PgPool, Row};
 use uuid::Uuid;
 
-use crate::domain::entities::Webhook;
+use crate::domain::entities::{Webhook, WebhookDeadLetter, WebhookOutboxEntry};
 use crate::domain::errors::RepositoryError;
 use crate::domain::repositories::WebhookRepository;
-use crate::domain::value_objects::WebhookEvent;
+use crate::domain::value_objects::{WebhookEvent, WebhookStatus};
 
 pub struct PostgresWebhookRepository {
     pool: PgPool,
@@ -17,61 +17,241 @@ impl PostgresWebhookRepository {
     }
 }
 
+const WEBHOOK_COLUMNS: &str = "id, account_id, url, event, created_at, secret, previous_secret, \
+    previous_secret_expires_at, timeout_ms, accepts_gzip, status, verification_token";
+
+/// Unique violations of `uq_webhooks_live_account_url_event` as `DuplicateEntry`
+fn map_subscription_conflict(e: sqlx::Error) -> RepositoryError {
+    if let Some(db_err) = e.as_database_error()
+        && db_err.is_unique_violation()
+    {
+        return RepositoryError::DuplicateEntry(
+            "A webhook for this URL and event already exists".to_string(),
+        );
+    }
+    RepositoryError::DatabaseError(e.to_string())
+}
+
+fn webhook_from_row(row: &sqlx::postgres::PgRow) -> Result<Webhook, RepositoryError> {
+    let event_str: String = row.get("event");
+    let event = serde_json::from_str::<WebhookEvent>(&format!("\"{}\"", event_str))
+        .map_err(|e| RepositoryError::DatabaseError(format!("Invalid event type: {}", e)))?;
+    let status = row
+        .get::<String, _>("status")
+        .parse::<WebhookStatus>()
+        .map_err(RepositoryError::DatabaseError)?;
+
+    Ok(Webhook {
+        id: row.get("id"),
+        account_id: row.get("account_id"),
+        url: row.get("url"),
+        event,
+        created_at: row.get("created_at"),
+        secret: row.get("secret"),
+        previous_secret: row.get("previous_secret"),
+        previous_secret_expires_at: row.get("previous_secret_expires_at"),
+        timeout_ms: row.get::<Option<i32>, _>("timeout_ms").map(|ms| ms as u32),
+        accepts_gzip: row.get("accepts_gzip"),
+        status,
+        verification_token: row.get("verification_token"),
+    })
+}
+
 #[async_trait]
 impl WebhookRepository for PostgresWebhookRepository {
     async fn create(&self, webhook: Webhook) -> Result<Webhook, RepositoryError> {
-        let event_str = webhook.event.to_string();
-
-        sqlx::query!(
+        sqlx::query(
             r#"
-            INSERT INTO webhooks (id, account_id, url, event, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $5)
+            INSERT INTO webhooks (
+                id, account_id, url, event, secret, timeout_ms, accepts_gzip, status, verification_token,
+                created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)
             "#,
-            webhook.id,
-            webhook.account_id,
-            webhook.url,
-            event_str,
-            webhook.created_at
         )
+        .bind(webhook.id)
+        .bind(webhook.account_id)
+        .bind(&webhook.url)
+        .bind(webhook.event.to_string())
+        .bind(&webhook.secret)
+        .bind(webhook.timeout_ms.map(|ms| ms as i32))
+        .bind(webhook.accepts_gzip)
+        .bind(webhook.status.as_str())
+        .bind(&webhook.verification_token)
+        .bind(webhook.created_at)
         .execute(&self.pool)
         .await
-        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        .map_err(map_subscription_conflict)?;
 
         Ok(webhook)
     }
 
-    async fn list_by_account(&self, account_id: Uuid) -> Result<Vec<Webhook>, RepositoryError> {
-        let records = sqlx::query!(
+    async fn create_or_get(&self, webhook: Webhook) -> Result<(Webhook, bool), RepositoryError> {
+        let inserted = sqlx::query(
             r#"
-            SELECT id, account_id, url, event, created_at 
-            FROM webhooks 
-            WHERE account_id = $1
-            ORDER BY created_at DESC
+            INSERT INTO webhooks (
+                id, account_id, url, event, secret, timeout_ms, accepts_gzip, status, verification_token,
+                created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)
+            ON CONFLICT (account_id, url, event) WHERE deleted_at IS NULL DO NOTHING
             "#,
-            account_id
         )
+        .bind(webhook.id)
+        .bind(webhook.account_id)
+        .bind(&webhook.url)
+        .bind(webhook.event.to_string())
+        .bind(&webhook.secret)
+        .bind(webhook.timeout_ms.map(|ms| ms as i32))
+        .bind(webhook.accepts_gzip)
+        .bind(webhook.status.as_str())
+        .bind(&webhook.verification_token)
+        .bind(webhook.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+        .rows_affected()
+            == 1;
+        if inserted {
+            return Ok((webhook, true));
+        }
+
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM webhooks WHERE account_id = $1 AND url = $2 AND event = $3 AND deleted_at IS NULL",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(webhook.account_id)
+        .bind(&webhook.url)
+        .bind(webhook.event.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+        // Deleted between the insert and the lookup
+        .ok_or_else(|| RepositoryError::NotFound("Webhook not found".to_string()))?;
+
+        Ok((webhook_from_row(&row)?, false))
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Webhook, RepositoryError> {
+        let row = sqlx::query(&format!("SELECT {} FROM webhooks WHERE id = $1 AND deleted_at IS NULL", WEBHOOK_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound("Webhook not found".to_string()))?;
+
+        webhook_from_row(&row)
+    }
+
+    async fn find_by_id_including_deleted(&self, id: Uuid) -> Result<Webhook, RepositoryError> {
+        let row = sqlx::query(&format!("SELECT {} FROM webhooks WHERE id = $1", WEBHOOK_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound("Webhook not found".to_string()))?;
+
+        webhook_from_row(&row)
+    }
+
+    async fn list_by_account(&self, account_id: Uuid) -> Result<Vec<Webhook>, RepositoryError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM webhooks WHERE account_id = $1 AND deleted_at IS NULL ORDER BY created_at DESC",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(account_id)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        let mut webhooks = Vec::new();
-        for r in records {
-            let event = serde_json::from_str::<WebhookEvent>(&format!("\"{}\"", r.event))
-                .map_err(|e| RepositoryError::DatabaseError(format!("Invalid event type: {}", e)))?;
+        rows.iter().map(webhook_from_row).collect()
+    }
 
-            webhooks.push(Webhook {
-                id: r.id,
-                account_id: r.account_id,
-                url: r.url,
-                event,
-                created_at: r.created_at,
-            });
+    async fn update(&self, webhook: &Webhook) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhooks
+            SET url = $2, event = $3, status = $4, verification_token = $5, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(webhook.id)
+        .bind(&webhook.url)
+        .bind(webhook.event.to_string())
+        .bind(webhook.status.as_str())
+        .bind(&webhook.verification_token)
+        .execute(&self.pool)
+        .await
+        .map_err(map_subscription_conflict)?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound("Webhook not found".to_string()));
         }
 
-        Ok(webhooks)
+        Ok(())
+    }
+
+    async fn update_secret(&self, webhook: &Webhook) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhooks
+            SET secret = $2, previous_secret = $3, previous_secret_expires_at = $4, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(webhook.id)
+        .bind(&webhook.secret)
+        .bind(&webhook.previous_secret)
+        .bind(webhook.previous_secret_expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound("Webhook not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn next_sequence(&self, id: Uuid) -> Result<i64, RepositoryError> {
+        // The row lock taken by the UPDATE serializes concurrent deliveries
+        let row = sqlx::query(
+            r#"
+            UPDATE webhooks SET last_sequence = last_sequence + 1
+            WHERE id = $1
+            RETURNING last_sequence
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+        .ok_or_else(|| RepositoryError::NotFound("Webhook not found".to_string()))?;
+
+        Ok(row.get("last_sequence"))
     }
 
     async fn delete(&self, id: Uuid) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhooks SET deleted_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound("Webhook not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn purge(&self, id: Uuid) -> Result<(), RepositoryError> {
         let result = sqlx::query!(
             r#"
             DELETE FROM webhooks WHERE id = $1
@@ -88,4 +268,116 @@ impl WebhookRepository for PostgresWebhookRepository {
 
         Ok(())
     }
+
+    async fn dead_letter(&self, letter: WebhookDeadLetter) -> Result<WebhookDeadLetter, RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_dead_letters (id, webhook_id, event, payload, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(letter.id)
+        .bind(letter.webhook_id)
+        .bind(letter.event.to_string())
+        .bind(&letter.payload)
+        .bind(&letter.reason)
+        .bind(letter.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(letter)
+    }
+
+    async fn list_dead_letters(&self, webhook_id: Uuid) -> Result<Vec<WebhookDeadLetter>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, webhook_id, event, payload, reason, created_at
+            FROM webhook_dead_letters
+            WHERE webhook_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(webhook_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let mut letters = Vec::new();
+        for row in rows {
+            let event_str: String = row.get("event");
+            let event = serde_json::from_str::<WebhookEvent>(&format!("\"{}\"", event_str))
+                .map_err(|e| RepositoryError::DatabaseError(format!("Invalid event type: {}", e)))?;
+
+            letters.push(WebhookDeadLetter {
+                id: row.get("id"),
+                webhook_id: row.get("webhook_id"),
+                event,
+                payload: row.get("payload"),
+                reason: row.get("reason"),
+                created_at: row.get("created_at"),
+            });
+        }
+
+        Ok(letters)
+    }
+
+    async fn enqueue_outbox(&self, entry: WebhookOutboxEntry) -> Result<WebhookOutboxEntry, RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_outbox (id, webhook_id, event, payload, request_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(entry.id)
+        .bind(entry.webhook_id)
+        .bind(entry.event.to_string())
+        .bind(&entry.payload)
+        .bind(&entry.request_id)
+        .bind(entry.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(entry)
+    }
+
+    async fn claim_outbox(&self, limit: i64) -> Result<Vec<WebhookOutboxEntry>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            DELETE FROM webhook_outbox
+            WHERE id IN (
+                SELECT id FROM webhook_outbox
+                ORDER BY created_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, webhook_id, event, payload, request_id, created_at
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let event_str: String = row.get("event");
+            let event = serde_json::from_str::<WebhookEvent>(&format!("\"{}\"", event_str))
+                .map_err(|e| RepositoryError::DatabaseError(format!("Invalid event type: {}", e)))?;
+
+            entries.push(WebhookOutboxEntry {
+                id: row.get("id"),
+                webhook_id: row.get("webhook_id"),
+                event,
+                payload: row.get("payload"),
+                request_id: row.get("request_id"),
+                created_at: row.get("created_at"),
+            });
+        }
+        // RETURNING does not preserve the subquery's order
+        entries.sort_by_key(|entry| entry.created_at);
+
+        Ok(entries)
+    }
 }
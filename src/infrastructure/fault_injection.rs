@@ -0,0 +1,86 @@
+//! Failure injection hooks for resilience tests.
+//!
+//! Compiled in only with the `test-fault-injection` feature; without it every
+//! hook is a constant `false` and optimizes away. Faults are armed per thread
+//! (tokio's default test runtime runs spawned tasks on the test thread, so
+//! parallel tests don't interfere) or process-wide through `DODO_FAULTS`,
+//! a comma-separated list such as `db_timeout,webhook_500`.
+
+#[cfg(all(feature = "test-fault-injection", not(debug_assertions)))]
+compile_error!("the `test-fault-injection` feature must not be enabled in release builds");
+
+/// A failure that can be forced at a hook point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// Acquiring a database connection times out
+    DbTimeout,
+    /// A webhook endpoint answers 500
+    WebhookServerError,
+    /// Postgres aborts the transaction with a serialization failure (SQLSTATE 40001)
+    SerializationConflict,
+}
+
+impl Fault {
+    /// Name used in `DODO_FAULTS`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DbTimeout => "db_timeout",
+            Self::WebhookServerError => "webhook_500",
+            Self::SerializationConflict => "serialization_conflict",
+        }
+    }
+}
+
+#[cfg(feature = "test-fault-injection")]
+mod armed {
+    use super::Fault;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    thread_local! {
+        static FAULTS: RefCell<HashMap<Fault, u32>> = RefCell::new(HashMap::new());
+    }
+
+    /// Fail the next `times` hits of `fault` on this thread.
+    pub fn inject(fault: Fault, times: u32) {
+        FAULTS.with(|faults| {
+            faults.borrow_mut().insert(fault, times);
+        });
+    }
+
+    /// Disarm every fault on this thread.
+    pub fn clear() {
+        FAULTS.with(|faults| faults.borrow_mut().clear());
+    }
+
+    pub(crate) fn should_fail(fault: Fault) -> bool {
+        let armed = FAULTS.with(|faults| {
+            let mut faults = faults.borrow_mut();
+            match faults.get_mut(&fault) {
+                Some(remaining) if *remaining > 0 => {
+                    *remaining -= 1;
+                    true
+                }
+                _ => false,
+            }
+        });
+
+        armed
+            || std::env::var("DODO_FAULTS")
+                .map(|faults| faults.split(',').any(|name| name.trim() == fault.as_str()))
+                .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "test-fault-injection")]
+pub use armed::{clear, inject};
+
+#[cfg(feature = "test-fault-injection")]
+pub(crate) use armed::should_fail;
+
+/// Whether the hook for `fault` should fail. Always `false` without the feature.
+#[cfg(not(feature = "test-fault-injection"))]
+#[inline(always)]
+pub(crate) fn should_fail(_fault: Fault) -> bool {
+    false
+}
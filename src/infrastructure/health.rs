@@ -0,0 +1,197 @@
+//! Process-wide liveness signals for the aggregated `GET /health/full` report.
+//!
+//! Background workers register their tick interval and record a heartbeat on
+//! every tick; the per-IP rate limiter reports how full its table is. The
+//! remaining subsystems are probed when the report is requested, and each
+//! reading is graded by one of the `*_status` functions below.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Ticks a worker may miss before it counts as stalled
+const STALLED_AFTER_TICKS: u32 = 3;
+
+/// Share of the rate limiter's IP table in use at which it counts as under pressure
+const RATE_LIMITER_PRESSURE: f64 = 0.9;
+
+/// Final webhook outcomes needed before the success rate is judged at all
+const MIN_WEBHOOK_SAMPLE: f64 = 10.0;
+
+/// Webhook success rate below which deliveries count as degraded
+const MIN_WEBHOOK_SUCCESS_RATE: f64 = 0.9;
+
+/// Grade of one subsystem, or of the whole service. Ordered from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    /// Serving, but something needs an operator's attention
+    Degraded,
+    /// Unable to serve requests
+    Unhealthy,
+}
+
+impl HealthStatus {
+    /// The worst of `statuses`; healthy when there are none
+    pub fn worst(statuses: impl IntoIterator<Item = HealthStatus>) -> HealthStatus {
+        statuses.into_iter().max().unwrap_or(HealthStatus::Healthy)
+    }
+}
+
+struct Worker {
+    interval: Duration,
+    last_heartbeat: Instant,
+}
+
+static WORKERS: LazyLock<Mutex<BTreeMap<&'static str, Worker>>> = LazyLock::new(Default::default);
+
+static RATE_LIMITER_TRACKED: AtomicUsize = AtomicUsize::new(0);
+static RATE_LIMITER_CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+/// Start tracking a background worker that ticks every `interval`
+pub fn register_worker(name: &'static str, interval: Duration) {
+    WORKERS.lock().unwrap().insert(
+        name,
+        Worker {
+            interval,
+            last_heartbeat: Instant::now(),
+        },
+    );
+}
+
+/// Record that the worker `name` finished a tick
+pub fn record_heartbeat(name: &'static str) {
+    if let Some(worker) = WORKERS.lock().unwrap().get_mut(name) {
+        worker.last_heartbeat = Instant::now();
+    }
+}
+
+/// Liveness of one registered background worker
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerLiveness {
+    pub name: &'static str,
+    pub status: HealthStatus,
+    pub seconds_since_heartbeat: f64,
+    pub interval_seconds: f64,
+}
+
+/// Every registered worker, by name
+pub fn worker_liveness() -> Vec<WorkerLiveness> {
+    WORKERS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, worker)| {
+            let since = worker.last_heartbeat.elapsed();
+            WorkerLiveness {
+                name,
+                status: worker_status(since, worker.interval),
+                seconds_since_heartbeat: since.as_secs_f64(),
+                interval_seconds: worker.interval.as_secs_f64(),
+            }
+        })
+        .collect()
+}
+
+/// A worker that has missed several ticks is stalled; requests are still served without it
+pub fn worker_status(since_heartbeat: Duration, interval: Duration) -> HealthStatus {
+    if since_heartbeat > interval * STALLED_AFTER_TICKS {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    }
+}
+
+/// Record how many IPs the rate limiter tracks out of its `capacity`
+pub fn record_rate_limiter_usage(tracked: usize, capacity: usize) {
+    RATE_LIMITER_TRACKED.store(tracked, Ordering::Relaxed);
+    RATE_LIMITER_CAPACITY.store(capacity, Ordering::Relaxed);
+}
+
+/// Tracked IPs and capacity last reported by the rate limiter, if it has seen a request
+pub fn rate_limiter_usage() -> Option<(usize, usize)> {
+    match RATE_LIMITER_CAPACITY.load(Ordering::Relaxed) {
+        0 => None,
+        capacity => Some((RATE_LIMITER_TRACKED.load(Ordering::Relaxed), capacity)),
+    }
+}
+
+/// A nearly full IP table means old clients are being evicted and regain a fresh quota
+pub fn rate_limiter_status(tracked: usize, capacity: usize) -> HealthStatus {
+    if capacity > 0 && tracked as f64 >= capacity as f64 * RATE_LIMITER_PRESSURE {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    }
+}
+
+/// Every connection checked out means new queries queue for one
+pub fn pool_status(in_use: u32, max: u32) -> HealthStatus {
+    if in_use >= max {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    }
+}
+
+/// Share of finished webhook deliveries that succeeded, once there are enough to judge
+pub fn webhook_success_rate(delivered: f64, failed: f64) -> Option<f64> {
+    let total = delivered + failed;
+    (total >= MIN_WEBHOOK_SAMPLE).then(|| delivered / total)
+}
+
+pub fn webhook_status(delivered: f64, failed: f64) -> HealthStatus {
+    match webhook_success_rate(delivered, failed) {
+        Some(rate) if rate < MIN_WEBHOOK_SUCCESS_RATE => HealthStatus::Degraded,
+        _ => HealthStatus::Healthy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worst_status_wins() {
+        use HealthStatus::*;
+        assert_eq!(HealthStatus::worst([]), Healthy);
+        assert_eq!(HealthStatus::worst([Healthy, Healthy]), Healthy);
+        assert_eq!(HealthStatus::worst([Healthy, Degraded, Healthy]), Degraded);
+        assert_eq!(HealthStatus::worst([Degraded, Unhealthy, Healthy]), Unhealthy);
+    }
+
+    #[test]
+    fn test_subsystem_thresholds() {
+        let tick = Duration::from_secs(10);
+        assert_eq!(worker_status(Duration::from_secs(25), tick), HealthStatus::Healthy);
+        assert_eq!(worker_status(Duration::from_secs(31), tick), HealthStatus::Degraded);
+
+        assert_eq!(rate_limiter_status(50, 100), HealthStatus::Healthy);
+        assert_eq!(rate_limiter_status(95, 100), HealthStatus::Degraded);
+
+        assert_eq!(pool_status(9, 10), HealthStatus::Healthy);
+        assert_eq!(pool_status(10, 10), HealthStatus::Degraded);
+
+        // Too few deliveries to judge
+        assert_eq!(webhook_status(0.0, 5.0), HealthStatus::Healthy);
+        assert_eq!(webhook_status(95.0, 5.0), HealthStatus::Healthy);
+        assert_eq!(webhook_status(50.0, 50.0), HealthStatus::Degraded);
+    }
+
+    #[test]
+    fn test_registered_workers_report_liveness() {
+        register_worker("test_worker", Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(10));
+        let stalled = worker_liveness().into_iter().find(|w| w.name == "test_worker").unwrap();
+        assert_eq!(stalled.status, HealthStatus::Degraded);
+
+        register_worker("test_worker", Duration::from_secs(60));
+        record_heartbeat("test_worker");
+        let alive = worker_liveness().into_iter().find(|w| w.name == "test_worker").unwrap();
+        assert_eq!(alive.status, HealthStatus::Healthy);
+        assert_eq!(alive.interval_seconds, 60.0);
+    }
+}
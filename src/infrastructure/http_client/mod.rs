@@ -1,3 +1,10 @@
 pub mod reqwest_webhook_dispatcher;
+pub mod signing;
+pub mod url_policy;
 
-pub use reqwest_webhook_dispatcher::ReqwestWebhookDispatcher;
\ No newline at end of file
+pub use reqwest_webhook_dispatcher::{DeliveryAttempt, ReqwestWebhookDispatcher};
+pub use signing::{
+    hmac_sha256_hex, request_signature, verify_request_signature, verify_signature, webhook_signature,
+    SignatureError,
+};
+pub use url_policy::WebhookUrlPolicy;
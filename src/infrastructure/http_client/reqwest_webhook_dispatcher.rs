@@ -1,81 +1,312 @@
 use async_trait::async_trait;
-use hmac::Mac;
-use reqwest::Client;
-use std::time::Duration;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use reqwest::{Client, RequestBuilder};
+use std::io::Write;
+use std::time::{Duration, Instant};
 
-use crate::domain::services::WebhookDispatcher;
+use crate::domain::services::{DeliveryContext, WebhookDispatcher};
+use crate::infrastructure::fault_injection::{self, Fault};
+use crate::infrastructure::http_client::signing::webhook_signature;
+use crate::infrastructure::metrics;
 
 
 use rand::Rng; // For jitter
-use tracing::{error, warn};
+
+/// `event` of the challenge sent to endpoints awaiting verification
+pub const VERIFICATION_EVENT: &str = "webhook.verification";
+
+/// Timeout for webhooks without their own override
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time allowed to establish a connection, within the total timeout
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Ceiling on the delay between retries
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Time one dispatch may spend retrying before giving up
+const DEFAULT_MAX_ELAPSED: Duration = Duration::from_secs(300);
+/// Upper bound on per-webhook timeout overrides
+const DEFAULT_MAX_TIMEOUT: Duration = Duration::from_secs(60);
+/// Smallest body gzipped for webhooks that accept it; smaller ones gain little
+const DEFAULT_GZIP_MIN_BYTES: usize = 1024;
+
+/// Tracing target of delivery outcome events, for filtering in the log pipeline
+pub const DELIVERY_LOG_TARGET: &str = "webhook_delivery";
+
+/// Emit one structured delivery outcome event at `$level`
+macro_rules! delivery_event {
+    ($level:expr, $context:expr, $url:expr, $attempt:expr, $status:expr, $latency_ms:expr, $outcome:expr, $error:expr) => {
+        tracing::event!(
+            target: DELIVERY_LOG_TARGET,
+            $level,
+            webhook_id = %$context.webhook_id,
+            url = $url,
+            event = $context.event.as_str(),
+            attempt = $attempt,
+            status = $status,
+            latency_ms = $latency_ms,
+            outcome = $outcome,
+            error = $error,
+            "Webhook delivery attempt {}",
+            $outcome
+        )
+    };
+}
+
+/// What the receiver did with a single, unretried delivery
+#[derive(Debug, Clone)]
+pub struct DeliveryAttempt {
+    /// HTTP status returned by the receiver; `None` when no response arrived
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    /// Transport error (connection refused, timeout, ...) when no response arrived
+    pub error: Option<String>,
+}
+
+impl DeliveryAttempt {
+    pub fn is_success(&self) -> bool {
+        matches!(self.status, Some(status) if (200..300).contains(&status))
+    }
+}
 
 #[derive(Clone)]
 pub struct ReqwestWebhookDispatcher {
     client: Client,
     max_retries: u32,
     initial_backoff_ms: u64,
+    max_backoff: Duration,
+    max_elapsed: Duration,
+    log_successes: bool,
+    timeout: Duration,
+    max_timeout: Duration,
+    gzip_min_bytes: usize,
 }
 
 impl ReqwestWebhookDispatcher {
     pub fn new(max_retries: u32, initial_backoff_ms: u64) -> Self {
+        // The total timeout is applied per request, see `timeout_for`
         let client = Client::builder()
-            .timeout(Duration::from_secs(10))
+            .connect_timeout(DEFAULT_CONNECT_TIMEOUT)
             .build()
             .unwrap_or_default();
-        Self { client, max_retries, initial_backoff_ms }
+        Self {
+            client,
+            max_retries,
+            initial_backoff_ms,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            max_elapsed: DEFAULT_MAX_ELAPSED,
+            log_successes: true,
+            timeout: DEFAULT_TIMEOUT,
+            max_timeout: DEFAULT_MAX_TIMEOUT,
+            gzip_min_bytes: DEFAULT_GZIP_MIN_BYTES,
+        }
+    }
+
+    /// Set the global delivery timeout and the cap on per-webhook overrides.
+    pub fn with_timeouts(mut self, timeout: Duration, max_timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.max_timeout = max_timeout;
+        self
+    }
+
+    /// Cap the delay between retries at `max_backoff`, and give up on a dispatch whose
+    /// next retry would start after `max_elapsed`, even if retries remain.
+    pub fn with_retry_limits(mut self, max_backoff: Duration, max_elapsed: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self.max_elapsed = max_elapsed;
+        self
     }
+
+    /// Delay before retry number `retry` (starting at 1), before jitter: the initial
+    /// backoff doubled for each earlier retry, capped at the maximum backoff.
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let factor = 2u64.checked_pow(retry.saturating_sub(1)).unwrap_or(u64::MAX);
+        Duration::from_millis(self.initial_backoff_ms.saturating_mul(factor)).min(self.max_backoff)
+    }
+
+    /// Give up connecting after `timeout`, so unreachable hosts fail fast instead of
+    /// using up the whole delivery timeout. Counts towards the total timeout.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.client = Client::builder().connect_timeout(timeout).build().unwrap_or_default();
+        self
+    }
+
+    /// Gzip bodies of at least `min_bytes` for webhooks flagged `accepts_gzip`.
+    pub fn with_gzip_min_bytes(mut self, min_bytes: usize) -> Self {
+        self.gzip_min_bytes = min_bytes;
+        self
+    }
+
+    /// Toggle logging successful deliveries (enabled by default).
+    /// Failed and retried attempts are always logged.
+    pub fn with_success_logging(mut self, enabled: bool) -> Self {
+        self.log_successes = enabled;
+        self
+    }
+
+    /// Deliver `payload` exactly once, without retries, logging or delivery metrics.
+    /// Used for integrator-triggered test pings, which report the raw outcome back.
+    pub async fn dispatch_once(
+        &self,
+        url: &str,
+        payload: &serde_json::Value,
+        secret: &str,
+        context: &DeliveryContext,
+    ) -> Result<DeliveryAttempt, String> {
+        let payload_string = serde_json::to_string(payload).map_err(|e| e.to_string())?;
+        Ok(self.attempt(url, &payload_string, secret, context).await)
+    }
+
+    /// Post a verification challenge and check the endpoint echoes it back.
+    ///
+    /// The endpoint proves it is controlled by the integrator by answering 2xx with
+    /// either the bare token or `{"challenge": "<token>"}` as the body.
+    pub async fn verify_endpoint(
+        &self,
+        url: &str,
+        token: &str,
+        secret: &str,
+        context: &DeliveryContext,
+    ) -> Result<bool, String> {
+        let payload = serde_json::json!({
+            "event": VERIFICATION_EVENT,
+            "webhook_id": context.webhook_id,
+            "challenge": token,
+        });
+        let payload_string = serde_json::to_string(&payload).map_err(|e| e.to_string())?;
+
+        let response = self
+            .signed_request(url, &payload_string, secret, context)
+            .body(payload_string)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Ok(false);
+        }
+
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        let echoed = match serde_json::from_str::<serde_json::Value>(&body) {
+            Ok(serde_json::Value::Object(fields)) => fields.get("challenge").and_then(|c| c.as_str()) == Some(token),
+            _ => body.trim() == token,
+        };
+        Ok(echoed)
+    }
+
+    /// The webhook's own timeout capped at the maximum, else the global timeout
+    fn timeout_for(&self, context: &DeliveryContext) -> Duration {
+        context.timeout.map_or(self.timeout, |timeout| timeout.min(self.max_timeout))
+    }
+
+    /// Attach `payload_string` as the body, gzipped when the webhook accepts it and it is
+    /// large enough. The signature was computed over the uncompressed JSON, so receivers
+    /// decompress before verifying.
+    fn with_body(&self, request: RequestBuilder, payload_string: &str, context: &DeliveryContext) -> RequestBuilder {
+        if context.accepts_gzip && payload_string.len() >= self.gzip_min_bytes {
+            match gzip(payload_string.as_bytes()) {
+                Ok(compressed) => return request.header("Content-Encoding", "gzip").body(compressed),
+                Err(e) => tracing::warn!("Failed to gzip webhook payload, sending it uncompressed: {}", e),
+            }
+        }
+        request.body(payload_string.to_string())
+    }
+
+    /// A POST to `url` carrying the signature headers for `payload_string`
+    fn signed_request(&self, url: &str, payload_string: &str, secret: &str, context: &DeliveryContext) -> RequestBuilder {
+        // Signed per request so retries carry a fresh timestamp
+        let timestamp = chrono::Utc::now().timestamp();
+        let mut signature = format!("sha256={}", webhook_signature(secret, timestamp, payload_string.as_bytes()));
+        if let Some(previous_secret) = &context.previous_secret {
+            let previous = webhook_signature(previous_secret, timestamp, payload_string.as_bytes());
+            signature.push_str(&format!(",sha256={}", previous));
+        }
+
+        let mut request = self.client
+            .post(url)
+            .timeout(self.timeout_for(context))
+            .header("Content-Type", "application/json")
+            .header("X-Dodo-Timestamp", timestamp.to_string())
+            .header("X-Dodo-Signature", signature);
+        if let Some(request_id) = &context.request_id {
+            request = request.header("X-Dodo-Request-Id", request_id);
+        }
+        if let Some(sequence) = context.sequence {
+            request = request.header("X-Dodo-Sequence", sequence.to_string());
+        }
+        request
+    }
+
+    /// Sign and send one request, timing the round trip
+    async fn attempt(&self, url: &str, payload_string: &str, secret: &str, context: &DeliveryContext) -> DeliveryAttempt {
+        let request = self.signed_request(url, payload_string, secret, context);
+        let started = Instant::now();
+        let result = if fault_injection::should_fail(Fault::WebhookServerError) {
+            Ok(reqwest::StatusCode::INTERNAL_SERVER_ERROR)
+        } else {
+            self.with_body(request, payload_string, context)
+                .send()
+                .await
+                .map(|res| res.status())
+        };
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(status) => DeliveryAttempt { status: Some(status.as_u16()), latency_ms, error: None },
+            Err(e) => DeliveryAttempt { status: None, latency_ms, error: Some(e.to_string()) },
+        }
+    }
+}
+
+fn gzip(body: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body)?;
+    encoder.finish()
 }
 
 #[async_trait]
 impl WebhookDispatcher for ReqwestWebhookDispatcher {
-    async fn dispatch(&self, url: &str, payload: &serde_json::Value, secret: &str) -> Result<(), String> {
+    async fn dispatch(
+        &self,
+        url: &str,
+        payload: &serde_json::Value,
+        secret: &str,
+        context: &DeliveryContext,
+    ) -> Result<(), String> {
         let payload_string = serde_json::to_string(payload).map_err(|e| e.to_string())?;
-        
-        // Compute HMAC
-        let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes())
-            .map_err(|e| format!("Invalid HMAC secret: {}", e))?;
-        hmac::Mac::update(&mut mac, payload_string.as_bytes());
-        let result = mac.finalize();
-        let signature_hex = hex::encode(result.into_bytes());
 
         let mut attempt = 0;
-        let mut backoff = self.initial_backoff_ms;
+        let started = Instant::now();
 
         loop {
-            let result = self.client
-                .post(url)
-                .header("Content-Type", "application/json")
-                .header("X-Dodo-Signature", format!("sha256={}", signature_hex))
-                .body(payload_string.clone())
-                .send()
-                .await;
-
-            match result {
-                Ok(res) => {
-                    if res.status().is_success() {
-                        return Ok(());
-                    } else {
-                        warn!("Webhook dispatch attempt {} failed with status: {}", attempt + 1, res.status());
-                    }
-                }
-                Err(e) => {
-                    warn!("Webhook dispatch attempt {} failed with error: {}", attempt + 1, e);
+            metrics::record_webhook_attempt();
+            let outcome = self.attempt(url, &payload_string, secret, context).await;
+            let (status, latency_ms, error) = (outcome.status, outcome.latency_ms, outcome.error.as_deref());
+
+            attempt += 1;
+            if outcome.is_success() {
+                if self.log_successes {
+                    delivery_event!(tracing::Level::INFO, context, url, attempt, status, latency_ms, "success", error);
                 }
+                metrics::record_webhook_delivery(true);
+                return Ok(());
             }
 
-            attempt += 1;
             if attempt > self.max_retries {
-                let msg = format!("Webhook dispatch failed after {} attempts", self.max_retries);
-                error!("{}", msg);
-                return Err(msg);
+                delivery_event!(tracing::Level::ERROR, context, url, attempt, status, latency_ms, "failed", error);
+                metrics::record_webhook_delivery(false);
+                return Err(format!("Webhook dispatch failed after {} attempts", self.max_retries));
             }
 
             // Exponential backoff with jitter
             let jitter: u64 = rand::rng().random_range(0..100);
-            let sleep_duration = Duration::from_millis(backoff + jitter);
+            let sleep_duration = self.backoff_for(attempt) + Duration::from_millis(jitter);
+            if started.elapsed() + sleep_duration > self.max_elapsed {
+                delivery_event!(tracing::Level::ERROR, context, url, attempt, status, latency_ms, "failed", error);
+                metrics::record_webhook_delivery(false);
+                return Err(format!("Webhook dispatch gave up after {} attempts in {:?}", attempt, started.elapsed()));
+            }
+            delivery_event!(tracing::Level::WARN, context, url, attempt, status, latency_ms, "retry", error);
+
             tokio::time::sleep(sleep_duration).await;
-            
-            backoff *= 2;
         }
     }
 }
@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Compute the hex-encoded HMAC-SHA256 of `payload` keyed with `secret`.
+///
+/// Shared by webhook deliveries (`X-Dodo-Signature`, see [`webhook_signature`]),
+/// signed API responses (`X-Response-Signature`) and signed client requests
+/// (`X-Request-Signature`, see [`request_signature`]), all sent as `sha256=<hex>`.
+pub fn hmac_sha256_hex(secret: &str, payload: &[u8]) -> String {
+    // HMAC accepts keys of any length, so this cannot fail.
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC can take a key of any size");
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Build the string a webhook signature covers: `{timestamp}.{body}`.
+///
+/// `timestamp` is the Unix time in seconds sent as `X-Dodo-Timestamp`.
+pub fn webhook_signing_payload(timestamp: i64, body: &[u8]) -> Vec<u8> {
+    let mut payload = format!("{}.", timestamp).into_bytes();
+    payload.extend_from_slice(body);
+    payload
+}
+
+/// Hex-encoded webhook signature for a delivery sent at `timestamp`
+pub fn webhook_signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    hmac_sha256_hex(secret, &webhook_signing_payload(timestamp, body))
+}
+
+/// Why a webhook or request signature was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SignatureError {
+    #[error("Signature is not valid hex")]
+    Malformed,
+    #[error("Timestamp is outside the tolerance window")]
+    Expired,
+    #[error("Signature does not match")]
+    Mismatch,
+}
+
+/// Verify an `X-Dodo-Signature` for a webhook received with `X-Dodo-Timestamp: {timestamp}`.
+///
+/// `signature` may carry the `sha256=` prefix, and may list several comma-separated
+/// signatures (sent while a rotated secret is in its grace period); any match is
+/// accepted. Deliveries whose timestamp is more than `tolerance` away from the
+/// current time are rejected, so a captured request cannot be replayed later.
+/// The comparison is constant-time.
+pub fn verify_signature(
+    secret: &str,
+    timestamp: i64,
+    body: &[u8],
+    signature: &str,
+    tolerance: Duration,
+) -> Result<(), SignatureError> {
+    verify_signed(secret, timestamp, &webhook_signing_payload(timestamp, body), signature, tolerance)
+}
+
+/// Build the string a client's request signature covers: `{timestamp}.{METHOD}.{path}.{body}`.
+///
+/// `path` includes the query string, so neither the target nor the body can be swapped.
+pub fn request_signing_payload(timestamp: i64, method: &str, path: &str, body: &[u8]) -> Vec<u8> {
+    let mut payload = format!("{}.{}.{}.", timestamp, method.to_ascii_uppercase(), path).into_bytes();
+    payload.extend_from_slice(body);
+    payload
+}
+
+/// Hex-encoded signature a client sends as `X-Request-Signature` for a request made at `timestamp`
+pub fn request_signature(secret: &str, timestamp: i64, method: &str, path: &str, body: &[u8]) -> String {
+    hmac_sha256_hex(secret, &request_signing_payload(timestamp, method, path, body))
+}
+
+/// Verify an `X-Request-Signature` sent with `X-Request-Timestamp: {timestamp}`.
+/// Accepts the same formats and applies the same replay window as [`verify_signature`].
+pub fn verify_request_signature(
+    secret: &str,
+    timestamp: i64,
+    method: &str,
+    path: &str,
+    body: &[u8],
+    signature: &str,
+    tolerance: Duration,
+) -> Result<(), SignatureError> {
+    let signed = request_signing_payload(timestamp, method, path, body);
+    verify_signed(secret, timestamp, &signed, signature, tolerance)
+}
+
+fn verify_signed(
+    secret: &str,
+    timestamp: i64,
+    signed: &[u8],
+    signature: &str,
+    tolerance: Duration,
+) -> Result<(), SignatureError> {
+    let age = chrono::Utc::now().timestamp().abs_diff(timestamp);
+    if age > tolerance.as_secs() {
+        return Err(SignatureError::Expired);
+    }
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC can take a key of any size");
+    mac.update(signed);
+
+    let mut result = Err(SignatureError::Malformed);
+    for candidate in signature.split(',') {
+        let candidate = candidate.trim();
+        let candidate = candidate.strip_prefix("sha256=").unwrap_or(candidate);
+        let Ok(expected) = hex::decode(candidate) else {
+            continue;
+        };
+        if mac.clone().verify_slice(&expected).is_ok() {
+            return Ok(());
+        }
+        result = Err(SignatureError::Mismatch);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: Duration = Duration::from_secs(300);
+
+    #[test]
+    fn test_verify_accepts_fresh_signature() {
+        let now = chrono::Utc::now().timestamp();
+        let signature = format!("sha256={}", webhook_signature("secret", now, b"{}"));
+        assert_eq!(verify_signature("secret", now, b"{}", &signature, TOLERANCE), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let now = chrono::Utc::now().timestamp();
+        let signature = webhook_signature("secret", now, b"{}");
+
+        assert_eq!(
+            verify_signature("secret", now, b"{\"amount\":1}", &signature, TOLERANCE),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verify_signature("other", now, b"{}", &signature, TOLERANCE),
+            Err(SignatureError::Mismatch)
+        );
+        // The timestamp is part of the signed string
+        assert_eq!(
+            verify_signature("secret", now - 1, b"{}", &signature, TOLERANCE),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verify_signature("secret", now, b"{}", "sha256=zz", TOLERANCE),
+            Err(SignatureError::Malformed)
+        );
+    }
+
+    #[test]
+    fn test_verify_accepts_any_listed_signature() {
+        let now = chrono::Utc::now().timestamp();
+        let header = format!(
+            "sha256={},sha256={}",
+            webhook_signature("new", now, b"{}"),
+            webhook_signature("old", now, b"{}")
+        );
+
+        assert_eq!(verify_signature("new", now, b"{}", &header, TOLERANCE), Ok(()));
+        assert_eq!(verify_signature("old", now, b"{}", &header, TOLERANCE), Ok(()));
+        assert_eq!(
+            verify_signature("other", now, b"{}", &header, TOLERANCE),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_stale_timestamp() {
+        let stale = chrono::Utc::now().timestamp() - 600;
+        let signature = webhook_signature("secret", stale, b"{}");
+        assert_eq!(
+            verify_signature("secret", stale, b"{}", &signature, TOLERANCE),
+            Err(SignatureError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_request_signature_covers_method_and_path() {
+        let now = chrono::Utc::now().timestamp();
+        let signature = request_signature("secret", now, "POST", "/transactions/deposit", b"{}");
+
+        assert_eq!(
+            verify_request_signature("secret", now, "post", "/transactions/deposit", b"{}", &signature, TOLERANCE),
+            Ok(())
+        );
+        assert_eq!(
+            verify_request_signature("secret", now, "POST", "/transactions/withdraw", b"{}", &signature, TOLERANCE),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verify_request_signature("secret", now, "PUT", "/transactions/deposit", b"{}", &signature, TOLERANCE),
+            Err(SignatureError::Mismatch)
+        );
+    }
+}
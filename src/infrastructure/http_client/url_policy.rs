@@ -0,0 +1,152 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use reqwest::Url;
+
+use crate::domain::errors::DomainError;
+
+/// Which webhook URLs the server is willing to send requests to.
+///
+/// Registering a URL that points at internal infrastructure (cloud metadata,
+/// databases on localhost, RFC1918 hosts) would let tenants probe it through us,
+/// so hostnames are resolved and every address they map to is checked.
+#[derive(Debug, Clone, Copy)]
+pub struct WebhookUrlPolicy {
+    require_https: bool,
+    allow_private_networks: bool,
+}
+
+impl Default for WebhookUrlPolicy {
+    fn default() -> Self {
+        Self::new(true, false)
+    }
+}
+
+impl WebhookUrlPolicy {
+    /// `allow_private_networks` is meant for local development: it skips the address
+    /// and port checks so receivers on localhost and arbitrary ports can be used.
+    pub fn new(require_https: bool, allow_private_networks: bool) -> Self {
+        Self { require_https, allow_private_networks }
+    }
+
+    /// Check `url`, resolving its host unless private networks are allowed.
+    pub async fn validate(&self, url: &str) -> Result<(), DomainError> {
+        let parsed = Url::parse(url).map_err(|e| invalid(format!("{} is not a valid URL: {}", url, e)))?;
+
+        match parsed.scheme() {
+            "https" => {}
+            "http" if !self.require_https => {}
+            _ => return Err(invalid("URL must use https".to_string())),
+        }
+
+        let host = parsed.host_str().ok_or_else(|| invalid("URL must have a host".to_string()))?;
+        if self.allow_private_networks {
+            return Ok(());
+        }
+
+        // `port()` is only set when it differs from the scheme's default
+        if let Some(port) = parsed.port() {
+            return Err(invalid(format!("Port {} is not allowed; use the standard port", port)));
+        }
+
+        let port = parsed.port_or_known_default().unwrap_or(443);
+        // IPv6 literals come back bracketed
+        let literal = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>();
+        let addresses: Vec<IpAddr> = match literal {
+            Ok(ip) => vec![ip],
+            Err(_) => tokio::net::lookup_host((host, port))
+                .await
+                .map_err(|e| invalid(format!("Could not resolve {}: {}", host, e)))?
+                .map(|addr| addr.ip())
+                .collect(),
+        };
+
+        if addresses.is_empty() {
+            return Err(invalid(format!("Could not resolve {}", host)));
+        }
+        if let Some(ip) = addresses.into_iter().find(|ip| is_internal(*ip)) {
+            return Err(invalid(format!("{} resolves to a non-public address ({})", host, ip)));
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid(message: String) -> DomainError {
+    DomainError::InvalidWebhookUrl(message)
+}
+
+/// Loopback, link-local, private and unspecified addresses
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_internal_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_internal_v4(mapped),
+            None => is_internal_v6(ip),
+        },
+    }
+}
+
+fn is_internal_v4(ip: Ipv4Addr) -> bool {
+    ip.is_loopback() || ip.is_link_local() || ip.is_private() || ip.is_unspecified() || ip.is_broadcast()
+}
+
+fn is_internal_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        // fe80::/10 link-local
+        || (first & 0xffc0) == 0xfe80
+        // fc00::/7 unique local, the IPv6 counterpart of RFC1918
+        || (first & 0xfe00) == 0xfc00
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn rejects(policy: WebhookUrlPolicy, url: &str) -> bool {
+        matches!(policy.validate(url).await, Err(DomainError::InvalidWebhookUrl(_)))
+    }
+
+    #[tokio::test]
+    async fn test_rejects_internal_addresses() {
+        let policy = WebhookUrlPolicy::default();
+        for url in [
+            "https://127.0.0.1/hook",
+            "https://localhost/hook",
+            "https://169.254.169.254/latest/meta-data",
+            "https://10.0.0.5/hook",
+            "https://172.16.0.1/hook",
+            "https://192.168.1.10/hook",
+            "https://0.0.0.0/hook",
+            "https://[::1]/hook",
+            "https://[fe80::1]/hook",
+            "https://[fd00::1]/hook",
+            "https://[::ffff:127.0.0.1]/hook",
+        ] {
+            assert!(rejects(policy, url).await, "{} should be rejected", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requires_https_and_standard_ports() {
+        let policy = WebhookUrlPolicy::default();
+        assert!(rejects(policy, "http://8.8.8.8/hook").await);
+        assert!(rejects(policy, "https://8.8.8.8:5432/hook").await);
+        assert!(rejects(policy, "ftp://8.8.8.8/hook").await);
+        assert!(rejects(policy, "not a url").await);
+        assert!(!rejects(policy, "https://8.8.8.8/hook").await);
+        assert!(!rejects(policy, "https://8.8.8.8:443/hook").await);
+
+        let plain_http = WebhookUrlPolicy::new(false, false);
+        assert!(!rejects(plain_http, "http://8.8.8.8/hook").await);
+        assert!(rejects(plain_http, "http://8.8.8.8:8080/hook").await);
+    }
+
+    #[tokio::test]
+    async fn test_private_networks_can_be_allowed_for_development() {
+        let policy = WebhookUrlPolicy::new(false, true);
+        assert!(!rejects(policy, "http://localhost:9000/hook").await);
+        assert!(!rejects(policy, "http://192.168.1.10:8080/hook").await);
+    }
+}
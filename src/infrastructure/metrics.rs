@@ -0,0 +1,296 @@
+//! Process-wide Prometheus metrics.
+//!
+//! A small counter/gauge/histogram registry rendered in the Prometheus text
+//! exposition format by `GET /metrics`. Series are created on first use,
+//! so only metrics that have been recorded appear in the output.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+/// Content type of the text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Upper bounds, in seconds, of the request latency histogram buckets
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+pub const WEBHOOK_DELIVERY_ATTEMPTS_TOTAL: &str = "webhook_delivery_attempts_total";
+pub const WEBHOOK_DELIVERY_SUCCESSES_TOTAL: &str = "webhook_delivery_successes_total";
+pub const WEBHOOK_DELIVERY_FAILURES_TOTAL: &str = "webhook_delivery_failures_total";
+pub const TRANSACTIONS_TOTAL: &str = "transactions_total";
+pub const TRANSACTION_VOLUME_TOTAL: &str = "transaction_volume_total";
+pub const DB_POOL_CONNECTIONS: &str = "db_pool_connections";
+pub const DB_POOL_IDLE_CONNECTIONS: &str = "db_pool_idle_connections";
+pub const DB_POOL_IN_USE_CONNECTIONS: &str = "db_pool_in_use_connections";
+pub const DB_POOL_MAX_CONNECTIONS: &str = "db_pool_max_connections";
+pub const RECONCILIATION_ACCOUNTS_CHECKED: &str = "reconciliation_accounts_checked";
+pub const RECONCILIATION_DRIFTED_ACCOUNTS: &str = "reconciliation_drifted_accounts";
+
+/// `# HELP` text for every metric the service records
+const HELP: [(&str, &str); 13] = [
+    (HTTP_REQUESTS_TOTAL, "HTTP requests by method, route and status code"),
+    (HTTP_REQUEST_DURATION_SECONDS, "HTTP request latency by method and route"),
+    (WEBHOOK_DELIVERY_ATTEMPTS_TOTAL, "Webhook HTTP delivery attempts, including retries"),
+    (WEBHOOK_DELIVERY_SUCCESSES_TOTAL, "Webhooks delivered with a 2xx response"),
+    (WEBHOOK_DELIVERY_FAILURES_TOTAL, "Webhooks abandoned after exhausting retries"),
+    (TRANSACTIONS_TOTAL, "Completed transactions by type"),
+    (TRANSACTION_VOLUME_TOTAL, "Sum of completed transaction amounts by type"),
+    (DB_POOL_CONNECTIONS, "Open database connections, idle or in use"),
+    (DB_POOL_IDLE_CONNECTIONS, "Open database connections waiting to be acquired"),
+    (DB_POOL_IN_USE_CONNECTIONS, "Database connections currently checked out"),
+    (DB_POOL_MAX_CONNECTIONS, "Configured connection pool size limit"),
+    (RECONCILIATION_ACCOUNTS_CHECKED, "Accounts compared with their transactions in the last reconciliation run"),
+    (RECONCILIATION_DRIFTED_ACCOUNTS, "Accounts whose stored balance disagreed with their transactions in the last run"),
+];
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    bucket_counts: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            bucket_counts: [0; LATENCY_BUCKETS.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|&bound| value <= bound) {
+            self.bucket_counts[bucket] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// Counter, gauge and histogram series keyed by metric name and label set
+#[derive(Debug, Default)]
+pub struct Registry {
+    counters: Mutex<BTreeMap<&'static str, BTreeMap<Labels, f64>>>,
+    gauges: Mutex<BTreeMap<&'static str, BTreeMap<Labels, f64>>>,
+    histograms: Mutex<BTreeMap<&'static str, BTreeMap<Labels, Histogram>>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `value` to a counter series
+    pub fn increment_counter(&self, name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        let mut counters = self.counters.lock().unwrap();
+        *counters
+            .entry(name)
+            .or_default()
+            .entry(owned_labels(labels))
+            .or_insert(0.0) += value;
+    }
+
+    /// Set a gauge series to `value`
+    pub fn set_gauge(&self, name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        let mut gauges = self.gauges.lock().unwrap();
+        gauges.entry(name).or_default().insert(owned_labels(labels), value);
+    }
+
+    /// Record one observation in a histogram series
+    pub fn observe_histogram(&self, name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        let mut histograms = self.histograms.lock().unwrap();
+        histograms
+            .entry(name)
+            .or_default()
+            .entry(owned_labels(labels))
+            .or_insert_with(Histogram::new)
+            .observe(value);
+    }
+
+    /// Sum of a counter across all its label sets; 0 if it was never incremented
+    pub fn counter_total(&self, name: &'static str) -> f64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(name)
+            .map(|series| series.values().sum())
+            .unwrap_or(0.0)
+    }
+
+    /// Render every series in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        for (name, series) in self.counters.lock().unwrap().iter() {
+            write_header(&mut out, name, "counter");
+            for (labels, value) in series {
+                let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+            }
+        }
+
+        for (name, series) in self.gauges.lock().unwrap().iter() {
+            write_header(&mut out, name, "gauge");
+            for (labels, value) in series {
+                let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+            }
+        }
+
+        for (name, series) in self.histograms.lock().unwrap().iter() {
+            write_header(&mut out, name, "histogram");
+            for (labels, histogram) in series {
+                let mut cumulative = 0;
+                for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.bucket_counts) {
+                    cumulative += count;
+                    let le = bound.to_string();
+                    let _ = writeln!(out, "{}_bucket{} {}", name, format_labels(labels, Some(&le)), cumulative);
+                }
+                let _ = writeln!(out, "{}_bucket{} {}", name, format_labels(labels, Some("+Inf")), histogram.count);
+                let _ = writeln!(out, "{}_sum{} {}", name, format_labels(labels, None), histogram.sum);
+                let _ = writeln!(out, "{}_count{} {}", name, format_labels(labels, None), histogram.count);
+            }
+        }
+
+        out
+    }
+}
+
+fn owned_labels(labels: &[(&'static str, &str)]) -> Labels {
+    labels.iter().map(|(key, value)| (*key, value.to_string())).collect()
+}
+
+fn write_header(out: &mut String, name: &str, kind: &str) {
+    if let Some((_, help)) = HELP.iter().find(|(metric, _)| *metric == name) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+    }
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Format `{key="value",...}`, appending `le` for histogram buckets
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label_value(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+/// The process-wide registry served by `GET /metrics`
+pub fn registry() -> &'static Registry {
+    &REGISTRY
+}
+
+/// Record a handled HTTP request
+pub fn record_http_request(method: &str, route: &str, status: u16, elapsed: Duration) {
+    let status = status.to_string();
+    registry().increment_counter(
+        HTTP_REQUESTS_TOTAL,
+        &[("method", method), ("route", route), ("status", &status)],
+        1.0,
+    );
+    registry().observe_histogram(
+        HTTP_REQUEST_DURATION_SECONDS,
+        &[("method", method), ("route", route)],
+        elapsed.as_secs_f64(),
+    );
+}
+
+/// Record one webhook HTTP attempt
+pub fn record_webhook_attempt() {
+    registry().increment_counter(WEBHOOK_DELIVERY_ATTEMPTS_TOTAL, &[], 1.0);
+}
+
+/// Record the final outcome of a webhook delivery
+pub fn record_webhook_delivery(delivered: bool) {
+    let name = if delivered {
+        WEBHOOK_DELIVERY_SUCCESSES_TOTAL
+    } else {
+        WEBHOOK_DELIVERY_FAILURES_TOTAL
+    };
+    registry().increment_counter(name, &[], 1.0);
+}
+
+/// Record a completed deposit, withdrawal or transfer
+pub fn record_transaction(transaction_type: &str, amount: Decimal) {
+    let labels = [("type", transaction_type)];
+    registry().increment_counter(TRANSACTIONS_TOTAL, &labels, 1.0);
+    registry().increment_counter(TRANSACTION_VOLUME_TOTAL, &labels, amount.to_f64().unwrap_or(0.0));
+}
+
+/// Record a snapshot of connection pool utilization
+pub fn record_pool_stats(size: u32, idle: u32, in_use: u32, max: u32) {
+    registry().set_gauge(DB_POOL_CONNECTIONS, &[], size as f64);
+    registry().set_gauge(DB_POOL_IDLE_CONNECTIONS, &[], idle as f64);
+    registry().set_gauge(DB_POOL_IN_USE_CONNECTIONS, &[], in_use as f64);
+    registry().set_gauge(DB_POOL_MAX_CONNECTIONS, &[], max as f64);
+}
+
+/// Record the outcome of a reconciliation run
+pub fn record_reconciliation(checked: usize, drifted: usize) {
+    registry().set_gauge(RECONCILIATION_ACCOUNTS_CHECKED, &[], checked as f64);
+    registry().set_gauge(RECONCILIATION_DRIFTED_ACCOUNTS, &[], drifted as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_and_histograms() {
+        let registry = Registry::new();
+        registry.increment_counter(TRANSACTIONS_TOTAL, &[("type", "credit")], 1.0);
+        registry.increment_counter(TRANSACTIONS_TOTAL, &[("type", "credit")], 1.0);
+        registry.observe_histogram(HTTP_REQUEST_DURATION_SECONDS, &[("route", "/health")], 0.02);
+
+        let output = registry.render();
+        assert!(output.contains("# TYPE transactions_total counter"));
+        assert!(output.contains("transactions_total{type=\"credit\"} 2"));
+        assert!(output.contains("# TYPE http_request_duration_seconds histogram"));
+        assert!(output.contains("http_request_duration_seconds_bucket{route=\"/health\",le=\"0.01\"} 0"));
+        assert!(output.contains("http_request_duration_seconds_bucket{route=\"/health\",le=\"0.025\"} 1"));
+        assert!(output.contains("http_request_duration_seconds_bucket{route=\"/health\",le=\"+Inf\"} 1"));
+        assert!(output.contains("http_request_duration_seconds_count{route=\"/health\"} 1"));
+        assert_eq!(registry.counter_total(TRANSACTIONS_TOTAL), 2.0);
+        assert_eq!(registry.counter_total(WEBHOOK_DELIVERY_FAILURES_TOTAL), 0.0);
+    }
+
+    #[test]
+    fn test_gauges_keep_the_latest_value() {
+        let registry = Registry::new();
+        registry.set_gauge(DB_POOL_IDLE_CONNECTIONS, &[], 4.0);
+        registry.set_gauge(DB_POOL_IDLE_CONNECTIONS, &[], 2.0);
+
+        let output = registry.render();
+        assert!(output.contains("# TYPE db_pool_idle_connections gauge"));
+        assert!(output.contains("db_pool_idle_connections 2\n"));
+    }
+
+    #[test]
+    fn test_label_values_are_escaped() {
+        let registry = Registry::new();
+        registry.increment_counter(HTTP_REQUESTS_TOTAL, &[("route", "a\"b")], 1.0);
+        assert!(registry.render().contains("http_requests_total{route=\"a\\\"b\"} 1"));
+    }
+}
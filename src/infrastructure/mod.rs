@@ -1,3 +1,6 @@
 pub mod config;
 pub mod database;
-pub mod http_client;
\ No newline at end of file
+pub mod fault_injection;
+pub mod health;
+pub mod http_client;
+pub mod metrics;
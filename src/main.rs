@@ -1,35 +1,43 @@
-mod application;
-mod domain;
-mod infrastructure;
-mod presentation;
-
 use axum::{routing::get, Router};
 use utoipa::OpenApi;
+use std::future::IntoFuture;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 
-use crate::application::services::{AccountService, AuthService, TransactionService, WebhookService};
-use crate::application::AppState;
-use crate::infrastructure::config::Config;
-use crate::infrastructure::database::{
-    self, PostgresAccountRepository, PostgresApiKeyRepository, PostgresTransactionRepository,
+use dodo_payments_assignment::application::services::{
+    AccountService, ArchivalService, AuthService, IdempotencyCleanupService, OutboxService, ReconciliationService,
+    TransactionService, WebhookService,
+};
+use dodo_payments_assignment::application::AppState;
+use dodo_payments_assignment::domain::value_objects::{LogFormat, Money, TransactionLimits, WebhookOverflowPolicy};
+use dodo_payments_assignment::infrastructure::config::Config;
+use dodo_payments_assignment::infrastructure::database::{
+    self, PostgresAccountRepository, PostgresApiKeyRepository, PostgresBalanceSnapshotRepository,
+    PostgresIdempotencyRepository, PostgresOutboxRepository, PostgresTransactionRepository,
     PostgresWebhookRepository,
 };
-use crate::infrastructure::http_client::ReqwestWebhookDispatcher;
+use dodo_payments_assignment::infrastructure::http_client::{ReqwestWebhookDispatcher, WebhookUrlPolicy};
+use dodo_payments_assignment::presentation::{self, middleware::in_flight::InFlightRequests};
 // Imports cleaned up
 // Actually, let's clean up unused imports too. `require_auth` is not used anymore.
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
+    let started_at = chrono::Utc::now();
 
     // Load configuration
     let config = Config::from_env()?;
 
+    // Initialize logging
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env());
+    match config.logging.format {
+        LogFormat::Json => subscriber.json().init(),
+        LogFormat::Pretty => subscriber.init(),
+    }
+
     tracing::info!("Starting Dodo Payments Service...");
     tracing::info!("Server: {}", config.server_address());
     tracing::info!("Database: {}", config.database_url());
@@ -40,76 +48,299 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "✓ Database connection pool created (max: {} connections)",
         config.database.max_connections
     );
+    if config.database.run_migrations {
+        // Fail fast rather than serve against a half-migrated schema
+        database::run_migrations(&pool).await?;
+        tracing::info!("✓ Database migrations applied");
+    }
+    if config.database.pool_metrics_interval_seconds > 0 {
+        database::spawn_pool_metrics(
+            pool.clone(),
+            Duration::from_secs(config.database.pool_metrics_interval_seconds),
+        );
+    }
 
     // Initialize Repositories
     let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
-    let transaction_repo = Arc::new(PostgresTransactionRepository::new(pool.clone()));
+    let transaction_repo = Arc::new(
+        PostgresTransactionRepository::new(pool.clone())
+            .with_balance_snapshots(config.accounts.record_balance_snapshots)
+            .with_invalid_row_skipping(config.database.skip_invalid_transaction_rows)
+            .with_retries(
+                config.database.max_attempts,
+                Duration::from_millis(config.database.retry_base_delay_ms),
+            )
+            .with_outbox_events(config.webhook.transactional_outbox),
+    );
     let api_key_repo = Arc::new(PostgresApiKeyRepository::new(pool.clone()));
     let webhook_repo = Arc::new(PostgresWebhookRepository::new(pool.clone()));
+    let idempotency_repo = Arc::new(PostgresIdempotencyRepository::new(pool.clone()));
 
     // Initialize Webhook Components
-    let webhook_dispatcher = Arc::new(ReqwestWebhookDispatcher::new(
-        config.webhook.max_retries,
-        config.webhook.initial_backoff_ms,
-    ));
-    let webhook_service = Arc::new(WebhookService::new(
-        webhook_repo.clone(),
-        account_repo.clone(),
-        webhook_dispatcher,
-    ));
+    let webhook_dispatcher = Arc::new(
+        ReqwestWebhookDispatcher::new(config.webhook.max_retries, config.webhook.initial_backoff_ms)
+            .with_success_logging(config.webhook.log_successful_deliveries)
+            .with_gzip_min_bytes(config.webhook.gzip_min_bytes)
+            .with_timeouts(
+                Duration::from_secs(config.webhook.timeout_seconds),
+                Duration::from_millis(config.webhook.max_timeout_ms),
+            )
+            .with_connect_timeout(Duration::from_millis(config.webhook.connect_timeout_ms))
+            .with_retry_limits(
+                Duration::from_millis(config.webhook.max_backoff_ms),
+                Duration::from_millis(config.webhook.max_elapsed_ms),
+            ),
+    );
+    let mut webhook_service = WebhookService::new(webhook_repo.clone(), account_repo.clone(), webhook_dispatcher.clone())
+        .with_payload_validation(config.webhook.validate_payloads)
+        .with_request_id_propagation(config.webhook.propagate_request_id)
+        .with_sequence_numbers(config.webhook.sequence_numbers);
+    if config.webhook.max_pending_dispatches > 0 {
+        webhook_service = webhook_service.with_dispatch_limit(
+            config.webhook.max_pending_dispatches,
+            config.webhook.overflow_policy,
+            Duration::from_millis(config.webhook.overflow_block_timeout_ms),
+        );
+    }
+    let webhook_service = Arc::new(webhook_service);
+    if config.webhook.max_pending_dispatches > 0 && config.webhook.overflow_policy == WebhookOverflowPolicy::Outbox {
+        webhook_service.clone().spawn_outbox_drain(Duration::from_secs(1), config.webhook.max_pending_dispatches as i64);
+    }
+    if config.webhook.transactional_outbox {
+        let outbox_service = Arc::new(
+            OutboxService::new(
+                Arc::new(PostgresOutboxRepository::new(pool.clone())),
+                transaction_repo.clone(),
+                webhook_service.clone(),
+            )
+            .with_batch_size(config.webhook.outbox_batch_size),
+        );
+        outbox_service.spawn(Duration::from_millis(config.webhook.outbox_poll_interval_ms));
+        tracing::info!("✓ Transactional webhook outbox enabled");
+    }
 
     // Initialize Services
-    let account_service = Arc::new(AccountService::new(account_repo));
-    let transaction_service = Arc::new(TransactionService::new(
-        transaction_repo,
-        Some(webhook_service),
-    ));
-    let auth_service = Arc::new(AuthService::new(api_key_repo));
+    let mut account_service = AccountService::new(account_repo.clone())
+        .with_unique_business_names(config.accounts.unique_business_names)
+        .with_opening_credits(transaction_repo.clone())
+        .with_webhooks(webhook_service.clone());
+    if config.accounts.record_balance_snapshots {
+        account_service = account_service
+            .with_balance_snapshots(Arc::new(PostgresBalanceSnapshotRepository::new(pool.clone())));
+    }
+    let account_service = Arc::new(account_service);
+    if config.retention.transaction_retention_days > 0 {
+        let archival_service = Arc::new(ArchivalService::new(
+            transaction_repo.clone(),
+            chrono::Duration::days(config.retention.transaction_retention_days as i64),
+        ));
+        archival_service.spawn(Duration::from_secs(config.retention.archival_interval_seconds));
+        tracing::info!(
+            "✓ Transaction archival enabled (retention: {} days)",
+            config.retention.transaction_retention_days
+        );
+    }
+    if config.idempotency.cleanup_interval_seconds > 0 {
+        let idempotency_cleanup_service = Arc::new(
+            IdempotencyCleanupService::new(
+                transaction_repo.clone(),
+                chrono::Duration::seconds(config.idempotency.ttl_seconds as i64),
+            )
+            .with_snapshots(idempotency_repo.clone()),
+        );
+        idempotency_cleanup_service.spawn(Duration::from_secs(config.idempotency.cleanup_interval_seconds));
+        tracing::info!(
+            "✓ Idempotency key cleanup enabled (TTL: {}s)",
+            config.idempotency.ttl_seconds
+        );
+    }
+    if config.reconciliation.interval_seconds > 0 {
+        let mut reconciliation_service = ReconciliationService::new(transaction_repo.clone())
+            .with_batch_size(config.reconciliation.batch_size)
+            .with_batch_pause(Duration::from_millis(config.reconciliation.batch_pause_ms));
+        if let (Some(url), Some(secret)) = (&config.reconciliation.alert_url, &config.reconciliation.alert_secret) {
+            reconciliation_service =
+                reconciliation_service.with_alert(webhook_dispatcher.clone(), url.clone(), secret.clone());
+        }
+        Arc::new(reconciliation_service).spawn(Duration::from_secs(config.reconciliation.interval_seconds));
+        tracing::info!(
+            "✓ Balance reconciliation enabled (every {}s)",
+            config.reconciliation.interval_seconds
+        );
+    }
+    let default_limits = TransactionLimits::new(
+        config.transactions.max_transaction_amount.map(Money::new).transpose()?,
+        config.transactions.daily_debit_limit.map(Money::new).transpose()?,
+    );
+    let transaction_service = Arc::new(
+        TransactionService::new(transaction_repo, Some(webhook_service.clone())).with_idempotency_repository(
+            idempotency_repo,
+            chrono::Duration::seconds(config.idempotency.ttl_seconds as i64),
+        )
+        .with_idempotency_key_format(config.idempotency.key_format)
+        .with_zero_amount_transactions(config.transactions.allow_zero_amount_transactions)
+        .with_min_amount(config.transactions.min_transaction_amount.map(Money::new).transpose()?)
+        .with_max_batch_legs(config.transactions.max_batch_legs)
+        .with_outbox_delivery(config.webhook.transactional_outbox)
+        .with_transaction_limits(account_repo.clone(), default_limits),
+    );
+    let mut auth_service = AuthService::new(api_key_repo)
+        .with_rotation_grace(chrono::Duration::seconds(config.auth.key_rotation_grace_seconds as i64));
+    if let Some(pepper) = &config.auth.key_pepper {
+        auth_service = auth_service
+            .with_pepper(pepper.clone())
+            .with_legacy_hashes(config.auth.accept_legacy_key_hashes);
+    }
+    let auth_service = Arc::new(auth_service);
+    if let Some(raw_key) = &config.auth.bootstrap_admin_key {
+        match auth_service.bootstrap_admin_key(raw_key).await? {
+            Some(api_key) => tracing::info!(
+                "✓ Seeded bootstrap admin key {} (hash {}…) for account {}",
+                api_key.id,
+                &api_key.key_hash[..12],
+                api_key.account_id
+            ),
+            None => tracing::warn!("BOOTSTRAP_ADMIN_KEY ignored: API keys already exist"),
+        }
+    }
 
     // Create Application State
     let app_state = AppState {
-        account_service,
+        account_service: account_service.clone(),
         transaction_service,
         auth_service,
         webhook_repository: webhook_repo,
+        webhook_dispatcher,
+        webhook_url_policy: WebhookUrlPolicy::new(
+            config.webhook.require_https,
+            config.webhook.allow_private_networks,
+        ),
+        require_webhook_verification: config.webhook.require_verification,
+        max_page_size: config.server.max_page_size,
+        pool: pool.clone(),
+        started_at,
     };
 
-    // Initialize Rate Limit Layer
-    let rate_limit_layer = crate::presentation::middleware::rate_limit::RateLimitLayer::new(
-        config.rate_limiting.requests_per_hour,
-    );
+    // Initialize Rate Limit Layers
+    // Public routes are limited per IP, authenticated routes per API key quota
+    let rate_limit_layer = dodo_payments_assignment::presentation::middleware::rate_limit::RateLimitLayer::from_config(
+        &config.rate_limiting,
+    )?;
+    let api_key_rate_limit_layer = dodo_payments_assignment::presentation::middleware::rate_limit::ApiKeyRateLimitLayer::new();
 
     // Build API Router
-    let protected_routes = Router::new()
+    let mut protected_routes = Router::new()
         .nest("/transactions", Router::new()
             .route("/deposit", axum::routing::post(presentation::api::transaction::deposit))
             .route("/withdraw", axum::routing::post(presentation::api::transaction::withdraw))
             .route("/transfer", axum::routing::post(presentation::api::transaction::transfer))
+            .route("/verify", axum::routing::post(presentation::api::transaction::verify))
+            .route("/authorize", axum::routing::post(presentation::api::transaction::authorize))
+            .route("/:id/capture", axum::routing::post(presentation::api::transaction::capture))
+            .route("/:id/void", axum::routing::post(presentation::api::transaction::void))
+            .route("/batch/preview", axum::routing::post(presentation::api::transaction::preview_batch))
             .route("/history", get(presentation::api::transaction::get_history))
+            .route("/:id", get(presentation::api::transaction::get_transaction))
         )
-        .route("/accounts/:id", get(presentation::api::account::get_account))
+        .route("/accounts/:id", get(presentation::api::account::get_account).patch(presentation::api::account::update_account))
         .route("/accounts", get(presentation::api::account::list_accounts))
+        .route("/accounts/batch", axum::routing::post(presentation::api::account::get_accounts))
+        .route("/accounts/:id/restore", axum::routing::post(presentation::api::account::restore_account))
+        .route("/accounts/:id/freeze", axum::routing::post(presentation::api::account::freeze_account))
+        .route("/accounts/:id/unfreeze", axum::routing::post(presentation::api::account::unfreeze_account))
+        .route("/accounts/:id/limits", axum::routing::put(presentation::api::account::update_limits))
+        .route("/accounts/:id/balance-history", get(presentation::api::account::get_balance_history))
+        .route("/accounts/:id/transactions.csv", get(presentation::api::transaction::export_csv))
+        .route("/accounts/:id/stats", get(presentation::api::transaction::account_stats))
+        .route(
+            "/accounts/:id/api-keys",
+            axum::routing::post(presentation::api::api_key::create_api_key)
+                .get(presentation::api::api_key::list_api_keys),
+        )
+        .route("/api-keys/:id/rotate", axum::routing::post(presentation::api::api_key::rotate_api_key))
         .route("/webhooks", axum::routing::get(presentation::api::webhook::list_webhooks))
-        .route("/webhooks/:id", axum::routing::delete(presentation::api::webhook::delete_webhook))
-        .route("/webhooks", axum::routing::post(presentation::api::webhook::create_webhook))
-        .layer(axum::middleware::from_fn_with_state(app_state.clone(), crate::presentation::middleware::auth::require_auth));
+        .route(
+            "/webhooks/:id",
+            axum::routing::patch(presentation::api::webhook::update_webhook)
+                .delete(presentation::api::webhook::delete_webhook),
+        )
+        .route("/webhooks/:id/rotate-secret", axum::routing::post(presentation::api::webhook::rotate_webhook_secret))
+        .route("/webhooks/:id/test", axum::routing::post(presentation::api::webhook::test_webhook))
+        .route("/webhooks/:id/verify", axum::routing::post(presentation::api::webhook::verify_webhook))
+        .route("/webhooks", axum::routing::post(presentation::api::webhook::create_webhook));
+
+    // Optionally sign authenticated responses; innermost so it sees the final handler body
+    if config.server.sign_responses {
+        let response_signing_layer =
+            dodo_payments_assignment::presentation::middleware::response_signing::ResponseSigningLayer::new(account_service.clone());
+        protected_routes = protected_routes.layer(axum::middleware::from_fn_with_state(
+            response_signing_layer,
+            dodo_payments_assignment::presentation::middleware::response_signing::ResponseSigningLayer::handle,
+        ));
+    }
+
+    // Optionally require signed mutations; inside auth, which resolves whose secret to check
+    if config.server.require_request_signatures {
+        let request_signing_layer = dodo_payments_assignment::presentation::middleware::request_signing::RequestSigningLayer::new(
+            account_service.clone(),
+            Duration::from_secs(config.server.request_signature_tolerance_seconds),
+        );
+        protected_routes = protected_routes.layer(axum::middleware::from_fn_with_state(
+            request_signing_layer,
+            dodo_payments_assignment::presentation::middleware::request_signing::RequestSigningLayer::handle,
+        ));
+    }
+
+    let protected_routes = protected_routes
+        .layer(axum::middleware::from_fn_with_state(api_key_rate_limit_layer, dodo_payments_assignment::presentation::middleware::rate_limit::ApiKeyRateLimitLayer::handle))
+        .layer(axum::middleware::from_fn_with_state(app_state.clone(), dodo_payments_assignment::presentation::middleware::auth::require_auth));
 
+    let public_routes = Router::new()
+        .route("/health", get(presentation::api::health::health_check))
+        .route("/health/ready", get(presentation::api::health::readiness_check))
+        .route("/health/full", get(presentation::api::health::full_health_check))
+        .route("/accounts", axum::routing::post(presentation::api::account::create_account))
+        .layer(axum::middleware::from_fn_with_state(rate_limit_layer, dodo_payments_assignment::presentation::middleware::rate_limit::RateLimitLayer::handle));
+
+
+    let in_flight = InFlightRequests::new();
+
+    // Restrictive unless CORS_ALLOWED_ORIGINS is set
+    let cors_layer = dodo_payments_assignment::presentation::middleware::cors::cors_layer(&config.cors)?;
 
     // Create OpenAPI Spec
-    let openapi = crate::presentation::api::openapi::ApiDoc::openapi();
+    let openapi = dodo_payments_assignment::presentation::api::openapi::ApiDoc::openapi();
 
     let api_router: Router = Router::new()
         // Public Endpoints
-        .route("/health", get(presentation::api::health::health_check))
-        .route("/accounts", axum::routing::post(presentation::api::account::create_account))
+        .merge(public_routes)
         // Protected Endpoints
         .merge(protected_routes)
         // Apply Global Middleware
+        // Bounds every request body before it is buffered; GET routes such as the CSV export have none
+        .layer(tower_http::limit::RequestBodyLimitLayer::new(config.server.max_body_bytes))
+        .layer(axum::extract::DefaultBodyLimit::disable())
+        .layer(axum::middleware::from_fn_with_state(
+            config.server.money_format,
+            dodo_payments_assignment::presentation::middleware::money_format::apply_money_format,
+        ))
+        .layer(axum::middleware::from_fn(dodo_payments_assignment::presentation::middleware::metrics::track_metrics))
         .layer(tower_http::trace::TraceLayer::new_for_http())
-        .layer(axum::middleware::from_fn_with_state(rate_limit_layer, crate::presentation::middleware::rate_limit::RateLimitLayer::handle))
+        .layer(cors_layer)
+        .layer(axum::middleware::from_fn(dodo_payments_assignment::presentation::middleware::access_log::log_access))
+        .layer(axum::middleware::from_fn(dodo_payments_assignment::presentation::middleware::request_id::assign_request_id))
+        // Keep credentials out of anything that debug-prints request headers
+        .layer(tower_http::sensitive_headers::SetSensitiveRequestHeadersLayer::new([
+            axum::http::header::AUTHORIZATION,
+            axum::http::HeaderName::from_static("x-api-key"),
+            axum::http::HeaderName::from_static("x-request-signature"),
+        ]))
+        .layer(axum::middleware::from_fn_with_state(in_flight.clone(), InFlightRequests::track))
         .with_state(app_state);
 
     let app = Router::new()
+        // Scraped by Prometheus; outside the auth and rate limit layers
+        .route("/metrics", get(presentation::api::metrics::metrics))
         .merge(utoipa_swagger_ui::SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", openapi))
         .merge(api_router)
         .into_make_service_with_connect_info::<SocketAddr>(); // Important for rate limiting
@@ -119,7 +350,81 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("✓ Service ready on {}", addr);
 
     let listener = TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+
+    // Stop accepting connections on SIGINT/SIGTERM, then let in-flight requests finish
+    let (shutdown_tx, mut shutdown_rx) = tokio::sync::watch::channel(false);
+    let server = axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.changed().await;
+        })
+        .into_future();
+    tokio::pin!(server);
+
+    tokio::select! {
+        result = &mut server => result?,
+        _ = shutdown_signal() => {
+            let shutdown_timeout = Duration::from_secs(config.server.shutdown_timeout_seconds);
+            let deadline = tokio::time::Instant::now() + shutdown_timeout;
+            let draining = in_flight.count();
+            tracing::info!("Shutdown signal received, draining {} in-flight requests", draining);
+            let _ = shutdown_tx.send(true);
+
+            match tokio::time::timeout_at(deadline, &mut server).await {
+                Ok(result) => {
+                    result?;
+                    tracing::info!("✓ Drained {} in-flight requests", draining);
+                }
+                Err(_) => tracing::warn!(
+                    "Shutdown timed out after {:?} with {} requests still in flight",
+                    shutdown_timeout,
+                    in_flight.count()
+                ),
+            }
+
+            // Give webhook dispatches spawned by the drained requests the rest of the grace period
+            let pending = webhook_service.pending_dispatches();
+            if pending > 0 {
+                tracing::info!("Waiting for {} pending webhook dispatches", pending);
+            }
+            if tokio::time::timeout_at(deadline, webhook_service.flush()).await.is_err() {
+                tracing::warn!("Abandoning webhook dispatches still pending after {:?}", shutdown_timeout);
+            }
+        }
+    }
+
+    pool.close().await;
+    tracing::info!("✓ Shutdown complete");
 
     Ok(())
+}
+
+/// Resolve on SIGINT (Ctrl+C) or, on Unix, SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to install Ctrl+C handler: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to install SIGTERM handler: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
\ No newline at end of file
@@ -1,15 +1,44 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
     response::IntoResponse,
-    Json,
+    Json, Extension,
 };
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
-use crate::application::dto::{CreateAccountRequest, AccountResponse};
+use crate::application::dto::{
+    CreateAccountRequest, AccountResponse, BalanceSnapshotResponse, BatchAccountLookupRequest, BatchAccountLookupResponse, Paginated, TransactionLimitsResponse, UpdateAccountRequest,
+    UpdateTransactionLimitsRequest,
+};
 use crate::application::AppState;
+use crate::application::services::AuthPrincipal;
 use crate::domain::errors::ApiError;
 use crate::presentation::api::error::ErrorResponse;
+use crate::presentation::api::validate_pagination;
+
+/// Accounts the caller cannot act for are indistinguishable from missing ones
+fn ensure_authorized(auth: &AuthPrincipal, id: Uuid) -> Result<(), ApiError> {
+    if auth.is_authorized_for(id) {
+        Ok(())
+    } else {
+        Err(ApiError::NotFound("Account not found".to_string()))
+    }
+}
+
+/// Let only admin keys through. Accounts the caller cannot act for at all are
+/// indistinguishable from missing ones.
+fn require_admin(auth: &AuthPrincipal, id: Uuid, action: &str) -> Result<(), ApiError> {
+    if auth.is_admin {
+        Ok(())
+    } else if auth.is_authorized_for(id) {
+        Err(ApiError::Forbidden(format!("Only admin keys may {}", action)))
+    } else {
+        Err(ApiError::NotFound("Account not found".to_string()))
+    }
+}
 
 /// Create a new account
 #[utoipa::path(
@@ -28,10 +57,10 @@ pub async fn create_account(
     let account = state
         .account_service
         .create_account(payload)
-        .await
-        .map_err(ApiError::from)?;
+        .await?;
 
-    Ok((StatusCode::CREATED, Json(account)))
+    let location = format!("/accounts/{}", account.id);
+    Ok((StatusCode::CREATED, [(header::LOCATION, location)], Json(account)))
 }
 
 /// Get account by ID
@@ -53,28 +82,289 @@ pub async fn get_account(
     let account = state
         .account_service
         .get_account(id)
-        .await
-        .map_err(ApiError::from)?;
+        .await?;
+
+    Ok((StatusCode::OK, Json(account)))
+}
+
+/// Look up many accounts at once
+///
+/// Returns the accounts found and the requested ids that do not exist. At most 200 ids per request.
+#[utoipa::path(
+    post,
+    path = "/accounts/batch",
+    request_body = BatchAccountLookupRequest,
+    responses(
+        (status = 200, description = "Accounts found and ids not found", body = BatchAccountLookupResponse),
+        (status = 400, description = "Too many ids", body = ErrorResponse)
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn get_accounts(
+    State(state): State<AppState>,
+    Json(payload): Json<BatchAccountLookupRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let accounts = state
+        .account_service
+        .get_accounts(payload)
+        .await?;
+
+    Ok((StatusCode::OK, Json(accounts)))
+}
+
+/// Update an account's business name
+#[utoipa::path(
+    patch,
+    path = "/accounts/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Account ID")
+    ),
+    request_body = UpdateAccountRequest,
+    responses(
+        (status = 200, description = "Account updated", body = AccountResponse),
+        (status = 400, description = "Invalid business name", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 409, description = "Business name already in use", body = ErrorResponse)
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn update_account(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateAccountRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_authorized(&auth, id)?;
+
+    let account = state
+        .account_service
+        .update_account(id, payload)
+        .await?;
 
     Ok((StatusCode::OK, Json(account)))
 }
 
+#[derive(Deserialize, IntoParams)]
+pub struct ListAccountsQuery {
+    /// Only accounts whose business name contains this text, case-insensitively
+    pub q: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
 /// List all accounts
 #[utoipa::path(
     get,
     path = "/accounts",
+    params(
+        ListAccountsQuery
+    ),
     responses(
-        (status = 200, description = "List of accounts", body = [AccountResponse])
+        (status = 200, description = "Page of accounts", body = Paginated<AccountResponse>),
+        (status = 400, description = "Bad request", body = ErrorResponse)
     )
 )]
 pub async fn list_accounts(
     State(state): State<AppState>,
+    Query(params): Query<ListAccountsQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let (limit, offset) = validate_pagination(params.limit, params.offset, state.max_page_size)?;
+
     let accounts = state
         .account_service
-        .list_accounts()
-        .await
-        .map_err(ApiError::from)?;
+        .list_accounts(params.q.as_deref(), limit, offset)
+        .await?;
 
     Ok((StatusCode::OK, Json(accounts)))
 }
+
+/// Restore a soft-deleted account
+#[utoipa::path(
+    post,
+    path = "/accounts/{id}/restore",
+    params(
+        ("id" = Uuid, Path, description = "Account ID")
+    ),
+    responses(
+        (status = 200, description = "Account restored", body = AccountResponse),
+        (status = 400, description = "Account is not deleted", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse)
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn restore_account(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_authorized(&auth, id)?;
+
+    let account = state
+        .account_service
+        .restore_account(id)
+        .await?;
+
+    Ok((StatusCode::OK, Json(account)))
+}
+
+/// Freeze an account, blocking deposits, withdrawals and transfers
+#[utoipa::path(
+    post,
+    path = "/accounts/{id}/freeze",
+    params(
+        ("id" = Uuid, Path, description = "Account ID")
+    ),
+    responses(
+        (status = 200, description = "Account frozen", body = AccountResponse),
+        (status = 400, description = "Account cannot be frozen", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse)
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn freeze_account(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_authorized(&auth, id)?;
+
+    let account = state
+        .account_service
+        .freeze_account(id)
+        .await?;
+
+    Ok((StatusCode::OK, Json(account)))
+}
+
+/// Set an account's transaction limits
+///
+/// Caps single withdrawals/outgoing transfers and their total per UTC day.
+/// A `null` limit falls back to the server default (`MAX_TRANSACTION_AMOUNT` / `DAILY_DEBIT_LIMIT`).
+/// Requires an admin key.
+#[utoipa::path(
+    put,
+    path = "/accounts/{id}/limits",
+    params(
+        ("id" = Uuid, Path, description = "Account ID")
+    ),
+    request_body = UpdateTransactionLimitsRequest,
+    responses(
+        (status = 200, description = "Limits updated", body = TransactionLimitsResponse),
+        (status = 400, description = "Invalid limit", body = ErrorResponse),
+        (status = 403, description = "Not an admin key", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse)
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn update_limits(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTransactionLimitsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&auth, id, "change transaction limits")?;
+
+    let limits = state
+        .account_service
+        .update_limits(id, payload)
+        .await?;
+
+    Ok((StatusCode::OK, Json(limits)))
+}
+
+/// Unfreeze a frozen account
+///
+/// Requires an admin key, so a compromised key cannot lift its own account's freeze.
+#[utoipa::path(
+    post,
+    path = "/accounts/{id}/unfreeze",
+    params(
+        ("id" = Uuid, Path, description = "Account ID")
+    ),
+    responses(
+        (status = 200, description = "Account unfrozen", body = AccountResponse),
+        (status = 400, description = "Account cannot be unfrozen", body = ErrorResponse),
+        (status = 403, description = "Not an admin key", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse)
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn unfreeze_account(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&auth, id, "unfreeze accounts")?;
+
+    let account = state
+        .account_service
+        .unfreeze_account(id)
+        .await?;
+
+    Ok((StatusCode::OK, Json(account)))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct BalanceHistoryQuery {
+    /// Only include snapshots recorded at or after this instant
+    pub from: Option<DateTime<Utc>>,
+    /// Only include snapshots recorded at or before this instant
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Get an account's balance over time
+#[utoipa::path(
+    get,
+    path = "/accounts/{id}/balance-history",
+    params(
+        ("id" = Uuid, Path, description = "Account ID"),
+        BalanceHistoryQuery
+    ),
+    responses(
+        (status = 200, description = "Balance after each transaction, oldest first", body = Vec<BalanceSnapshotResponse>),
+        (status = 400, description = "Bad request or snapshots disabled", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse)
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn get_balance_history(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<BalanceHistoryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_authorized(&auth, id)?;
+
+    if let (Some(from), Some(to)) = (params.from, params.to)
+        && from > to
+    {
+        return Err(ApiError::BadRequest("`from` must not be after `to`".to_string()));
+    }
+
+    let history = state
+        .account_service
+        .balance_history(id, params.from, params.to)
+        .await?;
+
+    Ok((StatusCode::OK, Json(history)))
+}
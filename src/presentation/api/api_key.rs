@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json, Extension,
+};
+use uuid::Uuid;
+
+use crate::application::dto::{ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse, RotatedApiKeyResponse};
+use crate::application::AppState;
+use crate::application::services::AuthPrincipal;
+use crate::domain::errors::ApiError;
+use crate::presentation::api::error::ErrorResponse;
+
+/// Create a new API key for an account
+///
+/// The raw key is returned in this response only; store it securely.
+#[utoipa::path(
+    post,
+    path = "/accounts/{id}/api-keys",
+    params(
+        ("id" = Uuid, Path, description = "Account ID")
+    ),
+    request_body = CreateApiKeyRequest,
+    security(
+        ("api_key" = [])
+    ),
+    responses(
+        (status = 201, description = "API key created", body = CreatedApiKeyResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse)
+    )
+)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let api_key = state
+        .auth_service
+        .create_api_key(&auth, id, payload)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(api_key)))
+}
+
+/// List an account's API keys
+///
+/// Keys are identified by their non-secret prefix; raw keys are never returned.
+#[utoipa::path(
+    get,
+    path = "/accounts/{id}/api-keys",
+    params(
+        ("id" = Uuid, Path, description = "Account ID")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    responses(
+        (status = 200, description = "API keys of the account, newest first", body = [ApiKeyResponse]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse)
+    )
+)]
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let api_keys = state
+        .auth_service
+        .list_api_keys(&auth, id)
+        .await?;
+
+    Ok((StatusCode::OK, Json(api_keys)))
+}
+
+/// Rotate an API key
+///
+/// Mints a replacement with the same quota and expiry. The raw key is returned in
+/// this response only. The old key keeps working until `previous_key_expires_at`.
+#[utoipa::path(
+    post,
+    path = "/api-keys/{id}/rotate",
+    params(
+        ("id" = Uuid, Path, description = "API key ID")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    responses(
+        (status = 201, description = "Replacement key created", body = RotatedApiKeyResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+        (status = 409, description = "API key was already rotated", body = ErrorResponse)
+    )
+)]
+pub async fn rotate_api_key(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let rotated = state
+        .auth_service
+        .rotate_api_key(&auth, id)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(rotated)))
+}
@@ -5,32 +5,102 @@ use axum::{
 };
 use serde::Serialize;
 use utoipa::ToSchema;
-use crate::domain::errors::ApiError;
+use crate::domain::errors::{ApiError, DomainError, ErrorCode, FieldError, ServiceError};
 
 #[derive(Serialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
     pub code: String,
+    /// Stable machine-readable error kind; branch on this rather than on `error`
+    pub error_code: ErrorCode,
+    /// Every invalid field of the request; only present with `VALIDATION_FAILED`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+/// HTTP status and status-class code for an error, ignoring any attached error code
+fn status_of(err: &ApiError) -> (StatusCode, &'static str) {
+    match err {
+        ApiError::BadRequest(_) | ApiError::Validation(_) => (StatusCode::BAD_REQUEST, "BAD_REQUEST"),
+        ApiError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED"),
+        ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "FORBIDDEN"),
+        ApiError::NotFound(_) => (StatusCode::NOT_FOUND, "NOT_FOUND"),
+        ApiError::Conflict(_) => (StatusCode::CONFLICT, "CONFLICT"),
+        ApiError::PayloadTooLarge(_) => (StatusCode::PAYLOAD_TOO_LARGE, "PAYLOAD_TOO_LARGE"),
+        ApiError::TooManyRequests(_) => (StatusCode::TOO_MANY_REQUESTS, "TOO_MANY_REQUESTS"),
+        ApiError::InternalServerError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
+        ApiError::ServiceUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, "SERVICE_UNAVAILABLE"),
+        ApiError::Coded { inner, .. } => status_of(inner),
+    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, code) = match &self {
-            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BAD_REQUEST"),
-            ApiError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED"),
-            ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "FORBIDDEN"),
-            ApiError::NotFound(_) => (StatusCode::NOT_FOUND, "NOT_FOUND"),
-            ApiError::Conflict(_) => (StatusCode::CONFLICT, "CONFLICT"),
-            ApiError::TooManyRequests(_) => (StatusCode::TOO_MANY_REQUESTS, "TOO_MANY_REQUESTS"),
-            ApiError::InternalServerError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
-            ApiError::ServiceUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, "SERVICE_UNAVAILABLE"),
-        };
+        let (status, code) = status_of(&self);
 
         let body = Json(ErrorResponse {
             error: self.to_string(),
             code: code.to_string(),
+            error_code: self.error_code(),
+            fields: self.fields().to_vec(),
         });
 
         (status, body).into_response()
     }
 }
+
+/// Lets handlers return service and domain errors directly, with the same body
+/// and status they get once converted to an `ApiError`
+impl IntoResponse for ServiceError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+impl IntoResponse for DomainError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::errors::RepositoryError;
+
+    #[test]
+    fn test_service_errors_map_to_statuses() {
+        let cases = [
+            (ServiceError::Domain(DomainError::AccountNotFound("a".into())), StatusCode::NOT_FOUND),
+            (ServiceError::Domain(DomainError::SelfTransferNotAllowed), StatusCode::BAD_REQUEST),
+            (ServiceError::Repository(RepositoryError::NotFound("a".into())), StatusCode::NOT_FOUND),
+            (ServiceError::Repository(RepositoryError::DuplicateEntry("a".into())), StatusCode::CONFLICT),
+            (ServiceError::Repository(RepositoryError::VersionConflict("a".into())), StatusCode::CONFLICT),
+            (ServiceError::Repository(RepositoryError::ConstraintViolation("a".into())), StatusCode::BAD_REQUEST),
+            (ServiceError::Repository(RepositoryError::RetriesExhausted("a".into())), StatusCode::SERVICE_UNAVAILABLE),
+            (ServiceError::Repository(RepositoryError::DatabaseError("a".into())), StatusCode::INTERNAL_SERVER_ERROR),
+            (ServiceError::ValidationError("a".into()), StatusCode::BAD_REQUEST),
+            (ServiceError::AuthorizationError("a".into()), StatusCode::FORBIDDEN),
+            (ServiceError::ExternalService("a".into()), StatusCode::SERVICE_UNAVAILABLE),
+            (ServiceError::WebhookDeliveryError("a".into()), StatusCode::INTERNAL_SERVER_ERROR),
+            (ServiceError::ConfigurationError("a".into()), StatusCode::INTERNAL_SERVER_ERROR),
+            (ServiceError::InternalError("a".into()), StatusCode::INTERNAL_SERVER_ERROR),
+        ];
+
+        for (err, status) in cases {
+            let description = err.to_string();
+            assert_eq!(err.into_response().status(), status, "{}", description);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_service_error_body_matches_api_error() {
+        let body = |response: Response| async move {
+            axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()
+        };
+        let direct = ServiceError::Domain(DomainError::AccountNotFound("acc_123".into())).into_response();
+        let converted = ApiError::from(ServiceError::Domain(DomainError::AccountNotFound("acc_123".into()))).into_response();
+
+        assert_eq!(body(direct).await, body(converted).await);
+    }
+}
@@ -4,35 +4,218 @@ use axum::{
     response::IntoResponse,
     Json,
 };
+use chrono::Utc;
 use serde_json::json;
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
 
 use crate::application::AppState;
+use crate::infrastructure::database;
+use crate::infrastructure::health::{self, HealthStatus};
+use crate::infrastructure::metrics;
 
+/// Commit the binary was built from, taken from `GIT_SHA` at build time
+const GIT_SHA: &str = match option_env!("GIT_SHA") {
+    Some(sha) => sha,
+    None => "unknown",
+};
+
+/// Liveness check. Reports the running build so deploys can confirm what is live.
 #[utoipa::path(
     get,
     path = "/health",
     responses(
-        (status = 200, description = "Service is healthy"),
+        (status = 200, description = "Service is healthy, with version and build info"),
         (status = 503, description = "Service unavailable")
     )
 )]
 pub async fn health_check(
     State(state): State<AppState>,
 ) -> impl IntoResponse {
+    let mut body = json!({
+        "status": "ok",
+        "version": env!("CARGO_PKG_VERSION"),
+        "git_sha": GIT_SHA,
+        "started_at": state.started_at,
+        "uptime_seconds": (Utc::now() - state.started_at).num_seconds(),
+    });
+
     match state.account_service.health_check().await {
-        Ok(_) => (
+        Ok(_) => (StatusCode::OK, Json(body)),
+        Err(_) => {
+            body["status"] = json!("error");
+            body["message"] = json!("Database connectivity failure");
+            (StatusCode::SERVICE_UNAVAILABLE, Json(body))
+        }
+    }
+}
+
+/// How long the readiness probe waits for the database before reporting unavailable.
+const READINESS_DB_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Readiness probe: 200 only if the database answers `SELECT 1` in time.
+/// `/health` stays the liveness check. Both outcomes include a connection pool snapshot.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "Service is ready to receive traffic"),
+        (status = 503, description = "Database is unreachable or too slow")
+    )
+)]
+pub async fn readiness_check(
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let probe = probe_database(&state.pool).await;
+    let stats = database::pool_stats(&state.pool);
+    let pool = json!({
+        "size": stats.size,
+        "idle": stats.idle,
+        "in_use": stats.in_use,
+        "max": state.pool.options().get_max_connections()
+    });
+
+    match probe.error {
+        None => (
             StatusCode::OK,
             Json(json!({
-                "status": "ok",
-                "version": env!("CARGO_PKG_VERSION")
-            })),
-        ),
-        Err(_) => (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(json!({
-                "status": "error",
-                "message": "Database connectivity failure"
+                "status": "ready",
+                "database": {
+                    "status": "ok",
+                    "latency_ms": probe.latency_ms
+                },
+                "pool": pool
             })),
         ),
+        Some(message) => {
+            tracing::warn!("Readiness check failed: {}", message);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({
+                    "status": "unavailable",
+                    "database": {
+                        "status": "error",
+                        "latency_ms": probe.latency_ms,
+                        "message": message
+                    },
+                    "pool": pool
+                })),
+            )
+        }
     }
 }
+
+/// Outcome of a `SELECT 1` round trip
+struct DatabaseProbe {
+    latency_ms: f64,
+    error: Option<String>,
+}
+
+async fn probe_database(pool: &PgPool) -> DatabaseProbe {
+    let started = Instant::now();
+    let result = tokio::time::timeout(
+        READINESS_DB_TIMEOUT,
+        sqlx::query("SELECT 1").execute(pool),
+    )
+    .await;
+
+    DatabaseProbe {
+        latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+        error: match result {
+            Ok(Ok(_)) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(_) => Some(format!("Timed out after {}ms", READINESS_DB_TIMEOUT.as_millis())),
+        },
+    }
+}
+
+/// Aggregated health of every subsystem
+///
+/// Grades the database, connection pool, webhook deliveries, IP rate limiter and
+/// background workers as `healthy`, `degraded` or `unhealthy`; the overall status is
+/// the worst of them. Only an unhealthy service answers 503.
+#[utoipa::path(
+    get,
+    path = "/health/full",
+    responses(
+        (status = 200, description = "Service is healthy or degraded; see the per-subsystem report"),
+        (status = 503, description = "At least one subsystem is unhealthy")
+    )
+)]
+pub async fn full_health_check(
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let probe = probe_database(&state.pool).await;
+    let database_status = match probe.error {
+        None => HealthStatus::Healthy,
+        Some(_) => HealthStatus::Unhealthy,
+    };
+
+    let stats = database::pool_stats(&state.pool);
+    let max_connections = state.pool.options().get_max_connections();
+    let pool_status = health::pool_status(stats.in_use, max_connections);
+
+    let delivered = metrics::registry().counter_total(metrics::WEBHOOK_DELIVERY_SUCCESSES_TOTAL);
+    let failed = metrics::registry().counter_total(metrics::WEBHOOK_DELIVERY_FAILURES_TOTAL);
+    let webhook_status = health::webhook_status(delivered, failed);
+
+    let (tracked_ips, max_tracked_ips) = health::rate_limiter_usage().unwrap_or((0, 0));
+    let rate_limiter_status = health::rate_limiter_status(tracked_ips, max_tracked_ips);
+
+    let workers = health::worker_liveness();
+    let workers_status = HealthStatus::worst(workers.iter().map(|worker| worker.status));
+
+    let status = HealthStatus::worst([
+        database_status,
+        pool_status,
+        webhook_status,
+        rate_limiter_status,
+        workers_status,
+    ]);
+    if status != HealthStatus::Healthy {
+        tracing::warn!("Health check reports {:?}", status);
+    }
+
+    let code = if status == HealthStatus::Unhealthy {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+
+    (
+        code,
+        Json(json!({
+            "status": status,
+            "version": env!("CARGO_PKG_VERSION"),
+            "subsystems": {
+                "database": {
+                    "status": database_status,
+                    "latency_ms": probe.latency_ms,
+                    "message": probe.error
+                },
+                "pool": {
+                    "status": pool_status,
+                    "size": stats.size,
+                    "idle": stats.idle,
+                    "in_use": stats.in_use,
+                    "max": max_connections
+                },
+                "webhooks": {
+                    "status": webhook_status,
+                    "delivered": delivered,
+                    "failed": failed,
+                    "success_rate": health::webhook_success_rate(delivered, failed)
+                },
+                "rate_limiter": {
+                    "status": rate_limiter_status,
+                    "tracked_ips": tracked_ips,
+                    "max_tracked_ips": max_tracked_ips
+                },
+                "workers": {
+                    "status": workers_status,
+                    "workers": workers
+                }
+            }
+        })),
+    )
+}
@@ -0,0 +1,17 @@
+use axum::{http::header, response::IntoResponse};
+
+use crate::infrastructure::metrics;
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Metrics in the Prometheus text exposition format", content_type = "text/plain")
+    )
+)]
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, metrics::CONTENT_TYPE)],
+        metrics::registry().render(),
+    )
+}
@@ -1,6 +1,87 @@
 pub mod account;
+pub mod api_key;
 pub mod error;
 pub mod health;
+pub mod metrics;
 pub mod transaction;
 pub mod webhook;
 pub mod openapi;
+
+use axum::extract::rejection::JsonRejection;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::de::DeserializeOwned;
+
+use crate::application::dto::ValidateFields;
+use crate::domain::errors::ApiError;
+
+/// Unwrap a JSON request body, reporting malformed or invalid fields as 400.
+///
+/// axum's own rejection is a plain-text 422; this keeps the usual error body and
+/// names the offending field, e.g. an amount above the maximum. A body cut off
+/// by the request body limit stays a 413.
+pub fn json_body<T>(payload: Result<Json<T>, JsonRejection>) -> Result<T, ApiError> {
+    payload.map(|Json(body)| body).map_err(|rejection| {
+        if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::PayloadTooLarge(rejection.body_text())
+        } else {
+            ApiError::BadRequest(rejection.body_text())
+        }
+    })
+}
+
+/// Unwrap a JSON request body, reporting every invalid field in one 400.
+///
+/// Malformed JSON is rejected as in [`json_body`]. Otherwise the fields are
+/// checked first, so all problems come back together under `fields`.
+pub fn validated_body<T>(payload: Result<Json<serde_json::Value>, JsonRejection>) -> Result<T, ApiError>
+where
+    T: DeserializeOwned + ValidateFields,
+{
+    let body = json_body(payload)?;
+    let fields = T::field_errors(&body);
+    if !fields.is_empty() {
+        return Err(ApiError::Validation(fields));
+    }
+    serde_json::from_value(body).map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+/// Validate pagination parameters shared by all list endpoints.
+///
+/// Negative values are rejected; `limit` is clamped to `max_limit`.
+pub fn validate_pagination(limit: i64, offset: i64, max_limit: i64) -> Result<(i64, i64), ApiError> {
+    if limit < 0 {
+        return Err(ApiError::BadRequest("`limit` must not be negative".to_string()));
+    }
+    if offset < 0 {
+        return Err(ApiError::BadRequest("`offset` must not be negative".to_string()));
+    }
+    Ok((limit.min(max_limit), offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::dto::TransferRequest;
+
+    #[test]
+    fn test_validate_pagination() {
+        assert_eq!(validate_pagination(10, 0, 100).unwrap(), (10, 0));
+        assert_eq!(validate_pagination(100_000_000, 20, 100).unwrap(), (100, 20));
+        assert!(matches!(validate_pagination(-1, 0, 100), Err(ApiError::BadRequest(_))));
+        assert!(matches!(validate_pagination(10, -5, 100), Err(ApiError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_validated_body_reports_every_field() {
+        let body = serde_json::json!({
+            "from_account_id": "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+            "amount": "ten",
+        });
+        let Err(ApiError::Validation(fields)) = validated_body::<TransferRequest>(Ok(Json(body))) else {
+            panic!("expected a validation error");
+        };
+        let names: Vec<_> = fields.iter().map(|field| field.field.as_str()).collect();
+        assert_eq!(names, ["to_account_id", "amount"]);
+    }
+}
@@ -1,46 +1,116 @@
 use utoipa::{Modify, OpenApi};
 use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
-use crate::application::dto::account_dto::{AccountResponse, CreateAccountRequest};
+use crate::application::dto::account_dto::{
+    AccountResponse, BalanceSnapshotResponse, BatchAccountLookupRequest, BatchAccountLookupResponse, CreateAccountRequest, TransactionLimitsResponse, UpdateAccountRequest,
+    UpdateTransactionLimitsRequest,
+};
+use crate::application::dto::api_key_dto::{ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse, RotatedApiKeyResponse};
 use crate::application::dto::transaction_dto::{
-    DepositRequest, TransactionResponse, TransferRequest, WithdrawRequest,
+    AccountStatsResponse, AuthorizeRequest, BatchOperation, BatchPreviewRequest, BatchPreviewResponse, DepositRequest,
+    ProjectedBalanceResponse, TransactionResponse, TransferDryRunResponse, TransferRequest, VerificationRequest,
+    WithdrawRequest,
+};
+use crate::application::dto::pagination_dto::Paginated;
+use crate::application::dto::webhook_dto::{
+    CreateWebhookRequest, UpdateWebhookRequest, WebhookResponse, WebhookSecretResponse, WebhookTestResponse,
 };
-use crate::application::dto::webhook_dto::{CreateWebhookRequest, WebhookResponse};
 use crate::domain::value_objects::webhook_event::WebhookEvent;
+use crate::domain::value_objects::account_status::AccountStatus;
+use crate::domain::value_objects::authorization_status::AuthorizationStatus;
+use crate::domain::value_objects::transaction_status::TransactionStatus;
+use crate::domain::value_objects::webhook_status::WebhookStatus;
+use crate::domain::errors::{ErrorCode, FieldError};
 use crate::presentation::api::error::ErrorResponse;
 
 use super::account;
+use super::api_key;
 use super::transaction;
 use super::webhook;
 use super::health;
+use super::metrics;
 
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
         health::health_check,
+        health::readiness_check,
+        health::full_health_check,
+        metrics::metrics,
         account::create_account,
         account::get_account,
+        account::get_accounts,
+        account::update_account,
         account::list_accounts,
+        account::restore_account,
+        account::freeze_account,
+        account::unfreeze_account,
+        account::update_limits,
+        account::get_balance_history,
+        api_key::create_api_key,
+        api_key::list_api_keys,
+        api_key::rotate_api_key,
         transaction::deposit,
         transaction::withdraw,
         transaction::transfer,
+        transaction::verify,
+        transaction::authorize,
+        transaction::capture,
+        transaction::void,
+        transaction::preview_batch,
         transaction::get_history,
+        transaction::export_csv,
+        transaction::account_stats,
+        transaction::get_transaction,
         webhook::create_webhook,
         webhook::list_webhooks,
+        webhook::update_webhook,
         webhook::delete_webhook,
+        webhook::rotate_webhook_secret,
+        webhook::test_webhook,
+        webhook::verify_webhook,
     ),
     components(
         schemas(
             CreateAccountRequest,
+            BatchAccountLookupRequest,
+            BatchAccountLookupResponse,
+            UpdateAccountRequest,
+            UpdateTransactionLimitsRequest,
+            TransactionLimitsResponse,
             AccountResponse,
+            AccountStatus,
+            WebhookStatus,
+            BalanceSnapshotResponse,
+            CreateApiKeyRequest,
+            ApiKeyResponse,
+            CreatedApiKeyResponse,
+            RotatedApiKeyResponse,
             DepositRequest,
             WithdrawRequest,
             TransferRequest,
+            VerificationRequest,
+            AuthorizeRequest,
+            AuthorizationStatus,
+            TransactionStatus,
             TransactionResponse,
+            BatchOperation,
+            BatchPreviewRequest,
+            BatchPreviewResponse,
+            ProjectedBalanceResponse,
+            AccountStatsResponse,
+            TransferDryRunResponse,
+            Paginated<TransactionResponse>,
+            Paginated<AccountResponse>,
             CreateWebhookRequest,
             WebhookResponse,
+            UpdateWebhookRequest,
+            WebhookSecretResponse,
+            WebhookTestResponse,
             WebhookEvent,
-            ErrorResponse
+            ErrorCode,
+            ErrorResponse,
+            FieldError
         )
     ),
     tags(
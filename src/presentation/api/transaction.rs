@@ -1,26 +1,135 @@
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    body::Body,
+    extract::{rejection::JsonRejection, Path, Query, State},
+    Extension,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 use uuid::Uuid;
 
-use crate::application::dto::{DepositRequest, TransferRequest, WithdrawRequest, TransactionResponse};
+use crate::application::dto::{
+    AccountStatsResponse, AuthorizeRequest, BatchPreviewRequest, BatchPreviewResponse, DepositRequest, Paginated, TransferDryRunResponse,
+    TransferRequest, WithdrawRequest, TransactionResponse, VerificationRequest,
+};
 use crate::application::AppState;
+use crate::application::services::AuthPrincipal;
 use crate::domain::errors::ApiError;
+use crate::domain::repositories::TransactionFilter;
+use crate::domain::value_objects::{Money, TransactionType};
 use crate::presentation::api::error::ErrorResponse;
+use crate::presentation::api::{json_body, validate_pagination, validated_body};
+use crate::presentation::middleware::request_id::RequestId;
 
 use utoipa::IntoParams;
 
 #[derive(Deserialize, IntoParams)]
 pub struct HistoryQuery {
-    pub account_id: Uuid,
+    /// Account whose history to list; required unless `all_accounts` is set
+    pub account_id: Option<Uuid>,
+    /// List the combined history of every account the API key is authorized for
+    #[serde(default)]
+    pub all_accounts: bool,
     #[serde(default = "default_limit")]
     pub limit: i64,
     #[serde(default = "default_offset")]
     pub offset: i64,
+    /// Only include transactions created at or after this instant
+    pub from: Option<DateTime<Utc>>,
+    /// Only include transactions created at or before this instant
+    pub to: Option<DateTime<Utc>>,
+    /// Only include transactions of this type (`credit`, `debit`, `transfer`, `verification` or `authorization`)
+    #[param(value_type = Option<String>)]
+    pub transaction_type: Option<TransactionType>,
+    /// Also include transactions moved to the archive by the retention job
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ExportQuery {
+    /// Only include transactions created at or after this instant
+    pub from: Option<DateTime<Utc>>,
+    /// Only include transactions created at or before this instant
+    pub to: Option<DateTime<Utc>>,
+    /// Only include transactions of this type (`credit`, `debit`, `transfer`, `verification` or `authorization`)
+    #[param(value_type = Option<String>)]
+    pub transaction_type: Option<TransactionType>,
+    /// Also include transactions moved to the archive by the retention job
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct StatsQuery {
+    /// Only include transactions created at or after this instant
+    pub from: Option<DateTime<Utc>>,
+    /// Only include transactions created at or before this instant
+    pub to: Option<DateTime<Utc>>,
+    /// Also include transactions moved to the archive by the retention job
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ExpandQuery {
+    /// Set to `account` to embed the affected account, as it is after the transaction
+    pub expand: Option<String>,
+}
+
+impl ExpandQuery {
+    /// Whether the account was asked for; unknown fields are rejected before anything is committed
+    fn account(&self) -> Result<bool, ApiError> {
+        let mut account = false;
+        for field in self.expand.iter().flat_map(|expand| expand.split(',')) {
+            match field.trim() {
+                "account" => account = true,
+                "" => {}
+                other => return Err(ApiError::BadRequest(format!("Cannot expand `{}`", other))),
+            }
+        }
+        Ok(account)
+    }
+}
+
+/// Embed the account a transaction acted on when `?expand=account` was asked for.
+///
+/// Transfers and debits report the source account, credits the destination.
+async fn expand(
+    state: &AppState,
+    expand_account: bool,
+    mut transaction: TransactionResponse,
+) -> Result<TransactionResponse, ApiError> {
+    if expand_account
+        && let Some(account_id) = transaction.from_account_id.or(transaction.to_account_id)
+    {
+        let account = state.account_service.get_account(account_id).await?;
+        transaction.account = Some(account);
+    }
+    Ok(transaction)
+}
+
+/// Rows fetched per query while exporting
+const EXPORT_PAGE_SIZE: i64 = 500;
+
+const CSV_HEADER: &str = "id,type,from,to,amount,currency,created_at\n";
+
+/// One CSV line; every field is a UUID, enum, number or timestamp, so none needs quoting
+fn csv_row(transaction: &TransactionResponse) -> String {
+    let account = |id: Option<Uuid>| id.map(|id| id.to_string()).unwrap_or_default();
+    format!(
+        "{},{},{},{},{},{},{}\n",
+        transaction.id,
+        transaction.transaction_type,
+        account(transaction.from_account_id),
+        account(transaction.to_account_id),
+        transaction.amount,
+        Money::CURRENCY,
+        transaction.created_at.to_rfc3339(),
+    )
 }
 
 fn default_limit() -> i64 {
@@ -31,76 +140,445 @@ fn default_offset() -> i64 {
     0
 }
 
+/// Header alternative to the `idempotency_key` body field
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// The request's idempotency key: the `idempotency_key` body field, else the
+/// `Idempotency-Key` header. Sending both is fine only if they are equal.
+fn idempotency_key(headers: &HeaderMap, body_key: Option<String>) -> Result<Option<String>, ApiError> {
+    let header_key = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .map(str::to_string)
+                .map_err(|_| ApiError::BadRequest("Idempotency-Key header must be visible ASCII".to_string()))
+        })
+        .transpose()?;
+
+    match (body_key, header_key) {
+        (Some(body_key), Some(header_key)) if body_key != header_key => Err(ApiError::BadRequest(
+            "Idempotency-Key header does not match the idempotency_key field".to_string(),
+        )),
+        (body_key, header_key) => Ok(body_key.or(header_key)),
+    }
+}
+
+/// 201 with a `Location` pointing at the new transaction
+fn created(transaction: TransactionResponse) -> impl IntoResponse {
+    let location = format!("/transactions/{}", transaction.id);
+    (StatusCode::CREATED, [(header::LOCATION, location)], Json(transaction))
+}
+
 /// Deposit funds
 #[utoipa::path(
     post,
     path = "/transactions/deposit",
+    params(
+        ExpandQuery,
+        ("Idempotency-Key" = Option<String>, Header, description = "Alternative to the `idempotency_key` field; must match it if both are sent")
+    ),
     request_body = DepositRequest,
     responses(
-        (status = 200, description = "Deposit successful", body = TransactionResponse),
+        (status = 201, description = "Deposit successful", body = TransactionResponse),
         (status = 400, description = "Bad request", body = ErrorResponse)
     )
 )]
 pub async fn deposit(
     State(state): State<AppState>,
-    Json(payload): Json<DepositRequest>,
+    Query(params): Query<ExpandQuery>,
+    request_id: Option<Extension<RequestId>>,
+    headers: HeaderMap,
+    payload: Result<Json<serde_json::Value>, JsonRejection>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let expand_account = params.account()?;
+    let mut payload: DepositRequest = validated_body(payload)?;
+    payload.idempotency_key = idempotency_key(&headers, payload.idempotency_key.take())?;
     let transaction = state
         .transaction_service
-        .deposit(payload)
-        .await
-        .map_err(ApiError::from)?;
+        .deposit(payload, request_id.map(|Extension(RequestId(id))| id))
+        .await?;
+    let transaction = expand(&state, expand_account, transaction).await?;
 
-    Ok((StatusCode::OK, Json(transaction)))
+    Ok(created(transaction))
 }
 
 /// Withdraw funds
 #[utoipa::path(
     post,
     path = "/transactions/withdraw",
+    params(
+        ExpandQuery,
+        ("Idempotency-Key" = Option<String>, Header, description = "Alternative to the `idempotency_key` field; must match it if both are sent")
+    ),
     request_body = WithdrawRequest,
     responses(
-        (status = 200, description = "Withdraw successful", body = TransactionResponse),
+        (status = 201, description = "Withdraw successful", body = TransactionResponse),
         (status = 400, description = "Insufficient funds or bad request", body = ErrorResponse)
     )
 )]
 pub async fn withdraw(
     State(state): State<AppState>,
-    Json(payload): Json<WithdrawRequest>,
+    Query(params): Query<ExpandQuery>,
+    request_id: Option<Extension<RequestId>>,
+    headers: HeaderMap,
+    payload: Result<Json<serde_json::Value>, JsonRejection>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let expand_account = params.account()?;
+    let mut payload: WithdrawRequest = validated_body(payload)?;
+    payload.idempotency_key = idempotency_key(&headers, payload.idempotency_key.take())?;
     let transaction = state
         .transaction_service
-        .withdraw(payload)
-        .await
-        .map_err(ApiError::from)?;
+        .withdraw(payload, request_id.map(|Extension(RequestId(id))| id))
+        .await?;
+    let transaction = expand(&state, expand_account, transaction).await?;
 
-    Ok((StatusCode::OK, Json(transaction)))
+    Ok(created(transaction))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct DryRunQuery {
+    /// Only check whether the transfer would succeed and project the balances; nothing is persisted
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 /// Transfer funds
+///
+/// With `?dry_run=true` the transfer is checked and applied inside a database transaction
+/// that is rolled back. The response says whether it would succeed and projects both
+/// balances; no transaction is recorded and no webhook is sent.
 #[utoipa::path(
     post,
     path = "/transactions/transfer",
+    params(
+        ExpandQuery,
+        DryRunQuery,
+        ("Idempotency-Key" = Option<String>, Header, description = "Alternative to the `idempotency_key` field; must match it if both are sent")
+    ),
     request_body = TransferRequest,
     responses(
-        (status = 200, description = "Transfer successful", body = TransactionResponse),
+        (status = 200, description = "Dry run: whether the transfer would succeed", body = TransferDryRunResponse),
+        (status = 201, description = "Transfer successful", body = TransactionResponse),
         (status = 400, description = "Insufficient funds or bad request", body = ErrorResponse)
     )
 )]
 pub async fn transfer(
     State(state): State<AppState>,
-    Json(payload): Json<TransferRequest>,
+    Query(params): Query<ExpandQuery>,
+    Query(dry_run): Query<DryRunQuery>,
+    request_id: Option<Extension<RequestId>>,
+    headers: HeaderMap,
+    payload: Result<Json<serde_json::Value>, JsonRejection>,
+) -> Result<Response, ApiError> {
+    let expand_account = params.account()?;
+    let mut payload: TransferRequest = validated_body(payload)?;
+    payload.idempotency_key = idempotency_key(&headers, payload.idempotency_key.take())?;
+    if dry_run.dry_run {
+        let projection = state.transaction_service.simulate_transfer(payload).await?;
+        return Ok((StatusCode::OK, Json(projection)).into_response());
+    }
+
+    let transaction = state
+        .transaction_service
+        .transfer(payload, request_id.map(|Extension(RequestId(id))| id))
+        .await?;
+    let transaction = expand(&state, expand_account, transaction).await?;
+
+    Ok(created(transaction).into_response())
+}
+
+/// Record a zero-amount verification transaction
+///
+/// Marks an account as live without moving money. Requires `ALLOW_ZERO_AMOUNT_TRANSACTIONS=true`.
+#[utoipa::path(
+    post,
+    path = "/transactions/verify",
+    params(
+        ExpandQuery,
+        ("Idempotency-Key" = Option<String>, Header, description = "Alternative to the `idempotency_key` field; must match it if both are sent")
+    ),
+    request_body = VerificationRequest,
+    responses(
+        (status = 201, description = "Verification recorded", body = TransactionResponse),
+        (status = 400, description = "Zero-amount transactions are disabled or the account is not active", body = ErrorResponse)
+    )
+)]
+pub async fn verify(
+    State(state): State<AppState>,
+    Query(params): Query<ExpandQuery>,
+    request_id: Option<Extension<RequestId>>,
+    headers: HeaderMap,
+    Json(mut payload): Json<VerificationRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let expand_account = params.account()?;
+    payload.idempotency_key = idempotency_key(&headers, payload.idempotency_key.take())?;
+    let transaction = state
+        .transaction_service
+        .verify(payload, request_id.map(|Extension(RequestId(id))| id))
+        .await?;
+    let transaction = expand(&state, expand_account, transaction).await?;
+
+    Ok(created(transaction))
+}
+
+/// Authorize a payment
+///
+/// Holds the funds on the account without debiting them. Held funds cannot be withdrawn
+/// or transferred until the authorization is captured or voided.
+#[utoipa::path(
+    post,
+    path = "/transactions/authorize",
+    params(
+        ExpandQuery,
+        ("Idempotency-Key" = Option<String>, Header, description = "Alternative to the `idempotency_key` field; must match it if both are sent")
+    ),
+    request_body = AuthorizeRequest,
+    responses(
+        (status = 201, description = "Funds held", body = TransactionResponse),
+        (status = 400, description = "Insufficient funds or bad request", body = ErrorResponse)
+    )
+)]
+pub async fn authorize(
+    State(state): State<AppState>,
+    Query(params): Query<ExpandQuery>,
+    headers: HeaderMap,
+    payload: Result<Json<serde_json::Value>, JsonRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let expand_account = params.account()?;
+    let mut payload: AuthorizeRequest = validated_body(payload)?;
+    payload.idempotency_key = idempotency_key(&headers, payload.idempotency_key.take())?;
+    let transaction = state
+        .transaction_service
+        .authorize(payload)
+        .await?;
+    let transaction = expand(&state, expand_account, transaction).await?;
+
+    Ok(created(transaction))
+}
+
+/// Capture an authorization
+///
+/// Debits the held funds from the account.
+#[utoipa::path(
+    post,
+    path = "/transactions/{id}/capture",
+    params(
+        ("id" = Uuid, Path, description = "Authorization transaction ID"),
+        ExpandQuery
+    ),
+    responses(
+        (status = 200, description = "Authorization captured", body = TransactionResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Authorization is not pending", body = ErrorResponse)
+    )
+)]
+pub async fn capture(
+    State(state): State<AppState>,
+    Query(params): Query<ExpandQuery>,
+    request_id: Option<Extension<RequestId>>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let expand_account = params.account()?;
+    let transaction = state
+        .transaction_service
+        .capture(id, request_id.map(|Extension(RequestId(request_id))| request_id))
+        .await?;
+    let transaction = expand(&state, expand_account, transaction).await?;
+
+    Ok((StatusCode::OK, Json(transaction)))
+}
+
+/// Void an authorization
+///
+/// Releases the held funds without debiting anything.
+#[utoipa::path(
+    post,
+    path = "/transactions/{id}/void",
+    params(
+        ("id" = Uuid, Path, description = "Authorization transaction ID"),
+        ExpandQuery
+    ),
+    responses(
+        (status = 200, description = "Authorization voided", body = TransactionResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 409, description = "Authorization is not pending", body = ErrorResponse)
+    )
+)]
+pub async fn void(
+    State(state): State<AppState>,
+    Query(params): Query<ExpandQuery>,
+    Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let expand_account = params.account()?;
     let transaction = state
         .transaction_service
-        .transfer(payload)
-        .await
-        .map_err(ApiError::from)?;
+        .void(id)
+        .await?;
+    let transaction = expand(&state, expand_account, transaction).await?;
 
     Ok((StatusCode::OK, Json(transaction)))
 }
 
+/// Get transaction by ID
+#[utoipa::path(
+    get,
+    path = "/transactions/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Transaction ID"),
+        ExpandQuery
+    ),
+    responses(
+        (status = 200, description = "Transaction details", body = TransactionResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_transaction(
+    State(state): State<AppState>,
+    Query(params): Query<ExpandQuery>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let expand_account = params.account()?;
+    let transaction = state
+        .transaction_service
+        .get_transaction(id)
+        .await?;
+    let transaction = expand(&state, expand_account, transaction).await?;
+
+    Ok((StatusCode::OK, Json(transaction)))
+}
+
+/// Preview a batch of operations
+///
+/// Applies the operations in order inside a database transaction that is rolled back,
+/// returning each affected account's projected balance. Nothing is persisted.
+#[utoipa::path(
+    post,
+    path = "/transactions/batch/preview",
+    request_body = BatchPreviewRequest,
+    responses(
+        (status = 200, description = "Projected balances and whether the batch would succeed", body = BatchPreviewResponse),
+        (status = 400, description = "Malformed operation", body = ErrorResponse)
+    )
+)]
+pub async fn preview_batch(
+    State(state): State<AppState>,
+    payload: Result<Json<BatchPreviewRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let payload = json_body(payload)?;
+    let preview = state
+        .transaction_service
+        .preview_batch(payload)
+        .await?;
+
+    Ok((StatusCode::OK, Json(preview)))
+}
+
+/// Download an account's transaction history as CSV
+///
+/// The body is streamed page by page, so large histories are never held in memory.
+#[utoipa::path(
+    get,
+    path = "/accounts/{id}/transactions.csv",
+    params(
+        ("id" = Uuid, Path, description = "Account ID"),
+        ExportQuery
+    ),
+    responses(
+        (status = 200, description = "CSV with columns id,type,from,to,amount,currency,created_at", content_type = "text/csv", body = String),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse)
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn export_csv(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    if let (Some(from), Some(to)) = (params.from, params.to)
+        && from > to
+    {
+        return Err(ApiError::BadRequest("`from` must not be after `to`".to_string()));
+    }
+
+    // Fail with a status code before the 200 and the header row are sent
+    state.account_service.get_account(id).await?;
+
+    let filter = TransactionFilter {
+        transaction_type: params.transaction_type,
+        from: params.from,
+        to: params.to,
+        include_archived: params.include_archived,
+    };
+    let pages = state.transaction_service.export_history(id, filter, EXPORT_PAGE_SIZE);
+
+    // An error mid-stream aborts the response, so a failed export never passes for a complete file
+    let rows = ReceiverStream::new(pages)
+        .map(|page| page.map(|transactions| transactions.iter().map(csv_row).collect::<String>()));
+    let body = Body::from_stream(tokio_stream::once(Ok(CSV_HEADER.to_string())).chain(rows));
+
+    let disposition = format!("attachment; filename=\"transactions-{}.csv\"", id);
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+/// Totals over an account's transactions
+///
+/// Amounts count completed transactions only; an account without any reports zeros.
+#[utoipa::path(
+    get,
+    path = "/accounts/{id}/stats",
+    params(
+        ("id" = Uuid, Path, description = "Account ID"),
+        StatsQuery
+    ),
+    responses(
+        (status = 200, description = "Account totals", body = AccountStatsResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse)
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn account_stats(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<StatsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    if let (Some(from), Some(to)) = (params.from, params.to)
+        && from > to
+    {
+        return Err(ApiError::BadRequest("`from` must not be after `to`".to_string()));
+    }
+
+    // An unknown account would otherwise look like one without transactions
+    state.account_service.get_account(id).await?;
+
+    let filter = TransactionFilter {
+        transaction_type: None,
+        from: params.from,
+        to: params.to,
+        include_archived: params.include_archived,
+    };
+    let stats = state.transaction_service.account_stats(id, filter).await?;
+
+    Ok((StatusCode::OK, Json(stats)))
+}
+
 /// Get transaction history
+///
+/// Lists one account's history, or with `all_accounts=true` the combined history of every
+/// account the API key is authorized for. A transfer between two of them appears once.
 #[utoipa::path(
     get,
     path = "/transactions/history",
@@ -108,19 +586,52 @@ pub async fn transfer(
         HistoryQuery
     ),
     responses(
-        (status = 200, description = "Transaction history", body = [TransactionResponse]),
-        (status = 400, description = "Bad request", body = ErrorResponse)
+        (status = 200, description = "Page of transaction history", body = Paginated<TransactionResponse>),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
     )
 )]
 pub async fn get_history(
     State(state): State<AppState>,
+    principal: Option<Extension<AuthPrincipal>>,
     Query(params): Query<HistoryQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let history = state
-        .transaction_service
-        .get_history(params.account_id, params.limit, params.offset)
-        .await
-        .map_err(ApiError::from)?;
+    let (limit, offset) = validate_pagination(params.limit, params.offset, state.max_page_size)?;
+
+    if let (Some(from), Some(to)) = (params.from, params.to)
+        && from > to
+    {
+        return Err(ApiError::BadRequest("`from` must not be after `to`".to_string()));
+    }
+
+    let filter = TransactionFilter {
+        transaction_type: params.transaction_type,
+        from: params.from,
+        to: params.to,
+        include_archived: params.include_archived,
+    };
+
+    let history = match (params.account_id, params.all_accounts) {
+        (Some(account_id), false) => {
+            state
+                .transaction_service
+                .get_history(account_id, filter, limit, offset)
+                .await?
+        }
+        (None, true) => {
+            let Some(Extension(principal)) = principal else {
+                return Err(ApiError::Unauthorized("An API key is required".to_string()));
+            };
+            state
+                .transaction_service
+                .get_history_for_accounts(&principal.account_ids, filter, limit, offset)
+                .await?
+        }
+        (Some(_), true) => {
+            return Err(ApiError::BadRequest("Pass either `account_id` or `all_accounts`, not both".to_string()));
+        }
+        (None, false) => return Err(ApiError::BadRequest("`account_id` is required".to_string())),
+    };
 
     Ok((StatusCode::OK, Json(history)))
 }
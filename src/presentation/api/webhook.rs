@@ -1,19 +1,28 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
     response::IntoResponse,
     Json, Extension,
 };
+use serde::Deserialize;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
-use crate::application::dto::{CreateWebhookRequest, WebhookResponse};
+use crate::application::dto::{
+    CreateWebhookRequest, UpdateWebhookRequest, WebhookResponse, WebhookSecretResponse, WebhookTestResponse,
+};
 use crate::application::AppState;
 use crate::domain::entities::Webhook;
 use crate::domain::repositories::WebhookRepository;
-use crate::domain::errors::{ApiError, ServiceError};
+use crate::domain::errors::{ApiError, RepositoryError};
+use crate::domain::services::DeliveryContext;
+use crate::infrastructure::http_client::reqwest_webhook_dispatcher::VERIFICATION_EVENT;
 use crate::application::services::AuthPrincipal;
 use crate::presentation::api::error::ErrorResponse;
 
+/// How long a rotated-out secret keeps signing deliveries alongside the new one
+const SECRET_ROTATION_GRACE_HOURS: i64 = 24;
+
 #[utoipa::path(
     post,
     path = "/webhooks",
@@ -22,6 +31,7 @@ use crate::presentation::api::error::ErrorResponse;
         ("api_key" = [])
     ),
     responses(
+        (status = 200, description = "The account already has this webhook; it is returned unchanged", body = WebhookResponse),
         (status = 201, description = "Webhook created", body = WebhookResponse),
         (status = 400, description = "Bad request", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse)
@@ -32,17 +42,39 @@ pub async fn create_webhook(
     Extension(_auth): Extension<AuthPrincipal>,
     Json(payload): Json<CreateWebhookRequest>,
 ) ->  Result<impl IntoResponse, ApiError> {
-    let webhook = Webhook::new(_auth.account_id, payload.url, payload.event)
+    state.webhook_url_policy.validate(&payload.url).await?;
+    let mut webhook = Webhook::new(_auth.account_id, payload.url, payload.event)
+        .and_then(|webhook| webhook.with_timeout_ms(payload.timeout_ms))
+        .map(|webhook| webhook.with_gzip(payload.accepts_gzip))
         .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if state.require_webhook_verification {
+        webhook.require_verification();
+    }
 
-    let created_webhook = state
+    // Creating the same subscription again is a no-op, so setup scripts can be re-run
+    let (mut created_webhook, inserted) = state
         .webhook_repository
-        .create(webhook)
-        .await
-        .map_err(ServiceError::from)
-        .map_err(ApiError::from)?;
+        .create_or_get(webhook)
+        .await?;
+    let status = if inserted {
+        verify_pending(&state, &mut created_webhook).await?;
+        StatusCode::CREATED
+    } else {
+        tracing::info!(
+            "Webhook {} already subscribes {} to {}, returning it",
+            created_webhook.id, created_webhook.url, created_webhook.event
+        );
+        StatusCode::OK
+    };
+
+    let secret = created_webhook.secret.clone();
+    let location = format!("/webhooks/{}", created_webhook.id);
+    let response = WebhookResponse {
+        secret: Some(secret),
+        ..WebhookResponse::from(created_webhook)
+    };
 
-    Ok((StatusCode::CREATED, Json(WebhookResponse::from(created_webhook))))
+    Ok((status, [(header::LOCATION, location)], Json(response)))
 }
 
 #[utoipa::path(
@@ -63,40 +95,306 @@ pub async fn list_webhooks(
     let webhooks = state
         .webhook_repository
         .list_by_account(_auth.account_id)
-        .await
-        .map_err(ServiceError::from)
-        .map_err(ApiError::from)?;
+        .await?;
 
     let response: Vec<WebhookResponse> = webhooks.into_iter().map(WebhookResponse::from).collect();
 
     Ok((StatusCode::OK, Json(response)))
 }
 
+/// Update a webhook's URL or event
+///
+/// The webhook keeps its id and signing secret.
 #[utoipa::path(
-    delete,
+    patch,
     path = "/webhooks/{id}",
     params(
         ("id" = Uuid, Path, description = "Webhook ID")
     ),
+    request_body = UpdateWebhookRequest,
     security(
         ("api_key" = [])
     ),
     responses(
-        (status = 204, description = "Webhook deleted"),
-        (status = 401, description = "Unauthorized", body = ErrorResponse)
+        (status = 200, description = "Webhook updated", body = WebhookResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+        (status = 409, description = "The account already has a webhook for this URL and event", body = ErrorResponse)
     )
 )]
-pub async fn delete_webhook(
+pub async fn update_webhook(
     State(state): State<AppState>,
-    Extension(_auth): Extension<AuthPrincipal>,
+    Extension(auth): Extension<AuthPrincipal>,
     Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhookRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let mut webhook = state
+        .webhook_repository
+        .find_by_id(id)
+        .await?;
+
+    // Other accounts' webhooks are indistinguishable from missing ones
+    if webhook.account_id != auth.account_id {
+        return Err(ApiError::NotFound("Webhook not found".to_string()));
+    }
+    if let Some(url) = &payload.url {
+        state.webhook_url_policy.validate(url).await?;
+    }
+
+    let url_changed = payload.url.as_ref().is_some_and(|url| *url != webhook.url);
+    webhook
+        .update(payload.url, payload.event)
+        .map_err(ApiError::BadRequest)?;
+    // A new URL has to prove itself like a new webhook
+    if url_changed && state.require_webhook_verification {
+        webhook.require_verification();
+    }
     state
         .webhook_repository
-        .delete(id)
+        .update(&webhook)
         .await
-        .map_err(ServiceError::from)
-        .map_err(ApiError::from)?;
+        .inspect_err(|e| {
+            if let RepositoryError::DuplicateEntry(_) = e {
+                tracing::warn!("Update of webhook {} conflicts with another subscription", webhook.id);
+            }
+        })?;
+    verify_pending(&state, &mut webhook).await?;
+
+    Ok((StatusCode::OK, Json(WebhookResponse::from(webhook))))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct DeleteWebhookQuery {
+    /// Remove the webhook and its delivery history for good instead of disabling it
+    #[serde(default)]
+    pub purge: bool,
+}
+
+/// Disable a webhook
+///
+/// The webhook stops receiving events and disappears from listings, but its delivery
+/// history is kept for audits. `?purge=true` removes it permanently instead.
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Webhook ID"),
+        DeleteWebhookQuery
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    responses(
+        (status = 204, description = "Webhook disabled, or purged with `?purge=true`"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<DeleteWebhookQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    // A disabled webhook can still be purged, so look it up whether or not it is live
+    let webhook = if params.purge {
+        state.webhook_repository.find_by_id_including_deleted(id).await?
+    } else {
+        state.webhook_repository.find_by_id(id).await?
+    };
+
+    // Other accounts' webhooks are indistinguishable from missing ones
+    if webhook.account_id != auth.account_id {
+        return Err(ApiError::NotFound("Webhook not found".to_string()));
+    }
+
+    if params.purge {
+        state.webhook_repository.purge(id).await?;
+    } else {
+        state.webhook_repository.delete(id).await?;
+    }
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// Rotate a webhook's signing secret
+///
+/// Returns the new secret; it cannot be retrieved again. The previous secret keeps
+/// signing deliveries alongside the new one for 24 hours.
+#[utoipa::path(
+    post,
+    path = "/webhooks/{id}/rotate-secret",
+    params(
+        ("id" = Uuid, Path, description = "Webhook ID")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Secret rotated", body = WebhookSecretResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    )
+)]
+pub async fn rotate_webhook_secret(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut webhook = state
+        .webhook_repository
+        .find_by_id(id)
+        .await?;
+
+    // Other accounts' webhooks are indistinguishable from missing ones
+    if webhook.account_id != auth.account_id {
+        return Err(ApiError::NotFound("Webhook not found".to_string()));
+    }
+
+    webhook.rotate_secret(chrono::Duration::hours(SECRET_ROTATION_GRACE_HOURS));
+    state
+        .webhook_repository
+        .update_secret(&webhook)
+        .await?;
+
+    Ok((StatusCode::OK, Json(WebhookSecretResponse::from(webhook))))
+}
+
+/// Send a test ping to a webhook
+///
+/// Delivers a signed `ping` event once, without retries, and reports the status code
+/// and latency observed from the receiver. A failed ping still returns 200; check
+/// `success`, `status_code` and `error`.
+#[utoipa::path(
+    post,
+    path = "/webhooks/{id}/test",
+    params(
+        ("id" = Uuid, Path, description = "Webhook ID")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Ping sent", body = WebhookTestResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    )
+)]
+pub async fn test_webhook(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let webhook = state
+        .webhook_repository
+        .find_by_id(id)
+        .await?;
+
+    if webhook.account_id != auth.account_id {
+        return Err(ApiError::NotFound("Webhook not found".to_string()));
+    }
+
+    let payload = serde_json::json!({
+        "event": "ping",
+        "webhook_id": webhook.id,
+        "timestamp": chrono::Utc::now(),
+    });
+    let context = DeliveryContext {
+        webhook_id: webhook.id,
+        event: "ping".to_string(),
+        request_id: None,
+        previous_secret: webhook.active_previous_secret().map(str::to_string),
+        timeout: webhook.timeout(),
+        accepts_gzip: webhook.accepts_gzip,
+        // Pings are not events, so they do not consume a sequence number
+        sequence: None,
+    };
+
+    let attempt = state
+        .webhook_dispatcher
+        .dispatch_once(&webhook.url, &payload, &webhook.secret, &context)
+        .await
+        .map_err(ApiError::InternalServerError)?;
+
+    let response = WebhookTestResponse {
+        webhook_id: webhook.id,
+        success: attempt.is_success(),
+        status_code: attempt.status,
+        latency_ms: attempt.latency_ms,
+        error: attempt.error,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Retry the verification handshake of a pending webhook
+///
+/// Posts a `webhook.verification` event carrying a `challenge` token; the endpoint is
+/// activated once it answers 2xx with the token (bare or as `{"challenge": ...}`).
+/// Returns the webhook with its resulting `status`.
+#[utoipa::path(
+    post,
+    path = "/webhooks/{id}/verify",
+    params(
+        ("id" = Uuid, Path, description = "Webhook ID")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Handshake attempted", body = WebhookResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    )
+)]
+pub async fn verify_webhook(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthPrincipal>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut webhook = state
+        .webhook_repository
+        .find_by_id(id)
+        .await?;
+
+    if webhook.account_id != auth.account_id {
+        return Err(ApiError::NotFound("Webhook not found".to_string()));
+    }
+
+    verify_pending(&state, &mut webhook).await?;
+
+    Ok((StatusCode::OK, Json(WebhookResponse::from(webhook))))
+}
+
+/// Send a pending webhook its challenge, activating it if the endpoint echoes it.
+/// A failed handshake is not an error; the webhook just stays pending.
+async fn verify_pending(state: &AppState, webhook: &mut Webhook) -> Result<(), ApiError> {
+    let Some(token) = webhook.verification_token.clone() else {
+        return Ok(());
+    };
+
+    let context = DeliveryContext {
+        webhook_id: webhook.id,
+        event: VERIFICATION_EVENT.to_string(),
+        timeout: webhook.timeout(),
+        ..DeliveryContext::default()
+    };
+    match state
+        .webhook_dispatcher
+        .verify_endpoint(&webhook.url, &token, &webhook.secret, &context)
+        .await
+    {
+        Ok(true) => {
+            webhook.activate();
+            state
+                .webhook_repository
+                .update(webhook)
+                .await?;
+        }
+        Ok(false) => tracing::info!("Webhook {} did not echo its verification challenge", webhook.id),
+        Err(e) => tracing::warn!("Verification request to webhook {} failed: {}", webhook.id, e),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use axum::{extract::Request, middleware::Next, response::Response};
+use std::time::Instant;
+
+use crate::application::services::AuthPrincipal;
+use crate::presentation::middleware::request_id::RequestId;
+
+/// Tracing target of access log lines, so they can be filtered separately.
+pub const ACCESS_LOG_TARGET: &str = "access_log";
+
+/// Middleware that emits one log line per completed request.
+///
+/// The line carries the method, path, status, latency and request id, plus the
+/// authenticated account when `require_auth` admitted the request. Only these
+/// fields are logged; headers (and so API keys and signatures) never are. With
+/// `LOG_FORMAT=json` each line is a single JSON object.
+pub async fn log_access(req: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_default();
+
+    let response = next.run(req).await;
+
+    let account_id = response
+        .extensions()
+        .get::<AuthPrincipal>()
+        .map(|principal| principal.account_id.to_string());
+
+    tracing::info!(
+        target: ACCESS_LOG_TARGET,
+        method = %method,
+        path = %path,
+        status = response.status().as_u16(),
+        latency_ms = started.elapsed().as_millis() as u64,
+        request_id = %request_id,
+        account_id = account_id.as_deref(),
+        "request completed"
+    );
+    response
+}
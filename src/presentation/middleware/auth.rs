@@ -1,31 +1,37 @@
 use axum::{
     extract::{Request, State},
-    http::StatusCode,
     middleware::Next,
     response::Response,
 };
 use axum::http::HeaderMap;
 
 use crate::application::AppState;
+use crate::domain::errors::{ApiError, DomainError, ServiceError};
 
 pub async fn require_auth(
     State(state): State<AppState>,
     headers: HeaderMap,
     mut request: Request,
     next: Next,
-) -> Result<Response, (StatusCode, String)> {
+) -> Result<Response, ApiError> {
     let api_key = headers
         .get("x-api-key")
         .and_then(|value| value.to_str().ok())
-        .ok_or((StatusCode::UNAUTHORIZED, "Missing x-api-key header".to_string()))?;
+        .ok_or_else(|| ApiError::Unauthorized("Missing x-api-key header".to_string()))?;
 
     let principal = state
         .auth_service
         .verify_api_key(api_key)
         .await
-        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid API key".to_string()))?;
+        .map_err(|e| match e {
+            ServiceError::Domain(DomainError::ApiKeyExpired) => ApiError::from(DomainError::ApiKeyExpired),
+            _ => ApiError::from(DomainError::InvalidApiKey),
+        })?;
 
-    request.extensions_mut().insert(principal);
+    request.extensions_mut().insert(principal.clone());
 
-    Ok(next.run(request).await)
+    let mut response = next.run(request).await;
+    // Echoed on the response so the outer access log can attribute the request
+    response.extensions_mut().insert(principal);
+    Ok(response)
 }
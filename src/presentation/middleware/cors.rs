@@ -0,0 +1,93 @@
+use axum::http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::infrastructure::config::{ConfigError, CorsConfig};
+
+/// Origin entry that allows every origin
+const ANY_ORIGIN: &str = "*";
+
+/// Build the CORS layer described by `config`.
+///
+/// With no allowed origins the layer adds no `Access-Control-*` headers, so
+/// browsers apply the same-origin policy. Any origin is only allowed when
+/// `*` is configured explicitly, and never together with credentials.
+pub fn cors_layer(config: &CorsConfig) -> Result<CorsLayer, ConfigError> {
+    let allow_origin = match config.allowed_origins.as_slice() {
+        [origin] if origin == ANY_ORIGIN => {
+            if config.allow_credentials {
+                return Err(ConfigError::InvalidValue("CORS_ALLOW_CREDENTIALS"));
+            }
+            AllowOrigin::any()
+        }
+        origins => AllowOrigin::list(
+            origins
+                .iter()
+                .map(|origin| {
+                    HeaderValue::from_str(origin)
+                        .ok()
+                        .filter(|_| origin != ANY_ORIGIN)
+                        .ok_or(ConfigError::InvalidValue("CORS_ALLOWED_ORIGINS"))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+    };
+
+    let methods = config
+        .allowed_methods
+        .iter()
+        .map(|method| {
+            Method::from_bytes(method.to_uppercase().as_bytes())
+                .map_err(|_| ConfigError::InvalidValue("CORS_ALLOWED_METHODS"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let headers = config
+        .allowed_headers
+        .iter()
+        .map(|header| {
+            HeaderName::from_bytes(header.to_lowercase().as_bytes())
+                .map_err(|_| ConfigError::InvalidValue("CORS_ALLOWED_HEADERS"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(methods)
+        .allow_headers(headers)
+        .allow_credentials(config.allow_credentials))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(origins: &[&str], allow_credentials: bool) -> CorsConfig {
+        CorsConfig {
+            allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            allowed_methods: vec!["get".to_string(), "POST".to_string()],
+            allowed_headers: vec!["Content-Type".to_string(), "x-api-key".to_string()],
+            allow_credentials,
+        }
+    }
+
+    #[test]
+    fn test_cors_config_validation() {
+        assert!(cors_layer(&config(&[], false)).is_ok());
+        assert!(cors_layer(&config(&["https://dashboard.example.com"], true)).is_ok());
+        assert!(cors_layer(&config(&["*"], false)).is_ok());
+
+        // A wildcard must stand alone and cannot be combined with credentials
+        assert!(matches!(
+            cors_layer(&config(&["*"], true)),
+            Err(ConfigError::InvalidValue("CORS_ALLOW_CREDENTIALS"))
+        ));
+        assert!(matches!(
+            cors_layer(&config(&["https://a.example.com", "*"], false)),
+            Err(ConfigError::InvalidValue("CORS_ALLOWED_ORIGINS"))
+        ));
+        assert!(matches!(
+            cors_layer(&config(&["bad\norigin"], false)),
+            Err(ConfigError::InvalidValue("CORS_ALLOWED_ORIGINS"))
+        ));
+    }
+}
@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Counts requests currently being handled, so shutdown can report what it drained.
+#[derive(Clone, Default)]
+pub struct InFlightRequests(Arc<AtomicUsize>);
+
+/// Decrements the in-flight count when the request finishes or is cancelled.
+struct InFlightGuard(Arc<AtomicUsize>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl InFlightRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of requests currently in flight
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Middleware that counts the request as in flight until its response is produced
+    pub async fn track(State(in_flight): State<InFlightRequests>, req: Request, next: Next) -> Response {
+        in_flight.0.fetch_add(1, Ordering::SeqCst);
+        let _guard = InFlightGuard(in_flight.0.clone());
+        next.run(req).await
+    }
+}
@@ -0,0 +1,30 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+
+use crate::infrastructure::metrics;
+
+/// Route label for requests that matched no route, so unknown paths share one series.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Middleware that records the count and latency of every request.
+///
+/// Requests are labelled with their route template (e.g. `/accounts/:id`)
+/// rather than the raw path, keeping the number of series bounded.
+pub async fn track_metrics(req: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+    let response = next.run(req).await;
+
+    metrics::record_http_request(&method, &route, response.status().as_u16(), started.elapsed());
+    response
+}
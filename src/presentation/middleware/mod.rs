@@ -1,2 +1,10 @@
+pub mod access_log;
 pub mod auth;
-pub mod rate_limit;
\ No newline at end of file
+pub mod cors;
+pub mod in_flight;
+pub mod metrics;
+pub mod money_format;
+pub mod rate_limit;
+pub mod request_id;
+pub mod request_signing;
+pub mod response_signing;
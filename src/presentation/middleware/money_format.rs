@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Request, State},
+    http::{header::ACCEPT, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::application::dto::money_format;
+use crate::domain::value_objects::MoneyFormat;
+
+/// `Accept` media type parameter selecting the money format, e.g. `application/json; money=rich`
+pub const MONEY_FORMAT_PARAMETER: &str = "money";
+
+/// Middleware that serializes the response's amounts in the format the client asked for.
+///
+/// A `money=plain|rich` parameter on the `Accept` header wins; otherwise the
+/// configured default (`MONEY_FORMAT`) applies. Unknown values are ignored.
+pub async fn apply_money_format(State(default): State<MoneyFormat>, req: Request, next: Next) -> Response {
+    let format = requested_format(req.headers()).unwrap_or(default);
+    money_format::scope(format, next.run(req)).await
+}
+
+fn requested_format(headers: &HeaderMap) -> Option<MoneyFormat> {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .flat_map(|media_range| media_range.split(';').skip(1))
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(MONEY_FORMAT_PARAMETER))
+        .and_then(|(_, value)| value.trim().trim_matches('"').parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_accept_parameter_selects_money_format() {
+        assert_eq!(requested_format(&accept("application/json; money=rich")), Some(MoneyFormat::Rich));
+        assert_eq!(requested_format(&accept("text/csv, application/json;money=\"plain\"")), Some(MoneyFormat::Plain));
+        assert_eq!(requested_format(&accept("application/json")), None);
+        assert_eq!(requested_format(&accept("application/json; money=fancy")), None);
+        assert_eq!(requested_format(&HeaderMap::new()), None);
+    }
+}
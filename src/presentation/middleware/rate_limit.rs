@@ -1,36 +1,226 @@
 use axum::{
     extract::{ConnectInfo, Request},
-    http::StatusCode,
+    http::{header::RETRY_AFTER, HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use dashmap::DashMap;
 use governor::{
-    clock::DefaultClock,
+    clock::{Clock, DefaultClock},
+    middleware::StateInformationMiddleware,
     state::{InMemoryState, NotKeyed},
     Quota, RateLimiter,
 };
-use std::{net::SocketAddr, num::NonZeroU32, sync::Arc};
+use ipnet::IpNet;
+use std::{
+    net::{IpAddr, SocketAddr},
+    num::NonZeroU32,
+    sync::{Arc, Weak},
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+use crate::application::services::AuthPrincipal;
+use crate::domain::errors::ApiError;
+use crate::infrastructure::config::{ConfigError, RateLimitConfig};
+use crate::infrastructure::health;
 
 /// Type alias for the rate limiter.
-/// We use a DirectRateLimiter which stores state in memory.
-type RateLimiterType = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+/// We use a DirectRateLimiter which stores state in memory. The state information
+/// middleware makes `check()` report the remaining capacity for the response headers.
+type RateLimiterType = RateLimiter<NotKeyed, InMemoryState, DefaultClock, StateInformationMiddleware>;
+
+fn new_limiter(quota: Quota) -> RateLimiterType {
+    RateLimiter::direct(quota).with_middleware::<StateInformationMiddleware>()
+}
+
+/// Check `limiter` and either run the request or reject it with 429.
+///
+/// Both outcomes carry `X-RateLimit-Limit`, `X-RateLimit-Remaining` and
+/// `X-RateLimit-Reset` (seconds until the quota is fully replenished);
+/// rejections also carry `Retry-After`.
+async fn check_and_run(limiter: &RateLimiterType, req: Request, next: Next) -> Response {
+    match limiter.check() {
+        Ok(snapshot) => {
+            let mut response = next.run(req).await;
+            insert_rate_limit_headers(
+                response.headers_mut(),
+                snapshot.quota(),
+                snapshot.remaining_burst_capacity(),
+            );
+            response
+        }
+        Err(not_until) => {
+            let retry_after = not_until.wait_time_from(limiter.clock().now());
+            let mut response = ApiError::TooManyRequests("Rate limit exceeded".to_string()).into_response();
+            insert_rate_limit_headers(response.headers_mut(), not_until.quota(), 0);
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(ceil_secs(retry_after)));
+            response
+        }
+    }
+}
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, quota: Quota, remaining: u32) {
+    let limit = quota.burst_size().get();
+    let reset = quota.replenish_interval() * limit.saturating_sub(remaining);
+    headers.insert("x-ratelimit-limit", HeaderValue::from(limit));
+    headers.insert("x-ratelimit-remaining", HeaderValue::from(remaining));
+    headers.insert("x-ratelimit-reset", HeaderValue::from(ceil_secs(reset)));
+}
+
+/// Round a duration up to whole seconds, as header values are integral.
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// Default upper bound on the number of IPs tracked at once.
+const DEFAULT_MAX_TRACKED_IPS: usize = 100_000;
+
+/// A per-IP rate limiter along with when it was last used.
+struct IpLimiter {
+    limiter: Arc<RateLimiterType>,
+    last_seen: Instant,
+}
+
+/// Remove limiters for IPs that have not been seen for at least `idle_ttl`.
+fn prune_idle_limiters(limiters: &DashMap<IpAddr, IpLimiter>, idle_ttl: Duration) {
+    limiters.retain(|_, entry| entry.last_seen.elapsed() < idle_ttl);
+}
 
 /// Middleware structure that holds the rate limiting state.
 #[derive(Clone)]
 pub struct RateLimitLayer {
     /// Map of IP addresses to their individual rate limiters.
-    limiters: Arc<DashMap<std::net::IpAddr, Arc<RateLimiterType>>>,
-    /// Requests allowed per hour.
-    requests_per_hour: u32,
+    limiters: Arc<DashMap<IpAddr, IpLimiter>>,
+    /// Quota applied to each IP, validated once when the layer is built.
+    quota: Quota,
+    /// Reverse proxies allowed to report the client IP via `X-Forwarded-For`.
+    trusted_proxies: Arc<Vec<IpNet>>,
+    /// Upper bound on tracked IPs; least recently seen limiters are evicted beyond it.
+    max_tracked_ips: usize,
 }
 
 impl RateLimitLayer {
     /// Create a new RateLimitLayer with the specified requests per hour.
-    pub fn new(requests_per_hour: u32) -> Self {
+    pub fn new(requests_per_hour: NonZeroU32) -> Self {
         Self {
             limiters: Arc::new(DashMap::new()),
-            requests_per_hour,
+            quota: Quota::per_hour(requests_per_hour),
+            trusted_proxies: Arc::new(Vec::new()),
+            max_tracked_ips: DEFAULT_MAX_TRACKED_IPS,
+        }
+    }
+
+    /// Create a new RateLimitLayer from the rate limiting configuration.
+    ///
+    /// Fails if `requests_per_hour` is zero. This also spawns the idle limiter
+    /// reaper, so it must be called from within a Tokio runtime.
+    pub fn from_config(config: &RateLimitConfig) -> Result<Self, ConfigError> {
+        let requests_per_hour = NonZeroU32::new(config.requests_per_hour)
+            .ok_or(ConfigError::InvalidValue("RATE_LIMIT_PER_HOUR"))?;
+        let layer = Self {
+            trusted_proxies: Arc::new(config.trusted_proxies.clone()),
+            max_tracked_ips: config.max_tracked_ips,
+            ..Self::new(requests_per_hour)
+        };
+        layer.spawn_idle_reaper(
+            Duration::from_secs(config.idle_ttl_seconds),
+            Duration::from_secs(config.sweep_interval_seconds),
+        );
+        Ok(layer)
+    }
+
+    /// Remove limiters for IPs that have not been seen for at least `idle_ttl`.
+    ///
+    /// A pruned IP simply starts over with a fresh quota on its next request.
+    pub fn prune_idle(&self, idle_ttl: Duration) {
+        prune_idle_limiters(&self.limiters, idle_ttl);
+    }
+
+    /// Number of IPs currently holding a limiter.
+    pub fn tracked_ip_count(&self) -> usize {
+        self.limiters.len()
+    }
+
+    /// Evict the least recently seen limiters once the map grows past `max_tracked_ips`.
+    ///
+    /// Evicts down to 90% of the cap so a stream of new IPs doesn't trigger a full
+    /// scan on every request.
+    fn enforce_capacity(&self) {
+        let tracked = self.limiters.len();
+        health::record_rate_limiter_usage(tracked, self.max_tracked_ips);
+        if tracked <= self.max_tracked_ips {
+            return;
+        }
+
+        let mut by_last_seen: Vec<(IpAddr, Instant)> = self
+            .limiters
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().last_seen))
+            .collect();
+        by_last_seen.sort_unstable_by_key(|(_, last_seen)| *last_seen);
+
+        let target = self.max_tracked_ips - self.max_tracked_ips / 10;
+        let excess = by_last_seen.len().saturating_sub(target);
+        for (ip, _) in by_last_seen.into_iter().take(excess) {
+            self.limiters.remove(&ip);
+        }
+    }
+
+    /// Spawn a background task that prunes idle limiters every `sweep_interval`.
+    ///
+    /// The task only holds a weak reference to the limiter map and exits once
+    /// every clone of the layer has been dropped.
+    fn spawn_idle_reaper(&self, idle_ttl: Duration, sweep_interval: Duration) {
+        let limiters: Weak<DashMap<IpAddr, IpLimiter>> = Arc::downgrade(&self.limiters);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(sweep_interval);
+            // The first tick completes immediately; skip it so the first sweep waits a full interval.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let Some(limiters) = limiters.upgrade() else {
+                    break;
+                };
+                prune_idle_limiters(&limiters, idle_ttl);
+            }
+        });
+    }
+
+    fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(&ip))
+    }
+
+    /// Resolve the client IP for a request received from `peer`.
+    ///
+    /// `X-Forwarded-For` is only honored when the peer is a trusted proxy. The hops
+    /// are walked from the right, skipping trusted proxies, so the client is the first
+    /// address our own proxies did not add. A missing or malformed header falls back
+    /// to the socket address.
+    fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.is_trusted_proxy(peer) {
+            return peer;
+        }
+
+        let forwarded_for = match headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()) {
+            Some(value) => value,
+            None => return peer,
+        };
+
+        let hops: Result<Vec<IpAddr>, _> = forwarded_for
+            .split(',')
+            .map(|hop| hop.trim().parse::<IpAddr>())
+            .collect();
+
+        match hops {
+            Ok(hops) => hops
+                .into_iter()
+                .rev()
+                .find(|ip| !self.is_trusted_proxy(*ip))
+                .unwrap_or(peer),
+            Err(_) => peer,
         }
     }
 
@@ -39,30 +229,85 @@ impl RateLimitLayer {
         axum::extract::State(state): axum::extract::State<RateLimitLayer>,
         req: Request,
         next: Next,
-    ) -> Result<Response, StatusCode> {
-        // Extract IP address from connection info
-        // Note: In production with a reverse proxy, you'd check X-Forwarded-For
+    ) -> Response {
+        // Extract IP address from connection info (or X-Forwarded-For behind a trusted proxy)
         let ip = match req.extensions().get::<ConnectInfo<SocketAddr>>() {
-            Some(ConnectInfo(addr)) => addr.ip(),
+            Some(ConnectInfo(addr)) => state.client_ip(addr.ip(), req.headers()),
             None => {
                 // If we can't determine IP, we might choose to block or allow.
                 // For safety, let's log and allow, or block.
                 // Here we block securely.
-                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
             }
         };
 
         // Get or create rate limiter for this IP
-        let limiter = state.limiters.entry(ip).or_insert_with(|| {
-            let quota = Quota::per_hour(NonZeroU32::new(state.requests_per_hour).expect("Rate limit must be > 0"));
-            Arc::new(RateLimiter::direct(quota))
-        }).clone();
+        let limiter = {
+            let mut entry = state.limiters.entry(ip).or_insert_with(|| IpLimiter {
+                limiter: Arc::new(new_limiter(state.quota)),
+                last_seen: Instant::now(),
+            });
+            entry.last_seen = Instant::now();
+            entry.limiter.clone()
+        };
+        state.enforce_capacity();
 
         // Check if request is allowed
-        if limiter.check().is_err() {
-            return Err(StatusCode::TOO_MANY_REQUESTS);
-        }
+        check_and_run(&limiter, req, next).await
+    }
+}
+
+/// Middleware structure that rate limits authenticated requests per API key.
+///
+/// Must be layered inside `require_auth` so the resolved `AuthPrincipal`
+/// is available in the request extensions.
+#[derive(Clone, Default)]
+pub struct ApiKeyRateLimitLayer {
+    /// Map of API key IDs to their quota and individual rate limiter.
+    limiters: Arc<DashMap<Uuid, (u32, Arc<RateLimiterType>)>>,
+}
+
+impl ApiKeyRateLimitLayer {
+    /// Create a new ApiKeyRateLimitLayer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Middleware handler function.
+    pub async fn handle(
+        axum::extract::State(state): axum::extract::State<ApiKeyRateLimitLayer>,
+        req: Request,
+        next: Next,
+    ) -> Response {
+        let principal = match req.extensions().get::<AuthPrincipal>() {
+            Some(principal) => principal.clone(),
+            None => {
+                // The layer is mounted without `require_auth` in front of it.
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
 
-        Ok(next.run(req).await)
+        // A key configured with a zero quota gets no requests at all.
+        let per_hour = match NonZeroU32::new(principal.rate_limit_per_hour) {
+            Some(per_hour) => per_hour,
+            None => {
+                return ApiError::TooManyRequests("API key has no request quota".to_string()).into_response();
+            }
+        };
+
+        // Get or create rate limiter for this key, rebuilding it if the key's quota changed
+        let limiter = {
+            let mut entry = state
+                .limiters
+                .entry(principal.api_key_id)
+                .or_insert_with(|| (per_hour.get(), Arc::new(new_limiter(Quota::per_hour(per_hour)))));
+            if entry.0 != per_hour.get() {
+                *entry = (per_hour.get(), Arc::new(new_limiter(Quota::per_hour(per_hour))));
+            }
+            entry.1.clone()
+        };
+
+        // Check if request is allowed
+        check_and_run(&limiter, req, next).await
     }
 }
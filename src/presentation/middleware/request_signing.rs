@@ -0,0 +1,129 @@
+use axum::{
+    body::Body,
+    extract::{OriginalUri, Request, State},
+    http::{HeaderMap, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::application::services::{AccountService, AuthPrincipal};
+use crate::domain::errors::ApiError;
+use crate::infrastructure::http_client::{verify_request_signature, SignatureError};
+
+/// Header carrying the client's request signature, formatted as `sha256=<hex>`.
+pub const REQUEST_SIGNATURE_HEADER: &str = "x-request-signature";
+
+/// Header carrying the Unix time in seconds the request was signed at.
+pub const REQUEST_TIMESTAMP_HEADER: &str = "x-request-timestamp";
+
+/// Middleware that rejects mutating requests not signed with the caller's account secret.
+///
+/// The mirror image of webhook signing: clients sign `{timestamp}.{METHOD}.{path}.{body}`
+/// with the secret their webhook deliveries are signed with, so a leaked API key alone
+/// cannot forge or replay a mutation. Safe methods (`GET`, `HEAD`, `OPTIONS`) pass through.
+/// Must be layered inside `require_auth` so the resolved `AuthPrincipal` is available.
+#[derive(Clone)]
+pub struct RequestSigningLayer {
+    account_service: Arc<AccountService>,
+    tolerance: Duration,
+}
+
+impl RequestSigningLayer {
+    /// Create a new RequestSigningLayer accepting timestamps up to `tolerance` from the server clock.
+    pub fn new(account_service: Arc<AccountService>, tolerance: Duration) -> Self {
+        Self { account_service, tolerance }
+    }
+
+    /// Middleware handler function.
+    pub async fn handle(
+        State(state): State<RequestSigningLayer>,
+        req: Request,
+        next: Next,
+    ) -> Response {
+        if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+            return next.run(req).await;
+        }
+
+        let principal = match req.extensions().get::<AuthPrincipal>() {
+            Some(principal) => principal.clone(),
+            None => {
+                // The layer is mounted without `require_auth` in front of it.
+                return ApiError::InternalServerError("Request signing misconfigured".to_string()).into_response();
+            }
+        };
+        let (timestamp, signature) = match signature_headers(req.headers()) {
+            Ok(headers) => headers,
+            Err(e) => return e.into_response(),
+        };
+
+        let secret = match state.account_service.signing_secret(principal.account_id).await {
+            Ok(secret) => secret,
+            Err(e) => {
+                tracing::error!("Failed to load signing secret for account {}: {}", principal.account_id, e);
+                return ApiError::InternalServerError("Failed to verify request signature".to_string()).into_response();
+            }
+        };
+
+        // Nested routers strip their prefix from the URI; the client signed the full path
+        let path = req
+            .extensions()
+            .get::<OriginalUri>()
+            .map(|uri| uri.0.clone())
+            .unwrap_or_else(|| req.uri().clone());
+        let path = path.path_and_query().map(|p| p.as_str()).unwrap_or("/").to_string();
+
+        // Buffer the body so it can be verified, then hand the handler an identical one
+        let (parts, body) = req.into_parts();
+        let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+            Ok(bytes) => bytes,
+            Err(e) if exceeds_body_limit(&e) => {
+                return ApiError::PayloadTooLarge("Request body too large".to_string()).into_response();
+            }
+            Err(e) => {
+                tracing::error!("Failed to buffer request body for signature verification: {}", e);
+                return ApiError::BadRequest("Unreadable request body".to_string()).into_response();
+            }
+        };
+
+        if let Err(e) = verify_request_signature(
+            &secret,
+            timestamp,
+            parts.method.as_str(),
+            &path,
+            &bytes,
+            &signature,
+            state.tolerance,
+        ) {
+            tracing::warn!("Rejected request signature for account {}: {}", principal.account_id, e);
+            let message = match e {
+                SignatureError::Expired => "Request timestamp is outside the tolerance window",
+                SignatureError::Malformed | SignatureError::Mismatch => "Invalid request signature",
+            };
+            return ApiError::Unauthorized(message.to_string()).into_response();
+        }
+
+        next.run(Request::from_parts(parts, Body::from(bytes))).await
+    }
+}
+
+/// The request's signing timestamp and signature, or a 401 naming what is missing
+fn signature_headers(headers: &HeaderMap) -> Result<(i64, String), ApiError> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    let signature = header(REQUEST_SIGNATURE_HEADER)
+        .ok_or_else(|| ApiError::Unauthorized("Missing X-Request-Signature header".to_string()))?;
+    let timestamp = header(REQUEST_TIMESTAMP_HEADER)
+        .ok_or_else(|| ApiError::Unauthorized("Missing X-Request-Timestamp header".to_string()))?
+        .parse::<i64>()
+        .map_err(|_| ApiError::Unauthorized("X-Request-Timestamp must be Unix seconds".to_string()))?;
+
+    Ok((timestamp, signature.to_string()))
+}
+
+/// Whether buffering stopped because the body passed the request body limit
+fn exceeds_body_limit(error: &axum::Error) -> bool {
+    std::iter::successors(Some(error as &dyn std::error::Error), |e| e.source())
+        .any(|e| e.is::<http_body_util::LengthLimitError>())
+}
@@ -0,0 +1,89 @@
+use axum::{
+    body::{Body, HttpBody},
+    extract::{Request, State},
+    http::{HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::application::services::{AccountService, AuthPrincipal};
+use crate::infrastructure::http_client::hmac_sha256_hex;
+
+/// Header carrying the response signature, formatted as `sha256=<hex>`.
+pub const RESPONSE_SIGNATURE_HEADER: &str = "x-response-signature";
+
+/// Largest response body that is buffered for signing.
+///
+/// Bodies above this size, or streamed bodies whose size is unknown, are
+/// passed through unsigned rather than held in memory.
+pub const MAX_SIGNED_BODY_BYTES: usize = 1024 * 1024;
+
+/// Middleware that signs response bodies with the caller's account secret.
+///
+/// Must be layered inside `require_auth` so the resolved `AuthPrincipal`
+/// is available in the request extensions. Clients verify the signature with
+/// the same secret used for their webhook deliveries.
+#[derive(Clone)]
+pub struct ResponseSigningLayer {
+    account_service: Arc<AccountService>,
+}
+
+impl ResponseSigningLayer {
+    /// Create a new ResponseSigningLayer resolving signing secrets through `account_service`.
+    pub fn new(account_service: Arc<AccountService>) -> Self {
+        Self { account_service }
+    }
+
+    /// Middleware handler function.
+    pub async fn handle(
+        State(state): State<ResponseSigningLayer>,
+        req: Request,
+        next: Next,
+    ) -> Response {
+        let principal = match req.extensions().get::<AuthPrincipal>() {
+            Some(principal) => principal.clone(),
+            None => {
+                // The layer is mounted without `require_auth` in front of it.
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+        // Resolve the secret up front so a lookup failure doesn't discard a handler's work
+        let secret = match state.account_service.signing_secret(principal.account_id).await {
+            Ok(secret) => secret,
+            Err(e) => {
+                tracing::error!("Failed to load signing secret for account {}: {}", principal.account_id, e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+        let response = next.run(req).await;
+
+        let within_cap = response
+            .body()
+            .size_hint()
+            .upper()
+            .is_some_and(|len| len <= MAX_SIGNED_BODY_BYTES as u64);
+        if !within_cap {
+            return response;
+        }
+
+        // Buffer the body so it can be signed, then rebuild the response around it
+        let (mut parts, body) = response.into_parts();
+        let bytes = match axum::body::to_bytes(body, MAX_SIGNED_BODY_BYTES).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                tracing::error!("Failed to buffer response body for signing: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+        let signature = format!("sha256={}", hmac_sha256_hex(&secret, &bytes));
+        if let Ok(value) = HeaderValue::from_str(&signature) {
+            parts.headers.insert(RESPONSE_SIGNATURE_HEADER, value);
+        }
+
+        Response::from_parts(parts, Body::from(bytes))
+    }
+}
@@ -0,0 +1,182 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use rust_decimal_macros::dec;
+use serde_json::json;
+use sha2::Digest;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use dodo_payments_assignment::application::services::{AccountService, AuthService, TransactionService};
+use dodo_payments_assignment::application::AppState;
+use dodo_payments_assignment::domain::entities::{Account, ApiKey};
+use dodo_payments_assignment::domain::repositories::{AccountRepository, ApiKeyRepository};
+use dodo_payments_assignment::domain::value_objects::Money;
+use dodo_payments_assignment::infrastructure::config::Config;
+use dodo_payments_assignment::infrastructure::database::{
+    create_pool, PostgresAccountRepository, PostgresApiKeyRepository, PostgresBalanceSnapshotRepository,
+    PostgresTransactionRepository, PostgresWebhookRepository,
+};
+use dodo_payments_assignment::infrastructure::http_client::{ReqwestWebhookDispatcher, WebhookUrlPolicy};
+use dodo_payments_assignment::presentation::api::account::{
+    freeze_account, get_balance_history, restore_account, unfreeze_account, update_account, update_limits,
+};
+use dodo_payments_assignment::presentation::middleware::auth::require_auth;
+
+struct TestApp {
+    app: Router,
+    account_repo: Arc<PostgresAccountRepository>,
+    api_key_repo: Arc<PostgresApiKeyRepository>,
+}
+
+async fn setup_app() -> TestApp {
+    let config = Config::from_env().unwrap();
+    let pool = create_pool(&config).await.unwrap();
+
+    let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
+    let api_key_repo = Arc::new(PostgresApiKeyRepository::new(pool.clone()));
+
+    let state = AppState {
+        account_service: Arc::new(
+            AccountService::new(account_repo.clone())
+                .with_balance_snapshots(Arc::new(PostgresBalanceSnapshotRepository::new(pool.clone()))),
+        ),
+        transaction_service: Arc::new(TransactionService::new(
+            Arc::new(PostgresTransactionRepository::new(pool.clone())),
+            None,
+        )),
+        auth_service: Arc::new(AuthService::new(api_key_repo.clone())),
+        webhook_repository: Arc::new(PostgresWebhookRepository::new(pool.clone())),
+        webhook_dispatcher: Arc::new(ReqwestWebhookDispatcher::new(0, 10)),
+        webhook_url_policy: WebhookUrlPolicy::default(),
+        require_webhook_verification: false,
+        max_page_size: 100,
+        pool,
+        started_at: chrono::Utc::now(),
+    };
+
+    let app = Router::new()
+        .route("/accounts/:id", axum::routing::patch(update_account))
+        .route("/accounts/:id/restore", axum::routing::post(restore_account))
+        .route("/accounts/:id/freeze", axum::routing::post(freeze_account))
+        .route("/accounts/:id/unfreeze", axum::routing::post(unfreeze_account))
+        .route("/accounts/:id/limits", axum::routing::put(update_limits))
+        .route("/accounts/:id/balance-history", axum::routing::get(get_balance_history))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_auth))
+        .with_state(state);
+
+    TestApp { app, account_repo, api_key_repo }
+}
+
+impl TestApp {
+    /// Create an account and a key for it, returning the account id and raw key
+    async fn account_with_key(&self, is_admin: bool) -> (Uuid, String) {
+        let account = Account::new(format!("Account Endpoint Test {}", Uuid::new_v4()), Money::new(dec!(0)).unwrap()).unwrap();
+        self.account_repo.create(&account).await.unwrap();
+
+        let raw_key = format!("test_key_{}", Uuid::new_v4());
+        let mut api_key = ApiKey::new(account.id, hex::encode(sha2::Sha256::digest(raw_key.as_bytes())));
+        api_key.is_admin = is_admin;
+        self.api_key_repo.create(&api_key).await.unwrap();
+
+        (account.id, raw_key)
+    }
+
+    async fn send(&self, method: &str, uri: String, api_key: &str, body: serde_json::Value) -> StatusCode {
+        self.app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .header("x-api-key", api_key)
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+}
+
+#[tokio::test]
+async fn test_only_admin_keys_change_limits() {
+    let test_app = setup_app().await;
+    let (account_id, own_key) = test_app.account_with_key(false).await;
+    let (_, stranger_key) = test_app.account_with_key(false).await;
+    let (_, admin_key) = test_app.account_with_key(true).await;
+    let limits = json!({ "per_transaction_limit": 1000000, "daily_debit_limit": null });
+    let uri = format!("/accounts/{}/limits", account_id);
+
+    // An account cannot raise its own caps
+    assert_eq!(test_app.send("PUT", uri.clone(), &own_key, limits.clone()).await, StatusCode::FORBIDDEN);
+    // Other accounts look missing
+    assert_eq!(test_app.send("PUT", uri.clone(), &stranger_key, limits.clone()).await, StatusCode::NOT_FOUND);
+
+    assert_eq!(test_app.send("PUT", uri, &admin_key, limits).await, StatusCode::OK);
+    let stored = test_app.account_repo.find_limits(account_id).await.unwrap();
+    assert_eq!(stored.per_transaction.unwrap().amount(), dec!(1000000));
+}
+
+#[tokio::test]
+async fn test_freeze_needs_the_account_and_unfreeze_an_admin_key() {
+    let test_app = setup_app().await;
+    let (account_id, own_key) = test_app.account_with_key(false).await;
+    let (_, stranger_key) = test_app.account_with_key(false).await;
+    let (_, admin_key) = test_app.account_with_key(true).await;
+    let freeze = format!("/accounts/{}/freeze", account_id);
+    let unfreeze = format!("/accounts/{}/unfreeze", account_id);
+
+    assert_eq!(test_app.send("POST", freeze.clone(), &stranger_key, json!({})).await, StatusCode::NOT_FOUND);
+    assert_eq!(test_app.send("POST", freeze, &own_key, json!({})).await, StatusCode::OK);
+
+    // The frozen account's own key cannot lift the freeze
+    assert_eq!(test_app.send("POST", unfreeze.clone(), &own_key, json!({})).await, StatusCode::FORBIDDEN);
+    assert_eq!(test_app.send("POST", unfreeze.clone(), &stranger_key, json!({})).await, StatusCode::NOT_FOUND);
+    assert_eq!(test_app.send("POST", unfreeze, &admin_key, json!({})).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_only_the_account_itself_can_rename_it() {
+    let test_app = setup_app().await;
+    let (account_id, own_key) = test_app.account_with_key(false).await;
+    let (_, stranger_key) = test_app.account_with_key(false).await;
+    let uri = format!("/accounts/{}", account_id);
+
+    let hijack = json!({ "business_name": format!("Hijacked {}", Uuid::new_v4()) });
+    assert_eq!(test_app.send("PATCH", uri.clone(), &stranger_key, hijack).await, StatusCode::NOT_FOUND);
+
+    let rename = format!("Renamed {}", Uuid::new_v4());
+    assert_eq!(test_app.send("PATCH", uri, &own_key, json!({ "business_name": rename })).await, StatusCode::OK);
+    assert_eq!(test_app.account_repo.find_by_id(account_id).await.unwrap().business_name, rename);
+}
+
+#[tokio::test]
+async fn test_only_the_account_itself_can_restore_it() {
+    let test_app = setup_app().await;
+    let (account_id, own_key) = test_app.account_with_key(false).await;
+    let (_, stranger_key) = test_app.account_with_key(false).await;
+    test_app.account_repo.delete(account_id).await.unwrap();
+    let uri = format!("/accounts/{}/restore", account_id);
+
+    assert_eq!(test_app.send("POST", uri.clone(), &stranger_key, json!({})).await, StatusCode::NOT_FOUND);
+    assert!(!test_app.account_repo.exists(account_id).await.unwrap());
+
+    assert_eq!(test_app.send("POST", uri, &own_key, json!({})).await, StatusCode::OK);
+    assert!(test_app.account_repo.exists(account_id).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_balance_history_is_private_to_the_account() {
+    let test_app = setup_app().await;
+    let (account_id, own_key) = test_app.account_with_key(false).await;
+    let (_, stranger_key) = test_app.account_with_key(false).await;
+    let uri = format!("/accounts/{}/balance-history", account_id);
+
+    assert_eq!(test_app.send("GET", uri.clone(), &stranger_key, json!({})).await, StatusCode::NOT_FOUND);
+    assert_eq!(test_app.send("GET", uri, &own_key, json!({})).await, StatusCode::OK);
+}
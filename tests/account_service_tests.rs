@@ -1,9 +1,12 @@
-use dodo_payments_assignment::application::dto::CreateAccountRequest;
+use dodo_payments_assignment::application::dto::{
+    BatchAccountLookupRequest, CreateAccountRequest, UpdateAccountRequest, UpdateTransactionLimitsRequest,
+};
 use dodo_payments_assignment::application::services::AccountService;
+use dodo_payments_assignment::application::services::account_service::MAX_BATCH_LOOKUP_IDS;
 use dodo_payments_assignment::domain::entities::Account;
-use dodo_payments_assignment::domain::errors::RepositoryError;
+use dodo_payments_assignment::domain::errors::{ApiError, RepositoryError, ServiceError};
 use dodo_payments_assignment::domain::repositories::AccountRepository;
-use dodo_payments_assignment::domain::value_objects::Money;
+use dodo_payments_assignment::domain::value_objects::{AccountStatus, Money, TransactionLimits};
 use async_trait::async_trait;
 use rust_decimal_macros::dec;
 use std::sync::{Arc, Mutex};
@@ -12,12 +15,14 @@ use uuid::Uuid;
 // Mock Repository
 struct MockAccountRepository {
     accounts: Mutex<Vec<Account>>,
+    limits: Mutex<Vec<(Uuid, TransactionLimits)>>,
 }
 
 impl MockAccountRepository {
     fn new() -> Self {
         Self {
             accounts: Mutex::new(Vec::new()),
+            limits: Mutex::new(Vec::new()),
         }
     }
 }
@@ -39,11 +44,52 @@ impl AccountRepository for MockAccountRepository {
             .ok_or(RepositoryError::NotFound("Account not found".to_string()))
     }
 
-    async fn update_balance(&self, _id: Uuid, _new_balance: Money) -> Result<(), RepositoryError> {
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Account>, RepositoryError> {
+        let accounts = self.accounts.lock().unwrap();
+        Ok(accounts.iter().filter(|a| ids.contains(&a.id)).cloned().collect())
+    }
+
+    async fn update_balance(&self, _id: Uuid, _new_balance: Money, expected_version: i64) -> Result<i64, RepositoryError> {
+        Ok(expected_version + 1)
+    }
+
+    async fn update_business_name(&self, id: Uuid, name: String) -> Result<(), RepositoryError> {
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or(RepositoryError::NotFound("Account not found".to_string()))?;
+        account.business_name = name;
+        account.updated_at = chrono::Utc::now();
         Ok(())
     }
 
-    async fn update_business_name(&self, _id: Uuid, _name: String) -> Result<(), RepositoryError> {
+    async fn update_status(&self, id: Uuid, status: AccountStatus) -> Result<(), RepositoryError> {
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or(RepositoryError::NotFound("Account not found".to_string()))?;
+        account.status = status;
+        Ok(())
+    }
+
+    async fn find_limits(&self, id: Uuid) -> Result<TransactionLimits, RepositoryError> {
+        let limits = self.limits.lock().unwrap();
+        Ok(limits
+            .iter()
+            .find(|(account_id, _)| *account_id == id)
+            .map(|(_, limits)| *limits)
+            .unwrap_or_default())
+    }
+
+    async fn update_limits(&self, id: Uuid, limits: &TransactionLimits) -> Result<(), RepositoryError> {
+        if !self.accounts.lock().unwrap().iter().any(|a| a.id == id) {
+            return Err(RepositoryError::NotFound("Account not found".to_string()));
+        }
+        let mut stored = self.limits.lock().unwrap();
+        stored.retain(|(account_id, _)| *account_id != id);
+        stored.push((id, *limits));
         Ok(())
     }
 
@@ -52,15 +98,48 @@ impl AccountRepository for MockAccountRepository {
         Ok(accounts.iter().any(|a| a.id == id))
     }
 
+    async fn exists_by_business_name(&self, business_name: &str) -> Result<bool, RepositoryError> {
+        let accounts = self.accounts.lock().unwrap();
+        Ok(accounts.iter().any(|a| a.business_name.eq_ignore_ascii_case(business_name)))
+    }
+
     async fn list(&self, _limit: i64, _offset: i64) -> Result<Vec<Account>, RepositoryError> {
         let accounts = self.accounts.lock().unwrap();
         Ok(accounts.clone())
     }
 
+    async fn search(&self, query: &str, _limit: i64, _offset: i64) -> Result<Vec<Account>, RepositoryError> {
+        let query = query.to_lowercase();
+        let accounts = self.accounts.lock().unwrap();
+        Ok(accounts.iter().filter(|a| a.business_name.to_lowercase().contains(&query)).cloned().collect())
+    }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        let accounts = self.accounts.lock().unwrap();
+        Ok(accounts.len() as i64)
+    }
+
+    async fn count_matching(&self, query: &str) -> Result<i64, RepositoryError> {
+        Ok(self.search(query, i64::MAX, 0).await?.len() as i64)
+    }
+
     async fn delete(&self, _id: Uuid) -> Result<(), RepositoryError> {
         Ok(())
     }
 
+    async fn restore(&self, id: Uuid) -> Result<Account, RepositoryError> {
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or(RepositoryError::NotFound("Account not found".to_string()))?;
+        if account.deleted_at.is_none() {
+            return Err(RepositoryError::InvalidState("Account is not deleted".to_string()));
+        }
+        account.deleted_at = None;
+        Ok(account.clone())
+    }
+
     async fn health_check(&self) -> Result<(), RepositoryError> {
         Ok(())
     }
@@ -73,6 +152,7 @@ async fn test_create_account() {
 
     let request = CreateAccountRequest {
         business_name: "Test Corp".to_string(),
+        initial_balance: None,
     };
 
     let response = service.create_account(request).await.expect("Failed to create account");
@@ -88,6 +168,7 @@ async fn test_get_account() {
 
     let request = CreateAccountRequest {
         business_name: "Test Corp".to_string(),
+        initial_balance: None,
     };
     let created = service.create_account(request).await.expect("Failed to create account");
 
@@ -104,3 +185,197 @@ async fn test_get_account_not_found() {
     let result = service.get_account(Uuid::new_v4()).await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_get_accounts_reports_missing_ids() {
+    let mock_repo = Arc::new(MockAccountRepository::new());
+    let service = AccountService::new(mock_repo);
+
+    let created = service
+        .create_account(CreateAccountRequest { business_name: "Batch Corp".to_string(), initial_balance: None })
+        .await
+        .expect("Failed to create account");
+    let missing = Uuid::new_v4();
+
+    let result = service
+        .get_accounts(BatchAccountLookupRequest { ids: vec![created.id, missing, created.id] })
+        .await
+        .expect("Batch lookup failed");
+    assert_eq!(result.accounts.len(), 1);
+    assert_eq!(result.accounts[0].id, created.id);
+    assert_eq!(result.not_found, vec![missing]);
+
+    // Over the cap is a bad request
+    let ids = (0..=MAX_BATCH_LOOKUP_IDS).map(|_| Uuid::new_v4()).collect();
+    let err = service
+        .get_accounts(BatchAccountLookupRequest { ids })
+        .await
+        .expect_err("Oversized batch should be rejected");
+    assert!(matches!(ApiError::from(err).base(), ApiError::BadRequest(_)));
+}
+
+#[tokio::test]
+async fn test_list_accounts_filters_by_business_name() {
+    let mock_repo = Arc::new(MockAccountRepository::new());
+    let service = AccountService::new(mock_repo);
+
+    for name in ["Acme Corp", "Globex", "ACME Labs"] {
+        service
+            .create_account(CreateAccountRequest { business_name: name.to_string(), initial_balance: None })
+            .await
+            .expect("Failed to create account");
+    }
+
+    let page = service.list_accounts(Some("acme"), 10, 0).await.expect("Failed to search accounts");
+    assert_eq!(page.total, 2);
+    assert!(page.items.iter().all(|account| account.business_name.to_lowercase().contains("acme")));
+
+    // A blank query lists everything
+    let page = service.list_accounts(Some("  "), 10, 0).await.expect("Failed to list accounts");
+    assert_eq!(page.total, 3);
+}
+
+#[tokio::test]
+async fn test_duplicate_business_name_conflicts_when_unique() {
+    let mock_repo = Arc::new(MockAccountRepository::new());
+    let service = AccountService::new(mock_repo).with_unique_business_names(true);
+
+    service
+        .create_account(CreateAccountRequest { business_name: "Acme Corp".to_string(), initial_balance: None })
+        .await
+        .expect("Failed to create account");
+
+    let err = service
+        .create_account(CreateAccountRequest { business_name: "acme corp".to_string(), initial_balance: None })
+        .await
+        .expect_err("Duplicate business name should be rejected");
+
+    let response = axum::response::IntoResponse::into_response(ApiError::from(err));
+    assert_eq!(response.status(), axum::http::StatusCode::CONFLICT);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["code"], "CONFLICT");
+    assert_eq!(json["error_code"], "DUPLICATE_BUSINESS_NAME");
+}
+
+#[tokio::test]
+async fn test_duplicate_business_name_allowed_by_default() {
+    let mock_repo = Arc::new(MockAccountRepository::new());
+    let service = AccountService::new(mock_repo);
+
+    for _ in 0..2 {
+        service
+            .create_account(CreateAccountRequest { business_name: "Acme Corp".to_string(), initial_balance: None })
+            .await
+            .expect("Duplicate names are allowed unless enforced");
+    }
+}
+
+#[tokio::test]
+async fn test_freeze_and_unfreeze_account() {
+    let mock_repo = Arc::new(MockAccountRepository::new());
+    let service = AccountService::new(mock_repo);
+
+    let account = service
+        .create_account(CreateAccountRequest { business_name: "Freeze Corp".to_string(), initial_balance: None })
+        .await
+        .expect("Failed to create account");
+    assert_eq!(account.status, AccountStatus::Active);
+
+    let frozen = service.freeze_account(account.id).await.expect("Failed to freeze");
+    assert_eq!(frozen.status, AccountStatus::Frozen);
+    assert_eq!(service.get_account(account.id).await.unwrap().status, AccountStatus::Frozen);
+
+    let unfrozen = service.unfreeze_account(account.id).await.expect("Failed to unfreeze");
+    assert_eq!(unfrozen.status, AccountStatus::Active);
+}
+
+#[tokio::test]
+async fn test_update_transaction_limits() {
+    let mock_repo = Arc::new(MockAccountRepository::new());
+    let service = AccountService::new(mock_repo.clone());
+
+    let account = service
+        .create_account(CreateAccountRequest { business_name: "Limits Corp".to_string(), initial_balance: None })
+        .await
+        .expect("Failed to create account");
+
+    let request = UpdateTransactionLimitsRequest {
+        per_transaction_limit: Some(dec!(250.00)),
+        daily_debit_limit: None,
+        low_balance_threshold: Some(dec!(20.00)),
+    };
+    let limits = service.update_limits(account.id, request).await.expect("Failed to set limits");
+    assert_eq!(limits.per_transaction_limit, Some(dec!(250.00)));
+    assert_eq!(limits.daily_debit_limit, None);
+    assert_eq!(limits.low_balance_threshold, Some(dec!(20.00)));
+
+    let stored = mock_repo.find_limits(account.id).await.unwrap();
+    assert_eq!(stored.per_transaction.map(|m| m.amount()), Some(dec!(250.00)));
+
+    // Zero and negative limits are rejected rather than silently blocking every debit
+    for invalid in [dec!(0), dec!(-5)] {
+        let request = UpdateTransactionLimitsRequest {
+            per_transaction_limit: None,
+            daily_debit_limit: Some(invalid),
+            low_balance_threshold: None,
+        };
+        let result = service.update_limits(account.id, request).await;
+        assert!(matches!(result, Err(ServiceError::ValidationError(_) | ServiceError::Domain(_))));
+    }
+}
+
+#[tokio::test]
+async fn test_restore_account_errors() {
+    let mock_repo = Arc::new(MockAccountRepository::new());
+    let service = AccountService::new(mock_repo);
+
+    let account = service
+        .create_account(CreateAccountRequest { business_name: "Restore Corp".to_string(), initial_balance: None })
+        .await
+        .expect("Failed to create account");
+
+    // Never deleted
+    let err = service.restore_account(account.id).await.expect_err("Restore should fail");
+    let response = axum::response::IntoResponse::into_response(ApiError::from(err));
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    // Nonexistent
+    let err = service.restore_account(Uuid::new_v4()).await.expect_err("Restore should fail");
+    let response = axum::response::IntoResponse::into_response(ApiError::from(err));
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_update_account_business_name() {
+    let mock_repo = Arc::new(MockAccountRepository::new());
+    let service = AccountService::new(mock_repo);
+
+    let account = service
+        .create_account(CreateAccountRequest { business_name: "Old Name".to_string(), initial_balance: None })
+        .await
+        .expect("Failed to create account");
+
+    let updated = service
+        .update_account(account.id, UpdateAccountRequest { business_name: "New Name".to_string() })
+        .await
+        .expect("Failed to update account");
+    assert_eq!(updated.business_name, "New Name");
+    assert!(updated.updated_at > account.updated_at);
+
+    // Invalid names are rejected by the domain rules
+    let err = service
+        .update_account(account.id, UpdateAccountRequest { business_name: "  ".to_string() })
+        .await
+        .expect_err("Empty name should be rejected");
+    let response = axum::response::IntoResponse::into_response(ApiError::from(err));
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    // Unknown accounts 404
+    let err = service
+        .update_account(Uuid::new_v4(), UpdateAccountRequest { business_name: "Whatever".to_string() })
+        .await
+        .expect_err("Missing account should 404");
+    let response = axum::response::IntoResponse::into_response(ApiError::from(err));
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
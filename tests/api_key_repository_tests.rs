@@ -1,4 +1,5 @@
 use dodo_payments_assignment::domain::entities::{Account, ApiKey};
+use dodo_payments_assignment::domain::errors::RepositoryError;
 use dodo_payments_assignment::domain::repositories::{AccountRepository, ApiKeyRepository};
 use dodo_payments_assignment::domain::value_objects::Money;
 use dodo_payments_assignment::infrastructure::config::Config;
@@ -43,6 +44,22 @@ async fn test_create_and_retrieve_api_key() {
     assert_eq!(retrieved.id, created.id);
 }
 
+#[tokio::test]
+async fn test_find_by_prefix() {
+    let repo = setup_repo().await;
+    let account = create_test_account().await;
+    let prefix = Uuid::new_v4().simple().to_string()[..8].to_string();
+
+    let mut api_key = ApiKey::new(account.id, Uuid::new_v4().to_string());
+    api_key.key_prefix = Some(prefix.clone());
+    let created = repo.create(&api_key).await.expect("Failed to create key");
+    assert_eq!(created.key_prefix.as_deref(), Some(prefix.as_str()));
+
+    let keys = repo.find_by_prefix(&prefix).await.expect("Failed to find keys");
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].id, created.id);
+}
+
 #[tokio::test]
 async fn test_find_by_account() {
     let repo = setup_repo().await;
@@ -104,3 +121,22 @@ async fn test_create_duplicate_hash_fails() {
     assert!(result.is_err());
     // Should verify it is DuplicateEntry error
 }
+
+#[tokio::test]
+async fn test_create_first_key_leaves_a_seeded_deployment_alone() {
+    let repo = setup_repo().await;
+    // Make sure some key exists, as on any deployment already in use
+    let account = create_test_account().await;
+    repo.create(&ApiKey::new(account.id, Uuid::new_v4().to_string())).await.expect("Failed to create key");
+
+    let first_account = Account::new(format!("Never Seeded {}", Uuid::new_v4()), Money::new(dec!(0)).unwrap()).unwrap();
+    let first_key = ApiKey::new(first_account.id, Uuid::new_v4().to_string());
+    let seeded = repo.create_first_key(&first_account, &first_key).await.expect("Failed to check for keys");
+    assert!(seeded.is_none());
+
+    // Neither the account nor the key was written
+    assert!(matches!(repo.find_by_id(first_key.id).await, Err(RepositoryError::NotFound(_))));
+    let config = Config::from_env().unwrap();
+    let accounts = PostgresAccountRepository::new(create_pool(&config).await.unwrap());
+    assert!(!accounts.exists(first_account.id).await.unwrap());
+}
@@ -0,0 +1,413 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use dodo_payments_assignment::application::dto::CreateApiKeyRequest;
+use dodo_payments_assignment::application::services::{AuthPrincipal, AuthService};
+use dodo_payments_assignment::domain::entities::{Account, ApiKey};
+use dodo_payments_assignment::domain::errors::{DomainError, RepositoryError, ServiceError};
+use dodo_payments_assignment::domain::repositories::ApiKeyRepository;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+// Mock Repository
+struct MockApiKeyRepository {
+    keys: Mutex<Vec<ApiKey>>,
+    // Accounts created along with the first key
+    accounts: Mutex<Vec<Account>>,
+}
+
+impl MockApiKeyRepository {
+    fn new() -> Self {
+        Self {
+            keys: Mutex::new(Vec::new()),
+            accounts: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for MockApiKeyRepository {
+    async fn create(&self, api_key: &ApiKey) -> Result<ApiKey, RepositoryError> {
+        let mut keys = self.keys.lock().unwrap();
+        keys.push(api_key.clone());
+        Ok(api_key.clone())
+    }
+
+    async fn find_by_hash(&self, key_hash: &str) -> Result<ApiKey, RepositoryError> {
+        let keys = self.keys.lock().unwrap();
+        keys.iter()
+            .find(|k| k.key_hash == key_hash)
+            .cloned()
+            .ok_or(RepositoryError::NotFound("API key not found".to_string()))
+    }
+
+    async fn find_by_prefix(&self, key_prefix: &str) -> Result<Vec<ApiKey>, RepositoryError> {
+        let keys = self.keys.lock().unwrap();
+        Ok(keys.iter().filter(|k| k.key_prefix.as_deref() == Some(key_prefix)).cloned().collect())
+    }
+
+    async fn find_by_account(&self, account_id: Uuid) -> Result<Vec<ApiKey>, RepositoryError> {
+        let keys = self.keys.lock().unwrap();
+        Ok(keys.iter().filter(|k| k.account_id == account_id).cloned().collect())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<ApiKey, RepositoryError> {
+        let keys = self.keys.lock().unwrap();
+        keys.iter()
+            .find(|k| k.id == id)
+            .cloned()
+            .ok_or(RepositoryError::NotFound("API key not found".to_string()))
+    }
+
+    async fn supersede(
+        &self,
+        old_id: Uuid,
+        replacement: &ApiKey,
+        old_expires_at: DateTime<Utc>,
+    ) -> Result<ApiKey, RepositoryError> {
+        let mut keys = self.keys.lock().unwrap();
+        let old = keys
+            .iter_mut()
+            .find(|k| k.id == old_id)
+            .ok_or(RepositoryError::NotFound("API key not found".to_string()))?;
+        if old.superseded_by.is_some() {
+            return Err(RepositoryError::DuplicateEntry("API key has already been rotated".to_string()));
+        }
+        old.superseded_by = Some(replacement.id);
+        old.expires_at = Some(old.expires_at.map_or(old_expires_at, |e| e.min(old_expires_at)));
+        keys.push(replacement.clone());
+        Ok(replacement.clone())
+    }
+
+    async fn update_last_used(&self, _id: Uuid) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn update_hash(&self, id: Uuid, key_hash: &str, key_prefix: &str) -> Result<(), RepositoryError> {
+        let mut keys = self.keys.lock().unwrap();
+        let key = keys
+            .iter_mut()
+            .find(|k| k.id == id)
+            .ok_or_else(|| RepositoryError::NotFound("Key not found".to_string()))?;
+        key.key_hash = key_hash.to_string();
+        key.key_prefix = Some(key_prefix.to_string());
+        Ok(())
+    }
+
+    async fn delete(&self, _id: Uuid) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn exists(&self, key_hash: &str) -> Result<bool, RepositoryError> {
+        let keys = self.keys.lock().unwrap();
+        Ok(keys.iter().any(|k| k.key_hash == key_hash))
+    }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        Ok(self.keys.lock().unwrap().len() as i64)
+    }
+
+    async fn create_first_key(&self, account: &Account, api_key: &ApiKey) -> Result<Option<ApiKey>, RepositoryError> {
+        let mut keys = self.keys.lock().unwrap();
+        if !keys.is_empty() {
+            return Ok(None);
+        }
+        self.accounts.lock().unwrap().push(account.clone());
+        keys.push(api_key.clone());
+        Ok(Some(api_key.clone()))
+    }
+}
+
+fn principal(account_id: Uuid) -> AuthPrincipal {
+    AuthPrincipal {
+        account_id,
+        account_ids: vec![account_id],
+        api_key_id: Uuid::new_v4(),
+        rate_limit_per_hour: 500,
+        is_admin: false,
+    }
+}
+
+#[tokio::test]
+async fn test_create_api_key_returns_usable_raw_key() {
+    let repo = Arc::new(MockApiKeyRepository::new());
+    let service = AuthService::new(repo.clone());
+    let account_id = Uuid::new_v4();
+
+    let created = service
+        .create_api_key(&principal(account_id), account_id, CreateApiKeyRequest { rate_limit_per_hour: Some(100), ttl_seconds: None, additional_account_ids: Vec::new() })
+        .await
+        .expect("Failed to create API key");
+
+    assert_eq!(created.account_id, account_id);
+    assert_eq!(created.rate_limit_per_hour, 100);
+
+    // Only the hash and a short prefix are stored
+    let stored = repo.find_by_account(account_id).await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_ne!(stored[0].key_hash, created.raw_key);
+    assert_eq!(stored[0].key_prefix.as_deref(), Some(&created.raw_key[..8]));
+    assert_eq!(created.key_prefix, stored[0].key_prefix);
+
+    // The raw key authenticates as the new key
+    let verified = service.verify_api_key(&created.raw_key).await.expect("Raw key should verify");
+    assert_eq!(verified.api_key_id, created.id);
+    assert_eq!(verified.rate_limit_per_hour, 100);
+}
+
+#[tokio::test]
+async fn test_peppered_hashes_upgrade_legacy_keys() {
+    let repo = Arc::new(MockApiKeyRepository::new());
+    let pepper = "a_server_side_pepper_of_32_chars!".to_string();
+    let account_id = Uuid::new_v4();
+
+    // A key created before the pepper was configured
+    let legacy = AuthService::new(repo.clone())
+        .create_api_key(&principal(account_id), account_id, CreateApiKeyRequest::default())
+        .await
+        .unwrap();
+    let legacy_hash = repo.find_by_account(account_id).await.unwrap()[0].key_hash.clone();
+
+    // Rejected outright once the transition window is closed
+    let strict = AuthService::new(repo.clone())
+        .with_pepper(pepper.clone())
+        .with_legacy_hashes(false);
+    let result = strict.verify_api_key(&legacy.raw_key).await;
+    assert!(matches!(result, Err(ServiceError::Domain(DomainError::InvalidApiKey))));
+
+    // Accepted during the transition and re-hashed under the pepper
+    let service = AuthService::new(repo.clone()).with_pepper(pepper.clone());
+    let verified = service.verify_api_key(&legacy.raw_key).await.expect("Legacy key should verify");
+    assert_eq!(verified.api_key_id, legacy.id);
+    let upgraded_hash = repo.find_by_id(legacy.id).await.unwrap().key_hash;
+    assert_ne!(upgraded_hash, legacy_hash);
+
+    // The upgraded key and newly created keys verify without the legacy fallback
+    assert!(strict.verify_api_key(&legacy.raw_key).await.is_ok());
+    let created = strict
+        .create_api_key(&principal(account_id), account_id, CreateApiKeyRequest::default())
+        .await
+        .unwrap();
+    assert!(strict.verify_api_key(&created.raw_key).await.is_ok());
+    assert!(AuthService::new(repo.clone()).verify_api_key(&created.raw_key).await.is_err());
+}
+
+#[tokio::test]
+async fn test_keys_without_prefix_verify_and_are_listed_by_prefix() {
+    let repo = Arc::new(MockApiKeyRepository::new());
+    let service = AuthService::new(repo.clone());
+    let account_id = Uuid::new_v4();
+
+    let created = service
+        .create_api_key(&principal(account_id), account_id, CreateApiKeyRequest::default())
+        .await
+        .unwrap();
+
+    // A key stored before prefixes were recorded
+    repo.keys.lock().unwrap()[0].key_prefix = None;
+    let verified = service.verify_api_key(&created.raw_key).await.expect("Key without prefix should verify");
+    assert_eq!(verified.api_key_id, created.id);
+
+    // Its prefix is filled in on use and shown in the listing
+    let listed = service.list_api_keys(&principal(account_id), account_id).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, created.id);
+    assert_eq!(listed[0].key_prefix.as_deref(), Some(&created.raw_key[..8]));
+
+    let result = service.list_api_keys(&principal(account_id), Uuid::new_v4()).await;
+    assert!(matches!(result, Err(ServiceError::AuthorizationError(_))));
+}
+
+#[tokio::test]
+async fn test_create_api_key_inherits_caller_quota() {
+    let service = AuthService::new(Arc::new(MockApiKeyRepository::new()));
+    let account_id = Uuid::new_v4();
+
+    let created = service
+        .create_api_key(&principal(account_id), account_id, CreateApiKeyRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(created.rate_limit_per_hour, 500);
+}
+
+#[tokio::test]
+async fn test_create_api_key_rejects_other_account_and_excess_quota() {
+    let service = AuthService::new(Arc::new(MockApiKeyRepository::new()));
+    let account_id = Uuid::new_v4();
+
+    let result = service
+        .create_api_key(&principal(account_id), Uuid::new_v4(), CreateApiKeyRequest::default())
+        .await;
+    assert!(matches!(result, Err(ServiceError::AuthorizationError(_))));
+
+    let result = service
+        .create_api_key(&principal(account_id), account_id, CreateApiKeyRequest { rate_limit_per_hour: Some(501), ttl_seconds: None, additional_account_ids: Vec::new() })
+        .await;
+    assert!(matches!(result, Err(ServiceError::ValidationError(_))));
+}
+
+#[tokio::test]
+async fn test_keys_can_span_several_accounts() {
+    let repo = Arc::new(MockApiKeyRepository::new());
+    let service = AuthService::new(repo.clone());
+    let (account_id, sibling_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let tenant = AuthPrincipal {
+        account_ids: vec![account_id, sibling_id],
+        ..principal(account_id)
+    };
+
+    let request = CreateApiKeyRequest { additional_account_ids: vec![sibling_id], ..CreateApiKeyRequest::default() };
+    let created = service.create_api_key(&tenant, account_id, request).await.unwrap();
+    assert_eq!(created.additional_account_ids, vec![sibling_id]);
+
+    let verified = service.verify_api_key(&created.raw_key).await.unwrap();
+    assert_eq!(verified.account_id, account_id);
+    assert_eq!(verified.account_ids, vec![account_id, sibling_id]);
+    assert!(verified.is_authorized_for(sibling_id));
+
+    // A key may act for the sibling, but cannot grant access it does not have
+    assert!(service.list_api_keys(&verified, sibling_id).await.is_ok());
+    let request = CreateApiKeyRequest { additional_account_ids: vec![Uuid::new_v4()], ..CreateApiKeyRequest::default() };
+    let result = service.create_api_key(&verified, account_id, request).await;
+    assert!(matches!(result, Err(ServiceError::AuthorizationError(_))));
+
+    // Rotation keeps the accounts
+    let rotated = service.rotate_api_key(&verified, created.id).await.unwrap();
+    assert_eq!(rotated.api_key.additional_account_ids, vec![sibling_id]);
+}
+
+#[tokio::test]
+async fn test_keys_spanning_foreign_accounts_stay_hidden() {
+    let repo = Arc::new(MockApiKeyRepository::new());
+    let service = AuthService::new(repo.clone());
+    let (account_id, sibling_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let tenant = AuthPrincipal {
+        account_ids: vec![account_id, sibling_id],
+        ..principal(account_id)
+    };
+
+    let request = CreateApiKeyRequest { additional_account_ids: vec![sibling_id], ..CreateApiKeyRequest::default() };
+    let created = service.create_api_key(&tenant, account_id, request).await.unwrap();
+
+    // A caller authorized only for the key's own account sees it, but not the sibling
+    let owner = principal(account_id);
+    let listed = service.list_api_keys(&owner, account_id).await.unwrap();
+    let listed = listed.iter().find(|k| k.id == created.id).expect("Key not listed");
+    assert!(listed.additional_account_ids.is_empty());
+
+    // Nor can it rotate the key into a replacement that acts for the sibling
+    let result = service.rotate_api_key(&owner, created.id).await;
+    assert!(matches!(result, Err(ServiceError::Repository(RepositoryError::NotFound(_)))));
+
+    let listed = service.list_api_keys(&tenant, account_id).await.unwrap();
+    assert_eq!(listed.iter().find(|k| k.id == created.id).unwrap().additional_account_ids, vec![sibling_id]);
+}
+
+#[tokio::test]
+async fn test_create_api_key_with_ttl_sets_expiry() {
+    let service = AuthService::new(Arc::new(MockApiKeyRepository::new()));
+    let account_id = Uuid::new_v4();
+
+    let created = service
+        .create_api_key(&principal(account_id), account_id, CreateApiKeyRequest { rate_limit_per_hour: None, ttl_seconds: Some(3600), additional_account_ids: Vec::new() })
+        .await
+        .unwrap();
+
+    let expires_at = created.expires_at.expect("TTL should set expires_at");
+    assert!(expires_at > Utc::now() + Duration::minutes(59));
+    assert!(service.verify_api_key(&created.raw_key).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_verify_api_key_rejects_expired_key() {
+    let repo = Arc::new(MockApiKeyRepository::new());
+    let service = AuthService::new(repo.clone());
+
+    let raw_key = "expired_key";
+    let key_hash = hex::encode(<sha2::Sha256 as sha2::Digest>::digest(raw_key.as_bytes()));
+    let mut api_key = ApiKey::new(Uuid::new_v4(), key_hash);
+    api_key.expires_at = Some(Utc::now() - Duration::seconds(1));
+    repo.create(&api_key).await.unwrap();
+
+    let result = service.verify_api_key(raw_key).await;
+    assert!(matches!(result, Err(ServiceError::Domain(DomainError::ApiKeyExpired))));
+}
+
+#[tokio::test]
+async fn test_bootstrap_admin_key_only_seeds_an_empty_deployment() {
+    let repo = Arc::new(MockApiKeyRepository::new());
+    let service = AuthService::new(repo.clone());
+    let raw_key = "bootstrap-admin-key-0123456789abcdef";
+
+    let seeded = service
+        .bootstrap_admin_key(raw_key)
+        .await
+        .expect("Bootstrap failed")
+        .expect("No key was seeded");
+
+    // The configured key authenticates as the new admin account
+    let principal = service.verify_api_key(raw_key).await.expect("Bootstrap key should verify");
+    assert_eq!(principal.api_key_id, seeded.id);
+    assert_eq!(principal.account_id, seeded.account_id);
+    assert!(principal.is_admin);
+    assert_eq!(repo.accounts.lock().unwrap().len(), 1);
+
+    // A second start leaves everything as is
+    let again = service.bootstrap_admin_key(raw_key).await.expect("Bootstrap failed");
+    assert!(again.is_none());
+    assert_eq!(repo.keys.lock().unwrap().len(), 1);
+    assert_eq!(repo.accounts.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_rotated_key_works_until_grace_period_ends() {
+    let repo = Arc::new(MockApiKeyRepository::new());
+    let service = AuthService::new(repo.clone()).with_rotation_grace(Duration::minutes(10));
+    let account_id = Uuid::new_v4();
+    let caller = principal(account_id);
+
+    let old = service
+        .create_api_key(&caller, account_id, CreateApiKeyRequest { rate_limit_per_hour: Some(100), ttl_seconds: None, additional_account_ids: Vec::new() })
+        .await
+        .unwrap();
+    let rotated = service.rotate_api_key(&caller, old.id).await.expect("Rotation failed");
+
+    assert_eq!(rotated.previous_key_id, old.id);
+    assert_ne!(rotated.api_key.raw_key, old.raw_key);
+    assert_eq!(rotated.api_key.rate_limit_per_hour, 100);
+    assert!(rotated.previous_key_expires_at > Utc::now() + Duration::minutes(9));
+
+    // Both keys authenticate during the grace period
+    let verified_old = service.verify_api_key(&old.raw_key).await.expect("Old key should still verify");
+    assert_eq!(verified_old.api_key_id, old.id);
+    let verified_new = service.verify_api_key(&rotated.api_key.raw_key).await.expect("New key should verify");
+    assert_eq!(verified_new.api_key_id, rotated.api_key.id);
+
+    // Once the grace period is over only the new key works
+    for key in repo.keys.lock().unwrap().iter_mut().filter(|k| k.id == old.id) {
+        key.expires_at = Some(Utc::now() - Duration::seconds(1));
+    }
+    let result = service.verify_api_key(&old.raw_key).await;
+    assert!(matches!(result, Err(ServiceError::Domain(DomainError::ApiKeyExpired))));
+    assert!(service.verify_api_key(&rotated.api_key.raw_key).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_rotate_api_key_rejects_repeat_and_foreign_keys() {
+    let service = AuthService::new(Arc::new(MockApiKeyRepository::new()));
+    let account_id = Uuid::new_v4();
+    let caller = principal(account_id);
+
+    let old = service
+        .create_api_key(&caller, account_id, CreateApiKeyRequest::default())
+        .await
+        .unwrap();
+
+    // Another account's key looks like it doesn't exist
+    let result = service.rotate_api_key(&principal(Uuid::new_v4()), old.id).await;
+    assert!(matches!(result, Err(ServiceError::Repository(RepositoryError::NotFound(_)))));
+
+    service.rotate_api_key(&caller, old.id).await.expect("Rotation failed");
+    let result = service.rotate_api_key(&caller, old.id).await;
+    assert!(matches!(result, Err(ServiceError::Repository(RepositoryError::DuplicateEntry(_)))));
+}
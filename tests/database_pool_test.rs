@@ -1,4 +1,4 @@
-use dodo_payments_assignment::infrastructure::{config::{Config, DatabaseConfig, ServerConfig, RateLimitConfig, WebhookConfig, LoggingConfig}, database};
+use dodo_payments_assignment::infrastructure::{config::{Config, DatabaseConfig, ServerConfig, RateLimitConfig, WebhookConfig, LoggingConfig, IdempotencyConfig, AccountConfig, RetentionConfig, TransactionConfig, AuthConfig, CorsConfig, ReconciliationConfig}, database};
 
 /// Helper to create test config with localhost database
 fn create_test_config() -> Config {
@@ -9,21 +9,96 @@ fn create_test_config() -> Config {
             max_connections: 10,
             min_connections: 2,
             acquire_timeout_seconds: 30,
+            acquire_warn_ms: 2000,
+            max_attempts: 3,
+            retry_base_delay_ms: 10,
+            skip_invalid_transaction_rows: false,
+            pool_metrics_interval_seconds: 0,
+            run_migrations: false,
         },
         server: ServerConfig {
             host: "0.0.0.0".to_string(),
             port: 8080,
+            sign_responses: false,
+            require_request_signatures: false,
+            request_signature_tolerance_seconds: 300,
+            max_page_size: 100,
+            max_body_bytes: 65536,
+            money_format: dodo_payments_assignment::domain::value_objects::MoneyFormat::Plain,
+            shutdown_timeout_seconds: 30,
         },
         rate_limiting: RateLimitConfig {
             requests_per_hour: 1000,
+            trusted_proxies: vec![],
+            idle_ttl_seconds: 3600,
+            sweep_interval_seconds: 300,
+            max_tracked_ips: 100_000,
         },
         webhook: WebhookConfig {
             timeout_seconds: 30,
+            connect_timeout_ms: 5000,
+            max_timeout_ms: 60000,
             max_retries: 5,
             initial_backoff_ms: 1000,
+            max_backoff_ms: 30000,
+            max_elapsed_ms: 300000,
+            validate_payloads: true,
+            propagate_request_id: true,
+            sequence_numbers: true,
+            log_successful_deliveries: true,
+            require_https: true,
+            allow_private_networks: false,
+            require_verification: false,
+            gzip_min_bytes: 1024,
+            max_pending_dispatches: 0,
+            overflow_policy: dodo_payments_assignment::domain::value_objects::WebhookOverflowPolicy::Shed,
+            overflow_block_timeout_ms: 1000,
+            transactional_outbox: false,
+            outbox_poll_interval_ms: 1000,
+            outbox_batch_size: 100,
         },
         logging: LoggingConfig {
             level: "info".to_string(),
+            format: dodo_payments_assignment::domain::value_objects::LogFormat::Pretty,
+        },
+        idempotency: IdempotencyConfig {
+            ttl_seconds: 86400,
+            key_format: dodo_payments_assignment::domain::value_objects::IdempotencyKeyFormat::Any,
+            cleanup_interval_seconds: 3600,
+        },
+        accounts: AccountConfig {
+            unique_business_names: false,
+            record_balance_snapshots: false,
+        },
+        retention: RetentionConfig {
+            transaction_retention_days: 0,
+            archival_interval_seconds: 3600,
+        },
+        transactions: TransactionConfig {
+            allow_zero_amount_transactions: false,
+            min_transaction_amount: None,
+            max_transaction_amount: None,
+            daily_debit_limit: None,
+            max_batch_legs: 100,
+        },
+        auth: AuthConfig {
+            bootstrap_admin_key: None,
+            key_rotation_grace_seconds: 3600,
+            key_pepper: None,
+            accept_legacy_key_hashes: true,
+        },
+        cors: CorsConfig {
+            allowed_origins: Vec::new(),
+            allowed_methods: Vec::new(),
+            allowed_headers: Vec::new(),
+            allow_credentials: false,
+        },
+        reconciliation: ReconciliationConfig {
+            interval_seconds: 0,
+            batch_size: 100,
+            batch_pause_ms: 100,
+            alert_url: None,
+            alert_secret: None,
         },
     }
 }
@@ -73,4 +148,24 @@ async fn test_pool_can_handle_multiple_connections() {
         let result = handle.await.expect("Task failed");
         assert_eq!(result, i as i32);
     }
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_migrations_apply_over_an_existing_schema() {
+    let config = create_test_config();
+    let pool = database::create_pool(&config)
+        .await
+        .expect("Failed to create database pool");
+
+    // The test database is already migrated, so both runs must be no-ops that succeed
+    database::run_migrations(&pool).await.expect("First migration run failed");
+    database::run_migrations(&pool).await.expect("Second migration run failed");
+
+    for table in ["accounts", "transactions", "webhooks", "api_keys", "idempotency_keys", "webhook_outbox"] {
+        let exists: (bool,) = sqlx::query_as("SELECT to_regclass($1) IS NOT NULL")
+            .bind(table)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(exists.0, "missing table {}", table);
+    }
+}
@@ -5,7 +5,8 @@ use dodo_payments_assignment::application::dto::transaction_dto::{
 use dodo_payments_assignment::application::services::account_service::AccountService;
 use dodo_payments_assignment::application::services::transaction_service::TransactionService;
 use dodo_payments_assignment::application::services::webhook_service::WebhookService;
-use dodo_payments_assignment::domain::services::WebhookDispatcher;
+use dodo_payments_assignment::domain::repositories::TransactionFilter;
+use dodo_payments_assignment::domain::services::{DeliveryContext, WebhookDispatcher};
 use dodo_payments_assignment::infrastructure::config::Config;
 use dodo_payments_assignment::infrastructure::database::postgres_account_repository::PostgresAccountRepository;
 use dodo_payments_assignment::infrastructure::database::postgres_api_key_repository::PostgresApiKeyRepository;
@@ -30,6 +31,7 @@ impl WebhookDispatcher for MockWebhookDispatcher {
         url: &str,
         payload: &Value,
         _secret: &str,
+        _context: &DeliveryContext,
     ) -> Result<(), String> {
         let mut dispatched = self.dispatched.lock().await;
         dispatched.push((url.to_string(), payload.clone()));
@@ -67,7 +69,8 @@ async fn setup_test_context() -> (
         dispatcher,
     ));
 
-    let account_service = Arc::new(AccountService::new(account_repo.clone()));
+    let account_service =
+        Arc::new(AccountService::new(account_repo.clone()).with_opening_credits(transaction_repo.clone()));
     let transaction_service = Arc::new(TransactionService::new(
         transaction_repo.clone(),
         Some(webhook_service.clone()),
@@ -84,6 +87,7 @@ async fn test_full_lifecycle() {
     let account_a = account_service
         .create_account(CreateAccountRequest {
             business_name: "Alice Corp".to_string(),
+            initial_balance: None,
         })
         .await
         .expect("Failed to create account A");
@@ -92,6 +96,7 @@ async fn test_full_lifecycle() {
     let account_b = account_service
         .create_account(CreateAccountRequest {
             business_name: "Bob Inc".to_string(),
+            initial_balance: None,
         })
         .await
         .expect("Failed to create account B");
@@ -102,7 +107,8 @@ async fn test_full_lifecycle() {
             account_id: account_a.id,
             amount: dec!(100.00),
             idempotency_key: Some(Uuid::new_v4().to_string()),
-        })
+            description: None,
+        }, None)
         .await
         .expect("Failed to deposit");
     assert_eq!(deposit.amount, dec!(100.00));
@@ -121,7 +127,8 @@ async fn test_full_lifecycle() {
             to_account_id: account_b.id,
             amount: dec!(50.00),
             idempotency_key: Some(Uuid::new_v4().to_string()),
-        })
+            description: None,
+        }, None)
         .await
         .expect("Failed to transfer");
 
@@ -131,7 +138,8 @@ async fn test_full_lifecycle() {
             account_id: account_b.id,
             amount: dec!(10.00),
             idempotency_key: Some(Uuid::new_v4().to_string()),
-        })
+            description: None,
+        }, None)
         .await
         .expect("Failed to withdraw");
 
@@ -159,12 +167,14 @@ async fn test_concurrency_invariant() {
     let acc1 = account_service
         .create_account(CreateAccountRequest {
             business_name: "Conc1".to_string(),
+            initial_balance: None,
         })
         .await
         .unwrap();
     let acc2 = account_service
         .create_account(CreateAccountRequest {
             business_name: "Conc2".to_string(),
+            initial_balance: None,
         })
         .await
         .unwrap();
@@ -175,7 +185,8 @@ async fn test_concurrency_invariant() {
             account_id: acc1.id,
             amount: dec!(1000.00),
             idempotency_key: Some(Uuid::new_v4().to_string()),
-        })
+            description: None,
+        }, None)
         .await
         .unwrap();
 
@@ -194,7 +205,8 @@ async fn test_concurrency_invariant() {
                 to_account_id: to,
                 amount: dec!(10.00),
                 idempotency_key: Some(Uuid::new_v4().to_string()),
-            })
+                description: None,
+            }, None)
             .await
         }));
     }
@@ -222,3 +234,37 @@ async fn test_concurrency_invariant() {
     assert_eq!(acc1_final.balance, dec!(900.00));
     assert_eq!(acc2_final.balance, dec!(100.00));
 }
+
+#[tokio::test]
+async fn test_create_account_with_initial_balance() {
+    let (account_service, transaction_service, _) = setup_test_context().await;
+
+    let account = account_service
+        .create_account(CreateAccountRequest {
+            business_name: "Funded Corp".to_string(),
+            initial_balance: Some(dec!(250.00)),
+        })
+        .await
+        .expect("Failed to create funded account");
+    assert_eq!(account.balance, dec!(250.00));
+
+    // The opening balance is backed by a credit on the ledger
+    let history = transaction_service
+        .get_history(account.id, TransactionFilter::default(), 10, 0)
+        .await
+        .expect("Failed to load history");
+    assert_eq!(history.total, 1);
+    assert_eq!(history.items[0].transaction_type, "credit");
+    assert_eq!(history.items[0].amount, dec!(250.00));
+
+    // Negative and sub-cent opening balances are rejected before anything is written
+    for initial_balance in [dec!(-1.00), dec!(10.001)] {
+        let result = account_service
+            .create_account(CreateAccountRequest {
+                business_name: "Invalid Funding Corp".to_string(),
+                initial_balance: Some(initial_balance),
+            })
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -3,14 +3,18 @@ use axum::{
     http::{Request, StatusCode},
     Router,
 };
-use dodo_payments_assignment::presentation::middleware::rate_limit::RateLimitLayer;
+use dodo_payments_assignment::application::services::AuthPrincipal;
+use dodo_payments_assignment::infrastructure::config::RateLimitConfig;
+use dodo_payments_assignment::presentation::middleware::rate_limit::{ApiKeyRateLimitLayer, RateLimitLayer};
+use std::num::NonZeroU32;
 use tower::ServiceExt; // for oneshot
+use uuid::Uuid;
 
 #[tokio::test]
 async fn test_rate_limiting() {
     // 1. Create a dummy router with rate limiting
     // Limit: 2 requests per hour
-    let rate_limit_layer = RateLimitLayer::new(2);
+    let rate_limit_layer = RateLimitLayer::new(NonZeroU32::new(2).unwrap());
 
     let app = Router::new()
         .route("/", axum::routing::get(|| async { "OK" }))
@@ -42,4 +46,220 @@ async fn test_rate_limiting() {
     // 5. Request 3 (Blocked)
     let res3 = send_request(&app).await.unwrap();
     assert_eq!(res3.status(), StatusCode::TOO_MANY_REQUESTS);
+    let body = axum::body::to_bytes(res3.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["error_code"], "RATE_LIMITED");
+}
+
+#[tokio::test]
+async fn test_rate_limiting_per_api_key() {
+    // Two keys with different quotas: key A allows 1 request, key B allows 2
+    let key_a = Uuid::new_v4();
+    let key_b = Uuid::new_v4();
+
+    // Stand-in for `require_auth`: resolve the principal from a header
+    let fake_auth = move |mut req: axum::extract::Request, next: axum::middleware::Next| async move {
+        let key = req.headers().get("x-key").and_then(|v| v.to_str().ok()).unwrap_or("a").to_string();
+        let (api_key_id, rate_limit_per_hour) = if key == "a" { (key_a, 1) } else { (key_b, 2) };
+        let account_id = Uuid::new_v4();
+        req.extensions_mut().insert(AuthPrincipal {
+            account_id,
+            account_ids: vec![account_id],
+            api_key_id,
+            rate_limit_per_hour,
+            is_admin: false,
+        });
+        next.run(req).await
+    };
+
+    let app = Router::new()
+        .route("/", axum::routing::get(|| async { "OK" }))
+        .layer(axum::middleware::from_fn_with_state(ApiKeyRateLimitLayer::new(), ApiKeyRateLimitLayer::handle))
+        .layer(axum::middleware::from_fn(fake_auth));
+
+    let send_request = |app: &Router, key: &'static str| {
+        let req = Request::builder()
+            .uri("/")
+            .header("x-key", key)
+            .body(Body::empty())
+            .unwrap();
+        app.clone().oneshot(req)
+    };
+
+    // Key A: first allowed, second blocked
+    assert_eq!(send_request(&app, "a").await.unwrap().status(), StatusCode::OK);
+    assert_eq!(send_request(&app, "a").await.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Key B has its own bucket with its own quota
+    assert_eq!(send_request(&app, "b").await.unwrap().status(), StatusCode::OK);
+    assert_eq!(send_request(&app, "b").await.unwrap().status(), StatusCode::OK);
+    assert_eq!(send_request(&app, "b").await.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+fn forwarded_request(peer: [u8; 4], forwarded_for: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .uri("/")
+        .extension(axum::extract::ConnectInfo(std::net::SocketAddr::from((peer, 1234))));
+    if let Some(value) = forwarded_for {
+        builder = builder.header("x-forwarded-for", value);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn test_rate_limiting_honors_forwarded_for_from_trusted_proxy() {
+    let config = RateLimitConfig {
+        requests_per_hour: 1,
+        trusted_proxies: vec!["127.0.0.1/32".parse().unwrap()],
+        idle_ttl_seconds: 3600,
+        sweep_interval_seconds: 300,
+        max_tracked_ips: 100_000,
+    };
+    let app = Router::new()
+        .route("/", axum::routing::get(|| async { "OK" }))
+        .layer(axum::middleware::from_fn_with_state(RateLimitLayer::from_config(&config).unwrap(), RateLimitLayer::handle));
+
+    // Each forwarded client gets its own bucket even though they share the proxy peer
+    let res = app.clone().oneshot(forwarded_request([127, 0, 0, 1], Some("203.0.113.1"))).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app.clone().oneshot(forwarded_request([127, 0, 0, 1], Some("203.0.113.2, 127.0.0.1"))).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app.clone().oneshot(forwarded_request([127, 0, 0, 1], Some("203.0.113.1"))).await.unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // A malformed header falls back to the proxy's own address
+    let res = app.clone().oneshot(forwarded_request([127, 0, 0, 1], Some("garbage"))).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app.clone().oneshot(forwarded_request([127, 0, 0, 1], None)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_rate_limiting_ignores_forwarded_for_from_untrusted_peer() {
+    let config = RateLimitConfig {
+        requests_per_hour: 1,
+        trusted_proxies: vec!["10.0.0.0/8".parse().unwrap()],
+        idle_ttl_seconds: 3600,
+        sweep_interval_seconds: 300,
+        max_tracked_ips: 100_000,
+    };
+    let app = Router::new()
+        .route("/", axum::routing::get(|| async { "OK" }))
+        .layer(axum::middleware::from_fn_with_state(RateLimitLayer::from_config(&config).unwrap(), RateLimitLayer::handle));
+
+    // Spoofed headers from a directly connected client must not open new buckets
+    let res = app.clone().oneshot(forwarded_request([198, 51, 100, 7], Some("203.0.113.1"))).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app.clone().oneshot(forwarded_request([198, 51, 100, 7], Some("203.0.113.2"))).await.unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_rate_limit_headers() {
+    // Limit: 2 requests per hour, i.e. one token replenished every 30 minutes
+    let app = Router::new()
+        .route("/", axum::routing::get(|| async { "OK" }))
+        .layer(axum::middleware::from_fn_with_state(RateLimitLayer::new(NonZeroU32::new(2).unwrap()), RateLimitLayer::handle));
+
+    let header = |res: &axum::response::Response, name: &str| -> u64 {
+        res.headers()
+            .get(name)
+            .unwrap_or_else(|| panic!("missing {} header", name))
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap()
+    };
+
+    let res1 = app.clone().oneshot(forwarded_request([127, 0, 0, 1], None)).await.unwrap();
+    assert_eq!(res1.status(), StatusCode::OK);
+    assert_eq!(header(&res1, "x-ratelimit-limit"), 2);
+    assert_eq!(header(&res1, "x-ratelimit-remaining"), 1);
+    assert_eq!(header(&res1, "x-ratelimit-reset"), 1800);
+    assert!(res1.headers().get("retry-after").is_none());
+
+    let res2 = app.clone().oneshot(forwarded_request([127, 0, 0, 1], None)).await.unwrap();
+    assert_eq!(res2.status(), StatusCode::OK);
+    assert_eq!(header(&res2, "x-ratelimit-remaining"), 0);
+    assert_eq!(header(&res2, "x-ratelimit-reset"), 3600);
+
+    let res3 = app.clone().oneshot(forwarded_request([127, 0, 0, 1], None)).await.unwrap();
+    assert_eq!(res3.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(header(&res3, "x-ratelimit-limit"), 2);
+    assert_eq!(header(&res3, "x-ratelimit-remaining"), 0);
+    let retry_after = header(&res3, "retry-after");
+    assert!(retry_after > 0 && retry_after <= 1800);
+}
+
+#[tokio::test]
+async fn test_prune_idle_rate_limiters() {
+    let rate_limit_layer = RateLimitLayer::new(NonZeroU32::new(1).unwrap());
+    let app = Router::new()
+        .route("/", axum::routing::get(|| async { "OK" }))
+        .layer(axum::middleware::from_fn_with_state(rate_limit_layer.clone(), RateLimitLayer::handle));
+
+    let res = app.clone().oneshot(forwarded_request([127, 0, 0, 1], None)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app.clone().oneshot(forwarded_request([127, 0, 0, 1], None)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Recently seen limiters survive a sweep
+    rate_limit_layer.prune_idle(std::time::Duration::from_secs(3600));
+    let res = app.clone().oneshot(forwarded_request([127, 0, 0, 1], None)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Idle limiters are dropped, so the IP starts over with a fresh quota
+    rate_limit_layer.prune_idle(std::time::Duration::ZERO);
+    let res = app.clone().oneshot(forwarded_request([127, 0, 0, 1], None)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_rate_limit_layer_validates_quota_at_build_time() {
+    // A zero quota is rejected when the layer is built rather than panicking per request
+    let config = RateLimitConfig {
+        requests_per_hour: 0,
+        trusted_proxies: vec![],
+        idle_ttl_seconds: 3600,
+        sweep_interval_seconds: 300,
+        max_tracked_ips: 100_000,
+    };
+    assert!(RateLimitLayer::from_config(&config).is_err());
+
+    // A valid quota serves many distinct, cold-cache IPs without issue
+    let config = RateLimitConfig { requests_per_hour: 1, ..config };
+    let app = Router::new()
+        .route("/", axum::routing::get(|| async { "OK" }))
+        .layer(axum::middleware::from_fn_with_state(RateLimitLayer::from_config(&config).unwrap(), RateLimitLayer::handle));
+
+    for last_octet in 1..=50u8 {
+        let res = app.clone().oneshot(forwarded_request([10, 0, 0, last_octet], None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limiter_map_stays_bounded() {
+    let config = RateLimitConfig {
+        requests_per_hour: 10,
+        trusted_proxies: vec![],
+        idle_ttl_seconds: 3600,
+        sweep_interval_seconds: 300,
+        max_tracked_ips: 10,
+    };
+    let rate_limit_layer = RateLimitLayer::from_config(&config).unwrap();
+    let app = Router::new()
+        .route("/", axum::routing::get(|| async { "OK" }))
+        .layer(axum::middleware::from_fn_with_state(rate_limit_layer.clone(), RateLimitLayer::handle));
+
+    // Spray requests from many distinct source IPs
+    for last_octet in 1..=200u8 {
+        let res = app.clone().oneshot(forwarded_request([10, 0, 1, last_octet], None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(rate_limit_layer.tracked_ip_count() <= 10);
+    }
+
+    // The most recently seen IP is still tracked, so its quota keeps counting down
+    let res = app.clone().oneshot(forwarded_request([10, 0, 1, 200], None)).await.unwrap();
+    assert_eq!(res.headers().get("x-ratelimit-remaining").unwrap(), "8");
 }
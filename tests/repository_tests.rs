@@ -1,4 +1,5 @@
 use dodo_payments_assignment::domain::entities::Account;
+use dodo_payments_assignment::domain::errors::RepositoryError;
 use dodo_payments_assignment::domain::repositories::AccountRepository;
 use dodo_payments_assignment::domain::value_objects::Money;
 use dodo_payments_assignment::infrastructure::config::Config;
@@ -20,7 +21,7 @@ async fn test_account_repository_crud() {
 
     // 2. Create Account
     let initial_balance = Money::new(dec!(100.00)).unwrap();
-    let new_account = Account::new("Test Corp".to_string(), initial_balance.clone())
+    let new_account = Account::new("Test Corp".to_string(), initial_balance)
         .expect("Failed to create account entity");
 
     let created = repository.create(&new_account).await.expect("Failed to persist account");
@@ -33,23 +34,50 @@ async fn test_account_repository_crud() {
     assert_eq!(fetched.id, created.id);
     assert_eq!(fetched.business_name, created.business_name);
 
+    // Batch lookup skips unknown ids
+    let found = repository
+        .find_by_ids(&[created.id, uuid::Uuid::new_v4()])
+        .await
+        .expect("Failed to find accounts");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, created.id);
+
     // 4. Update Balance
+    assert_eq!(fetched.version, 0);
     let new_balance = Money::new(dec!(200.00)).unwrap();
-    repository.update_balance(created.id, new_balance.clone()).await.expect("Failed to update balance");
+    let version = repository
+        .update_balance(created.id, new_balance, fetched.version)
+        .await
+        .expect("Failed to update balance");
+    assert_eq!(version, 1);
     
     let updated = repository.find_by_id(created.id).await.expect("Failed to find updated account");
     assert_eq!(updated.balance, new_balance);
+    assert_eq!(updated.version, 1);
+
+    // A writer still holding the old version must not overwrite the update
+    let stale = repository.update_balance(created.id, Money::new(dec!(50.00)).unwrap(), fetched.version).await;
+    assert!(matches!(stale, Err(RepositoryError::VersionConflict(_))));
+    assert_eq!(repository.find_by_id(created.id).await.unwrap().balance, new_balance);
 
     // 5. Update Business Name
     repository.update_business_name(created.id, "Updated Corp".to_string()).await.expect("Failed to update name");
     
     let updated_name = repository.find_by_id(created.id).await.expect("Failed to find updated account");
     assert_eq!(updated_name.business_name, "Updated Corp");
+    assert_eq!(updated_name.version, 2);
 
     // 6. List
     let list = repository.list(10, 0).await.expect("Failed to list accounts");
     assert!(list.iter().any(|a| a.id == created.id));
 
+    // Search is case-insensitive and treats wildcards literally
+    let found = repository.search("updated CORP", 10, 0).await.expect("Failed to search accounts");
+    assert!(found.iter().any(|a| a.id == created.id));
+    assert!(repository.count_matching("updated corp").await.expect("Failed to count accounts") >= 1);
+    let found = repository.search("%", 10, 0).await.expect("Failed to search accounts");
+    assert!(found.iter().all(|a| a.business_name.contains('%')));
+
     // 7. Soft Delete
     repository.delete(created.id).await.expect("Failed to delete account");
 
@@ -59,4 +87,13 @@ async fn test_account_repository_crud() {
 
     let exists_result = repository.exists(created.id).await.expect("Failed to check existence");
     assert!(!exists_result); // Should be false
+
+    // 9. Restore
+    let restored = repository.restore(created.id).await.expect("Failed to restore account");
+    assert!(restored.deleted_at.is_none());
+    assert!(repository.exists(created.id).await.unwrap());
+
+    // 10. Restoring a live account is rejected
+    let restore_again = repository.restore(created.id).await;
+    assert!(matches!(restore_again, Err(RepositoryError::InvalidState(_))));
 }
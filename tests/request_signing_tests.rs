@@ -0,0 +1,192 @@
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use dodo_payments_assignment::application::services::{AccountService, AuthPrincipal};
+use dodo_payments_assignment::domain::entities::Account;
+use dodo_payments_assignment::domain::errors::RepositoryError;
+use dodo_payments_assignment::domain::repositories::AccountRepository;
+use dodo_payments_assignment::domain::value_objects::{AccountStatus, Money, TransactionLimits};
+use dodo_payments_assignment::infrastructure::http_client::request_signature;
+use dodo_payments_assignment::presentation::middleware::request_signing::RequestSigningLayer;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt; // for oneshot
+use uuid::Uuid;
+
+// Mock Repository holding a single account
+struct MockAccountRepository {
+    account: Account,
+}
+
+#[async_trait]
+impl AccountRepository for MockAccountRepository {
+    async fn create(&self, account: &Account) -> Result<Account, RepositoryError> {
+        Ok(account.clone())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Account, RepositoryError> {
+        if id == self.account.id {
+            Ok(self.account.clone())
+        } else {
+            Err(RepositoryError::NotFound("Account not found".to_string()))
+        }
+    }
+
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Account>, RepositoryError> {
+        Ok(ids.iter().filter(|id| **id == self.account.id).map(|_| self.account.clone()).collect())
+    }
+
+    async fn update_balance(&self, _id: Uuid, _new_balance: Money, expected_version: i64) -> Result<i64, RepositoryError> {
+        Ok(expected_version + 1)
+    }
+
+    async fn update_business_name(&self, _id: Uuid, _name: String) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn update_status(&self, _id: Uuid, _status: AccountStatus) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn find_limits(&self, _id: Uuid) -> Result<TransactionLimits, RepositoryError> {
+        Ok(TransactionLimits::default())
+    }
+
+    async fn update_limits(&self, _id: Uuid, _limits: &TransactionLimits) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn exists(&self, id: Uuid) -> Result<bool, RepositoryError> {
+        Ok(id == self.account.id)
+    }
+
+    async fn exists_by_business_name(&self, business_name: &str) -> Result<bool, RepositoryError> {
+        Ok(self.account.business_name.eq_ignore_ascii_case(business_name))
+    }
+
+    async fn list(&self, _limit: i64, _offset: i64) -> Result<Vec<Account>, RepositoryError> {
+        Ok(vec![self.account.clone()])
+    }
+
+    async fn search(&self, query: &str, _limit: i64, _offset: i64) -> Result<Vec<Account>, RepositoryError> {
+        let name = self.account.business_name.to_lowercase();
+        Ok(if name.contains(&query.to_lowercase()) { vec![self.account.clone()] } else { vec![] })
+    }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        Ok(1)
+    }
+
+    async fn count_matching(&self, query: &str) -> Result<i64, RepositoryError> {
+        Ok(self.search(query, 1, 0).await?.len() as i64)
+    }
+
+    async fn delete(&self, _id: Uuid) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn restore(&self, _id: Uuid) -> Result<Account, RepositoryError> {
+        Ok(self.account.clone())
+    }
+
+    async fn health_check(&self) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+}
+
+/// A deposit route nested like the real one, echoing its body, behind request signing
+fn signed_app(account: Account) -> Router {
+    let account_id = account.id;
+    let account_service = Arc::new(AccountService::new(Arc::new(MockAccountRepository { account })));
+
+    // Stand-in for `require_auth`
+    let fake_auth = move |mut req: axum::extract::Request, next: axum::middleware::Next| async move {
+        req.extensions_mut().insert(AuthPrincipal {
+            account_id,
+            account_ids: vec![account_id],
+            api_key_id: Uuid::new_v4(),
+            rate_limit_per_hour: 1000,
+            is_admin: false,
+        });
+        next.run(req).await
+    };
+
+    Router::new()
+        .nest(
+            "/transactions",
+            Router::new().route(
+                "/deposit",
+                axum::routing::post(|body: String| async move { body }).get(|| async { "listing" }),
+            ),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            RequestSigningLayer::new(account_service, Duration::from_secs(300)),
+            RequestSigningLayer::handle,
+        ))
+        .layer(axum::middleware::from_fn(fake_auth))
+}
+
+fn post(body: &str, timestamp: Option<i64>, signature: Option<String>) -> Request<Body> {
+    let mut request = Request::builder().method("POST").uri("/transactions/deposit");
+    if let Some(timestamp) = timestamp {
+        request = request.header("x-request-timestamp", timestamp.to_string());
+    }
+    if let Some(signature) = signature {
+        request = request.header("x-request-signature", signature);
+    }
+    request.body(Body::from(body.to_string())).unwrap()
+}
+
+#[tokio::test]
+async fn test_signed_request_reaches_the_handler_intact() {
+    let account = Account::new("Request Signing Corp".to_string(), Money::new(dec!(0.00)).unwrap()).unwrap();
+    let secret = account.webhook_secret.clone();
+    let body = r#"{"amount":"10.00"}"#;
+    let now = chrono::Utc::now().timestamp();
+    let signature = format!("sha256={}", request_signature(&secret, now, "POST", "/transactions/deposit", body.as_bytes()));
+
+    let response = signed_app(account).oneshot(post(body, Some(now), Some(signature))).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let echoed = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&echoed[..], body.as_bytes());
+}
+
+#[tokio::test]
+async fn test_unsigned_tampered_and_stale_requests_are_rejected() {
+    let account = Account::new("Request Signing Corp".to_string(), Money::new(dec!(0.00)).unwrap()).unwrap();
+    let secret = account.webhook_secret.clone();
+    let app = signed_app(account);
+    let body = r#"{"amount":"10.00"}"#;
+    let now = chrono::Utc::now().timestamp();
+    let sign = |timestamp: i64| {
+        format!("sha256={}", request_signature(&secret, timestamp, "POST", "/transactions/deposit", body.as_bytes()))
+    };
+
+    let unsigned = app.clone().oneshot(post(body, None, None)).await.unwrap();
+    assert_eq!(unsigned.status(), StatusCode::UNAUTHORIZED);
+
+    let tampered = app
+        .clone()
+        .oneshot(post(r#"{"amount":"9999.00"}"#, Some(now), Some(sign(now))))
+        .await
+        .unwrap();
+    assert_eq!(tampered.status(), StatusCode::UNAUTHORIZED);
+
+    let stale = now - 600;
+    let replayed = app.clone().oneshot(post(body, Some(stale), Some(sign(stale)))).await.unwrap();
+    assert_eq!(replayed.status(), StatusCode::UNAUTHORIZED);
+    let error = axum::body::to_bytes(replayed.into_body(), usize::MAX).await.unwrap();
+    assert!(String::from_utf8_lossy(&error).contains("tolerance"));
+
+    // Reads need no signature
+    let read = app
+        .oneshot(Request::builder().uri("/transactions/deposit").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(read.status(), StatusCode::OK);
+}
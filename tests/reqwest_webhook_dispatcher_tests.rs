@@ -1,7 +1,12 @@
-use dodo_payments_assignment::domain::services::WebhookDispatcher;
-use dodo_payments_assignment::infrastructure::http_client::ReqwestWebhookDispatcher;
+use dodo_payments_assignment::domain::entities::Webhook;
+use dodo_payments_assignment::domain::services::{DeliveryContext, WebhookDispatcher};
+use dodo_payments_assignment::domain::value_objects::WebhookEvent;
+use dodo_payments_assignment::infrastructure::http_client::reqwest_webhook_dispatcher::DELIVERY_LOG_TARGET;
+use dodo_payments_assignment::infrastructure::http_client::{verify_signature, ReqwestWebhookDispatcher};
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use tracing_subscriber::layer::SubscriberExt;
 use axum::{routing::post, Router};
 use tokio::net::TcpListener;
 
@@ -30,13 +35,40 @@ async fn start_mock_server(response_sequence: Vec<u16>) -> (String, Arc<Mutex<Ve
     (url, sequence)
 }
 
+// Collects the fields of every delivery outcome event
+#[derive(Clone, Default)]
+struct DeliveryEvents(Arc<Mutex<Vec<HashMap<String, String>>>>);
+
+#[derive(Default)]
+struct FieldMap(HashMap<String, String>);
+
+impl tracing::field::Visit for FieldMap {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for DeliveryEvents {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+        if event.metadata().target() == DELIVERY_LOG_TARGET {
+            let mut fields = FieldMap::default();
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+}
+
 #[tokio::test]
 async fn test_dispatch_success_first_attempt() {
     let (url, _) = start_mock_server(vec![200]).await;
     let dispatcher = ReqwestWebhookDispatcher::new(3, 10);
     let payload = json!({"event": "test"});
     
-    let result = dispatcher.dispatch(&url, &payload, "secret").await;
+    let result = dispatcher.dispatch(&url, &payload, "secret", &DeliveryContext::default()).await;
     assert!(result.is_ok());
 }
 
@@ -48,7 +80,7 @@ async fn test_dispatch_retries_on_failure_and_succeeds() {
     let dispatcher = ReqwestWebhookDispatcher::new(3, 10);
     let payload = json!({"event": "test"});
     
-    let result = dispatcher.dispatch(&url, &payload, "secret").await;
+    let result = dispatcher.dispatch(&url, &payload, "secret", &DeliveryContext::default()).await;
     assert!(result.is_ok());
 }
 
@@ -61,8 +93,264 @@ async fn test_dispatch_fails_after_max_retries() {
     let dispatcher = ReqwestWebhookDispatcher::new(max_retries, 10);
     let payload = json!({"event": "test"});
     
-    let result = dispatcher.dispatch(&url, &payload, "secret").await;
+    let result = dispatcher.dispatch(&url, &payload, "secret", &DeliveryContext::default()).await;
     
     assert!(result.is_err());
     assert_eq!(result.unwrap_err(), format!("Webhook dispatch failed after {} attempts", max_retries));
 }
+
+#[tokio::test]
+async fn test_dispatch_sends_request_id_header() {
+    let received = Arc::new(Mutex::new(None));
+    let received_clone = received.clone();
+
+    let app = Router::new().route("/webhook", post(move |headers: axum::http::HeaderMap| {
+        let received = received_clone.clone();
+        async move {
+            *received.lock().unwrap() = headers
+                .get("x-dodo-request-id")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            axum::http::StatusCode::OK
+        }
+    }));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dispatcher = ReqwestWebhookDispatcher::new(0, 10);
+    let payload = json!({"event": "test"});
+
+    let context = DeliveryContext {
+        request_id: Some("req-123".to_string()),
+        ..DeliveryContext::default()
+    };
+
+    let result = dispatcher.dispatch(&url, &payload, "secret", &context).await;
+    assert!(result.is_ok());
+    assert_eq!(received.lock().unwrap().as_deref(), Some("req-123"));
+}
+
+#[tokio::test]
+async fn test_successful_dispatch_logs_structured_outcome() {
+    let events = DeliveryEvents::default();
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(events.clone()));
+
+    let (url, _) = start_mock_server(vec![200]).await;
+    let dispatcher = ReqwestWebhookDispatcher::new(3, 10);
+    let webhook_id = uuid::Uuid::new_v4();
+    let context = DeliveryContext {
+        webhook_id,
+        event: "transaction.deposit.completed".to_string(),
+        ..DeliveryContext::default()
+    };
+
+    let result = dispatcher.dispatch(&url, &json!({"event": "test"}), "secret", &context).await;
+    assert!(result.is_ok());
+
+    let events = events.0.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event["webhook_id"], webhook_id.to_string());
+    assert_eq!(event["url"], url);
+    assert_eq!(event["event"], "transaction.deposit.completed");
+    assert_eq!(event["attempt"], "1");
+    assert_eq!(event["status"], "200");
+    assert!(event["latency_ms"].parse::<u64>().is_ok());
+    assert_eq!(event["outcome"], "success");
+    assert!(!event.contains_key("error"));
+}
+
+#[tokio::test]
+async fn test_dispatch_signs_timestamp_and_body() {
+    let received = Arc::new(Mutex::new(None));
+    let received_clone = received.clone();
+
+    let app = Router::new().route("/webhook", post(move |headers: axum::http::HeaderMap, body: axum::body::Bytes| {
+        let received = received_clone.clone();
+        async move {
+            let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+            *received.lock().unwrap() = Some((header("x-dodo-timestamp"), header("x-dodo-signature"), body));
+            axum::http::StatusCode::OK
+        }
+    }));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dispatcher = ReqwestWebhookDispatcher::new(0, 10);
+    let result = dispatcher
+        .dispatch(&url, &json!({"event": "test"}), "secret", &DeliveryContext::default())
+        .await;
+    assert!(result.is_ok());
+
+    let (timestamp, signature, body) = received.lock().unwrap().take().expect("Webhook not received");
+    let timestamp: i64 = timestamp.expect("Missing X-Dodo-Timestamp").parse().unwrap();
+    let signature = signature.expect("Missing X-Dodo-Signature");
+    let tolerance = std::time::Duration::from_secs(300);
+
+    assert!(verify_signature("secret", timestamp, &body, &signature, tolerance).is_ok());
+    assert!(verify_signature("wrong", timestamp, &body, &signature, tolerance).is_err());
+    // Replaying the body under another timestamp fails
+    assert!(verify_signature("secret", timestamp + 1, &body, &signature, tolerance).is_err());
+}
+
+#[tokio::test]
+async fn test_dispatch_honors_per_webhook_timeouts() {
+    // Receiver that takes 300ms to answer
+    let app = Router::new().route("/slow", post(|| async {
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        axum::http::StatusCode::OK
+    }));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/slow", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let impatient = Webhook::new(uuid::Uuid::new_v4(), url.clone(), WebhookEvent::TransactionCompleted)
+        .and_then(|webhook| webhook.with_timeout_ms(Some(100)))
+        .unwrap();
+    let patient = Webhook::new(uuid::Uuid::new_v4(), url.clone(), WebhookEvent::TransactionCompleted)
+        .and_then(|webhook| webhook.with_timeout_ms(Some(2000)))
+        .unwrap();
+    let context_for = |webhook: &Webhook| DeliveryContext {
+        webhook_id: webhook.id,
+        timeout: webhook.timeout(),
+        ..DeliveryContext::default()
+    };
+
+    // The global timeout alone would fail every delivery
+    let dispatcher = ReqwestWebhookDispatcher::new(0, 10)
+        .with_timeouts(std::time::Duration::from_millis(50), std::time::Duration::from_secs(5));
+    let payload = json!({"event": "test"});
+
+    let started = std::time::Instant::now();
+    let result = dispatcher.dispatch(&url, &payload, "secret", &context_for(&impatient)).await;
+    assert!(result.is_err());
+    assert!(started.elapsed() < std::time::Duration::from_millis(300));
+
+    let result = dispatcher.dispatch(&url, &payload, "secret", &context_for(&patient)).await;
+    assert!(result.is_ok());
+
+    // Overrides are capped by the maximum
+    let capped = ReqwestWebhookDispatcher::new(0, 10)
+        .with_timeouts(std::time::Duration::from_secs(5), std::time::Duration::from_millis(100));
+    let result = capped.dispatch(&url, &payload, "secret", &context_for(&patient)).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_timed_out_attempts_are_retried_then_fail() {
+    let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let app = Router::new().route("/slow", post(move || {
+        let attempts = attempts_clone.clone();
+        async move {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            axum::http::StatusCode::OK
+        }
+    }));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/slow", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dispatcher = ReqwestWebhookDispatcher::new(1, 10)
+        .with_timeouts(std::time::Duration::from_millis(50), std::time::Duration::from_secs(5))
+        .with_connect_timeout(std::time::Duration::from_millis(20));
+    let payload = json!({"event": "test"});
+
+    let result = dispatcher.dispatch(&url, &payload, "secret", &DeliveryContext::default()).await;
+    assert!(result.is_err());
+    assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_backoff_plateaus_at_the_ceiling() {
+    let dispatcher = ReqwestWebhookDispatcher::new(100, 100)
+        .with_retry_limits(std::time::Duration::from_millis(1000), std::time::Duration::from_secs(60));
+
+    let delays: Vec<u64> = (1..=6).map(|retry| dispatcher.backoff_for(retry).as_millis() as u64).collect();
+    assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+    // Far past the point where doubling would overflow
+    assert_eq!(dispatcher.backoff_for(90), std::time::Duration::from_millis(1000));
+}
+
+#[tokio::test]
+async fn test_dispatch_stops_retrying_after_max_elapsed() {
+    let (url, remaining) = start_mock_server(vec![500; 20]).await;
+    let dispatcher = ReqwestWebhookDispatcher::new(20, 100)
+        .with_retry_limits(std::time::Duration::from_millis(100), std::time::Duration::from_millis(250));
+    let payload = json!({"event": "test"});
+
+    let result = dispatcher.dispatch(&url, &payload, "secret", &DeliveryContext::default()).await;
+    assert!(result.is_err());
+    // Each retry waits 100-200ms, so only a couple fit in the budget
+    let attempts = 20 - remaining.lock().unwrap().len();
+    assert!((2..=3).contains(&attempts), "made {} attempts", attempts);
+}
+
+#[tokio::test]
+async fn test_large_payloads_are_gzipped_and_signed_uncompressed() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+
+    let app = Router::new().route("/webhook", post(move |headers: axum::http::HeaderMap, body: axum::body::Bytes| {
+        let received = received_clone.clone();
+        async move {
+            let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+            received.lock().unwrap().push((
+                header("content-encoding"),
+                header("x-dodo-timestamp"),
+                header("x-dodo-signature"),
+                body,
+            ));
+            axum::http::StatusCode::OK
+        }
+    }));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dispatcher = ReqwestWebhookDispatcher::new(0, 10).with_gzip_min_bytes(1024);
+    let large = json!({"event": "test", "metadata": "x".repeat(4096)});
+    let small = json!({"event": "test"});
+    let gzip_context = DeliveryContext { accepts_gzip: true, ..DeliveryContext::default() };
+
+    dispatcher.dispatch(&url, &large, "secret", &gzip_context).await.unwrap();
+    dispatcher.dispatch(&url, &small, "secret", &gzip_context).await.unwrap();
+    dispatcher.dispatch(&url, &large, "secret", &DeliveryContext::default()).await.unwrap();
+
+    let received = received.lock().unwrap();
+    let tolerance = std::time::Duration::from_secs(300);
+
+    // Large payload to a gzip-capable receiver: compressed, signature covers the decompressed JSON
+    let (encoding, timestamp, signature, body) = &received[0];
+    assert_eq!(encoding.as_deref(), Some("gzip"));
+    assert!(body.len() < 1024);
+    let mut decompressed = Vec::new();
+    std::io::Read::read_to_end(&mut flate2::read::GzDecoder::new(&body[..]), &mut decompressed).unwrap();
+    assert_eq!(serde_json::from_slice::<serde_json::Value>(&decompressed).unwrap(), large);
+    let timestamp: i64 = timestamp.as_deref().unwrap().parse().unwrap();
+    let signature = signature.as_deref().unwrap();
+    assert!(verify_signature("secret", timestamp, &decompressed, signature, tolerance).is_ok());
+    assert!(verify_signature("secret", timestamp, body, signature, tolerance).is_err());
+
+    // Below the threshold, or to a receiver without the flag, bodies go out as plain JSON
+    for (encoding, _, _, body) in &received[1..] {
+        assert!(encoding.is_none());
+        assert!(serde_json::from_slice::<serde_json::Value>(body).is_ok());
+    }
+}
@@ -0,0 +1,230 @@
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use dodo_payments_assignment::application::services::{AccountService, AuthPrincipal};
+use dodo_payments_assignment::domain::entities::Account;
+use dodo_payments_assignment::domain::errors::RepositoryError;
+use dodo_payments_assignment::domain::repositories::AccountRepository;
+use dodo_payments_assignment::domain::value_objects::{AccountStatus, Money, TransactionLimits};
+use dodo_payments_assignment::infrastructure::http_client::hmac_sha256_hex;
+use dodo_payments_assignment::presentation::middleware::response_signing::ResponseSigningLayer;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use tower::ServiceExt; // for oneshot
+use uuid::Uuid;
+
+// Mock Repository holding a single account
+struct MockAccountRepository {
+    account: Account,
+}
+
+#[async_trait]
+impl AccountRepository for MockAccountRepository {
+    async fn create(&self, account: &Account) -> Result<Account, RepositoryError> {
+        Ok(account.clone())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Account, RepositoryError> {
+        if id == self.account.id {
+            Ok(self.account.clone())
+        } else {
+            Err(RepositoryError::NotFound("Account not found".to_string()))
+        }
+    }
+
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Account>, RepositoryError> {
+        Ok(ids.iter().filter(|id| **id == self.account.id).map(|_| self.account.clone()).collect())
+    }
+
+    async fn update_balance(&self, _id: Uuid, _new_balance: Money, expected_version: i64) -> Result<i64, RepositoryError> {
+        Ok(expected_version + 1)
+    }
+
+    async fn update_business_name(&self, _id: Uuid, _name: String) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn update_status(&self, _id: Uuid, _status: AccountStatus) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn find_limits(&self, _id: Uuid) -> Result<TransactionLimits, RepositoryError> {
+        Ok(TransactionLimits::default())
+    }
+
+    async fn update_limits(&self, _id: Uuid, _limits: &TransactionLimits) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn exists(&self, id: Uuid) -> Result<bool, RepositoryError> {
+        Ok(id == self.account.id)
+    }
+
+    async fn exists_by_business_name(&self, business_name: &str) -> Result<bool, RepositoryError> {
+        Ok(self.account.business_name.eq_ignore_ascii_case(business_name))
+    }
+
+    async fn list(&self, _limit: i64, _offset: i64) -> Result<Vec<Account>, RepositoryError> {
+        Ok(vec![self.account.clone()])
+    }
+
+    async fn search(&self, query: &str, _limit: i64, _offset: i64) -> Result<Vec<Account>, RepositoryError> {
+        let name = self.account.business_name.to_lowercase();
+        Ok(if name.contains(&query.to_lowercase()) { vec![self.account.clone()] } else { vec![] })
+    }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        Ok(1)
+    }
+
+    async fn count_matching(&self, query: &str) -> Result<i64, RepositoryError> {
+        Ok(self.search(query, 1, 0).await?.len() as i64)
+    }
+
+    async fn delete(&self, _id: Uuid) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn restore(&self, _id: Uuid) -> Result<Account, RepositoryError> {
+        Ok(self.account.clone())
+    }
+
+    async fn health_check(&self) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_response_signature_verifies_against_body() {
+    let account = Account::new("Signed Corp".to_string(), Money::new(dec!(0.00)).unwrap()).unwrap();
+    let account_id = account.id;
+    let secret = account.webhook_secret.clone();
+    let account_service = Arc::new(AccountService::new(Arc::new(MockAccountRepository { account })));
+
+    // Stand-in for `require_auth`
+    let fake_auth = move |mut req: axum::extract::Request, next: axum::middleware::Next| async move {
+        req.extensions_mut().insert(AuthPrincipal {
+            account_id,
+            account_ids: vec![account_id],
+            api_key_id: Uuid::new_v4(),
+            rate_limit_per_hour: 1000,
+            is_admin: false,
+        });
+        next.run(req).await
+    };
+
+    let app = Router::new()
+        .route("/", axum::routing::get(|| async { r#"{"status":"ok"}"# }))
+        .layer(axum::middleware::from_fn_with_state(
+            ResponseSigningLayer::new(account_service),
+            ResponseSigningLayer::handle,
+        ))
+        .layer(axum::middleware::from_fn(fake_auth));
+
+    let response = app
+        .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let signature = response
+        .headers()
+        .get("x-response-signature")
+        .expect("missing X-Response-Signature header")
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], br#"{"status":"ok"}"#);
+    assert_eq!(signature, format!("sha256={}", hmac_sha256_hex(&secret, &body)));
+}
+
+#[tokio::test]
+async fn test_streamed_response_is_passed_through_unsigned() {
+    let account = Account::new("Streaming Corp".to_string(), Money::new(dec!(0.00)).unwrap()).unwrap();
+    let account_id = account.id;
+    let account_service = Arc::new(AccountService::new(Arc::new(MockAccountRepository { account })));
+
+    let fake_auth = move |mut req: axum::extract::Request, next: axum::middleware::Next| async move {
+        req.extensions_mut().insert(AuthPrincipal {
+            account_id,
+            account_ids: vec![account_id],
+            api_key_id: Uuid::new_v4(),
+            rate_limit_per_hour: 1000,
+            is_admin: false,
+        });
+        next.run(req).await
+    };
+
+    // A streamed body has no upper size bound, so it must not be buffered
+    let streamed = || async {
+        let chunks = tokio_stream::iter(vec![
+            Ok::<_, std::io::Error>(axum::body::Bytes::from_static(b"chunk-1,")),
+            Ok(axum::body::Bytes::from_static(b"chunk-2")),
+        ]);
+        Body::from_stream(chunks)
+    };
+
+    let app = Router::new()
+        .route("/", axum::routing::get(streamed))
+        .layer(axum::middleware::from_fn_with_state(
+            ResponseSigningLayer::new(account_service),
+            ResponseSigningLayer::handle,
+        ))
+        .layer(axum::middleware::from_fn(fake_auth));
+
+    let response = app
+        .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("x-response-signature").is_none());
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], b"chunk-1,chunk-2");
+}
+
+#[tokio::test]
+async fn test_unknown_account_fails_before_handler_runs() {
+    let account = Account::new("Known Corp".to_string(), Money::new(dec!(0.00)).unwrap()).unwrap();
+    let account_service = Arc::new(AccountService::new(Arc::new(MockAccountRepository { account })));
+    let ran = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+    let fake_auth = move |mut req: axum::extract::Request, next: axum::middleware::Next| async move {
+        let stranger = Uuid::new_v4();
+        req.extensions_mut().insert(AuthPrincipal {
+            account_id: stranger,
+            account_ids: vec![stranger],
+            api_key_id: Uuid::new_v4(),
+            rate_limit_per_hour: 1000,
+            is_admin: false,
+        });
+        next.run(req).await
+    };
+
+    let handler_ran = ran.clone();
+    let app = Router::new()
+        .route(
+            "/",
+            axum::routing::post(move || async move {
+                handler_ran.store(true, std::sync::atomic::Ordering::SeqCst);
+                "done"
+            }),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            ResponseSigningLayer::new(account_service),
+            ResponseSigningLayer::handle,
+        ))
+        .layer(axum::middleware::from_fn(fake_auth));
+
+    let response = app
+        .oneshot(Request::builder().method("POST").uri("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(!ran.load(std::sync::atomic::Ordering::SeqCst));
+}
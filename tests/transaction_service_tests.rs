@@ -1,9 +1,17 @@
-use dodo_payments_assignment::application::dto::{DepositRequest, TransferRequest, WithdrawRequest};
+use dodo_payments_assignment::application::dto::{
+    AuthorizeRequest, DepositRequest, TransferRequest, VerificationRequest, WithdrawRequest,
+};
 use dodo_payments_assignment::application::services::TransactionService;
-use dodo_payments_assignment::domain::entities::Transaction;
-use dodo_payments_assignment::domain::errors::RepositoryError;
-use dodo_payments_assignment::domain::repositories::TransactionRepository;
-use dodo_payments_assignment::domain::value_objects::TransactionType;
+use dodo_payments_assignment::domain::entities::{Account, IdempotencyRecord, Transaction};
+use dodo_payments_assignment::domain::errors::{DomainError, RepositoryError, ServiceError};
+use dodo_payments_assignment::domain::repositories::{
+    AccountRepository, AccountStats, BatchPreview, IdempotencyRepository, ReconciliationPage, TransactionFilter,
+    TransactionRepository,
+};
+use dodo_payments_assignment::domain::value_objects::{
+    AccountStatus, AuthorizationStatus, IdempotencyKeyFormat, Money, TransactionLimits, TransactionStatus,
+    TransactionType,
+};
 use async_trait::async_trait;
 use rust_decimal_macros::dec;
 use std::sync::{Arc, Mutex};
@@ -20,6 +28,18 @@ impl MockTransactionRepository {
             transactions: Mutex::new(Vec::new()),
         }
     }
+
+    fn settle(&self, id: Uuid, next: AuthorizationStatus) -> Result<Transaction, RepositoryError> {
+        let mut transactions = self.transactions.lock().unwrap();
+        let authorization = transactions
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or(RepositoryError::NotFound(format!("Transaction {} not found", id)))?;
+        authorization
+            .transition_authorization(next)
+            .map_err(|e| RepositoryError::ConstraintViolation(e.to_string()))?;
+        Ok(authorization.clone())
+    }
 }
 
 #[async_trait]
@@ -29,13 +49,27 @@ impl TransactionRepository for MockTransactionRepository {
         Ok(transaction.clone())
     }
 
-    async fn find_by_id(&self, _id: Uuid) -> Result<Transaction, RepositoryError> {
-        unimplemented!()
+    async fn find_by_id(&self, id: Uuid) -> Result<Transaction, RepositoryError> {
+        self.transactions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|t| t.id == id)
+            .cloned()
+            .ok_or(RepositoryError::NotFound(format!("Transaction {} not found", id)))
     }
     
     async fn find_by_idempotency_key(&self, _key: &str) -> Result<Transaction, RepositoryError> {
         unimplemented!()
     }
+
+    async fn find_recent_by_idempotency_key(
+        &self,
+        _key: &str,
+        _since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Transaction, RepositoryError> {
+        unimplemented!()
+    }
     
     async fn idempotency_key_exists(&self, _key: &str) -> Result<bool, RepositoryError> {
         unimplemented!()
@@ -55,6 +89,61 @@ impl TransactionRepository for MockTransactionRepository {
             .collect())
     }
 
+    async fn list_by_account_filtered(
+        &self,
+        account_id: Uuid,
+        filter: &TransactionFilter,
+        _limit: i64,
+        _offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError> {
+        let transactions = self.transactions.lock().unwrap();
+        Ok(transactions
+            .iter()
+            .filter(|t| t.from_account_id == Some(account_id) || t.to_account_id == Some(account_id))
+            .filter(|t| filter.transaction_type.is_none_or(|ty| t.transaction_type == ty))
+            .filter(|t| filter.from.is_none_or(|from| t.created_at >= from))
+            .filter(|t| filter.to.is_none_or(|to| t.created_at <= to))
+            .cloned()
+            .collect())
+    }
+
+    async fn account_stats(&self, _account_id: Uuid, _filter: &TransactionFilter) -> Result<AccountStats, RepositoryError> {
+        Ok(AccountStats::default())
+    }
+
+    async fn count_by_account(
+        &self,
+        account_id: Uuid,
+        filter: &TransactionFilter,
+    ) -> Result<i64, RepositoryError> {
+        let transactions = self.list_by_account_filtered(account_id, filter, i64::MAX, 0).await?;
+        Ok(transactions.len() as i64)
+    }
+
+    async fn list_by_accounts_filtered(
+        &self,
+        account_ids: &[Uuid],
+        filter: &TransactionFilter,
+        _limit: i64,
+        _offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError> {
+        let touches = |id: Option<Uuid>| id.is_some_and(|id| account_ids.contains(&id));
+        let transactions = self.transactions.lock().unwrap();
+        Ok(transactions
+            .iter()
+            .filter(|t| touches(t.from_account_id) || touches(t.to_account_id))
+            .filter(|t| filter.transaction_type.is_none_or(|ty| t.transaction_type == ty))
+            .filter(|t| filter.from.is_none_or(|from| t.created_at >= from))
+            .filter(|t| filter.to.is_none_or(|to| t.created_at <= to))
+            .cloned()
+            .collect())
+    }
+
+    async fn count_by_accounts(&self, account_ids: &[Uuid], filter: &TransactionFilter) -> Result<i64, RepositoryError> {
+        let transactions = self.list_by_accounts_filtered(account_ids, filter, i64::MAX, 0).await?;
+        Ok(transactions.len() as i64)
+    }
+
     async fn list_by_type(
         &self,
         _transaction_type: TransactionType,
@@ -64,6 +153,15 @@ impl TransactionRepository for MockTransactionRepository {
         unimplemented!()
     }
 
+    async fn list_by_status(
+        &self,
+        _status: TransactionStatus,
+        _limit: i64,
+        _offset: i64,
+    ) -> Result<Vec<Transaction>, RepositoryError> {
+        unimplemented!()
+    }
+
     async fn list(&self, _limit: i64, _offset: i64) -> Result<Vec<Transaction>, RepositoryError> {
         unimplemented!()
     }
@@ -76,6 +174,15 @@ impl TransactionRepository for MockTransactionRepository {
         Ok(transaction.clone())
     }
 
+    async fn execute_opening_credit(
+        &self,
+        _account: &Account,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError> {
+        self.transactions.lock().unwrap().push(transaction.clone());
+        Ok(transaction.clone())
+    }
+
     async fn execute_debit(
         &self,
         transaction: &Transaction,
@@ -91,6 +198,192 @@ impl TransactionRepository for MockTransactionRepository {
         self.transactions.lock().unwrap().push(transaction.clone());
         Ok(transaction.clone())
     }
+
+    async fn execute_verification(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError> {
+        self.transactions.lock().unwrap().push(transaction.clone());
+        Ok(transaction.clone())
+    }
+
+    async fn execute_authorization(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Transaction, RepositoryError> {
+        self.transactions.lock().unwrap().push(transaction.clone());
+        Ok(transaction.clone())
+    }
+
+    async fn execute_capture(&self, authorization_id: Uuid) -> Result<Transaction, RepositoryError> {
+        self.settle(authorization_id, AuthorizationStatus::Captured)
+    }
+
+    async fn execute_void(&self, authorization_id: Uuid) -> Result<Transaction, RepositoryError> {
+        self.settle(authorization_id, AuthorizationStatus::Voided)
+    }
+
+    async fn archive_before(
+        &self,
+        _cutoff: chrono::DateTime<chrono::Utc>,
+        _limit: i64,
+    ) -> Result<u64, RepositoryError> {
+        Ok(0)
+    }
+
+    async fn expire_idempotency_keys(
+        &self,
+        _cutoff: chrono::DateTime<chrono::Utc>,
+        _limit: i64,
+    ) -> Result<u64, RepositoryError> {
+        Ok(0)
+    }
+
+    async fn sum_debits_since(
+        &self,
+        account_id: Uuid,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<rust_decimal::Decimal, RepositoryError> {
+        let transactions = self.transactions.lock().unwrap();
+        Ok(transactions
+            .iter()
+            .filter(|t| t.from_account_id == Some(account_id) && t.created_at >= since)
+            .filter(|t| match t.transaction_type {
+                TransactionType::Debit | TransactionType::Transfer => true,
+                TransactionType::Authorization => t.authorization_status != Some(AuthorizationStatus::Voided),
+                _ => false,
+            })
+            .map(|t| t.amount.amount())
+            .sum())
+    }
+
+    async fn preview_batch(&self, _operations: &[Transaction]) -> Result<BatchPreview, RepositoryError> {
+        Ok(BatchPreview { balances: Vec::new(), failure: None })
+    }
+
+    async fn simulate_transfer(&self, _transfer: &Transaction) -> Result<BatchPreview, RepositoryError> {
+        Ok(BatchPreview { balances: Vec::new(), failure: None })
+    }
+
+    async fn find_balance_drift(
+        &self,
+        _after: Option<Uuid>,
+        _limit: i64,
+    ) -> Result<ReconciliationPage, RepositoryError> {
+        Ok(ReconciliationPage::default())
+    }
+}
+
+// Mock account store; only transaction limits are consulted by the service
+struct MockLimitsRepository {
+    limits: TransactionLimits,
+}
+
+#[async_trait]
+impl AccountRepository for MockLimitsRepository {
+    async fn create(&self, _account: &Account) -> Result<Account, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn find_by_id(&self, _id: Uuid) -> Result<Account, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn find_by_ids(&self, _ids: &[Uuid]) -> Result<Vec<Account>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn update_balance(&self, _id: Uuid, _new_balance: Money, _expected_version: i64) -> Result<i64, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn update_business_name(&self, _id: Uuid, _name: String) -> Result<(), RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn update_status(&self, _id: Uuid, _status: AccountStatus) -> Result<(), RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn find_limits(&self, _id: Uuid) -> Result<TransactionLimits, RepositoryError> {
+        Ok(self.limits)
+    }
+
+    async fn update_limits(&self, _id: Uuid, _limits: &TransactionLimits) -> Result<(), RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn exists(&self, _id: Uuid) -> Result<bool, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn exists_by_business_name(&self, _business_name: &str) -> Result<bool, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn list(&self, _limit: i64, _offset: i64) -> Result<Vec<Account>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn search(&self, _query: &str, _limit: i64, _offset: i64) -> Result<Vec<Account>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn count_matching(&self, _query: &str) -> Result<i64, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn delete(&self, _id: Uuid) -> Result<(), RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn restore(&self, _id: Uuid) -> Result<Account, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn health_check(&self) -> Result<(), RepositoryError> {
+        unimplemented!()
+    }
+}
+
+// Mock Idempotency Store
+struct MockIdempotencyRepository {
+    records: Mutex<Vec<IdempotencyRecord>>,
+}
+
+impl MockIdempotencyRepository {
+    fn new() -> Self {
+        Self {
+            records: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for MockIdempotencyRepository {
+    async fn find_by_key(&self, key: &str) -> Result<IdempotencyRecord, RepositoryError> {
+        let records = self.records.lock().unwrap();
+        records
+            .iter()
+            .find(|r| r.key == key && !r.is_expired(chrono::Utc::now()))
+            .cloned()
+            .ok_or(RepositoryError::NotFound("Idempotency key not found".to_string()))
+    }
+
+    async fn save(&self, record: &IdempotencyRecord) -> Result<IdempotencyRecord, RepositoryError> {
+        let mut records = self.records.lock().unwrap();
+        records.retain(|r| r.key != record.key);
+        records.push(record.clone());
+        Ok(record.clone())
+    }
+
+    async fn delete_expired(&self) -> Result<u64, RepositoryError> {
+        Ok(0)
+    }
 }
 
 #[tokio::test]
@@ -100,8 +393,8 @@ async fn test_deposit() {
     let account_id = Uuid::new_v4();
     let amount = dec!(100.00);
 
-    let request = DepositRequest { account_id, amount, idempotency_key: None };
-    let response = service.deposit(request).await.expect("Deposit failed");
+    let request = DepositRequest { account_id, amount, idempotency_key: None, description: None };
+    let response = service.deposit(request, None).await.expect("Deposit failed");
 
     assert_eq!(response.to_account_id, Some(account_id));
     assert_eq!(response.amount, amount);
@@ -115,8 +408,8 @@ async fn test_withdraw() {
     let account_id = Uuid::new_v4();
     let amount = dec!(50.00);
 
-    let request = WithdrawRequest { account_id, amount, idempotency_key: None };
-    let response = service.withdraw(request).await.expect("Withdraw failed");
+    let request = WithdrawRequest { account_id, amount, idempotency_key: None, description: None };
+    let response = service.withdraw(request, None).await.expect("Withdraw failed");
 
     assert_eq!(response.from_account_id, Some(account_id));
     assert_eq!(response.amount, amount);
@@ -131,14 +424,194 @@ async fn test_transfer() {
     let to_id = Uuid::new_v4();
     let amount = dec!(25.00);
 
-    let request = TransferRequest { from_account_id: from_id, to_account_id: to_id, amount, idempotency_key: None };
-    let response = service.transfer(request).await.expect("Transfer failed");
+    let request = TransferRequest { from_account_id: from_id, to_account_id: to_id, amount, idempotency_key: None, description: None };
+    let response = service.transfer(request, None).await.expect("Transfer failed");
 
     assert_eq!(response.from_account_id, Some(from_id));
     assert_eq!(response.to_account_id, Some(to_id));
     assert_eq!(response.transaction_type, "transfer");
 }
 
+#[tokio::test]
+async fn test_withdrawals_and_transfers_respect_limits() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    // The account overrides the per-transaction default and inherits the daily one
+    let account_limits = MockLimitsRepository {
+        limits: TransactionLimits::new(Some(Money::new(dec!(100.00)).unwrap()), None),
+    };
+    let defaults = TransactionLimits::new(
+        Some(Money::new(dec!(1000.00)).unwrap()),
+        Some(Money::new(dec!(150.00)).unwrap()),
+    );
+    let service = TransactionService::new(mock_repo.clone(), None)
+        .with_transaction_limits(Arc::new(account_limits), defaults);
+    let account_id = Uuid::new_v4();
+
+    let too_large = WithdrawRequest { account_id, amount: dec!(100.01), idempotency_key: None, description: None };
+    let err = service.withdraw(too_large, None).await.unwrap_err();
+    assert!(matches!(err, ServiceError::Domain(DomainError::LimitExceeded(_))));
+
+    service
+        .withdraw(WithdrawRequest { account_id, amount: dec!(100.00), idempotency_key: None, description: None }, None)
+        .await
+        .expect("Withdrawal within limits failed");
+
+    // 100 of the 150 daily allowance is used, so a 60 transfer is over
+    let transfer = TransferRequest {
+        from_account_id: account_id,
+        to_account_id: Uuid::new_v4(),
+        amount: dec!(60.00),
+        idempotency_key: None,
+        description: None,
+    };
+    let err = service.transfer(transfer, None).await.unwrap_err();
+    assert!(matches!(err, ServiceError::Domain(DomainError::LimitExceeded(_))));
+
+    let transfer = TransferRequest {
+        from_account_id: account_id,
+        to_account_id: Uuid::new_v4(),
+        amount: dec!(50.00),
+        idempotency_key: None,
+        description: None,
+    };
+    service.transfer(transfer, None).await.expect("Transfer within limits failed");
+
+    // Deposits are never limited
+    service
+        .deposit(DepositRequest { account_id, amount: dec!(5000.00), idempotency_key: None, description: None }, None)
+        .await
+        .expect("Deposit failed");
+    assert_eq!(mock_repo.transactions.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_zero_amounts_only_allowed_for_verification() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None).with_zero_amount_transactions(true);
+    let account_id = Uuid::new_v4();
+
+    // Money-moving types still reject zero under the flag
+    let request = DepositRequest { account_id, amount: dec!(0.00), idempotency_key: None, description: None };
+    let result = service.deposit(request, None).await;
+    assert!(matches!(result, Err(ServiceError::Domain(DomainError::InvalidAmount(_)))));
+
+    let request = VerificationRequest { account_id, idempotency_key: None };
+    let response = service.verify(request, None).await.expect("Verification failed");
+    assert_eq!(response.transaction_type, "verification");
+    assert_eq!(response.to_account_id, Some(account_id));
+    assert_eq!(response.amount, dec!(0));
+    assert_eq!(mock_repo.transactions.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_amounts_below_the_minimum_are_rejected() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None)
+        .with_min_amount(Some(Money::new(dec!(0.50)).unwrap()));
+    let (from_id, to_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let deposit = DepositRequest { account_id: from_id, amount: dec!(0.49), idempotency_key: None, description: None };
+    let withdrawal = WithdrawRequest { account_id: from_id, amount: dec!(0.49), idempotency_key: None, description: None };
+    let transfer = TransferRequest { from_account_id: from_id, to_account_id: to_id, amount: dec!(0.49), idempotency_key: None, description: None };
+    let results = [
+        service.deposit(deposit, None).await,
+        service.withdraw(withdrawal, None).await,
+        service.transfer(transfer, None).await,
+    ];
+    for result in results {
+        match result {
+            Err(ServiceError::Domain(DomainError::InvalidAmount(msg))) => assert_eq!(msg, "Amount must be at least 0.50 USD"),
+            other => panic!("expected InvalidAmount, got {:?}", other),
+        }
+    }
+    assert!(mock_repo.transactions.lock().unwrap().is_empty());
+
+    // The minimum itself is accepted
+    let request = DepositRequest { account_id: from_id, amount: dec!(0.50), idempotency_key: None, description: None };
+    service.deposit(request, None).await.expect("Deposit failed");
+}
+
+#[tokio::test]
+async fn test_transfer_dry_run_validates_without_recording() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None);
+    let (from_id, to_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let request = TransferRequest { from_account_id: from_id, to_account_id: to_id, amount: dec!(10.00), idempotency_key: None, description: None };
+    let projection = service.simulate_transfer(request).await.expect("Dry run failed");
+    assert!(projection.would_succeed);
+    assert!(projection.error.is_none());
+
+    // Requests the transfer itself would refuse are refused the same way
+    let request = TransferRequest { from_account_id: from_id, to_account_id: from_id, amount: dec!(10.00), idempotency_key: None, description: None };
+    assert!(matches!(service.simulate_transfer(request).await, Err(ServiceError::Domain(_))));
+    assert!(mock_repo.transactions.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_verification_rejected_without_flag() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None);
+
+    let request = VerificationRequest { account_id: Uuid::new_v4(), idempotency_key: None };
+    let result = service.verify(request, None).await;
+
+    assert!(matches!(result, Err(ServiceError::Domain(DomainError::InvalidAmount(_)))));
+    assert!(mock_repo.transactions.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_get_transaction() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo, None);
+    let account_id = Uuid::new_v4();
+
+    let deposit = service
+        .deposit(DepositRequest { account_id, amount: dec!(100.00), idempotency_key: None, description: None }, None)
+        .await
+        .unwrap();
+
+    let found = service.get_transaction(deposit.id).await.expect("Failed to get transaction");
+    assert_eq!(found.id, deposit.id);
+    assert_eq!(found.amount, dec!(100.00));
+
+    let missing = service.get_transaction(Uuid::new_v4()).await;
+    assert!(matches!(missing, Err(ServiceError::Repository(RepositoryError::NotFound(_)))));
+}
+
+#[tokio::test]
+async fn test_authorizations_can_be_captured_or_voided_once() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None);
+    let account_id = Uuid::new_v4();
+
+    let authorize = |amount| AuthorizeRequest { account_id, amount, idempotency_key: None };
+    let first = service.authorize(authorize(dec!(40.00))).await.expect("Authorization failed");
+    assert_eq!(first.transaction_type, "authorization");
+    assert_eq!(first.authorization_status, Some(AuthorizationStatus::Pending));
+
+    let captured = service.capture(first.id, None).await.expect("Capture failed");
+    assert_eq!(captured.authorization_status, Some(AuthorizationStatus::Captured));
+
+    // A settled authorization cannot be settled again
+    let err = service.capture(first.id, None).await.unwrap_err();
+    assert!(matches!(err, ServiceError::Domain(DomainError::InvalidTransactionState(_))));
+    let err = service.void(first.id).await.unwrap_err();
+    assert!(matches!(err, ServiceError::Domain(DomainError::InvalidTransactionState(_))));
+
+    let second = service.authorize(authorize(dec!(10.00))).await.unwrap();
+    let voided = service.void(second.id).await.expect("Void failed");
+    assert_eq!(voided.authorization_status, Some(AuthorizationStatus::Voided));
+
+    // Only authorizations can be captured
+    let deposit = service
+        .deposit(DepositRequest { account_id, amount: dec!(5.00), idempotency_key: None, description: None }, None)
+        .await
+        .unwrap();
+    let err = service.capture(deposit.id, None).await.unwrap_err();
+    assert!(matches!(err, ServiceError::Domain(DomainError::InvalidTransactionType(_))));
+}
+
 #[tokio::test]
 async fn test_get_history() {
     let mock_repo = Arc::new(MockTransactionRepository::new());
@@ -146,10 +619,118 @@ async fn test_get_history() {
     let account_id = Uuid::new_v4();
     
     // Seed some transactions (using deposit helper for mock simplicity)
-    service.deposit(DepositRequest { account_id, amount: dec!(100.00), idempotency_key: None }).await.unwrap();
-    service.withdraw(WithdrawRequest { account_id, amount: dec!(20.00), idempotency_key: None }).await.unwrap();
+    service.deposit(DepositRequest { account_id, amount: dec!(100.00), idempotency_key: None, description: None }, None).await.unwrap();
+    service.withdraw(WithdrawRequest { account_id, amount: dec!(20.00), idempotency_key: None, description: None }, None).await.unwrap();
 
-    let history = service.get_history(account_id, 10, 0).await.expect("Failed to get history");
+    let history = service.get_history(account_id, TransactionFilter::default(), 10, 0).await.expect("Failed to get history");
     
-    assert_eq!(history.len(), 2);
+    assert_eq!(history.items.len(), 2);
+    assert_eq!(history.total, 2);
+    assert_eq!(history.limit, 10);
+    assert_eq!(history.offset, 0);
+}
+
+#[tokio::test]
+async fn test_get_history_filtered_by_type_and_date() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None);
+    let account_id = Uuid::new_v4();
+
+    service.deposit(DepositRequest { account_id, amount: dec!(100.00), idempotency_key: None, description: None }, None).await.unwrap();
+    service.withdraw(WithdrawRequest { account_id, amount: dec!(20.00), idempotency_key: None, description: None }, None).await.unwrap();
+
+    let credits = TransactionFilter {
+        transaction_type: Some(TransactionType::Credit),
+        ..TransactionFilter::default()
+    };
+    let history = service.get_history(account_id, credits, 10, 0).await.unwrap();
+    assert_eq!(history.total, 1);
+    assert_eq!(history.items[0].transaction_type, "credit");
+
+    let future = TransactionFilter {
+        from: Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+        ..TransactionFilter::default()
+    };
+    let history = service.get_history(account_id, future, 10, 0).await.unwrap();
+    assert!(history.items.is_empty());
+    assert_eq!(history.total, 0);
+}
+
+#[tokio::test]
+async fn test_get_history_across_accounts() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None);
+    let (first, second, unrelated) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+    for account_id in [first, second, unrelated] {
+        service.deposit(DepositRequest { account_id, amount: dec!(100.00), idempotency_key: None, description: None }, None).await.unwrap();
+    }
+    service.transfer(TransferRequest { from_account_id: first, to_account_id: second, amount: dec!(10.00), idempotency_key: None, description: None }, None).await.unwrap();
+
+    // Both deposits, and the transfer between the two accounts once
+    let history = service.get_history_for_accounts(&[first, second], TransactionFilter::default(), 10, 0).await.unwrap();
+    assert_eq!(history.total, 3);
+    assert!(history.items.iter().all(|t| t.to_account_id != Some(unrelated)));
+
+    let history = service.get_history_for_accounts(&[first], TransactionFilter::default(), 10, 0).await.unwrap();
+    assert_eq!(history.total, 2);
+}
+
+#[tokio::test]
+async fn test_uuid_idempotency_keys_can_be_required() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None).with_idempotency_key_format(IdempotencyKeyFormat::Uuid);
+    let account_id = Uuid::new_v4();
+
+    let weak = DepositRequest { account_id, amount: dec!(10.00), idempotency_key: Some("abc".to_string()), description: None };
+    let err = service.deposit(weak, None).await.unwrap_err();
+    assert!(matches!(err, ServiceError::Domain(DomainError::InvalidAmount(_))));
+    assert!(mock_repo.transactions.lock().unwrap().is_empty());
+
+    let strong = DepositRequest { account_id, amount: dec!(10.00), idempotency_key: Some(Uuid::new_v4().to_string()), description: None };
+    service.deposit(strong, None).await.expect("UUID key should be accepted");
+}
+
+#[tokio::test]
+async fn test_deposit_replays_stored_snapshot() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None)
+        .with_idempotency_repository(Arc::new(MockIdempotencyRepository::new()), chrono::Duration::hours(1));
+    let account_id = Uuid::new_v4();
+    let request = || DepositRequest { account_id, amount: dec!(100.00), idempotency_key: Some("key-1".to_string()), description: None };
+
+    let first = service.deposit(request(), None).await.unwrap();
+    let second = service.deposit(request(), None).await.unwrap();
+
+    assert_eq!(first.id, second.id);
+    assert_eq!(mock_repo.transactions.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_replay_ignores_how_the_amount_is_written() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None)
+        .with_idempotency_repository(Arc::new(MockIdempotencyRepository::new()), chrono::Duration::hours(1));
+    let account_id = Uuid::new_v4();
+    let request = |amount| DepositRequest { account_id, amount, idempotency_key: Some("key-3".to_string()), description: None };
+
+    let first = service.deposit(request(dec!(100)), None).await.unwrap();
+    let second = service.deposit(request(dec!(100.00)), None).await.unwrap();
+
+    assert_eq!(first.id, second.id);
+    assert_eq!(mock_repo.transactions.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_idempotency_key_reused_for_different_request_conflicts() {
+    let mock_repo = Arc::new(MockTransactionRepository::new());
+    let service = TransactionService::new(mock_repo.clone(), None)
+        .with_idempotency_repository(Arc::new(MockIdempotencyRepository::new()), chrono::Duration::hours(1));
+    let account_id = Uuid::new_v4();
+
+    service.deposit(DepositRequest { account_id, amount: dec!(100.00), idempotency_key: Some("key-2".to_string()), description: None }, None).await.unwrap();
+    let result = service.deposit(DepositRequest { account_id, amount: dec!(5.00), idempotency_key: Some("key-2".to_string()), description: None }, None).await;
+
+    assert!(matches!(result, Err(ServiceError::Domain(_))));
+    assert_eq!(mock_repo.transactions.lock().unwrap().len(), 1);
 }
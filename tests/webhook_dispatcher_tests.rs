@@ -1,24 +1,28 @@
 use std::sync::{Arc, Mutex};
 use async_trait::async_trait;
 use serde_json::Value;
-use tokio::sync::Notify;
+use tokio::sync::{Notify, Semaphore};
 
-use dodo_payments_assignment::application::dto::DepositRequest;
+use dodo_payments_assignment::application::dto::{DepositRequest, WithdrawRequest};
 use dodo_payments_assignment::application::services::{TransactionService, WebhookService};
 use dodo_payments_assignment::domain::entities::{Account, Webhook};
 use dodo_payments_assignment::domain::repositories::{AccountRepository, WebhookRepository};
-use dodo_payments_assignment::domain::services::WebhookDispatcher;
-use dodo_payments_assignment::domain::value_objects::{Money, WebhookEvent};
+use dodo_payments_assignment::domain::services::{DeliveryContext, WebhookDispatcher};
+use dodo_payments_assignment::domain::value_objects::{Money, TransactionLimits, WebhookEvent, WebhookOverflowPolicy};
 use dodo_payments_assignment::infrastructure::config::Config;
 use dodo_payments_assignment::infrastructure::database::{
     create_pool, PostgresAccountRepository, PostgresTransactionRepository, PostgresWebhookRepository,
 };
 use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+/// A captured dispatch: url, payload, secret and the previous secret, if any
+type DispatchCall = (String, Value, String, Option<String>);
 
 // Mock Dispatcher to capture calls
 #[derive(Clone)]
 struct MockWebhookDispatcher {
-    calls: Arc<Mutex<Vec<(String, Value, String)>>>,
+    calls: Arc<Mutex<Vec<DispatchCall>>>,
     notify: Arc<Notify>,
 }
 
@@ -33,8 +37,19 @@ impl MockWebhookDispatcher {
 
 #[async_trait]
 impl WebhookDispatcher for MockWebhookDispatcher {
-    async fn dispatch(&self, url: &str, payload: &Value, secret: &str) -> Result<(), String> {
-        self.calls.lock().unwrap().push((url.to_string(), payload.clone(), secret.to_string()));
+    async fn dispatch(
+        &self,
+        url: &str,
+        payload: &Value,
+        secret: &str,
+        context: &DeliveryContext,
+    ) -> Result<(), String> {
+        self.calls.lock().unwrap().push((
+            url.to_string(),
+            payload.clone(),
+            secret.to_string(),
+            context.request_id.clone(),
+        ));
         self.notify.notify_one();
         Ok(())
     }
@@ -71,6 +86,7 @@ async fn test_webhook_dispatch_on_deposit() {
         "https://example.com/callback".to_string(),
         WebhookEvent::TransactionCompleted,
     ).unwrap();
+    let webhook_secret = webhook.secret.clone();
     webhook_repo.create(webhook).await.unwrap();
 
     // 3. Perform Deposit
@@ -78,9 +94,10 @@ async fn test_webhook_dispatch_on_deposit() {
         account_id: account.id,
         amount: dec!(100.0),
         idempotency_key: None,
+        description: None,
     };
     
-    let _ = transaction_service.deposit(deposit).await.unwrap();
+    let _ = transaction_service.deposit(deposit, None).await.unwrap();
 
     // 4. Wait for background task
     let timeout = tokio::time::timeout(std::time::Duration::from_secs(2), mock_dispatcher.notify.notified());
@@ -91,8 +108,8 @@ async fn test_webhook_dispatch_on_deposit() {
     assert_eq!(calls.len(), 1);
     assert_eq!(calls[0].0, "https://example.com/callback");
     
-    // Verify secret was passed correctly
-    assert_eq!(calls[0].2, account.webhook_secret);
+    // Verify the webhook's own secret was passed
+    assert_eq!(calls[0].2, webhook_secret);
     
     // Check payload
     let payload = &calls[0].1;
@@ -107,3 +124,518 @@ async fn test_webhook_dispatch_on_deposit() {
     // transaction_type instead of type
     assert_eq!(payload["transaction_type"].as_str().unwrap(), "credit");
 }
+
+#[tokio::test]
+async fn test_deposit_specific_webhook_ignores_withdrawals() {
+    let config = Config::from_env().unwrap();
+    let pool = create_pool(&config).await.unwrap();
+
+    let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
+    let transaction_repo = Arc::new(PostgresTransactionRepository::new(pool.clone()));
+    let webhook_repo = Arc::new(PostgresWebhookRepository::new(pool.clone()));
+
+    let mock_dispatcher = Arc::new(MockWebhookDispatcher::new());
+    let webhook_service = Arc::new(WebhookService::new(
+        webhook_repo.clone(),
+        account_repo.clone(),
+        mock_dispatcher.clone(),
+    ));
+
+    let transaction_service = TransactionService::new(
+        transaction_repo,
+        Some(webhook_service),
+    );
+
+    let account = Account::new("Deposit Webhook Test".to_string(), Money::new(dec!(0.0)).unwrap()).unwrap();
+    account_repo.create(&account).await.unwrap();
+
+    // Subscribe to deposits only
+    let webhook = Webhook::new(
+        account.id,
+        "https://example.com/deposits".to_string(),
+        WebhookEvent::TransactionDepositCompleted,
+    ).unwrap();
+    webhook_repo.create(webhook).await.unwrap();
+
+    // A deposit fires the webhook
+    transaction_service.deposit(DepositRequest {
+        account_id: account.id,
+        amount: dec!(100.0),
+        idempotency_key: None,
+        description: None,
+    }, None).await.unwrap();
+
+    let timeout = tokio::time::timeout(std::time::Duration::from_secs(2), mock_dispatcher.notify.notified());
+    assert!(timeout.await.is_ok(), "Timed out waiting for webhook dispatch");
+    assert_eq!(mock_dispatcher.calls.lock().unwrap().len(), 1);
+
+    // A withdrawal does not
+    transaction_service.withdraw(WithdrawRequest {
+        account_id: account.id,
+        amount: dec!(10.0),
+        idempotency_key: None,
+        description: None,
+    }, None).await.unwrap();
+
+    let timeout = tokio::time::timeout(std::time::Duration::from_millis(500), mock_dispatcher.notify.notified());
+    assert!(timeout.await.is_err(), "Deposit webhook fired for a withdrawal");
+
+    let calls = mock_dispatcher.calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].1["transaction_type"].as_str().unwrap(), "credit");
+}
+
+#[tokio::test]
+async fn test_balance_low_fires_when_threshold_is_crossed() {
+    let config = Config::from_env().unwrap();
+    let pool = create_pool(&config).await.unwrap();
+
+    let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
+    let transaction_repo = Arc::new(PostgresTransactionRepository::new(pool.clone()));
+    let webhook_repo = Arc::new(PostgresWebhookRepository::new(pool.clone()));
+
+    let mock_dispatcher = Arc::new(MockWebhookDispatcher::new());
+    let webhook_service = Arc::new(WebhookService::new(
+        webhook_repo.clone(),
+        account_repo.clone(),
+        mock_dispatcher.clone(),
+    ));
+    let transaction_service = TransactionService::new(transaction_repo, Some(webhook_service.clone()));
+
+    let account = Account::new("Low Balance Test".to_string(), Money::new(dec!(100.0)).unwrap()).unwrap();
+    account_repo.create(&account).await.unwrap();
+    let limits = TransactionLimits::default().with_low_balance(Some(Money::new(dec!(50.00)).unwrap()));
+    account_repo.update_limits(account.id, &limits).await.unwrap();
+
+    let webhook = Webhook::new(
+        account.id,
+        "https://example.com/low-balance".to_string(),
+        WebhookEvent::BalanceLow,
+    ).unwrap();
+    webhook_repo.create(webhook).await.unwrap();
+
+    // 100 -> 70 stays above, 70 -> 40 crosses, 40 -> 30 was already below
+    for amount in [dec!(30.0), dec!(30.0), dec!(10.0)] {
+        transaction_service.withdraw(WithdrawRequest {
+            account_id: account.id,
+            amount,
+            idempotency_key: None,
+            description: None,
+        }, None).await.unwrap();
+        webhook_service.flush().await;
+    }
+
+    let calls = mock_dispatcher.calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].1["balance"], "40.00");
+    assert_eq!(calls[0].1["previous_balance"], "70.00");
+    assert_eq!(calls[0].1["threshold"], "50.00");
+}
+
+#[tokio::test]
+async fn test_malformed_payload_is_dead_lettered_not_dispatched() {
+    let config = Config::from_env().unwrap();
+    let pool = create_pool(&config).await.unwrap();
+
+    let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
+    let webhook_repo = Arc::new(PostgresWebhookRepository::new(pool.clone()));
+
+    let mock_dispatcher = Arc::new(MockWebhookDispatcher::new());
+    let webhook_service = WebhookService::new(
+        webhook_repo.clone(),
+        account_repo.clone(),
+        mock_dispatcher.clone(),
+    );
+
+    let account = Account::new("Dead Letter Test".to_string(), Money::new(dec!(0.0)).unwrap()).unwrap();
+    account_repo.create(&account).await.unwrap();
+
+    let webhook = Webhook::new(
+        account.id,
+        "https://example.com/dead-letter".to_string(),
+        WebhookEvent::TransactionCompleted,
+    ).unwrap();
+    let webhook_id = webhook.id;
+    webhook_repo.create(webhook).await.unwrap();
+
+    // Missing `amount` and `created_at`, and `id` has the wrong type
+    webhook_service.notify_async(
+        account.id,
+        WebhookEvent::TransactionDepositCompleted,
+        serde_json::json!({ "id": 42, "transaction_type": "credit" }),
+        None,
+    ).await;
+
+    let timeout = tokio::time::timeout(std::time::Duration::from_millis(500), mock_dispatcher.notify.notified());
+    assert!(timeout.await.is_err(), "Malformed payload was dispatched");
+    assert!(mock_dispatcher.calls.lock().unwrap().is_empty());
+
+    let letters = webhook_repo.list_dead_letters(webhook_id).await.unwrap();
+    assert_eq!(letters.len(), 1);
+    assert_eq!(letters[0].event, WebhookEvent::TransactionDepositCompleted);
+    assert_eq!(letters[0].payload["id"], 42);
+    assert!(!letters[0].reason.is_empty());
+}
+
+/// A payload whose serialization always fails
+struct Unserializable;
+
+impl serde::Serialize for Unserializable {
+    fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("boom"))
+    }
+}
+
+#[tokio::test]
+async fn test_unserializable_payload_is_dead_lettered_once_per_webhook() {
+    let config = Config::from_env().unwrap();
+    let pool = create_pool(&config).await.unwrap();
+
+    let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
+    let webhook_repo = Arc::new(PostgresWebhookRepository::new(pool.clone()));
+
+    let mock_dispatcher = Arc::new(MockWebhookDispatcher::new());
+    let webhook_service = WebhookService::new(
+        webhook_repo.clone(),
+        account_repo.clone(),
+        mock_dispatcher.clone(),
+    );
+
+    let account = Account::new("Unserializable Test".to_string(), Money::new(dec!(0.0)).unwrap()).unwrap();
+    account_repo.create(&account).await.unwrap();
+
+    let mut subscribed = Vec::new();
+    for path in ["first", "second"] {
+        let webhook = Webhook::new(
+            account.id,
+            format!("https://example.com/{}", path),
+            WebhookEvent::TransactionCompleted,
+        ).unwrap();
+        subscribed.push(webhook.id);
+        webhook_repo.create(webhook).await.unwrap();
+    }
+    let withdrawals_only = Webhook::new(
+        account.id,
+        "https://example.com/withdrawals".to_string(),
+        WebhookEvent::TransactionWithdrawalCompleted,
+    ).unwrap();
+    let withdrawals_only_id = withdrawals_only.id;
+    webhook_repo.create(withdrawals_only).await.unwrap();
+
+    webhook_service.notify_async(account.id, WebhookEvent::TransactionDepositCompleted, Unserializable, None).await;
+    webhook_service.flush().await;
+
+    assert!(mock_dispatcher.calls.lock().unwrap().is_empty());
+    for webhook_id in subscribed {
+        let letters = webhook_repo.list_dead_letters(webhook_id).await.unwrap();
+        assert_eq!(letters.len(), 1);
+        assert!(letters[0].payload.is_null());
+        assert!(letters[0].reason.contains("boom"), "{}", letters[0].reason);
+    }
+    assert!(webhook_repo.list_dead_letters(withdrawals_only_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_webhook_carries_triggering_request_id() {
+    let config = Config::from_env().unwrap();
+    let pool = create_pool(&config).await.unwrap();
+
+    let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
+    let transaction_repo = Arc::new(PostgresTransactionRepository::new(pool.clone()));
+    let webhook_repo = Arc::new(PostgresWebhookRepository::new(pool.clone()));
+
+    let mock_dispatcher = Arc::new(MockWebhookDispatcher::new());
+    let webhook_service = Arc::new(WebhookService::new(
+        webhook_repo.clone(),
+        account_repo.clone(),
+        mock_dispatcher.clone(),
+    ));
+
+    let transaction_service = TransactionService::new(
+        transaction_repo,
+        Some(webhook_service),
+    );
+
+    let account = Account::new("Request Id Test".to_string(), Money::new(dec!(0.0)).unwrap()).unwrap();
+    account_repo.create(&account).await.unwrap();
+
+    let webhook = Webhook::new(
+        account.id,
+        "https://example.com/request-id".to_string(),
+        WebhookEvent::TransactionCompleted,
+    ).unwrap();
+    webhook_repo.create(webhook).await.unwrap();
+
+    transaction_service.deposit(DepositRequest {
+        account_id: account.id,
+        amount: dec!(100.0),
+        idempotency_key: None,
+        description: None,
+    }, Some("req-deposit-42".to_string())).await.unwrap();
+
+    let timeout = tokio::time::timeout(std::time::Duration::from_secs(2), mock_dispatcher.notify.notified());
+    assert!(timeout.await.is_ok(), "Timed out waiting for webhook dispatch");
+
+    let calls = mock_dispatcher.calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].1["request_id"].as_str(), Some("req-deposit-42"));
+    assert_eq!(calls[0].3.as_deref(), Some("req-deposit-42"));
+}
+
+/// Dispatcher that takes a while to deliver, to observe pending dispatches
+struct SlowWebhookDispatcher {
+    delivered: Arc<Mutex<usize>>,
+}
+
+#[async_trait]
+impl WebhookDispatcher for SlowWebhookDispatcher {
+    async fn dispatch(
+        &self,
+        _url: &str,
+        _payload: &Value,
+        _secret: &str,
+        _context: &DeliveryContext,
+    ) -> Result<(), String> {
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        *self.delivered.lock().unwrap() += 1;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_flush_waits_for_pending_dispatches() {
+    let config = Config::from_env().unwrap();
+    let pool = create_pool(&config).await.unwrap();
+
+    let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
+    let transaction_repo = Arc::new(PostgresTransactionRepository::new(pool.clone()));
+    let webhook_repo = Arc::new(PostgresWebhookRepository::new(pool.clone()));
+
+    let delivered = Arc::new(Mutex::new(0));
+    let webhook_service = Arc::new(WebhookService::new(
+        webhook_repo.clone(),
+        account_repo.clone(),
+        Arc::new(SlowWebhookDispatcher { delivered: delivered.clone() }),
+    ));
+    let transaction_service = TransactionService::new(transaction_repo, Some(webhook_service.clone()));
+
+    let account = Account::new("Webhook Flush Test".to_string(), Money::new(dec!(0.0)).unwrap()).unwrap();
+    account_repo.create(&account).await.unwrap();
+    let webhook = Webhook::new(
+        account.id,
+        "https://example.com/flush".to_string(),
+        WebhookEvent::TransactionCompleted,
+    ).unwrap();
+    webhook_repo.create(webhook).await.unwrap();
+
+    let deposit = DepositRequest {
+        account_id: account.id,
+        amount: dec!(10.0),
+        idempotency_key: None,
+        description: None,
+    };
+    transaction_service.deposit(deposit, None).await.unwrap();
+
+    // The deposit returns before the webhook is delivered
+    assert_eq!(*delivered.lock().unwrap(), 0);
+    assert_eq!(webhook_service.pending_dispatches(), 1);
+
+    let flushed = tokio::time::timeout(std::time::Duration::from_secs(5), webhook_service.flush()).await;
+    assert!(flushed.is_ok(), "Timed out flushing webhook dispatches");
+    assert_eq!(*delivered.lock().unwrap(), 1);
+    assert_eq!(webhook_service.pending_dispatches(), 0);
+}
+
+#[tokio::test]
+async fn test_webhook_deliveries_are_numbered_in_sequence() {
+    let config = Config::from_env().unwrap();
+    let pool = create_pool(&config).await.unwrap();
+
+    let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
+    let transaction_repo = Arc::new(PostgresTransactionRepository::new(pool.clone()));
+    let webhook_repo = Arc::new(PostgresWebhookRepository::new(pool.clone()));
+
+    let mock_dispatcher = Arc::new(MockWebhookDispatcher::new());
+    let webhook_service = Arc::new(WebhookService::new(
+        webhook_repo.clone(),
+        account_repo.clone(),
+        mock_dispatcher.clone(),
+    ));
+    let transaction_service = TransactionService::new(transaction_repo, Some(webhook_service.clone()));
+
+    let account = Account::new("Webhook Sequence Test".to_string(), Money::new(dec!(0.0)).unwrap()).unwrap();
+    account_repo.create(&account).await.unwrap();
+    let webhook = Webhook::new(
+        account.id,
+        "https://example.com/sequence".to_string(),
+        WebhookEvent::TransactionCompleted,
+    ).unwrap();
+    webhook_repo.create(webhook).await.unwrap();
+
+    for _ in 0..5 {
+        transaction_service.deposit(DepositRequest {
+            account_id: account.id,
+            amount: dec!(10.0),
+            idempotency_key: None,
+            description: None,
+        }, None).await.unwrap();
+    }
+
+    let flushed = tokio::time::timeout(std::time::Duration::from_secs(5), webhook_service.flush()).await;
+    assert!(flushed.is_ok(), "Timed out flushing webhook dispatches");
+
+    // Dispatch tasks race each other, so compare the numbers rather than arrival order
+    let mut sequences: Vec<i64> = mock_dispatcher
+        .calls
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|call| call.1["sequence"].as_i64().expect("delivery without a sequence"))
+        .collect();
+    sequences.sort_unstable();
+    assert_eq!(sequences, vec![1, 2, 3, 4, 5]);
+}
+
+/// Dispatcher that holds every delivery until the test opens the gate
+struct GatedWebhookDispatcher {
+    gate: Arc<Semaphore>,
+    delivered: Arc<Mutex<usize>>,
+}
+
+impl GatedWebhookDispatcher {
+    fn new() -> Self {
+        Self {
+            gate: Arc::new(Semaphore::new(0)),
+            delivered: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    fn open(&self) {
+        self.gate.add_permits(1000);
+    }
+}
+
+#[async_trait]
+impl WebhookDispatcher for GatedWebhookDispatcher {
+    async fn dispatch(
+        &self,
+        _url: &str,
+        _payload: &Value,
+        _secret: &str,
+        _context: &DeliveryContext,
+    ) -> Result<(), String> {
+        self.gate.acquire().await.unwrap().forget();
+        *self.delivered.lock().unwrap() += 1;
+        Ok(())
+    }
+}
+
+/// An account with one active webhook, and a service allowing a single dispatch in flight
+async fn saturation_setup(
+    policy: WebhookOverflowPolicy,
+    block_timeout: std::time::Duration,
+) -> (WebhookService, Arc<GatedWebhookDispatcher>, Arc<PostgresWebhookRepository>, Uuid, Uuid) {
+    let config = Config::from_env().unwrap();
+    let pool = create_pool(&config).await.unwrap();
+    let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
+    let webhook_repo = Arc::new(PostgresWebhookRepository::new(pool));
+
+    let account = Account::new("Webhook Saturation Test".to_string(), Money::new(dec!(0.0)).unwrap()).unwrap();
+    account_repo.create(&account).await.unwrap();
+    let webhook = Webhook::new(
+        account.id,
+        "https://example.com/saturated".to_string(),
+        WebhookEvent::TransactionCompleted,
+    ).unwrap();
+    let webhook_id = webhook.id;
+    webhook_repo.create(webhook).await.unwrap();
+
+    let dispatcher = Arc::new(GatedWebhookDispatcher::new());
+    let service = WebhookService::new(webhook_repo.clone(), account_repo, dispatcher.clone())
+        .with_dispatch_limit(1, policy, block_timeout);
+    (service, dispatcher, webhook_repo, account.id, webhook_id)
+}
+
+fn saturation_payload() -> Value {
+    serde_json::json!({
+        "id": Uuid::new_v4(),
+        "transaction_type": "credit",
+        "amount": "1.00",
+        "created_at": "2025-01-01T00:00:00Z",
+    })
+}
+
+#[tokio::test]
+async fn test_shed_policy_dead_letters_overflow() {
+    let (service, dispatcher, webhook_repo, account_id, webhook_id) =
+        saturation_setup(WebhookOverflowPolicy::Shed, std::time::Duration::from_secs(5)).await;
+
+    service.notify_async(account_id, WebhookEvent::TransactionCompleted, saturation_payload(), None).await;
+    let started = std::time::Instant::now();
+    service.notify_async(account_id, WebhookEvent::TransactionCompleted, saturation_payload(), Some("req-shed".to_string())).await;
+    assert!(started.elapsed() < std::time::Duration::from_secs(1), "Shedding should not wait for a slot");
+
+    let letters = webhook_repo.list_dead_letters(webhook_id).await.unwrap();
+    assert_eq!(letters.len(), 1);
+    assert!(letters[0].reason.contains("saturated"));
+    assert_eq!(letters[0].payload["request_id"].as_str(), Some("req-shed"));
+
+    dispatcher.open();
+    service.flush().await;
+    assert_eq!(*dispatcher.delivered.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_block_policy_waits_for_a_slot_until_timeout() {
+    let (service, dispatcher, webhook_repo, account_id, webhook_id) =
+        saturation_setup(WebhookOverflowPolicy::Block, std::time::Duration::from_secs(5)).await;
+
+    service.notify_async(account_id, WebhookEvent::TransactionCompleted, saturation_payload(), None).await;
+    let gate = dispatcher.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        gate.open();
+    });
+
+    // Held up until the first delivery frees its slot, then dispatched normally
+    let started = std::time::Instant::now();
+    service.notify_async(account_id, WebhookEvent::TransactionCompleted, saturation_payload(), None).await;
+    assert!(started.elapsed() >= std::time::Duration::from_millis(150));
+    service.flush().await;
+    assert_eq!(*dispatcher.delivered.lock().unwrap(), 2);
+    assert!(webhook_repo.list_dead_letters(webhook_id).await.unwrap().is_empty());
+
+    // Past the timeout the delivery is dead-lettered instead
+    let (service, dispatcher, webhook_repo, account_id, webhook_id) =
+        saturation_setup(WebhookOverflowPolicy::Block, std::time::Duration::from_millis(100)).await;
+    service.notify_async(account_id, WebhookEvent::TransactionCompleted, saturation_payload(), None).await;
+    let started = std::time::Instant::now();
+    service.notify_async(account_id, WebhookEvent::TransactionCompleted, saturation_payload(), None).await;
+    assert!(started.elapsed() >= std::time::Duration::from_millis(100));
+    assert_eq!(webhook_repo.list_dead_letters(webhook_id).await.unwrap().len(), 1);
+
+    dispatcher.open();
+    service.flush().await;
+    assert_eq!(*dispatcher.delivered.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_outbox_policy_parks_overflow_until_drained() {
+    let (service, dispatcher, webhook_repo, account_id, webhook_id) =
+        saturation_setup(WebhookOverflowPolicy::Outbox, std::time::Duration::from_secs(5)).await;
+
+    service.notify_async(account_id, WebhookEvent::TransactionCompleted, saturation_payload(), None).await;
+    service.notify_async(account_id, WebhookEvent::TransactionCompleted, saturation_payload(), None).await;
+    assert!(webhook_repo.list_dead_letters(webhook_id).await.unwrap().is_empty());
+
+    // Nothing is drained while the pool is still full
+    assert_eq!(service.drain_outbox(10).await.unwrap(), 0);
+
+    dispatcher.open();
+    service.flush().await;
+    assert_eq!(*dispatcher.delivered.lock().unwrap(), 1);
+
+    assert_eq!(service.drain_outbox(10).await.unwrap(), 1);
+    service.flush().await;
+    assert_eq!(*dispatcher.delivered.lock().unwrap(), 2);
+}
@@ -6,9 +6,9 @@ use serde_json::json;
 use tower::ServiceExt; 
 use sha2::Digest; 
 
-use dodo_payments_assignment::application::dto::WebhookResponse;
+use dodo_payments_assignment::application::dto::{WebhookResponse, WebhookSecretResponse, WebhookTestResponse};
 use dodo_payments_assignment::domain::entities::ApiKey;
-use dodo_payments_assignment::domain::value_objects::{Money, WebhookEvent};
+use dodo_payments_assignment::domain::value_objects::{Money, WebhookEvent, WebhookStatus};
 use rust_decimal_macros::dec; 
 use dodo_payments_assignment::infrastructure::config::Config;
 use dodo_payments_assignment::infrastructure::database::{
@@ -16,10 +16,14 @@ use dodo_payments_assignment::infrastructure::database::{
     PostgresWebhookRepository,
 };
 use dodo_payments_assignment::domain::repositories::{AccountRepository, ApiKeyRepository};
-use dodo_payments_assignment::application::services::{AccountService, AuthService, TransactionService};
+use dodo_payments_assignment::infrastructure::http_client::{verify_signature, ReqwestWebhookDispatcher, WebhookUrlPolicy};
+use dodo_payments_assignment::application::services::{AccountService, AuthService, TransactionService, WebhookService};
 use dodo_payments_assignment::application::AppState;
 use dodo_payments_assignment::presentation::api::{
-    webhook::{create_webhook, delete_webhook, list_webhooks},
+    webhook::{
+        create_webhook, delete_webhook, list_webhooks, rotate_webhook_secret, test_webhook, update_webhook,
+        verify_webhook,
+    },
 };
 use dodo_payments_assignment::presentation::middleware::auth::require_auth;
 use axum::Router;
@@ -27,6 +31,10 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 async fn setup_app_and_key() -> (Router, String, Uuid) {
+    setup_app(false).await
+}
+
+async fn setup_app(require_webhook_verification: bool) -> (Router, String, Uuid) {
     let config = Config::from_env().unwrap();
     let pool = create_pool(&config).await.unwrap();
 
@@ -44,6 +52,13 @@ async fn setup_app_and_key() -> (Router, String, Uuid) {
         transaction_service,
         auth_service,
         webhook_repository: webhook_repo,
+        webhook_dispatcher: Arc::new(ReqwestWebhookDispatcher::new(3, 10)),
+        // The ping test receives on 127.0.0.1
+        webhook_url_policy: WebhookUrlPolicy::new(false, true),
+        require_webhook_verification,
+        max_page_size: 100,
+        pool: pool.clone(),
+        started_at: chrono::Utc::now(),
     };
 
     let account = dodo_payments_assignment::domain::entities::Account::new(
@@ -60,7 +75,10 @@ async fn setup_app_and_key() -> (Router, String, Uuid) {
 
     let protected_routes = Router::new()
         .route("/webhooks", axum::routing::post(create_webhook).get(list_webhooks))
-        .route("/webhooks/:id", axum::routing::delete(delete_webhook))
+        .route("/webhooks/:id", axum::routing::patch(update_webhook).delete(delete_webhook))
+        .route("/webhooks/:id/rotate-secret", axum::routing::post(rotate_webhook_secret))
+        .route("/webhooks/:id/test", axum::routing::post(test_webhook))
+        .route("/webhooks/:id/verify", axum::routing::post(verify_webhook))
         .route_layer(axum::middleware::from_fn_with_state(
             state.clone(),
             require_auth,
@@ -96,14 +114,79 @@ async fn test_create_webhook() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::CREATED);
+    let location = response.headers().get("location").unwrap().to_str().unwrap().to_string();
     
     let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
     let response_dto: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
     
+    assert_eq!(location, format!("/webhooks/{}", response_dto.id));
     assert_eq!(response_dto.url, "https://example.com/webhook");
     assert_eq!(response_dto.event, WebhookEvent::TransactionCompleted);
 }
 
+#[tokio::test]
+async fn test_create_webhook_is_idempotent() {
+    let (app, api_key, _account_id) = setup_app_and_key().await;
+    let create = |url: &str| {
+        let payload = json!({ "url": url, "event": "transaction.completed" });
+        app.clone().oneshot(
+            Request::builder()
+                .uri("/webhooks")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("x-api-key", api_key.clone())
+                .body(Body::from(serde_json::to_vec(&payload).unwrap()))
+                .unwrap(),
+        )
+    };
+
+    let first = create("https://example.com/setup").await.unwrap();
+    assert_eq!(first.status(), StatusCode::CREATED);
+    let body_bytes = axum::body::to_bytes(first.into_body(), usize::MAX).await.unwrap();
+    let first: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+
+    // Re-running the same setup returns the existing webhook
+    let second = create("https://example.com/setup").await.unwrap();
+    assert_eq!(second.status(), StatusCode::OK);
+    let body_bytes = axum::body::to_bytes(second.into_body(), usize::MAX).await.unwrap();
+    let second: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(second.id, first.id);
+    assert_eq!(second.secret, first.secret);
+
+    let response = app.clone()
+        .oneshot(
+            Request::builder()
+                .uri("/webhooks")
+                .method("GET")
+                .header("x-api-key", api_key.clone())
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let webhooks: Vec<WebhookResponse> = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(webhooks.len(), 1);
+
+    // Moving another webhook onto the same subscription is a genuine conflict
+    let other = create("https://example.com/other").await.unwrap();
+    let body_bytes = axum::body::to_bytes(other.into_body(), usize::MAX).await.unwrap();
+    let other: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/webhooks/{}", other.id))
+                .method("PATCH")
+                .header("content-type", "application/json")
+                .header("x-api-key", api_key)
+                .body(Body::from(serde_json::to_vec(&json!({"url": "https://example.com/setup"})).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
 #[tokio::test]
 async fn test_list_webhooks() {
     let (app, api_key, _account_id) = setup_app_and_key().await;
@@ -179,7 +262,7 @@ async fn test_delete_webhook() {
      let response = app
         .oneshot(
             Request::builder()
-                .uri(&format!("/webhooks/{}", created.id))
+                .uri(format!("/webhooks/{}", created.id))
                 .method("DELETE")
                 .header("x-api-key", api_key)
                 .body(Body::empty())
@@ -190,3 +273,443 @@ async fn test_delete_webhook() {
 
     assert_eq!(response.status(), StatusCode::NO_CONTENT);
 }
+
+#[tokio::test]
+async fn test_other_accounts_cannot_delete_or_purge_webhooks() {
+    let (app, api_key, _account_id) = setup_app_and_key().await;
+    let (other_app, other_key, _) = setup_app_and_key().await;
+    let payload = json!({ "url": "https://example.com/webhook-owned", "event": "transaction.completed" });
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/webhooks")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("x-api-key", api_key.clone())
+                .body(Body::from(serde_json::to_vec(&payload).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let created: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+
+    let delete = |app: Router, key: String, uri: String| {
+        app.oneshot(
+            Request::builder()
+                .uri(uri)
+                .method("DELETE")
+                .header("x-api-key", key)
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+
+    for uri in [format!("/webhooks/{}", created.id), format!("/webhooks/{}?purge=true", created.id)] {
+        let response = delete(other_app.clone(), other_key.clone(), uri).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    // Disabled by its owner, it still cannot be purged by anyone else
+    let response = delete(app.clone(), api_key.clone(), format!("/webhooks/{}", created.id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = delete(other_app, other_key, format!("/webhooks/{}?purge=true", created.id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = delete(app, api_key, format!("/webhooks/{}?purge=true", created.id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_deleted_webhooks_are_disabled_until_purged() {
+    let (app, api_key, _account_id) = setup_app_and_key().await;
+    let pool = create_pool(&Config::from_env().unwrap()).await.unwrap();
+    let send = |method: &str, uri: String, body: Body| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header("content-type", "application/json")
+                .header("x-api-key", api_key.clone())
+                .body(body)
+                .unwrap(),
+        )
+    };
+    let payload = json!({ "url": "https://example.com/webhook-disable", "event": "transaction.completed" });
+
+    let response = send("POST", "/webhooks".to_string(), Body::from(serde_json::to_vec(&payload).unwrap())).await.unwrap();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let created: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+
+    let response = send("DELETE", format!("/webhooks/{}", created.id), Body::empty()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    // Gone from listings, but the row is kept
+    let response = send("GET", "/webhooks".to_string(), Body::empty()).await.unwrap();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let webhooks: Vec<WebhookResponse> = serde_json::from_slice(&body_bytes).unwrap();
+    assert!(webhooks.is_empty());
+    let disabled: bool = sqlx::query_scalar("SELECT deleted_at IS NOT NULL FROM webhooks WHERE id = $1")
+        .bind(created.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(disabled);
+
+    // The same subscription can be created again
+    let response = send("POST", "/webhooks".to_string(), Body::from(serde_json::to_vec(&payload).unwrap())).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let recreated: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+    assert_ne!(recreated.id, created.id);
+
+    let response = send("DELETE", format!("/webhooks/{}", created.id), Body::empty()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = send("DELETE", format!("/webhooks/{}?purge=true", created.id), Body::empty()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM webhooks WHERE id = $1")
+        .bind(created.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+}
+
+#[tokio::test]
+async fn test_rotate_webhook_secret() {
+    let (app, api_key, _account_id) = setup_app_and_key().await;
+
+    let payload = json!({
+        "url": "https://example.com/webhook-rotate",
+        "event": "transaction.completed"
+    });
+
+    let create_response = app.clone()
+        .oneshot(
+            Request::builder()
+                .uri("/webhooks")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("x-api-key", api_key.clone())
+                .body(Body::from(serde_json::to_vec(&payload).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body_bytes = axum::body::to_bytes(create_response.into_body(), usize::MAX).await.unwrap();
+    let created: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+    let original_secret = created.secret.expect("Secret missing from create response");
+
+    let response = app.clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/webhooks/{}/rotate-secret", created.id))
+                .method("POST")
+                .header("x-api-key", api_key.clone())
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let rotated: WebhookSecretResponse = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(rotated.webhook_id, created.id);
+    assert_ne!(rotated.secret, original_secret);
+    assert!(rotated.previous_secret_expires_at.is_some());
+
+    // Secrets are never listed
+    let response = app.clone()
+        .oneshot(
+            Request::builder()
+                .uri("/webhooks")
+                .header("x-api-key", api_key)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let webhooks: Vec<WebhookResponse> = serde_json::from_slice(&body_bytes).unwrap();
+    assert!(webhooks.iter().all(|webhook| webhook.secret.is_none()));
+
+    // Another account cannot rotate it
+    let (other_app, other_key, _) = setup_app_and_key().await;
+    let response = other_app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/webhooks/{}/rotate-secret", created.id))
+                .method("POST")
+                .header("x-api-key", other_key)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_ping_webhook_reports_receiver_status() {
+    let (app, api_key, _account_id) = setup_app_and_key().await;
+
+    // Receiver that records the ping and answers 202
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let receiver = Router::new().route(
+        "/hook",
+        axum::routing::post(move |headers: axum::http::HeaderMap, body: axum::body::Bytes| {
+            let received = received_clone.clone();
+            async move {
+                received.lock().unwrap().push((headers, body));
+                StatusCode::ACCEPTED
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, receiver).await.unwrap();
+    });
+
+    let payload = json!({
+        "url": url,
+        "event": "transaction.completed"
+    });
+    let create_response = app.clone()
+        .oneshot(
+            Request::builder()
+                .uri("/webhooks")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("x-api-key", api_key.clone())
+                .body(Body::from(serde_json::to_vec(&payload).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body_bytes = axum::body::to_bytes(create_response.into_body(), usize::MAX).await.unwrap();
+    let created: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+    let secret = created.secret.expect("Secret missing from create response");
+
+    let response = app.clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/webhooks/{}/test", created.id))
+                .method("POST")
+                .header("x-api-key", api_key)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let result: WebhookTestResponse = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(result.webhook_id, created.id);
+    assert!(result.success);
+    assert_eq!(result.status_code, Some(202));
+    assert!(result.error.is_none());
+
+    // Exactly one signed ping arrived
+    let received = received.lock().unwrap().clone();
+    assert_eq!(received.len(), 1);
+    let (headers, body) = &received[0];
+    let ping: serde_json::Value = serde_json::from_slice(body).unwrap();
+    assert_eq!(ping["event"], "ping");
+    assert_eq!(ping["webhook_id"], created.id.to_string());
+    let timestamp: i64 = headers["x-dodo-timestamp"].to_str().unwrap().parse().unwrap();
+    let signature = headers["x-dodo-signature"].to_str().unwrap();
+    verify_signature(&secret, timestamp, body, signature, std::time::Duration::from_secs(300))
+        .expect("Ping signature should verify");
+
+    // Another account cannot ping it
+    let (other_app, other_key, _) = setup_app_and_key().await;
+    let response = other_app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/webhooks/{}/test", created.id))
+                .method("POST")
+                .header("x-api-key", other_key)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_update_webhook() {
+    let (app, api_key, _account_id) = setup_app_and_key().await;
+
+    let payload = json!({
+        "url": "https://example.com/webhook-old",
+        "event": "transaction.completed"
+    });
+    let create_response = app.clone()
+        .oneshot(
+            Request::builder()
+                .uri("/webhooks")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("x-api-key", api_key.clone())
+                .body(Body::from(serde_json::to_vec(&payload).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body_bytes = axum::body::to_bytes(create_response.into_body(), usize::MAX).await.unwrap();
+    let created: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+
+    let update = json!({
+        "url": "https://example.com/webhook-new",
+        "event": "transaction.deposit.completed"
+    });
+    let response = app.clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/webhooks/{}", created.id))
+                .method("PATCH")
+                .header("content-type", "application/json")
+                .header("x-api-key", api_key.clone())
+                .body(Body::from(serde_json::to_vec(&update).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let updated: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(updated.id, created.id);
+    assert_eq!(updated.url, "https://example.com/webhook-new");
+    assert_eq!(updated.event, WebhookEvent::TransactionDepositCompleted);
+
+    // URLs go through the same validation as on create
+    let response = app.clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/webhooks/{}", created.id))
+                .method("PATCH")
+                .header("content-type", "application/json")
+                .header("x-api-key", api_key)
+                .body(Body::from(serde_json::to_vec(&json!({"url": "ftp://example.com"})).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Another account cannot change it or tell that it exists
+    let (other_app, other_key, _) = setup_app_and_key().await;
+    let response = other_app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/webhooks/{}", created.id))
+                .method("PATCH")
+                .header("content-type", "application/json")
+                .header("x-api-key", other_key)
+                .body(Body::from(serde_json::to_vec(&json!({"url": "https://evil.example.com"})).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+// Receiver that records every body; `echo` decides whether it answers challenges
+async fn start_receiver(echo: bool) -> (String, Arc<std::sync::Mutex<Vec<serde_json::Value>>>) {
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let receiver = Router::new().route(
+        "/hook",
+        axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+            let received = received_clone.clone();
+            async move {
+                received.lock().unwrap().push(body.clone());
+                let challenge = body["challenge"].clone();
+                axum::Json(if echo { json!({ "challenge": challenge }) } else { json!({}) })
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, receiver).await.unwrap();
+    });
+    (url, received)
+}
+
+#[tokio::test]
+async fn test_webhooks_activate_only_after_echoing_the_challenge() {
+    let (app, api_key, account_id) = setup_app(true).await;
+    let (cooperating_url, cooperating_received) = start_receiver(true).await;
+    let (silent_url, silent_received) = start_receiver(false).await;
+
+    let mut created = Vec::new();
+    for url in [&cooperating_url, &silent_url] {
+        let payload = json!({ "url": url, "event": "transaction.completed" });
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/webhooks")
+                    .method("POST")
+                    .header("content-type", "application/json")
+                    .header("x-api-key", api_key.clone())
+                    .body(Body::from(serde_json::to_vec(&payload).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        created.push(serde_json::from_slice::<WebhookResponse>(&body_bytes).unwrap());
+    }
+
+    assert_eq!(created[0].status, WebhookStatus::Active);
+    assert_eq!(created[1].status, WebhookStatus::Pending);
+    assert_eq!(cooperating_received.lock().unwrap()[0]["event"], "webhook.verification");
+    assert_eq!(silent_received.lock().unwrap().len(), 1);
+
+    // Retrying the handshake against a silent endpoint leaves it pending
+    let response = app.clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/webhooks/{}/verify", created[1].id))
+                .method("POST")
+                .header("x-api-key", api_key)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let retried: WebhookResponse = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(retried.status, WebhookStatus::Pending);
+    assert_eq!(silent_received.lock().unwrap().len(), 2);
+
+    // Only the verified endpoint receives events
+    let config = Config::from_env().unwrap();
+    let pool = create_pool(&config).await.unwrap();
+    let webhook_service = WebhookService::new(
+        Arc::new(PostgresWebhookRepository::new(pool.clone())),
+        Arc::new(PostgresAccountRepository::new(pool)),
+        Arc::new(ReqwestWebhookDispatcher::new(0, 10)),
+    )
+    .with_payload_validation(false);
+    webhook_service.notify_async(account_id, WebhookEvent::TransactionCompleted, json!({ "amount": "1.00" }), None).await;
+    webhook_service.flush().await;
+
+    let cooperating_received = cooperating_received.lock().unwrap();
+    assert_eq!(cooperating_received.len(), 2);
+    assert_eq!(cooperating_received[1]["amount"], "1.00");
+    assert_eq!(silent_received.lock().unwrap().len(), 2);
+}
@@ -49,8 +49,8 @@ async fn test_create_and_list_webhooks() {
     assert_eq!(list[0].id, webhook.id);
     assert_eq!(list[0].event, event);
 
-    // Clean up
-    repo.delete(webhook.id).await.expect("Failed to delete webhook");
+    // Clean up; a disabled webhook still references the account, so remove it for good
+    repo.purge(webhook.id).await.expect("Failed to purge webhook");
     sqlx::query!("DELETE FROM accounts WHERE id = $1", account_id)
         .execute(&pool)
         .await
@@ -87,8 +87,14 @@ async fn test_delete_webhook() {
     // Verify gone
     let list = repo.list_by_account(account_id).await.unwrap();
     assert_eq!(list.len(), 0);
-    
-    // Clean up account
+
+    // Disabled, not removed: only found when deleted webhooks are included
+    assert!(repo.find_by_id(webhook.id).await.is_err());
+    let disabled = repo.find_by_id_including_deleted(webhook.id).await.expect("Disabled webhook not found");
+    assert_eq!(disabled.id, webhook.id);
+
+    // Clean up
+    repo.purge(webhook.id).await.expect("Failed to purge webhook");
     sqlx::query!("DELETE FROM accounts WHERE id = $1", account_id)
         .execute(&pool)
         .await